@@ -0,0 +1,78 @@
+// Run under gdb by `scripts/tests.sh`. Like the debuginfo tests of rustc, every `gdb-command` is
+// passed to gdb and the output has to contain every `gdb-check`.
+
+// gdb-command:break debuginfo::zzz
+// gdb-command:run
+// gdb-command:up
+
+// gdb-command:print a
+// gdb-check:= 42
+// gdb-command:print s
+// gdb-check:= "abc"
+// gdb-command:print t
+// gdb-check:= (1, -2, 3)
+// gdb-command:print arr
+// gdb-check:= [4, 5, 6]
+// gdb-command:print p
+// gdb-check:Point3 {x: 7, y: 8, z: 9}
+
+// Enums print their active variant, with a tag or with a niche.
+// gdb-command:print circle
+// gdb-check:Circle(10)
+// gdb-command:print square
+// gdb-check:Square {side: 3}
+// gdb-command:print some
+// gdb-check:Some(5)
+// gdb-command:print none
+// gdb-check:None
+
+// The captured variables of a closure have the same names as with the LLVM backend.
+// gdb-command:print closure.arr
+// gdb-check:= [4, 5, 6]
+// gdb-command:print closure.t
+// gdb-check:= (1, -2, 3)
+
+struct Point3 {
+    x: i32,
+    y: i32,
+    z: i32,
+}
+
+enum Shape {
+    Circle(u32),
+    Square { side: u32 },
+}
+
+// Taking a reference to a variable keeps it on the stack, where its location is valid for the
+// whole function.
+#[inline(never)]
+fn observe<T>(_: &T) {}
+
+#[inline(never)]
+fn zzz() {}
+
+fn main() {
+    let a = 42u32;
+    let s = "abc";
+    let t = (1u8, -2i64, 3u16);
+    let arr = [4u16, 5, 6];
+    let p = Point3 { x: 7, y: 8, z: 9 };
+    let closure = move || arr[0] as i64 + t.1;
+    let circle = Shape::Circle(10);
+    let square = Shape::Square { side: 3 };
+    let some = Some(5u8);
+    let none: Option<&u32> = None;
+
+    observe(&a);
+    observe(&s);
+    observe(&circle);
+    observe(&square);
+    observe(&some);
+    observe(&none);
+
+    zzz();
+
+    assert_eq!(closure(), 2);
+    assert_eq!(p.x + p.y + p.z, 24);
+    assert!(matches!((circle, square), (Shape::Circle(10), Shape::Square { side: 3 })));
+}
@@ -75,6 +75,26 @@ function base_sysroot_tests() {
     echo "[AOT] mod_bench"
     $MY_RUSTC example/mod_bench.rs --crate-type bin --target "$TARGET_TRIPLE"
     $RUN_WRAPPER ./target/out/mod_bench
+
+    echo "[AOT] debuginfo"
+    $MY_RUSTC example/debuginfo.rs --crate-type bin --target "$TARGET_TRIPLE"
+    $RUN_WRAPPER ./target/out/debuginfo
+    # Variable locations are only emitted for the architectures known to `stack_pointer_register`.
+    if [[ "$HOST_TRIPLE" = "$TARGET_TRIPLE" && "$TARGET_TRIPLE" =~ ^(x86_64|aarch64|riscv64|s390x)- ]] \
+        && command -v gdb >/dev/null; then
+        echo "[DEBUGINFO] debuginfo"
+        sed -n 's#^// gdb-command:##p' example/debuginfo.rs > target/out/debuginfo.gdb
+        gdb -batch -x target/out/debuginfo.gdb ./target/out/debuginfo > target/out/debuginfo.gdb.out 2>&1
+        sed -n 's#^// gdb-check:##p' example/debuginfo.rs | while read -r check; do
+            if ! grep -qF -- "$check" target/out/debuginfo.gdb.out; then
+                echo "gdb output does not contain \`$check\`:"
+                cat target/out/debuginfo.gdb.out
+                exit 1
+            fi
+        done
+    else
+        echo "[DEBUGINFO] debuginfo (skipped)"
+    fi
 }
 
 function extended_sysroot_tests() {
@@ -7,9 +7,12 @@
 
 use crate::prelude::*;
 
+use rustc_codegen_ssa::debuginfo::closure_saved_names_of_captured_variables;
+use rustc_hir::def::CtorKind;
 use rustc_index::vec::IndexVec;
+use rustc_target::abi::{TagEncoding, Variants};
 
-use cranelift_codegen::entity::EntityRef;
+use cranelift_codegen::entity::{EntityRef, PrimaryMap};
 use cranelift_codegen::ir::{Endianness, LabelValueLoc, ValueLabel};
 use cranelift_codegen::isa::TargetIsa;
 use cranelift_codegen::ValueLocRange;
@@ -18,11 +21,13 @@
     Address, AttributeValue, DwarfUnit, Expression, LineProgram, LineString, Location,
     LocationList, Range, RangeList, UnitEntryId,
 };
-use gimli::{Encoding, Format, LineEncoding, RunTimeEndian, X86_64};
+use gimli::{AArch64, Encoding, Format, LineEncoding, Register, RiscV, RunTimeEndian, X86_64};
 
 pub(crate) use emit::{DebugReloc, DebugRelocName};
 pub(crate) use unwind::UnwindContext;
 
+use crate::pointer::PointerBase;
+
 pub(crate) struct DebugContext<'tcx> {
     tcx: TyCtxt<'tcx>,
 
@@ -31,6 +36,10 @@ pub(crate) struct DebugContext<'tcx> {
     dwarf: DwarfUnit,
     unit_range_list: RangeList,
 
+    /// The register that stack slot offsets are relative to. Variable locations are only emitted
+    /// for targets where it is known.
+    stack_pointer: Option<Register>,
+
     types: FxHashMap<Ty<'tcx>, UnitEntryId>,
 }
 
@@ -101,6 +110,14 @@ pub(crate) fn new(tcx: TyCtxt<'tcx>, isa: &dyn TargetIsa) -> Self {
             root.set(gimli::DW_AT_low_pc, AttributeValue::Address(Address::Constant(0)));
         }
 
+        let stack_pointer = stack_pointer_register(&tcx.sess.target.arch);
+        if stack_pointer.is_none() {
+            tcx.sess.warn(&format!(
+                "cg_clif doesn't emit the locations of variables in debuginfo for {}",
+                tcx.sess.target.arch
+            ));
+        }
+
         DebugContext {
             tcx,
 
@@ -108,6 +125,7 @@ pub(crate) fn new(tcx: TyCtxt<'tcx>, isa: &dyn TargetIsa) -> Self {
 
             dwarf,
             unit_range_list: RangeList(Vec::new()),
+            stack_pointer,
 
             types: FxHashMap::default(),
         }
@@ -137,7 +155,9 @@ fn dwarf_ty(&mut self, ty: Ty<'tcx>) -> UnitEntryId {
             ty::Int(_) => primitive(&mut self.dwarf, gimli::DW_ATE_signed),
             ty::Float(_) => primitive(&mut self.dwarf, gimli::DW_ATE_float),
             ty::Ref(_, pointee_ty, _mutbl)
-            | ty::RawPtr(ty::TypeAndMut { ty: pointee_ty, mutbl: _mutbl }) => {
+            | ty::RawPtr(ty::TypeAndMut { ty: pointee_ty, mutbl: _mutbl })
+                if !matches!(layout.abi, Abi::ScalarPair(..)) =>
+            {
                 let type_id = new_entry(&mut self.dwarf, gimli::DW_TAG_pointer_type);
 
                 // Ensure that type is inserted before recursing to avoid duplicates
@@ -152,39 +172,103 @@ fn dwarf_ty(&mut self, ty: Ty<'tcx>) -> UnitEntryId {
 
                 type_id
             }
+            ty::Ref(_, pointee_ty, _) | ty::RawPtr(ty::TypeAndMut { ty: pointee_ty, mutbl: _ }) => {
+                // Fat pointer. Use the same layout and field names as the LLVM backend, which
+                // gdb and lldb recognize as slices and trait objects.
+                let type_id = new_entry(&mut self.dwarf, gimli::DW_TAG_structure_type);
+
+                // Ensure that type is inserted before recursing to avoid duplicates
+                self.types.insert(ty, type_id);
+
+                let tail =
+                    self.tcx.struct_tail_erasing_lifetimes(*pointee_ty, ParamEnv::reveal_all());
+                let (data_name, data_ty, extra_name, extra_ty) = match tail.kind() {
+                    ty::Slice(elem_ty) => {
+                        ("data_ptr", self.tcx.mk_imm_ptr(*elem_ty), "length", self.tcx.types.usize)
+                    }
+                    ty::Str => (
+                        "data_ptr",
+                        self.tcx.mk_imm_ptr(self.tcx.types.u8),
+                        "length",
+                        self.tcx.types.usize,
+                    ),
+                    _ => (
+                        "pointer",
+                        self.tcx.mk_imm_ptr(self.tcx.types.u8),
+                        "vtable",
+                        self.tcx.mk_imm_ptr(self.tcx.types.usize),
+                    ),
+                };
+                self.add_member(type_id, data_name, data_ty, Size::ZERO);
+                self.add_member(type_id, extra_name, extra_ty, self.tcx.data_layout.pointer_size);
+
+                type_id
+            }
             ty::Adt(adt_def, _substs) if adt_def.is_struct() && !layout.is_unsized() => {
                 let type_id = new_entry(&mut self.dwarf, gimli::DW_TAG_structure_type);
 
                 // Ensure that type is inserted before recursing to avoid duplicates
                 self.types.insert(ty, type_id);
 
-                let variant = adt_def.non_enum_variant();
-
-                for (field_idx, field_def) in variant.fields.iter().enumerate() {
-                    let field_offset = layout.fields.offset(field_idx);
-                    let field_layout = layout.field(
-                        &layout::LayoutCx { tcx: self.tcx, param_env: ParamEnv::reveal_all() },
-                        field_idx,
-                    );
-
-                    let field_type = self.dwarf_ty(field_layout.ty);
-
-                    let field_id = self.dwarf.unit.add(type_id, gimli::DW_TAG_member);
-                    let field_entry = self.dwarf.unit.get_mut(field_id);
-
-                    field_entry.set(
-                        gimli::DW_AT_name,
-                        AttributeValue::String(field_def.name.as_str().to_string().into_bytes()),
-                    );
-                    field_entry.set(
-                        gimli::DW_AT_data_member_location,
-                        AttributeValue::Udata(field_offset.bytes()),
-                    );
-                    field_entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(field_type));
+                self.add_fields(type_id, layout, adt_def.non_enum_variant());
+
+                type_id
+            }
+            ty::Adt(adt_def, _substs) if adt_def.is_enum() => {
+                let type_id = new_entry(&mut self.dwarf, gimli::DW_TAG_structure_type);
+
+                // Ensure that type is inserted before recursing to avoid duplicates
+                self.types.insert(ty, type_id);
+
+                self.add_variant_part(type_id, layout, *adt_def);
+
+                type_id
+            }
+            ty::Tuple(_) => {
+                let type_id = new_entry(&mut self.dwarf, gimli::DW_TAG_structure_type);
+
+                // Ensure that type is inserted before recursing to avoid duplicates
+                self.types.insert(ty, type_id);
+
+                for field_idx in 0..layout.fields.count() {
+                    self.add_field(type_id, layout, field_idx, &format!("__{}", field_idx));
                 }
 
                 type_id
             }
+            ty::Closure(def_id, _) => {
+                let type_id = new_entry(&mut self.dwarf, gimli::DW_TAG_structure_type);
+
+                // Ensure that type is inserted before recursing to avoid duplicates
+                self.types.insert(ty, type_id);
+
+                // Use the same names for the captured variables as the LLVM backend.
+                let capture_names = closure_saved_names_of_captured_variables(self.tcx, *def_id);
+                for (field_idx, capture_name) in capture_names.iter().enumerate() {
+                    self.add_field(type_id, layout, field_idx, capture_name);
+                }
+
+                type_id
+            }
+            ty::Array(elem_ty, len) => {
+                let type_id = new_entry(&mut self.dwarf, gimli::DW_TAG_array_type);
+
+                // Ensure that type is inserted before recursing to avoid duplicates
+                self.types.insert(ty, type_id);
+
+                let elem_type = self.dwarf_ty(*elem_ty);
+                let type_entry = self.dwarf.unit.get_mut(type_id);
+                type_entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(elem_type));
+
+                let subrange_id = self.dwarf.unit.add(type_id, gimli::DW_TAG_subrange_type);
+                let subrange_entry = self.dwarf.unit.get_mut(subrange_id);
+                subrange_entry.set(
+                    gimli::DW_AT_count,
+                    AttributeValue::Udata(len.eval_usize(self.tcx, ParamEnv::reveal_all())),
+                );
+
+                type_id
+            }
             _ => new_entry(&mut self.dwarf, gimli::DW_TAG_structure_type),
         };
 
@@ -198,10 +282,145 @@ fn dwarf_ty(&mut self, ty: Ty<'tcx>) -> UnitEntryId {
         type_id
     }
 
-    fn define_local(&mut self, scope: UnitEntryId, name: String, ty: Ty<'tcx>) -> UnitEntryId {
+    /// Adds the fields of `variant`, named like the LLVM backend does: tuple-like fields are
+    /// called `__0`, `__1` and so on, which gdb and lldb print as tuple structs.
+    fn add_fields(
+        &mut self,
+        type_id: UnitEntryId,
+        layout: TyAndLayout<'tcx>,
+        variant: &ty::VariantDef,
+    ) {
+        for (field_idx, field_def) in variant.fields.iter().enumerate() {
+            if variant.ctor_kind == CtorKind::Fn {
+                self.add_field(type_id, layout, field_idx, &format!("__{}", field_idx));
+            } else {
+                self.add_field(type_id, layout, field_idx, field_def.name.as_str());
+            }
+        }
+    }
+
+    /// Describes the variants of an enum with a `DW_TAG_variant_part` like the LLVM backend does
+    /// on non-MSVC targets:
+    ///
+    /// ```txt
+    /// DW_TAG_structure_type            (the enum)
+    ///   DW_TAG_variant_part
+    ///     DW_AT_discr                  (reference to the tag member)
+    ///     DW_TAG_member                (the tag, absent for enums with a single variant)
+    ///     DW_TAG_variant
+    ///       DW_AT_discr_value          (absent for the dataful variant of a niche layout)
+    ///       DW_TAG_member              (named like the variant, at offset 0)
+    ///   DW_TAG_structure_type          (the fields of the variant)
+    /// ```
+    fn add_variant_part(
+        &mut self,
+        type_id: UnitEntryId,
+        layout: TyAndLayout<'tcx>,
+        adt_def: ty::AdtDef<'tcx>,
+    ) {
+        let variant_part_id = self.dwarf.unit.add(type_id, gimli::DW_TAG_variant_part);
+
+        let variant_indices = match &layout.variants {
+            // Enums without variants have a single variant index too.
+            Variants::Single { index } => {
+                adt_def.variants().indices().filter(|idx| idx == index).collect::<Vec<_>>()
+            }
+            Variants::Multiple { tag, tag_field, variants, .. } => {
+                // The tag is described as an unsigned integer, like the discriminant values.
+                let tag_ty = match tag.size(&self.tcx).bytes() {
+                    1 => self.tcx.types.u8,
+                    2 => self.tcx.types.u16,
+                    4 => self.tcx.types.u32,
+                    8 => self.tcx.types.u64,
+                    _ => self.tcx.types.u128,
+                };
+                let tag_type = self.dwarf_ty(tag_ty);
+
+                let tag_id = self.dwarf.unit.add(variant_part_id, gimli::DW_TAG_member);
+                let tag_entry = self.dwarf.unit.get_mut(tag_id);
+                tag_entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(tag_type));
+                tag_entry.set(
+                    gimli::DW_AT_data_member_location,
+                    AttributeValue::Udata(layout.fields.offset(*tag_field).bytes()),
+                );
+                tag_entry.set(gimli::DW_AT_artificial, AttributeValue::Flag(true));
+
+                let variant_part_entry = self.dwarf.unit.get_mut(variant_part_id);
+                variant_part_entry.set(gimli::DW_AT_discr, AttributeValue::UnitRef(tag_id));
+
+                variants.indices().collect()
+            }
+        };
+
+        for variant_idx in variant_indices {
+            let variant_def = adt_def.variant(variant_idx);
+            let variant_layout = layout.for_variant(
+                &layout::LayoutCx { tcx: self.tcx, param_env: ParamEnv::reveal_all() },
+                variant_idx,
+            );
+
+            let struct_id = self.dwarf.unit.add(type_id, gimli::DW_TAG_structure_type);
+            let struct_entry = self.dwarf.unit.get_mut(struct_id);
+            struct_entry.set(
+                gimli::DW_AT_name,
+                AttributeValue::String(variant_def.name.as_str().as_bytes().to_vec()),
+            );
+            struct_entry.set(gimli::DW_AT_byte_size, AttributeValue::Udata(layout.size.bytes()));
+            self.add_fields(struct_id, variant_layout, variant_def);
+
+            let variant_id = self.dwarf.unit.add(variant_part_id, gimli::DW_TAG_variant);
+            if let Some(discr_value) = discriminant_value(self.tcx, layout, variant_idx) {
+                let variant_entry = self.dwarf.unit.get_mut(variant_id);
+                variant_entry.set(gimli::DW_AT_discr_value, AttributeValue::Udata(discr_value));
+            }
+
+            let member_id = self.dwarf.unit.add(variant_id, gimli::DW_TAG_member);
+            let member_entry = self.dwarf.unit.get_mut(member_id);
+            member_entry.set(
+                gimli::DW_AT_name,
+                AttributeValue::String(variant_def.name.as_str().as_bytes().to_vec()),
+            );
+            member_entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(struct_id));
+            member_entry.set(gimli::DW_AT_data_member_location, AttributeValue::Udata(0));
+        }
+    }
+
+    fn add_field(
+        &mut self,
+        type_id: UnitEntryId,
+        layout: TyAndLayout<'tcx>,
+        field_idx: usize,
+        name: &str,
+    ) {
+        let field_layout = layout.field(
+            &layout::LayoutCx { tcx: self.tcx, param_env: ParamEnv::reveal_all() },
+            field_idx,
+        );
+        self.add_member(type_id, name, field_layout.ty, layout.fields.offset(field_idx));
+    }
+
+    fn add_member(&mut self, type_id: UnitEntryId, name: &str, ty: Ty<'tcx>, offset: Size) {
+        let member_type = self.dwarf_ty(ty);
+
+        let member_id = self.dwarf.unit.add(type_id, gimli::DW_TAG_member);
+        let member_entry = self.dwarf.unit.get_mut(member_id);
+
+        member_entry.set(gimli::DW_AT_name, AttributeValue::String(name.as_bytes().to_vec()));
+        member_entry.set(gimli::DW_AT_data_member_location, AttributeValue::Udata(offset.bytes()));
+        member_entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(member_type));
+    }
+
+    fn define_local(
+        &mut self,
+        scope: UnitEntryId,
+        name: String,
+        ty: Ty<'tcx>,
+        is_arg: bool,
+    ) -> UnitEntryId {
         let dw_ty = self.dwarf_ty(ty);
 
-        let var_id = self.dwarf.unit.add(scope, gimli::DW_TAG_variable);
+        let tag = if is_arg { gimli::DW_TAG_formal_parameter } else { gimli::DW_TAG_variable };
+        let var_id = self.dwarf.unit.add(scope, tag);
         let var_entry = self.dwarf.unit.get_mut(var_id);
 
         var_entry.set(gimli::DW_AT_name, AttributeValue::String(name.into_bytes()));
@@ -249,45 +468,57 @@ pub(crate) fn define_function(
         // Using Udata for DW_AT_high_pc requires at least DWARF4
         func_entry.set(gimli::DW_AT_high_pc, AttributeValue::Udata(u64::from(end)));
 
-        // FIXME make it more reliable and implement scopes before re-enabling this.
-        if false {
-            let value_labels_ranges = std::collections::HashMap::new(); // FIXME
-
-            for (local, _local_decl) in mir.local_decls.iter_enumerated() {
-                let ty = self.tcx.subst_and_normalize_erasing_regions(
-                    instance.substs,
-                    ty::ParamEnv::reveal_all(),
-                    mir.local_decls[local].ty,
-                );
-                let var_id = self.define_local(entry_id, format!("{:?}", local), ty);
-
-                let location = place_location(
-                    self,
-                    isa,
-                    symbol,
-                    &local_map,
-                    &value_labels_ranges,
-                    Place { local, projection: ty::List::empty() },
-                );
+        let stack_pointer = match self.stack_pointer {
+            Some(stack_pointer) => stack_pointer,
+            None => return,
+        };
 
-                let var_entry = self.dwarf.unit.get_mut(var_id);
-                var_entry.set(gimli::DW_AT_location, location);
-            }
+        let compile_result = context.mach_compile_result.as_ref().unwrap();
+
+        // FIXME add variables to the scope they are declared in instead of the function
+        for var_debug_info in &mir.var_debug_info {
+            let place = match var_debug_info.value {
+                VarDebugInfoContents::Place(place) if place.projection.is_empty() => place,
+                // FIXME support projections and constants
+                _ => continue,
+            };
+
+            let ty = self.tcx.subst_and_normalize_erasing_regions(
+                instance.substs,
+                ty::ParamEnv::reveal_all(),
+                mir.local_decls[place.local].ty,
+            );
+            let is_arg = (1..=mir.arg_count).contains(&place.local.as_usize());
+            let var_id = self.define_local(entry_id, var_debug_info.name.to_string(), ty, is_arg);
+
+            let location = place_location(
+                self,
+                isa,
+                stack_pointer,
+                symbol,
+                &local_map,
+                &compile_result.value_labels_ranges,
+                &compile_result.stackslot_offsets,
+                place,
+            );
+
+            let var_entry = self.dwarf.unit.get_mut(var_id);
+            var_entry.set(gimli::DW_AT_location, location);
         }
-
-        // FIXME create locals for all entries in mir.var_debug_info
     }
 }
 
 fn place_location<'tcx>(
     debug_context: &mut DebugContext<'tcx>,
     isa: &dyn TargetIsa,
+    stack_pointer: Register,
     symbol: usize,
     local_map: &IndexVec<mir::Local, CPlace<'tcx>>,
     #[allow(rustc::default_hash_types)] value_labels_ranges: &std::collections::HashMap<
         ValueLabel,
         Vec<ValueLocRange>,
     >,
+    stack_slot_offsets: &PrimaryMap<StackSlot, u32>,
     place: Place<'tcx>,
 ) -> AttributeValue {
     assert!(place.projection.is_empty()); // FIXME implement them
@@ -305,7 +536,8 @@ fn place_location<'tcx>(
                                 addend: i64::from(value_loc_range.start),
                             },
                             end: Address::Symbol { symbol, addend: i64::from(value_loc_range.end) },
-                            data: translate_loc(isa, value_loc_range.loc).unwrap(),
+                            data: translate_loc(isa, stack_pointer, value_loc_range.loc)
+                                .unwrap(),
                         })
                         .collect(),
                 );
@@ -328,30 +560,87 @@ fn place_location<'tcx>(
 
             AttributeValue::Exprloc(Expression::new())
         }
-        CPlaceInner::Addr(_, _) => {
-            // FIXME implement this (used by arguments and returns)
+        CPlaceInner::Addr(ptr, None) => match ptr.debug_base_and_offset() {
+            (PointerBase::Stack(stack_slot), offset) => {
+                // Stack slot offsets are relative to the stack pointer after the prologue.
+                let offset: i64 = offset.into();
+                let mut expr = Expression::new();
+                expr.op_breg(stack_pointer, i64::from(stack_slot_offsets[stack_slot]) + offset);
+                AttributeValue::Exprloc(expr)
+            }
+            (PointerBase::Addr(_) | PointerBase::Dangling(_), _) => {
+                // FIXME implement this (used by arguments and returns passed by reference)
 
-            AttributeValue::Exprloc(Expression::new())
+                AttributeValue::Exprloc(Expression::new())
+            }
+        },
+        CPlaceInner::Addr(_, Some(_)) => {
+            // Unsized locals can't have debuginfo
 
-            // For PointerBase::Stack:
-            //AttributeValue::Exprloc(translate_loc(ValueLoc::Stack(*stack_slot)).unwrap())
+            AttributeValue::Exprloc(Expression::new())
         }
     }
 }
 
 // Adapted from https://github.com/CraneStation/wasmtime/blob/5a1845b4caf7a5dba8eda1fef05213a532ed4259/crates/debug/src/transform/expression.rs#L59-L137
-fn translate_loc(isa: &dyn TargetIsa, loc: LabelValueLoc) -> Option<Expression> {
+fn translate_loc(
+    isa: &dyn TargetIsa,
+    stack_pointer: Register,
+    loc: LabelValueLoc,
+) -> Option<Expression> {
     match loc {
         LabelValueLoc::Reg(reg) => {
             let machine_reg = isa.map_regalloc_reg_to_dwarf(reg).unwrap();
             let mut expr = Expression::new();
-            expr.op_reg(gimli::Register(machine_reg));
+            expr.op_reg(Register(machine_reg));
             Some(expr)
         }
         LabelValueLoc::SPOffset(offset) => {
             let mut expr = Expression::new();
-            expr.op_breg(X86_64::RSP, offset);
+            expr.op_breg(stack_pointer, offset);
             Some(expr)
         }
     }
 }
+
+/// Returns the DWARF register number of the stack pointer of `arch`.
+fn stack_pointer_register(arch: &str) -> Option<Register> {
+    match arch {
+        "x86_64" => Some(X86_64::RSP),
+        "aarch64" => Some(AArch64::SP),
+        "riscv64" => Some(RiscV::SP),
+        // gimli has no register names for s390x. The stack pointer is `%r15`.
+        "s390x" => Some(Register(15)),
+        _ => None,
+    }
+}
+
+/// Returns the value of the tag of `variant_idx`, like `compute_discriminant_value` of the LLVM
+/// backend. The dataful variant of a niche layout has no value of its own.
+fn discriminant_value<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    layout: TyAndLayout<'tcx>,
+    variant_idx: VariantIdx,
+) -> Option<u64> {
+    let value = match &layout.variants {
+        Variants::Single { .. } => return None,
+        Variants::Multiple { tag, tag_encoding: TagEncoding::Direct, .. } => {
+            tag.size(&tcx).truncate(layout.ty.discriminant_for_variant(tcx, variant_idx)?.val)
+        }
+        Variants::Multiple {
+            tag,
+            tag_encoding: TagEncoding::Niche { dataful_variant, niche_variants, niche_start },
+            ..
+        } => {
+            if variant_idx == *dataful_variant {
+                return None;
+            }
+            let value = u128::from(variant_idx.as_u32())
+                .wrapping_sub(u128::from(niche_variants.start().as_u32()))
+                .wrapping_add(*niche_start);
+            tag.size(&tcx).truncate(value)
+        }
+    };
+    // FIXME emit 128-bit values as a block
+    u64::try_from(value).ok()
+}
@@ -165,6 +165,16 @@ fn init(&self, sess: &Session) {
             Lto::No | Lto::ThinLocal => {}
             Lto::Thin | Lto::Fat => sess.warn("LTO is not supported. You may get a linker error."),
         }
+
+        // Cranelift can't yet express the exceptional edge of a call, so no landing pads are
+        // generated. `.eh_frame` is still emitted, so panics unwind through functions compiled by
+        // cg_clif, but without running drops and without being caught by `catch_unwind`.
+        if sess.panic_strategy() == rustc_target::spec::PanicStrategy::Unwind {
+            sess.warn(
+                "unwinding is not yet supported: values are not dropped during unwinding and \
+                 panics can't be caught. Consider using -Cpanic=abort.",
+            );
+        }
     }
 
     fn target_features(&self, _sess: &Session) -> Vec<rustc_span::Symbol> {
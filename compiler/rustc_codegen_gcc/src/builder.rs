@@ -792,6 +792,15 @@ fn typeid_metadata(&mut self, _typeid: String) -> RValue<'gcc> {
         self.context.new_rvalue_from_int(self.int_type, 0)
     }
 
+    fn kcfi_type_metadata(&mut self, _function: RValue<'gcc>, _typeid: u32) {
+        // Unsupported.
+    }
+
+    fn add_kcfi_check(&mut self, call: RValue<'gcc>, _typeid: u32) -> RValue<'gcc> {
+        // Unsupported.
+        call
+    }
+
 
     fn store(&mut self, val: RValue<'gcc>, ptr: RValue<'gcc>, align: Align) -> RValue<'gcc> {
         self.store_with_flags(val, ptr, align, MemFlags::empty())
@@ -52,6 +52,9 @@ pub fn gcc_neg(&self, a: RValue<'gcc>) -> RValue<'gcc> {
             self.cx.context.new_unary_op(None, UnaryOp::Minus, a.get_type(), a)
         }
         else {
+            // NOTE: like for the other compiler-builtins functions called in this file, the array
+            // representing the non-native integer is passed where __negti2 expects a 128-bit
+            // integer: both are passed the same way, in two 64-bit registers.
             let param_a = self.context.new_parameter(None, a_type, "a");
             let func = self.context.new_function(None, FunctionType::Extern, a_type, &[param_a], "__negti2", false);
             self.context.new_call(None, func, &[a])
@@ -967,11 +967,8 @@ fn rotate_right(&mut self, value: RValue<'gcc>, shift: RValue<'gcc>, width: u64)
     }
 
     fn saturating_add(&mut self, lhs: RValue<'gcc>, rhs: RValue<'gcc>, signed: bool, width: u64) -> RValue<'gcc> {
-        let func = self.current_func.borrow().expect("func");
-
         if signed {
             // Algorithm from: https://stackoverflow.com/a/56531252/389119
-            let after_block = func.new_block("after");
             let func_name =
                 match width {
                     8 => "__builtin_add_overflow",
@@ -981,37 +978,15 @@ fn saturating_add(&mut self, lhs: RValue<'gcc>, rhs: RValue<'gcc>, signed: bool,
                     128 => "__builtin_add_overflow",
                     _ => unreachable!(),
                 };
-            let overflow_func = self.context.get_builtin_function(func_name);
-            let result_type = lhs.get_type();
-            let res = func.new_local(None, result_type, "saturating_sum");
-            let overflow = self.overflow_call(overflow_func, &[lhs, rhs, res.get_address(None)], None);
-
-            let then_block = func.new_block("then");
-
-            let unsigned_type = self.context.new_int_type(width as i32 / 8, false);
-            let shifted = self.context.new_cast(None, lhs, unsigned_type) >> self.context.new_rvalue_from_int(unsigned_type, width as i32 - 1);
-            let uint_max = self.context.new_unary_op(None, UnaryOp::BitwiseNegate, unsigned_type,
-                self.context.new_rvalue_from_int(unsigned_type, 0)
-            );
-            let int_max = uint_max >> self.context.new_rvalue_one(unsigned_type);
-            then_block.add_assignment(None, res, self.context.new_cast(None, shifted + int_max, result_type));
-            then_block.end_with_jump(None, after_block);
-
-            self.llbb().end_with_conditional(None, overflow, then_block, after_block);
-
-            // NOTE: since jumps were added in a place rustc does not
-            // expect, the current block in the state need to be updated.
-            self.switch_to_block(after_block);
-
-            res.to_rvalue()
+            self.signed_saturating_operation(lhs, rhs, width, func_name, "__rust_i128_addo", "saturating_sum")
         }
         else {
             // Algorithm from: http://locklessinc.com/articles/sat_arithmetic/
-            let res = lhs + rhs;
+            let res = self.gcc_add(lhs, rhs);
             let res_type = res.get_type();
-            let cond = self.context.new_comparison(None, ComparisonOp::LessThan, res, lhs);
-            let value = self.context.new_unary_op(None, UnaryOp::Minus, res_type, self.context.new_cast(None, cond, res_type));
-            res | value
+            let cond = self.gcc_icmp(IntPredicate::IntULT, res, lhs);
+            let value = self.gcc_neg(self.gcc_int_cast(cond, res_type));
+            self.gcc_or(res, value)
         }
     }
 
@@ -1019,69 +994,71 @@ fn saturating_add(&mut self, lhs: RValue<'gcc>, rhs: RValue<'gcc>, signed: bool,
     fn saturating_sub(&mut self, lhs: RValue<'gcc>, rhs: RValue<'gcc>, signed: bool, width: u64) -> RValue<'gcc> {
         if signed {
             // Also based on algorithm from: https://stackoverflow.com/a/56531252/389119
-            let result_type = lhs.get_type();
-            let func = self.current_func.borrow().expect("func");
-            let res = func.new_local(None, result_type, "saturating_diff");
-            let supports_native_type = self.is_native_int_type(result_type);
-            let overflow =
-                if supports_native_type {
-                    let func_name =
-                        match width {
-                            8 => "__builtin_sub_overflow",
-                            16 => "__builtin_sub_overflow",
-                            32 => "__builtin_ssub_overflow",
-                            64 => "__builtin_ssubll_overflow",
-                            128 => "__builtin_sub_overflow",
-                            _ => unreachable!(),
-                        };
-                    let overflow_func = self.context.get_builtin_function(func_name);
-                    self.overflow_call(overflow_func, &[lhs, rhs, res.get_address(None)], None)
-                }
-                else {
-                    let func_name =
-                        match width {
-                            128 => "__rust_i128_subo",
-                            _ => unreachable!(),
-                        };
-                    let param_a = self.context.new_parameter(None, result_type, "a");
-                    let param_b = self.context.new_parameter(None, result_type, "b");
-                    let result_field = self.context.new_field(None, result_type, "result");
-                    let overflow_field = self.context.new_field(None, self.bool_type, "overflow");
-                    let return_type = self.context.new_struct_type(None, "result_overflow", &[result_field, overflow_field]);
-                    let func = self.context.new_function(None, FunctionType::Extern, return_type.as_type(), &[param_a, param_b], func_name, false);
-                    let result = self.context.new_call(None, func, &[lhs, rhs]);
-                    let overflow = result.access_field(None, overflow_field);
-                    let int_result = result.access_field(None, result_field);
-                    self.llbb().add_assignment(None, res, int_result);
-                    overflow
+            let func_name =
+                match width {
+                    8 => "__builtin_sub_overflow",
+                    16 => "__builtin_sub_overflow",
+                    32 => "__builtin_ssub_overflow",
+                    64 => "__builtin_ssubll_overflow",
+                    128 => "__builtin_sub_overflow",
+                    _ => unreachable!(),
                 };
+            self.signed_saturating_operation(lhs, rhs, width, func_name, "__rust_i128_subo", "saturating_diff")
+        }
+        else {
+            let res = self.gcc_sub(lhs, rhs);
+            let comparison = self.gcc_icmp(IntPredicate::IntULE, res, lhs);
+            let comparison = self.gcc_int_cast(comparison, lhs.get_type());
+            let unary_op = self.gcc_neg(comparison);
+            self.gcc_and(res, unary_op)
+        }
+    }
+
+    /// Computes `lhs op rhs` with the overflow builtin `builtin_name`, or with the compiler-builtins
+    /// function `non_native_func_name` when the integers are not supported natively, and saturates
+    /// the result to the minimum or maximum value, depending on the sign of `lhs`, on overflow.
+    fn signed_saturating_operation(&mut self, lhs: RValue<'gcc>, rhs: RValue<'gcc>, width: u64, builtin_name: &str, non_native_func_name: &str, result_name: &str) -> RValue<'gcc> {
+        let result_type = lhs.get_type();
+        let func = self.current_func.borrow().expect("func");
+        let res = func.new_local(None, result_type, result_name);
+        let overflow =
+            if self.is_native_int_type(result_type) {
+                let overflow_func = self.context.get_builtin_function(builtin_name);
+                self.overflow_call(overflow_func, &[lhs, rhs, res.get_address(None)], None)
+            }
+            else {
+                assert_eq!(width, 128);
+                let param_a = self.context.new_parameter(None, result_type, "a");
+                let param_b = self.context.new_parameter(None, result_type, "b");
+                let result_field = self.context.new_field(None, result_type, "result");
+                let overflow_field = self.context.new_field(None, self.bool_type, "overflow");
+                let return_type = self.context.new_struct_type(None, "result_overflow", &[result_field, overflow_field]);
+                let func = self.context.new_function(None, FunctionType::Extern, return_type.as_type(), &[param_a, param_b], non_native_func_name, false);
+                let result = self.context.new_call(None, func, &[lhs, rhs]);
+                let overflow = result.access_field(None, overflow_field);
+                let int_result = result.access_field(None, result_field);
+                self.llbb().add_assignment(None, res, int_result);
+                overflow
+            };
 
-            let then_block = func.new_block("then");
-            let after_block = func.new_block("after");
+        let then_block = func.new_block("then");
+        let after_block = func.new_block("after");
 
-            // NOTE: convert the type to unsigned to have an unsigned shift.
-            let unsigned_type = result_type.to_unsigned(&self.cx);
-            let shifted = self.gcc_lshr(self.gcc_int_cast(lhs, unsigned_type), self.gcc_int(unsigned_type, width as i64 - 1));
-            let uint_max = self.gcc_not(self.gcc_int(unsigned_type, 0));
-            let int_max = self.gcc_lshr(uint_max, self.gcc_int(unsigned_type, 1));
-            then_block.add_assignment(None, res, self.gcc_int_cast(self.gcc_add(shifted, int_max), result_type));
-            then_block.end_with_jump(None, after_block);
+        // NOTE: convert the type to unsigned to have an unsigned shift.
+        let unsigned_type = result_type.to_unsigned(&self.cx);
+        let shifted = self.gcc_lshr(self.gcc_int_cast(lhs, unsigned_type), self.gcc_int(unsigned_type, width as i64 - 1));
+        let uint_max = self.gcc_not(self.gcc_int(unsigned_type, 0));
+        let int_max = self.gcc_lshr(uint_max, self.gcc_int(unsigned_type, 1));
+        then_block.add_assignment(None, res, self.gcc_int_cast(self.gcc_add(shifted, int_max), result_type));
+        then_block.end_with_jump(None, after_block);
 
-            self.llbb().end_with_conditional(None, overflow, then_block, after_block);
+        self.llbb().end_with_conditional(None, overflow, then_block, after_block);
 
-            // NOTE: since jumps were added in a place rustc does not
-            // expect, the current block in the state need to be updated.
-            self.switch_to_block(after_block);
+        // NOTE: since jumps were added in a place rustc does not
+        // expect, the current block in the state need to be updated.
+        self.switch_to_block(after_block);
 
-            res.to_rvalue()
-        }
-        else {
-            let res = lhs - rhs;
-            let comparison = self.context.new_comparison(None, ComparisonOp::LessThanEquals, res, lhs);
-            let comparison = self.context.new_cast(None, comparison, lhs.get_type());
-            let unary_op = self.context.new_unary_op(None, UnaryOp::Minus, comparison.get_type(), comparison);
-            self.and(res, unary_op)
-        }
+        res.to_rvalue()
     }
 }
 
@@ -124,6 +124,14 @@ function test_libcore() {
     popd
 }
 
+function test_liballoc() {
+    pushd build_sysroot/sysroot_src/library/alloc/tests
+    echo "[TEST] liballoc"
+    rm -r ./target || true
+    ../../../../../cargo.sh test
+    popd
+}
+
 # TODO(antoyo): uncomment when it works.
 #pushd regex
 #echo "[TEST] rust-lang/regex example shootout-regex-dna"
@@ -231,6 +239,10 @@ case $1 in
         test_libcore
         ;;
 
+    "--test-liballoc")
+        test_liballoc
+        ;;
+
     "--clean-ui-tests")
         clean_ui_tests
         ;;
@@ -249,6 +261,7 @@ case $1 in
         build_sysroot
         std_tests
         test_libcore
+        test_liballoc
         test_rustc
         ;;
 esac
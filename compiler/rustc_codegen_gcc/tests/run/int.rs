@@ -149,5 +149,49 @@ fn main(argc: isize, _argv: *const *const u8) -> isize {
     assert_eq!(var4 / (argc + 1) as u128, 61618259944854013736810163053136969792);
     assert_eq!(var4 / (argc + 2) as u128, 41078839963236009157873442035424646528);
 
+    // Negation.
+    assert_eq!(-(argc as i128), -1);
+    assert_eq!(-(var6 * argc as i128), -18446744073709551616);
+    assert_eq!(-(-(var6 + argc as i128)), 18446744073709551617);
+    assert_eq!((i128::MIN + argc as i128 - 1).wrapping_neg(), i128::MIN);
+
+    // Unsigned saturating addition.
+    assert_eq!((u8::MAX - 1).saturating_add(argc as u8), u8::MAX);
+    assert_eq!(u8::MAX.saturating_add(argc as u8), u8::MAX);
+    assert_eq!(var2.saturating_add(argc as u128), 10475372733397991553);
+    assert_eq!((u64::MAX as u128).saturating_add(argc as u128), 18446744073709551616);
+    assert_eq!(var3.saturating_add(var4 + argc as u128), 316473039779416054947240652212547879169);
+    assert_eq!(var3.saturating_add(var5 + argc as u128), u128::MAX);
+    assert_eq!(u128::MAX.saturating_add(argc as u128), u128::MAX);
+
+    // Unsigned saturating subtraction.
+    assert_eq!((argc as u8 - 1).saturating_sub(argc as u8), 0);
+    assert_eq!(var2.saturating_sub(var + argc as u128), 10475372733263773695);
+    assert_eq!((var6 as u128).saturating_sub(argc as u128), 18446744073709551615);
+    assert_eq!(var.saturating_sub(var2 + argc as u128), 0);
+    assert_eq!((argc as u128).saturating_sub(argc as u128 + 1), 0);
+
+    // Signed saturating addition.
+    assert_eq!(100_i8.saturating_add(argc as i8), 101);
+    assert_eq!(i8::MAX.saturating_add(argc as i8), i8::MAX);
+    assert_eq!(i16::MIN.saturating_add(-(argc as i16)), i16::MIN);
+    assert_eq!(i32::MAX.saturating_add(argc as i32), i32::MAX);
+    assert_eq!(i64::MIN.saturating_add(-(argc as i64)), i64::MIN);
+    assert_eq!(var6.saturating_add(argc as i128), 18446744073709551617);
+    assert_eq!((-var6).saturating_add(-(argc as i128)), -18446744073709551617);
+    assert_eq!(i128::MAX.saturating_add(argc as i128), i128::MAX);
+    assert_eq!(i128::MIN.saturating_add(-(argc as i128)), i128::MIN);
+
+    // Signed saturating subtraction.
+    assert_eq!((-100_i8).saturating_sub(argc as i8), -101);
+    assert_eq!(i8::MIN.saturating_sub(argc as i8), i8::MIN);
+    assert_eq!(i16::MAX.saturating_sub(-(argc as i16)), i16::MAX);
+    assert_eq!(i32::MIN.saturating_sub(argc as i32), i32::MIN);
+    assert_eq!(i64::MAX.saturating_sub(-(argc as i64)), i64::MAX);
+    assert_eq!(var6.saturating_sub(argc as i128), 18446744073709551615);
+    assert_eq!((-var6).saturating_sub(argc as i128), -18446744073709551617);
+    assert_eq!(i128::MIN.saturating_sub(argc as i128), i128::MIN);
+    assert_eq!(i128::MAX.saturating_sub(-(argc as i128)), i128::MAX);
+
     0
 }
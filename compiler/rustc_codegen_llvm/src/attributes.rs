@@ -79,6 +79,9 @@ pub fn sanitize_attrs<'ll>(
 
         attrs.push(llvm::AttributeKind::SanitizeMemTag.create_attr(cx.llcx));
     }
+    if enabled.contains(SanitizerSet::SHADOWCALLSTACK) {
+        attrs.push(llvm::AttributeKind::ShadowCallStack.create_attr(cx.llcx));
+    }
     attrs
 }
 
@@ -107,9 +110,17 @@ pub fn frame_pointer_type_attr<'ll>(cx: &CodegenCx<'ll, '_>) -> Option<&'ll Attr
     Some(llvm::CreateAttrStringValue(cx.llcx, "frame-pointer", attr_value))
 }
 
-/// Tell LLVM what instrument function to insert.
+/// Tell LLVM what instrument functions to insert.
 #[inline]
-fn instrument_function_attr<'ll>(cx: &CodegenCx<'ll, '_>) -> Option<&'ll Attribute> {
+fn instrument_function_attr<'ll>(
+    cx: &CodegenCx<'ll, '_>,
+    flags: CodegenFnAttrFlags,
+) -> SmallVec<[&'ll Attribute; 2]> {
+    let mut attrs = SmallVec::new();
+    // Naked functions cannot have a prologue or an epilogue to put the calls in.
+    if flags.intersects(CodegenFnAttrFlags::NO_INSTRUMENT_FUNCTION | CodegenFnAttrFlags::NAKED) {
+        return attrs;
+    }
     if cx.sess().instrument_mcount() {
         // Similar to `clang -pg` behavior. Handled by the
         // `post-inline-ee-instrument` LLVM pass.
@@ -118,14 +129,27 @@ fn instrument_function_attr<'ll>(cx: &CodegenCx<'ll, '_>) -> Option<&'ll Attribu
         // See test/CodeGen/mcount.c in clang.
         let mcount_name = cx.sess().target.mcount.as_ref();
 
-        Some(llvm::CreateAttrStringValue(
+        attrs.push(llvm::CreateAttrStringValue(
             cx.llcx,
             "instrument-function-entry-inlined",
             &mcount_name,
-        ))
-    } else {
-        None
+        ));
+    } else if cx.sess().instrument_functions() {
+        // Similar to `clang -finstrument-functions` behavior. Handled by the `ee-instrument`
+        // LLVM pass, which runs before inlining and passes the address of the function and of
+        // its call site to these hooks.
+        attrs.push(llvm::CreateAttrStringValue(
+            cx.llcx,
+            "instrument-function-entry",
+            "__cyg_profile_func_enter",
+        ));
+        attrs.push(llvm::CreateAttrStringValue(
+            cx.llcx,
+            "instrument-function-exit",
+            "__cyg_profile_func_exit",
+        ));
     }
+    attrs
 }
 
 fn probestack_attr<'ll>(cx: &CodegenCx<'ll, '_>) -> Option<&'ll Attribute> {
@@ -245,7 +269,12 @@ pub fn from_fn_attrs<'ll, 'tcx>(
         OptimizeAttr::Speed => {}
     }
 
-    let inline = if codegen_fn_attrs.flags.contains(CodegenFnAttrFlags::NAKED) {
+    // Outlined cold paths share the attributes of the function they were outlined from, but
+    // inlining them back into it would defeat their purpose.
+    let is_cold_panic_shim = matches!(instance.def, ty::InstanceDef::ColdPanicShim(..));
+
+    let inline = if codegen_fn_attrs.flags.contains(CodegenFnAttrFlags::NAKED) || is_cold_panic_shim
+    {
         InlineAttr::Never
     } else if codegen_fn_attrs.inline == InlineAttr::None && instance.def.requires_inline(cx.tcx) {
         InlineAttr::Hint
@@ -280,11 +309,11 @@ pub fn from_fn_attrs<'ll, 'tcx>(
 
     // FIXME: none of these three functions interact with source level attributes.
     to_add.extend(frame_pointer_type_attr(cx));
-    to_add.extend(instrument_function_attr(cx));
+    to_add.extend(instrument_function_attr(cx, codegen_fn_attrs.flags));
     to_add.extend(probestack_attr(cx));
     to_add.extend(stackprotector_attr(cx));
 
-    if codegen_fn_attrs.flags.contains(CodegenFnAttrFlags::COLD) {
+    if codegen_fn_attrs.flags.contains(CodegenFnAttrFlags::COLD) || is_cold_panic_shim {
         to_add.push(AttributeKind::Cold.create_attr(cx.llcx));
     }
     if codegen_fn_attrs.flags.contains(CodegenFnAttrFlags::FFI_RETURNS_TWICE) {
@@ -12,8 +12,8 @@
 use crate::ModuleLlvm;
 use rustc_codegen_ssa::back::link::ensure_removed;
 use rustc_codegen_ssa::back::write::{
-    BitcodeSection, CodegenContext, EmitObj, ModuleConfig, TargetMachineFactoryConfig,
-    TargetMachineFactoryFn,
+    BitcodeSection, CodegenContext, EmitObj, FunctionProfile, ModuleConfig,
+    TargetMachineFactoryConfig, TargetMachineFactoryFn,
 };
 use rustc_codegen_ssa::traits::*;
 use rustc_codegen_ssa::{CompiledModule, ModuleCodegen};
@@ -380,7 +380,20 @@ fn report_inline_asm(
                 ));
             }
         }
-        llvm::diagnostic::PGO(diagnostic_ref) | llvm::diagnostic::Linker(diagnostic_ref) => {
+        llvm::diagnostic::PGO(diagnostic_ref) => {
+            let msg = llvm::build_string(|s| {
+                llvm::LLVMRustWriteDiagnosticInfoToString(diagnostic_ref, s)
+            })
+            .expect("non-UTF8 diagnostic");
+            // Stale profiles are reported once for all functions, after optimization.
+            match stale_profile_function(&msg) {
+                Some(function) => {
+                    cgcx.diag_emitter.stale_profile(function.to_string(), demangle(function))
+                }
+                None => diag_handler.warn(&msg),
+            }
+        }
+        llvm::diagnostic::Linker(diagnostic_ref) => {
             let msg = llvm::build_string(|s| {
                 llvm::LLVMRustWriteDiagnosticInfoToString(diagnostic_ref, s)
             })
@@ -398,6 +411,76 @@ fn report_inline_asm(
     }
 }
 
+/// Returns the function named in `msg` if it is the warning LLVM emits when the profile data of
+/// a function does not match its control flow, e.g.
+/// `foo.3a1fbbbh-cgu.0: function control flow change detected (hash mismatch) _ZN3foo3barE
+/// Hash = 1234`.
+fn stale_profile_function(msg: &str) -> Option<&str> {
+    let (_, rest) = msg.split_once("(hash mismatch) ")?;
+    let (function, _) = rest.rsplit_once(" Hash = ")?;
+    Some(function)
+}
+
+fn demangle(symbol: &str) -> String {
+    format!("{:#}", rustc_demangle::demangle(symbol))
+}
+
+unsafe extern "C" fn function_profile_callback(
+    functions: *mut c_void,
+    name: *const c_char,
+    name_len: size_t,
+    has_entry_count: bool,
+    entry_count: u64,
+    hot: bool,
+) {
+    let functions = &mut *(functions as *mut Vec<FunctionProfile>);
+    let symbol_name = String::from_utf8_lossy(slice::from_raw_parts(name as *const u8, name_len));
+    functions.push(FunctionProfile {
+        name: demangle(&symbol_name),
+        symbol_name: symbol_name.into_owned(),
+        entry_count: has_entry_count.then(|| entry_count),
+        hot,
+    });
+}
+
+/// Sends how the `-C profile-use` profile applied to the functions of `module` to the main
+/// thread, for `-Z pgo-report`.
+unsafe fn report_function_profiles(
+    cgcx: &CodegenContext<LlvmCodegenBackend>,
+    module: &ModuleCodegen<ModuleLlvm>,
+) {
+    let mut functions = Vec::<FunctionProfile>::new();
+    llvm::LLVMRustModuleFunctionProfiles(
+        module.module_llvm.llmod(),
+        function_profile_callback,
+        &mut functions as *mut _ as *mut c_void,
+    );
+    cgcx.diag_emitter.function_profiles(functions);
+}
+
+pub(crate) fn merge_profiles(
+    profiles: &[PathBuf],
+    output: &Path,
+) -> Result<Option<String>, String> {
+    let profiles: Vec<CString> = profiles.iter().map(|profile| path_to_c_string(profile)).collect();
+    let profiles: Vec<*const c_char> = profiles.iter().map(|profile| profile.as_ptr()).collect();
+    let output = path_to_c_string(output);
+    let mut result = llvm::LLVMRustResult::Failure;
+    let warning = llvm::build_string(|warning| unsafe {
+        result = llvm::LLVMRustMergeInstrProfiles(
+            profiles.as_ptr(),
+            profiles.len(),
+            output.as_ptr(),
+            warning,
+        );
+    })
+    .expect("non-UTF8 warning");
+    match result.into_result() {
+        Ok(()) => Ok(if warning.is_empty() { None } else { Some(warning) }),
+        Err(()) => Err(llvm::last_error().unwrap_or_else(|| "unknown error".to_string())),
+    }
+}
+
 fn get_pgo_gen_path(config: &ModuleConfig) -> Option<CString> {
     match config.pgo_gen {
         SwitchWithOptPath::Enabled(ref opt_dir_path) => {
@@ -493,6 +576,7 @@ pub(crate) unsafe fn optimize_with_new_llvm_pass_manager(
         pgo_use_path.as_ref().map_or(std::ptr::null(), |s| s.as_ptr()),
         config.instrument_coverage,
         config.instrument_gcov,
+        config.instrument_functions,
         pgo_sample_use_path.as_ref().map_or(std::ptr::null(), |s| s.as_ptr()),
         config.debug_info_for_profiling,
         llvm_selfprofiler,
@@ -540,14 +624,18 @@ pub(crate) unsafe fn optimize(
                 _ if cgcx.opts.cg.linker_plugin_lto.enabled() => llvm::OptStage::PreLinkThinLTO,
                 _ => llvm::OptStage::PreLinkNoLTO,
             };
-            return optimize_with_new_llvm_pass_manager(
+            optimize_with_new_llvm_pass_manager(
                 cgcx,
                 diag_handler,
                 module,
                 config,
                 opt_level,
                 opt_stage,
-            );
+            )?;
+            if config.pgo_report {
+                report_function_profiles(cgcx, module);
+            }
+            return Ok(());
         }
 
         if cgcx.prof.llvm_recording_enabled() {
@@ -573,6 +661,12 @@ pub(crate) unsafe fn optimize(
                 llvm::LLVMRustAddPass(fpm, find_pass("verify").unwrap());
             }
 
+            if config.instrument_functions {
+                // Like Clang, insert the `-Z instrument-functions` calls before inlining. The
+                // `post-inline-ee-instrument` pass in the code generator handles the rest.
+                llvm::LLVMRustAddPass(fpm, find_pass("ee-instrument").unwrap());
+            }
+
             let mut extra_passes = Vec::new();
             let mut have_name_anon_globals_pass = false;
 
@@ -684,6 +778,10 @@ pub(crate) unsafe fn optimize(
         // Deallocate managers that we're now done with
         llvm::LLVMDisposePassManager(fpm);
         llvm::LLVMDisposePassManager(mpm);
+
+        if config.pgo_report {
+            report_function_profiles(cgcx, module);
+        }
     }
     Ok(())
 }
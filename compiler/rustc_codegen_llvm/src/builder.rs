@@ -647,6 +647,31 @@ fn typeid_metadata(&mut self, typeid: String) -> Self::Value {
         }
     }
 
+    fn kcfi_type_metadata(&mut self, function: &'ll Value, typeid: u32) {
+        let kind = "kcfi_type";
+        let v = [self.const_u32(typeid)];
+        unsafe {
+            let kind_id = llvm::LLVMGetMDKindIDInContext(
+                self.cx.llcx,
+                kind.as_ptr().cast(),
+                kind.len() as c_uint,
+            );
+            llvm::LLVMGlobalSetMetadata(
+                function,
+                kind_id,
+                llvm::LLVMValueAsMetadata(llvm::LLVMMDNodeInContext(
+                    self.cx.llcx,
+                    v.as_ptr(),
+                    v.len() as c_uint,
+                )),
+            )
+        }
+    }
+
+    fn add_kcfi_check(&mut self, call: &'ll Value, typeid: u32) -> &'ll Value {
+        unsafe { llvm::LLVMRustAddKCFIOperandBundle(call, typeid) }
+    }
+
     fn store(&mut self, val: &'ll Value, ptr: &'ll Value, align: Align) -> &'ll Value {
         self.store_with_flags(val, ptr, align, MemFlags::empty())
     }
@@ -2,7 +2,8 @@
 
 use libc::c_uint;
 use rustc_codegen_ssa::debuginfo::{
-    type_names::compute_debuginfo_type_name, wants_c_like_enum_debuginfo,
+    closure_saved_names_of_captured_variables, type_names::compute_debuginfo_type_name,
+    wants_c_like_enum_debuginfo,
 };
 use rustc_middle::{
     bug,
@@ -20,7 +21,7 @@
     common::CodegenCx,
     debuginfo::{
         metadata::{
-            build_field_di_node, closure_saved_names_of_captured_variables,
+            build_field_di_node,
             enums::tag_base_type,
             file_metadata, generator_layout_and_saved_local_names, size_and_align_of,
             type_map::{self, UniqueTypeId},
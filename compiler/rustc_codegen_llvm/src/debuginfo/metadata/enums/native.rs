@@ -4,7 +4,6 @@
     common::CodegenCx,
     debuginfo::{
         metadata::{
-            closure_saved_names_of_captured_variables,
             enums::tag_base_type,
             file_metadata, generator_layout_and_saved_local_names, size_and_align_of, type_di_node,
             type_map::{self, Stub, StubInfo, UniqueTypeId},
@@ -20,7 +19,10 @@
 };
 use libc::c_uint;
 use rustc_codegen_ssa::{
-    debuginfo::{type_names::compute_debuginfo_type_name, wants_c_like_enum_debuginfo},
+    debuginfo::{
+        closure_saved_names_of_captured_variables, type_names::compute_debuginfo_type_name,
+        wants_c_like_enum_debuginfo,
+    },
     traits::ConstMethods,
 };
 use rustc_middle::{
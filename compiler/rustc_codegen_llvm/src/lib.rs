@@ -42,6 +42,7 @@
 
 use std::any::Any;
 use std::ffi::CStr;
+use std::path::{Path, PathBuf};
 
 mod back {
     pub mod archive;
@@ -167,6 +168,14 @@ fn spawn_named_thread<F, T>(
             f()
         })
     }
+
+    fn merge_profiles(
+        &self,
+        profiles: &[PathBuf],
+        output: &Path,
+    ) -> Result<Option<String>, String> {
+        back::write::merge_profiles(profiles, output)
+    }
 }
 
 impl WriteBackendMethods for LlvmCodegenBackend {
@@ -253,6 +262,11 @@ pub fn new() -> Box<dyn CodegenBackend> {
 impl CodegenBackend for LlvmCodegenBackend {
     fn init(&self, sess: &Session) {
         llvm_util::init(sess); // Make sure llvm is inited
+
+        // Older LLVM versions ignore the `kcfi` operand bundles and module flag.
+        if sess.is_sanitizer_kcfi_enabled() && llvm_util::get_version() < (16, 0, 0) {
+            sess.err("`-Zsanitizer=kcfi` requires LLVM 16 or later");
+        }
     }
 
     fn provide(&self, providers: &mut Providers) {
@@ -191,6 +191,7 @@ pub enum AttributeKind {
     StackProtect = 32,
     NoUndef = 33,
     SanitizeMemTag = 34,
+    ShadowCallStack = 35,
 }
 
 /// LLVMIntPredicate
@@ -796,9 +797,6 @@ impl CounterMappingRegion {
             }
         }
 
-        // This function might be used in the future; the LLVM API is still evolving, as is coverage
-        // support.
-        #[allow(dead_code)]
         crate fn branch_region(
             counter: coverage_map::Counter,
             false_counter: coverage_map::Counter,
@@ -991,6 +989,8 @@ pub fn from_generic(kind: rustc_session::config::DebugInfo) -> Self {
 pub type SelfProfileBeforePassCallback =
     unsafe extern "C" fn(*mut c_void, *const c_char, *const c_char);
 pub type SelfProfileAfterPassCallback = unsafe extern "C" fn(*mut c_void);
+pub type FunctionProfileCallback =
+    unsafe extern "C" fn(*mut c_void, *const c_char, size_t, bool, u64, bool);
 
 extern "C" {
     pub fn LLVMRustInstallFatalErrorHandler();
@@ -1924,6 +1924,20 @@ pub fn LLVMRustCoverageWriteMappingToBuffer(
     pub fn LLVMRustCoverageWriteMappingVarNameToString(Str: &RustString);
 
     pub fn LLVMRustCoverageMappingVersion() -> u32;
+
+    #[allow(improper_ctypes)]
+    pub fn LLVMRustMergeInstrProfiles(
+        Inputs: *const *const c_char,
+        InputsLen: size_t,
+        Output: *const c_char,
+        WarningOut: &RustString,
+    ) -> LLVMRustResult;
+    pub fn LLVMRustModuleFunctionProfiles(
+        M: &Module,
+        Callback: FunctionProfileCallback,
+        CallbackPayload: *mut c_void,
+    );
+
     pub fn LLVMRustDebugMetadataVersion() -> u32;
     pub fn LLVMRustVersionMajor() -> u32;
     pub fn LLVMRustVersionMinor() -> u32;
@@ -2342,6 +2356,7 @@ pub fn LLVMRustOptimizeWithNewPassManager<'a>(
         PGOUsePath: *const c_char,
         InstrumentCoverage: bool,
         InstrumentGCOV: bool,
+        InstrumentFunctions: bool,
         PGOSampleUsePath: *const c_char,
         DebugInfoForProfiling: bool,
         llvm_selfprofiler: *mut c_void,
@@ -2453,6 +2468,7 @@ pub fn LLVMRustBuildOperandBundleDef<'a>(
         NumInputs: c_uint,
     ) -> &'a mut OperandBundleDef<'a>;
     pub fn LLVMRustFreeOperandBundleDef<'a>(Bundle: &'a mut OperandBundleDef<'a>);
+    pub fn LLVMRustAddKCFIOperandBundle<'a>(Call: &'a Value, TypeId: u32) -> &'a Value;
 
     pub fn LLVMRustPositionBuilderAtStart<'a>(B: &Builder<'a>, BB: &'a BasicBlock);
 
@@ -86,6 +86,9 @@ fn llvm_arg_to_arg_name(full_arg: &str) -> &str {
         if sess.print_llvm_passes() {
             add("-debug-pass=Structure", false);
         }
+        if let Some(limit) = sess.opts.debugging_opts.llvm_opt_bisect_limit {
+            add(&format!("-opt-bisect-limit={}", limit), false);
+        }
         if sess.target.generate_arange_section
             && !sess.opts.debugging_opts.no_generate_arange_section
         {
@@ -0,0 +1,226 @@
+//! The size report written by `-Z emit-size-report`.
+//!
+//! The report lists every mono item of the crate with the size estimate the partitioner used for
+//! it and the size of the machine code (or data) that was actually emitted for it, read back from
+//! the symbol tables of the object files of the crate. Items are also grouped by the crate that
+//! defines them and by generic definition, so that the instantiations responsible for most of the
+//! size of a binary can be found without linking it.
+
+use crate::{CompiledModule, ModuleKind};
+
+use object::{Object, ObjectSection, ObjectSymbol, SymbolKind, SymbolSection};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_middle::mir::mono::{CodegenUnit, MonoItem};
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::TyCtxt;
+use rustc_session::Session;
+use rustc_span::def_id::LOCAL_CRATE;
+
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// A mono item of the crate, as it was placed by the partitioner.
+pub struct SizeReportItem {
+    symbol_name: String,
+    description: String,
+    /// The crate defining the item, which is not the crate instantiating it for generics.
+    krate: String,
+    /// The path of the generic definition this item is an instantiation of, if any.
+    generic: Option<String>,
+    size_estimate: usize,
+    /// The number of codegen units containing a copy of this item.
+    copies: usize,
+}
+
+pub fn collect_size_report_items<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    codegen_units: &[CodegenUnit<'tcx>],
+) -> Vec<SizeReportItem> {
+    let mut copies: FxHashMap<MonoItem<'tcx>, usize> = Default::default();
+    for cgu in codegen_units {
+        for &mono_item in cgu.items().keys() {
+            *copies.entry(mono_item).or_default() += 1;
+        }
+    }
+
+    copies
+        .into_iter()
+        .map(|(mono_item, copies)| {
+            let (krate, generic) = match mono_item {
+                MonoItem::Fn(instance) => {
+                    let generic = instance.substs.non_erasable_generics().next().is_some();
+                    let def_id = instance.def_id();
+                    let generic = generic.then(|| with_no_trimmed_paths!(tcx.def_path_str(def_id)));
+                    (def_id.krate, generic)
+                }
+                MonoItem::Static(def_id) => (def_id.krate, None),
+                MonoItem::GlobalAsm(_) => (LOCAL_CRATE, None),
+            };
+            SizeReportItem {
+                symbol_name: mono_item.symbol_name(tcx).name.to_string(),
+                description: with_no_trimmed_paths!(mono_item.to_string()),
+                krate: tcx.crate_name(krate).to_string(),
+                generic,
+                size_estimate: mono_item.size_estimate(tcx),
+                copies,
+            }
+        })
+        .collect()
+}
+
+/// Returns the total size of the defined symbols of an object file by name. Symbols that have no
+/// size, like all symbols of Mach-O and COFF object files, extend to the next symbol of their
+/// section.
+fn symbol_sizes(path: &Path) -> Result<FxHashMap<String, u64>, String> {
+    let data = fs::read(path).map_err(|err| err.to_string())?;
+    let file = object::File::parse(&*data).map_err(|err| err.to_string())?;
+
+    let mut symbols = Vec::new();
+    for symbol in file.symbols() {
+        if !symbol.is_definition() || !matches!(symbol.kind(), SymbolKind::Text | SymbolKind::Data)
+        {
+            continue;
+        }
+        if let (SymbolSection::Section(section), Ok(name)) = (symbol.section(), symbol.name()) {
+            symbols.push((section.0, symbol.address(), symbol.size(), name));
+        }
+    }
+    symbols.sort_by_key(|&(section, address, ..)| (section, address));
+
+    let mut sizes: FxHashMap<String, u64> = Default::default();
+    for (i, &(section, address, size, name)) in symbols.iter().enumerate() {
+        let size = if size != 0 {
+            size
+        } else {
+            let end = match symbols.get(i + 1) {
+                Some(&(next_section, next_address, ..)) if next_section == section => next_address,
+                _ => file
+                    .section_by_index(object::SectionIndex(section))
+                    .map_or(address, |section| section.address() + section.size()),
+            };
+            end.saturating_sub(address)
+        };
+        *sizes.entry(name.to_string()).or_default() += size;
+    }
+    Ok(sizes)
+}
+
+#[derive(Default)]
+struct Group {
+    actual_size: u64,
+    size_estimate: usize,
+    items: usize,
+}
+
+fn write_groups(report: &mut String, title: &str, count: &str, groups: FxHashMap<&str, Group>) {
+    let mut groups: Vec<_> = groups.into_iter().collect();
+    groups.sort_by(|(a_name, a), (b_name, b)| {
+        (b.actual_size, b.size_estimate, a_name).cmp(&(a.actual_size, a.size_estimate, b_name))
+    });
+    let _ = writeln!(report, "\n{}:", title);
+    let _ = writeln!(report, "{:>10} {:>10} {:>10}  name", "actual", "estimate", count);
+    for (name, group) in groups {
+        let _ = writeln!(
+            report,
+            "{:>10} {:>10} {:>10}  {}",
+            group.actual_size, group.size_estimate, group.items, name
+        );
+    }
+}
+
+/// Writes the size report of the crate next to its outputs, once all of its codegen units have
+/// been compiled to object files.
+pub fn write_size_report(
+    sess: &Session,
+    path: &Path,
+    crate_name: &str,
+    items: &[SizeReportItem],
+    modules: &[CompiledModule],
+) {
+    let mut actual_sizes: FxHashMap<String, u64> = Default::default();
+    let mut objects = 0;
+    for module in modules {
+        if module.kind != ModuleKind::Regular {
+            continue;
+        }
+        let Some(object) = &module.object else { continue };
+        match symbol_sizes(object) {
+            Ok(sizes) => {
+                objects += 1;
+                for (name, size) in sizes {
+                    *actual_sizes.entry(name).or_default() += size;
+                }
+            }
+            Err(err) => sess.warn(&format!(
+                "`-Z emit-size-report` could not read the symbols of `{}`: {}",
+                object.display(),
+                err
+            )),
+        }
+    }
+    if objects == 0 {
+        sess.warn("`-Z emit-size-report` found no object files, so actual sizes are not reported");
+    }
+
+    // Object files of some targets prefix symbol names with an underscore.
+    let actual_size = |item: &SizeReportItem| {
+        actual_sizes
+            .get(&item.symbol_name)
+            .or_else(|| actual_sizes.get(&format!("_{}", item.symbol_name)))
+            .copied()
+    };
+
+    let mut items: Vec<_> = items.iter().map(|item| (item, actual_size(item))).collect();
+    items.sort_by(|(a, a_size), (b, b_size)| {
+        (b_size, b.size_estimate, &a.description).cmp(&(a_size, a.size_estimate, &b.description))
+    });
+
+    let mut crates: FxHashMap<&str, Group> = Default::default();
+    let mut generics: FxHashMap<&str, Group> = Default::default();
+    let mut without_code = Group::default();
+    for &(item, actual_size) in &items {
+        let mut groups = vec![crates.entry(item.krate.as_str()).or_default()];
+        groups.extend(item.generic.as_deref().map(|generic| generics.entry(generic).or_default()));
+        if actual_size.is_none() {
+            groups.push(&mut without_code);
+        }
+        for group in groups {
+            group.actual_size += actual_size.unwrap_or(0);
+            group.size_estimate += item.size_estimate;
+            group.items += 1;
+        }
+    }
+
+    let mut report = String::new();
+    let _ = writeln!(report, "size report for crate `{}`", crate_name);
+    let _ = writeln!(
+        report,
+        "actual: bytes of machine code and data, summed over all copies of an item"
+    );
+    let _ = writeln!(report, "estimate: the size estimate used to partition codegen units");
+    let _ = writeln!(
+        report,
+        "{} of {} mono items (estimate {}) have no code, as they were inlined or optimized out",
+        without_code.items,
+        items.len(),
+        without_code.size_estimate
+    );
+    write_groups(&mut report, "by crate", "items", crates);
+    write_groups(&mut report, "by generic definition", "instances", generics);
+
+    let _ = writeln!(report, "\nby mono item:");
+    let _ = writeln!(report, "{:>10} {:>10} {:>10}  crate  item", "actual", "estimate", "copies");
+    for (item, actual_size) in items {
+        let actual_size = actual_size.map_or_else(|| "-".to_string(), |size| size.to_string());
+        let _ = writeln!(
+            report,
+            "{:>10} {:>10} {:>10}  {}  {}",
+            actual_size, item.size_estimate, item.copies, item.krate, item.description
+        );
+    }
+
+    if let Err(err) = fs::write(path, report) {
+        sess.err(&format!("failed to write size report to `{}`: {}", path.display(), err));
+    }
+}
@@ -1,5 +1,6 @@
 use super::link::{self, ensure_removed};
 use super::lto::{self, SerializedModule};
+use super::size_report::{self, SizeReportItem};
 use super::symbol_export::symbol_name_for_instance_in_crate;
 
 use crate::{
@@ -8,7 +9,7 @@
 
 use crate::traits::*;
 use jobserver::{Acquired, Client};
-use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::memmap::Mmap;
 use rustc_data_structures::profiling::SelfProfilerRef;
 use rustc_data_structures::profiling::TimingGuard;
@@ -30,11 +31,12 @@
 use rustc_session::config::{Passes, SwitchWithOptPath};
 use rustc_session::Session;
 use rustc_span::source_map::SourceMap;
-use rustc_span::symbol::sym;
+use rustc_span::symbol::{sym, Symbol};
 use rustc_span::{BytePos, FileName, InnerSpan, Pos, Span};
 use rustc_target::spec::{MergeFunctions, SanitizerSet};
 
 use std::any::Any;
+use std::cell::RefCell;
 use std::fs;
 use std::io;
 use std::mem;
@@ -71,6 +73,7 @@ pub enum BitcodeSection {
 }
 
 /// Module-specific configuration for `optimize_and_codegen`.
+#[derive(Clone)]
 pub struct ModuleConfig {
     /// Names of additional optimization passes to run.
     pub passes: Vec<String>,
@@ -84,9 +87,11 @@ pub struct ModuleConfig {
     pub pgo_gen: SwitchWithOptPath,
     pub pgo_use: Option<PathBuf>,
     pub pgo_sample_use: Option<PathBuf>,
+    pub pgo_report: bool,
     pub debug_info_for_profiling: bool,
     pub instrument_coverage: bool,
     pub instrument_gcov: bool,
+    pub instrument_functions: bool,
 
     pub sanitizer: SanitizerSet,
     pub sanitizer_recover: SanitizerSet,
@@ -180,6 +185,10 @@ macro_rules! if_regular {
             ),
             pgo_use: if_regular!(sess.opts.cg.profile_use.clone(), None),
             pgo_sample_use: if_regular!(sess.opts.debugging_opts.profile_sample_use.clone(), None),
+            pgo_report: if_regular!(
+                sess.opts.debugging_opts.pgo_report && sess.opts.cg.profile_use.is_some(),
+                false
+            ),
             debug_info_for_profiling: sess.opts.debugging_opts.debug_info_for_profiling,
             instrument_coverage: if_regular!(sess.instrument_coverage(), false),
             instrument_gcov: if_regular!(
@@ -189,6 +198,7 @@ macro_rules! if_regular {
                 sess.opts.debugging_opts.profile && !is_compiler_builtins,
                 false
             ),
+            instrument_functions: sess.instrument_functions(),
 
             sanitizer: if_regular!(sess.opts.debugging_opts.sanitizer, SanitizerSet::empty()),
             sanitizer_recover: if_regular!(
@@ -333,6 +343,8 @@ pub struct CodegenContext<B: WriteBackendMethods> {
     pub debuginfo: config::DebugInfo,
     pub split_debuginfo: rustc_target::spec::SplitDebuginfo,
     pub split_dwarf_kind: rustc_session::config::SplitDwarfKind,
+    /// The codegen units to optimize if `-Z opt-bisect-cgus` is used; the others are not optimized.
+    pub opt_bisect_cgus: Option<Arc<FxHashSet<String>>>,
 
     // Number of cgus excluding the allocator/metadata modules
     pub total_cgus: usize,
@@ -442,8 +454,12 @@ pub fn start_async_codegen<B: ExtraBackendMethods>(
 
     let crate_info = CrateInfo::new(tcx, target_cpu);
 
-    let regular_config =
+    let mut regular_config =
         ModuleConfig::new(ModuleKind::Regular, sess, no_builtins, is_compiler_builtins);
+    let merged_profile = merge_raw_profiles(&backend, sess, &tcx.output_filenames(()));
+    if let Some(merged_profile) = &merged_profile {
+        regular_config.pgo_use = Some(merged_profile.clone());
+    }
     let metadata_config =
         ModuleConfig::new(ModuleKind::Metadata, sess, no_builtins, is_compiler_builtins);
     let allocator_config =
@@ -478,7 +494,66 @@ pub fn start_async_codegen<B: ExtraBackendMethods>(
         shared_emitter_main,
         future: coordinator_thread,
         output_filenames: tcx.output_filenames(()).clone(),
+        merged_profile,
+        size_report_items: None,
+    }
+}
+
+/// Returns the raw (`.profraw`) and indexed (`.profdata`) profiles to merge if `-C profile-use`
+/// was given a directory of profiles or a raw profile instead of an indexed profile.
+fn profiles_to_merge(path: &Path) -> io::Result<Option<Vec<PathBuf>>> {
+    if path.is_dir() {
+        let mut profiles = Vec::new();
+        for entry in fs::read_dir(path)? {
+            let profile = entry?.path();
+            let ext = profile.extension().and_then(|ext| ext.to_str());
+            if matches!(ext, Some("profraw" | "profdata")) {
+                profiles.push(profile);
+            }
+        }
+        // Merging is not commutative when counters saturate, so keep it deterministic.
+        profiles.sort();
+        Ok(Some(profiles))
+    } else if path.extension().map_or(false, |ext| ext == "profraw") {
+        Ok(Some(vec![path.to_path_buf()]))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Merges the profiles given to `-C profile-use` into an indexed profile, if it was not given one
+/// already, and returns the path of the merged profile.
+fn merge_raw_profiles<B: ExtraBackendMethods>(
+    backend: &B,
+    sess: &Session,
+    outputs: &OutputFilenames,
+) -> Option<PathBuf> {
+    let path = sess.opts.cg.profile_use.as_ref()?;
+    let profiles = match profiles_to_merge(path) {
+        Ok(profiles) => profiles?,
+        Err(err) => sess.fatal(&format!(
+            "failed to read the profiles in `{}` passed to `-C profile-use`: {}",
+            path.display(),
+            err
+        )),
+    };
+    if profiles.is_empty() {
+        sess.fatal(&format!(
+            "no `.profraw` or `.profdata` files found in `{}` passed to `-C profile-use`",
+            path.display()
+        ));
+    }
+
+    let _timer = sess.timer("merge_raw_profiles");
+    let merged = outputs.temp_path_ext("profdata", None);
+    match backend.merge_profiles(&profiles, &merged) {
+        Ok(Some(warning)) => sess.warn(&format!("some profile data was dropped: {}", warning)),
+        Ok(None) => {}
+        Err(err) => {
+            sess.fatal(&format!("failed to merge the profiles passed to `-C profile-use`: {}", err))
+        }
     }
+    Some(merged)
 }
 
 fn copy_all_cgu_workproducts_to_incr_comp_cache_dir(
@@ -749,6 +824,35 @@ fn execute_work_item<B: ExtraBackendMethods>(
     }
 }
 
+/// Returns the codegen units to optimize for `-Z opt-bisect-cgus=N`: the first `N` codegen units
+/// of the crate, ordered by name so that the same `N` selects the same codegen units in every
+/// compilation of the same code. Also prints which codegen units are optimized, so that the
+/// codegen unit whose optimization causes a miscompilation can be identified.
+fn opt_bisect_cgus(tcx: TyCtxt<'_>) -> Option<Arc<FxHashSet<String>>> {
+    let limit = tcx.sess.opts.debugging_opts.opt_bisect_cgus?;
+    let mut names: Vec<_> =
+        tcx.collect_and_partition_mono_items(()).1.iter().map(|cgu| cgu.name()).collect();
+    names.sort_unstable_by(|a, b| a.as_str().cmp(b.as_str()));
+    let total = names.len();
+    let crate_name = tcx.crate_name(LOCAL_CRATE);
+    let mut optimized = FxHashSet::default();
+    for (i, name) in names.into_iter().enumerate() {
+        let action = if i < limit { "optimizing" } else { "not optimizing" };
+        tcx.sess.note_without_error(&format!(
+            "opt-bisect-cgus: {} codegen unit ({}) of {} in crate `{}`: {}",
+            action,
+            i + 1,
+            total,
+            crate_name,
+            name
+        ));
+        if i < limit {
+            optimized.insert(name.to_string());
+        }
+    }
+    Some(Arc::new(optimized))
+}
+
 // Actual LTO type we end up choosing based on multiple factors.
 pub enum ComputedLtoType {
     No,
@@ -804,6 +908,21 @@ fn execute_optimize_work_item<B: ExtraBackendMethods>(
 ) -> Result<WorkItemResult<B>, FatalError> {
     let diag_handler = cgcx.create_diag_handler();
 
+    let skip_optimizations = cgcx.opt_bisect_cgus.as_ref().map_or(false, |optimized| {
+        module.kind == ModuleKind::Regular && !optimized.contains(&module.name)
+    });
+    let unoptimized_config;
+    let module_config = if skip_optimizations {
+        unoptimized_config = ModuleConfig {
+            opt_level: Some(config::OptLevel::No),
+            opt_size: Some(config::OptLevel::No),
+            ..module_config.clone()
+        };
+        &unoptimized_config
+    } else {
+        module_config
+    };
+
     unsafe {
         B::optimize(cgcx, &diag_handler, &module, module_config)?;
     }
@@ -1065,6 +1184,7 @@ fn start_executing_work<B: ExtraBackendMethods>(
         debuginfo: tcx.sess.opts.debuginfo,
         split_debuginfo: tcx.sess.split_debuginfo(),
         split_dwarf_kind: tcx.sess.opts.debugging_opts.split_dwarf_kind,
+        opt_bisect_cgus: opt_bisect_cgus(tcx),
     };
 
     // This is the "main loop" of parallel work happening for parallel codegen.
@@ -1667,9 +1787,23 @@ fn drop(&mut self) {
     .expect("failed to spawn thread");
 }
 
+/// How the `-C profile-use` profile applied to a function of a codegen unit, for `-Z pgo-report`.
+pub struct FunctionProfile {
+    /// The symbol name of the function.
+    pub symbol_name: String,
+    /// The demangled name of the function.
+    pub name: String,
+    /// The entry count of the function, if the profile has data for it.
+    pub entry_count: Option<u64>,
+    /// Whether the profile considers the function hot.
+    pub hot: bool,
+}
+
 enum SharedEmitterMessage {
     Diagnostic(Diagnostic),
     InlineAsmError(u32, String, Level, Option<(String, Vec<InnerSpan>)>),
+    StaleProfile { symbol_name: String, name: String },
+    FunctionProfiles(Vec<FunctionProfile>),
     AbortIfErrors,
     Fatal(String),
 }
@@ -1681,13 +1815,25 @@ pub struct SharedEmitter {
 
 pub struct SharedEmitterMain {
     receiver: Receiver<SharedEmitterMessage>,
+    /// The demangled names of the functions whose profile data was ignored because it does not
+    /// match their control flow, by symbol name.
+    stale_profiles: RefCell<FxHashMap<String, String>>,
+    /// The functions of all codegen units, by symbol name.
+    function_profiles: RefCell<FxHashMap<String, FunctionProfile>>,
 }
 
 impl SharedEmitter {
     pub fn new() -> (SharedEmitter, SharedEmitterMain) {
         let (sender, receiver) = channel();
 
-        (SharedEmitter { sender }, SharedEmitterMain { receiver })
+        (
+            SharedEmitter { sender },
+            SharedEmitterMain {
+                receiver,
+                stale_profiles: Default::default(),
+                function_profiles: Default::default(),
+            },
+        )
     }
 
     pub fn inline_asm_error(
@@ -1700,6 +1846,17 @@ pub fn inline_asm_error(
         drop(self.sender.send(SharedEmitterMessage::InlineAsmError(cookie, msg, level, source)));
     }
 
+    /// Reports that the profile data of a function was ignored because it does not match the
+    /// control flow of the function anymore.
+    pub fn stale_profile(&self, symbol_name: String, name: String) {
+        drop(self.sender.send(SharedEmitterMessage::StaleProfile { symbol_name, name }));
+    }
+
+    /// Reports how the profile applied to the functions of a codegen unit, for `-Z pgo-report`.
+    pub fn function_profiles(&self, functions: Vec<FunctionProfile>) {
+        drop(self.sender.send(SharedEmitterMessage::FunctionProfiles(functions)));
+    }
+
     pub fn fatal(&self, msg: &str) {
         drop(self.sender.send(SharedEmitterMessage::Fatal(msg.to_string())));
     }
@@ -1790,6 +1947,19 @@ pub fn check(&self, sess: &Session, blocking: bool) {
 
                     err.emit();
                 }
+                Ok(SharedEmitterMessage::StaleProfile { symbol_name, name }) => {
+                    self.stale_profiles.borrow_mut().insert(symbol_name, name);
+                }
+                Ok(SharedEmitterMessage::FunctionProfiles(functions)) => {
+                    let mut function_profiles = self.function_profiles.borrow_mut();
+                    for function in functions {
+                        // Functions can be instantiated in more than one codegen unit.
+                        let known = function_profiles.get(&function.symbol_name);
+                        if known.map_or(true, |known| known.entry_count < function.entry_count) {
+                            function_profiles.insert(function.symbol_name.clone(), function);
+                        }
+                    }
+                }
                 Ok(SharedEmitterMessage::AbortIfErrors) => {
                     sess.abort_if_errors();
                 }
@@ -1802,6 +1972,64 @@ pub fn check(&self, sess: &Session, blocking: bool) {
             }
         }
     }
+
+    /// Warns about the functions whose profile data was ignored and prints the report of
+    /// `-Z pgo-report`, once all codegen units have been optimized.
+    fn report_profile_use(&self, sess: &Session, crate_name: Symbol) {
+        let stale_profiles = self.stale_profiles.take();
+        if !stale_profiles.is_empty() {
+            let mut names: Vec<_> =
+                stale_profiles.values().map(|name| format!("`{}`", name)).collect();
+            names.sort();
+            let (count, these) = if names.len() == 1 {
+                ("1 function".to_string(), "this function")
+            } else {
+                (format!("{} functions", names.len()), "these functions")
+            };
+            sess.struct_warn(&format!("the profile data of {} is stale and was ignored", count))
+                .note(&format!(
+                    "the control flow of {} changed since the profile was collected: {}",
+                    these,
+                    names.join(", ")
+                ))
+                .help("collect a new profile with `-C profile-generate` to optimize them again")
+                .emit();
+        }
+
+        if !sess.opts.debugging_opts.pgo_report || sess.opts.cg.profile_use.is_none() {
+            return;
+        }
+        let function_profiles = self.function_profiles.take();
+        let total = function_profiles.len();
+        let percent = |n: usize| if total == 0 { 0.0 } else { n as f64 * 100.0 / total as f64 };
+        let profiled = function_profiles.values().filter(|f| f.entry_count.is_some()).count();
+        let is_stale = |name: &String| stale_profiles.contains_key(name);
+        let stale = function_profiles.keys().filter(|name| is_stale(name)).count();
+        let missing = function_profiles
+            .iter()
+            .filter(|(name, f)| f.entry_count.is_none() && !is_stale(name))
+            .count();
+        let mut hot: Vec<_> = function_profiles.values().filter(|f| f.hot).collect();
+        hot.sort_by(|a, b| b.entry_count.cmp(&a.entry_count).then_with(|| a.name.cmp(&b.name)));
+        let entries: u64 = function_profiles.values().filter_map(|f| f.entry_count).sum();
+        let hot_entries: u64 = hot.iter().filter_map(|f| f.entry_count).sum();
+        let hot_coverage =
+            if entries == 0 { 0.0 } else { hot_entries as f64 * 100.0 / entries as f64 };
+
+        println!("pgo-report: crate `{}`", crate_name);
+        println!("pgo-report: functions: {}", total);
+        println!("pgo-report:   with profile data: {} ({:.1}%)", profiled, percent(profiled));
+        println!("pgo-report:   with stale profile data: {} ({:.1}%)", stale, percent(stale));
+        println!("pgo-report:   without profile data: {} ({:.1}%)", missing, percent(missing));
+        println!(
+            "pgo-report: hot functions: {} ({:.1}% of profiled function entries)",
+            hot.len(),
+            hot_coverage
+        );
+        for function in hot {
+            println!("pgo-report:   {:>12} {}", function.entry_count.unwrap_or(0), function.name);
+        }
+    }
 }
 
 pub struct OngoingCodegen<B: ExtraBackendMethods> {
@@ -1814,6 +2042,10 @@ pub struct OngoingCodegen<B: ExtraBackendMethods> {
     pub shared_emitter_main: SharedEmitterMain,
     pub future: thread::JoinHandle<Result<CompiledModules, ()>>,
     pub output_filenames: Arc<OutputFilenames>,
+    /// The indexed profile merged from the profiles given to `-C profile-use`, if any.
+    pub merged_profile: Option<PathBuf>,
+    /// The mono items to write the `-Z emit-size-report` report for.
+    pub size_report_items: Option<Vec<SizeReportItem>>,
 }
 
 impl<B: ExtraBackendMethods> OngoingCodegen<B> {
@@ -1837,6 +2069,25 @@ pub fn join(self, sess: &Session) -> (CodegenResults, FxHashMap<WorkProductId, W
 
         sess.abort_if_errors();
 
+        self.shared_emitter_main.report_profile_use(sess, self.crate_info.local_crate_name);
+        if let Some(merged_profile) = &self.merged_profile {
+            if !sess.opts.cg.save_temps {
+                ensure_removed(sess.diagnostic(), merged_profile);
+            }
+        }
+
+        if let Some(items) = &self.size_report_items {
+            sess.time("write_size_report", || {
+                size_report::write_size_report(
+                    sess,
+                    &self.output_filenames.with_extension("size-report"),
+                    self.crate_info.local_crate_name.as_str(),
+                    items,
+                    &compiled_modules.modules,
+                )
+            });
+        }
+
         let work_products =
             copy_all_cgu_workproducts_to_incr_comp_cache_dir(sess, &compiled_modules);
         produce_final_output_artifacts(sess, &compiled_modules, &self.output_filenames);
@@ -1,4 +1,5 @@
 use crate::back::metadata::create_compressed_metadata_file;
+use crate::back::size_report;
 use crate::back::write::{
     compute_per_cgu_lto_type, start_async_codegen, submit_codegened_module_to_llvm,
     submit_post_lto_module_to_llvm, submit_pre_lto_module_to_llvm, ComputedLtoType, OngoingCodegen,
@@ -548,7 +549,7 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
         None
     };
 
-    let ongoing_codegen = start_async_codegen(
+    let mut ongoing_codegen = start_async_codegen(
         backend.clone(),
         tcx,
         target_cpu,
@@ -556,6 +557,10 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
         metadata_module,
         codegen_units.len(),
     );
+    if tcx.sess.opts.debugging_opts.emit_size_report {
+        ongoing_codegen.size_report_items =
+            Some(size_report::collect_size_report_items(tcx, codegen_units));
+    }
     let ongoing_codegen = AbortCodegenOnDrop::<B>(Some(ongoing_codegen));
 
     // Codegen an allocator shim, if necessary.
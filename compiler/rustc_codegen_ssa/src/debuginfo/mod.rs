@@ -1,5 +1,9 @@
-use rustc_middle::ty::{self, layout::TyAndLayout};
+use rustc_hir::def_id::DefId;
+use rustc_index::vec::Idx;
+use rustc_middle::mir;
+use rustc_middle::ty::{self, layout::TyAndLayout, TyCtxt};
 use rustc_target::abi::Size;
+use smallvec::SmallVec;
 
 // FIXME(eddyb) find a place for this (or a way to replace it).
 pub mod type_names;
@@ -32,3 +36,33 @@ pub fn wants_c_like_enum_debuginfo<'tcx>(enum_type_and_layout: TyAndLayout<'tcx>
         _ => false,
     }
 }
+
+/// Returns names of captured upvars for closures and generators.
+///
+/// Here are some examples:
+///  - `name__field1__field2` when the upvar is captured by value.
+///  - `_ref__name__field` when the upvar is captured by reference.
+///
+/// For generators this only contains upvars that are shared by all states.
+pub fn closure_saved_names_of_captured_variables(
+    tcx: TyCtxt<'_>,
+    def_id: DefId,
+) -> SmallVec<[String; 16]> {
+    let body = tcx.optimized_mir(def_id);
+
+    body.var_debug_info
+        .iter()
+        .filter_map(|var| {
+            let is_ref = match var.value {
+                mir::VarDebugInfoContents::Place(place) if place.local == mir::Local::new(1) => {
+                    // The projection is either `[.., Field, Deref]` or `[.., Field]`. It
+                    // implies whether the variable is captured by value or by reference.
+                    matches!(place.projection.last().unwrap(), mir::ProjectionElem::Deref)
+                }
+                _ => return None,
+            };
+            let prefix = if is_ref { "_ref__" } else { "" };
+            Some(prefix.to_owned() + var.name.as_str())
+        })
+        .collect()
+}
@@ -20,10 +20,11 @@
 use rustc_middle::ty::{self, Instance, Ty, TypeFoldable};
 use rustc_span::source_map::Span;
 use rustc_span::{sym, Symbol};
-use rustc_symbol_mangling::typeid_for_fnabi;
+use rustc_symbol_mangling::{kcfi_typeid_for_fnabi, typeid_for_fnabi};
 use rustc_target::abi::call::{ArgAbi, FnAbi, PassMode};
 use rustc_target::abi::{self, HasDataLayout, WrappingRange};
 use rustc_target::spec::abi::Abi;
+use rustc_target::spec::SanitizerSet;
 
 /// Used by `FunctionCx::codegen_terminator` for emitting common patterns
 /// e.g., creating a basic block, calling a function, etc.
@@ -132,6 +133,7 @@ fn do_call<Bx: BuilderMethods<'a, 'tcx>>(
         llargs: &[Bx::Value],
         destination: Option<(ReturnDest<'tcx, Bx::Value>, mir::BasicBlock)>,
         cleanup: Option<mir::BasicBlock>,
+        kcfi_typeid: Option<u32>,
     ) {
         // If there is a cleanup block and the function we're calling can unwind, then
         // do an invoke, otherwise do a call.
@@ -162,8 +164,11 @@ fn do_call<Bx: BuilderMethods<'a, 'tcx>>(
             } else {
                 fx.unreachable_block()
             };
-            let invokeret =
+            let mut invokeret =
                 bx.invoke(fn_ty, fn_ptr, &llargs, ret_llbb, unwind_block, self.funclet(fx));
+            if let Some(typeid) = kcfi_typeid {
+                invokeret = bx.add_kcfi_check(invokeret, typeid);
+            }
             bx.apply_attrs_callsite(&fn_abi, invokeret);
             if fx.mir[self.bb].is_cleanup {
                 bx.do_not_inline(invokeret);
@@ -175,7 +180,10 @@ fn do_call<Bx: BuilderMethods<'a, 'tcx>>(
                 fx.store_return(bx, ret_dest, &fn_abi.ret, invokeret);
             }
         } else {
-            let llret = bx.call(fn_ty, fn_ptr, &llargs, self.funclet(fx));
+            let mut llret = bx.call(fn_ty, fn_ptr, &llargs, self.funclet(fx));
+            if let Some(typeid) = kcfi_typeid {
+                llret = bx.add_kcfi_check(llret, typeid);
+            }
             bx.apply_attrs_callsite(&fn_abi, llret);
             if fx.mir[self.bb].is_cleanup {
                 // Cleanup is always the cold path. Don't inline
@@ -415,6 +423,7 @@ fn codegen_drop_terminator(
             args,
             Some((ReturnDest::Nothing, target)),
             unwind,
+            None,
         );
     }
 
@@ -496,7 +505,7 @@ fn codegen_assert_terminator(
         let llfn = bx.get_fn_addr(instance);
 
         // Codegen the actual panic invoke/call.
-        helper.do_call(self, &mut bx, fn_abi, llfn, &args, None, cleanup);
+        helper.do_call(self, &mut bx, fn_abi, llfn, &args, None, cleanup, None);
     }
 
     fn codegen_abort_terminator(
@@ -515,7 +524,7 @@ fn codegen_abort_terminator(
         let llfn = bx.get_fn_addr(instance);
 
         // Codegen the actual panic invoke/call.
-        helper.do_call(self, &mut bx, fn_abi, llfn, &[], None, None);
+        helper.do_call(self, &mut bx, fn_abi, llfn, &[], None, None, None);
     }
 
     /// Returns `true` if this is indeed a panic intrinsic and codegen is done.
@@ -589,6 +598,7 @@ enum AssertIntrinsic {
                     &[msg.0, msg.1, location],
                     destination.as_ref().map(|(_, bb)| (ReturnDest::Nothing, *bb)),
                     cleanup,
+                    None,
                 );
             } else {
                 // a NOP
@@ -926,6 +936,7 @@ fn codegen_call_terminator(
                 &llargs,
                 destination.as_ref().map(|&(_, target)| (ret_dest, target)),
                 cleanup,
+                None,
             );
 
             bx.switch_to_block(bb_fail);
@@ -935,6 +946,21 @@ fn codegen_call_terminator(
             return;
         }
 
+        // For backends that support KCFI, which checks the type id of the callee right before
+        // indirect calls.
+        let kcfi_typeid = if bx.tcx().sess.is_sanitizer_kcfi_enabled()
+            && is_indirect_call
+            && !bx
+                .tcx()
+                .codegen_fn_attrs(self.instance.def_id())
+                .no_sanitize
+                .contains(SanitizerSet::KCFI)
+        {
+            Some(kcfi_typeid_for_fnabi(bx.tcx(), fn_abi))
+        } else {
+            None
+        };
+
         helper.do_call(
             self,
             &mut bx,
@@ -943,6 +969,7 @@ fn codegen_call_terminator(
             &llargs,
             destination.as_ref().map(|&(_, target)| (ret_dest, target)),
             cleanup,
+            kcfi_typeid,
         );
     }
 
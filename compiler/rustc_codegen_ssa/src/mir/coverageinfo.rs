@@ -53,6 +53,14 @@ pub fn codegen_coverage(&self, bx: &mut Bx, coverage: Coverage, scope: SourceSco
                     code_region.expect("unreachable regions always have code regions"),
                 );
             }
+            CoverageKind::Branch { true_count, false_count } => {
+                bx.add_coverage_branch(
+                    instance,
+                    true_count,
+                    false_count,
+                    code_region.expect("branch regions always have code regions"),
+                );
+            }
         }
     }
 }
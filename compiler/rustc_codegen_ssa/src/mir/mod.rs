@@ -3,7 +3,7 @@
 use rustc_middle::mir::interpret::ErrorHandled;
 use rustc_middle::ty::layout::{FnAbiOf, HasTyCtxt, TyAndLayout};
 use rustc_middle::ty::{self, Instance, Ty, TypeFoldable};
-use rustc_symbol_mangling::typeid_for_fnabi;
+use rustc_symbol_mangling::{kcfi_typeid_for_fnabi, typeid_for_fnabi};
 use rustc_target::abi::call::{FnAbi, PassMode};
 
 use std::iter;
@@ -255,6 +255,13 @@ pub fn codegen_mir<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>>(
         let typeid = typeid_for_fnabi(cx.tcx(), fn_abi);
         bx.type_metadata(llfn, typeid);
     }
+
+    // For backends that support KCFI, which checks the type id of the callee right before
+    // indirect calls.
+    if cx.tcx().sess.is_sanitizer_kcfi_enabled() {
+        let typeid = kcfi_typeid_for_fnabi(cx.tcx(), fn_abi);
+        bx.kcfi_type_metadata(llfn, typeid);
+    }
 }
 
 /// Produces, for each argument, a `Value` pointing at the
@@ -23,6 +23,7 @@
 pub use rustc_data_structures::sync::MetadataRef;
 
 use std::any::Any;
+use std::path::{Path, PathBuf};
 
 pub trait BackendTypes {
     type Value: CodegenObject;
@@ -160,4 +161,15 @@ fn spawn_named_thread<F, T>(
     {
         std::thread::Builder::new().name(name).spawn(f)
     }
+
+    /// Merges the raw and indexed instrumentation profiles in `profiles` into the indexed
+    /// profile `output`, for `-C profile-use`. Returns a warning if some of the profile data
+    /// could not be merged.
+    fn merge_profiles(
+        &self,
+        _profiles: &[PathBuf],
+        _output: &Path,
+    ) -> Result<Option<String>, String> {
+        Err("merging profiles is not supported by this codegen backend".to_string())
+    }
 }
@@ -162,6 +162,10 @@ fn write_operand_repeatedly(
     fn nonnull_metadata(&mut self, load: Self::Value);
     fn type_metadata(&mut self, function: Self::Function, typeid: String);
     fn typeid_metadata(&mut self, typeid: String) -> Self::Value;
+    fn kcfi_type_metadata(&mut self, function: Self::Function, typeid: u32);
+    /// Makes the indirect call or invoke `call` check that its callee has the KCFI type id
+    /// `typeid`, and returns the resulting call, which may be a new value.
+    fn add_kcfi_check(&mut self, call: Self::Value, typeid: u32) -> Self::Value;
 
     fn store(&mut self, val: Self::Value, ptr: Self::Value, align: Align) -> Self::Value;
     fn store_with_flags(
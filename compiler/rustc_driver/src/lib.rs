@@ -38,6 +38,7 @@
 use rustc_session::lint::{Lint, LintId};
 use rustc_session::{config, DiagnosticOutput, Session};
 use rustc_session::{early_error, early_error_no_abort, early_warn};
+use rustc_smir::stable_mir;
 use rustc_span::source_map::{FileLoader, FileName};
 use rustc_span::symbol::sym;
 
@@ -113,6 +114,12 @@ fn after_analysis<'tcx>(
     ) -> Compilation {
         Compilation::Continue
     }
+    /// Called after analysis with a versioned, read-only view of the crate's MIR that does not
+    /// expose the compiler's internal data structures. Return value instructs the compiler
+    /// whether to continue the compilation afterwards (defaults to `Compilation::Continue`)
+    fn after_analysis_stable_mir(&mut self, _cx: &stable_mir::Context<'_>) -> Compilation {
+        Compilation::Continue
+    }
 }
 
 #[derive(Default)]
@@ -403,6 +410,13 @@ fn run_compiler(
                 return early_exit();
             }
 
+            let compilation = queries.global_ctxt()?.peek_mut().enter(|tcx| {
+                callbacks.after_analysis_stable_mir(&stable_mir::Context::new(tcx))
+            });
+            if compilation == Compilation::Stop {
+                return early_exit();
+            }
+
             queries.ongoing_codegen()?;
 
             if sess.opts.debugging_opts.print_type_sizes {
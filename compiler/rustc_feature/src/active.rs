@@ -467,6 +467,9 @@ pub fn set(&self, features: &mut Features, span: Span) {
     /// Allows function attribute `#[no_coverage]`, to bypass coverage
     /// instrumentation of that function.
     (active, no_coverage, "1.53.0", Some(84605), None),
+    /// Allows function attribute `#[no_instrument_function]`, to bypass the
+    /// `-Z instrument-functions` and `-Z instrument-mcount` instrumentation of that function.
+    (active, no_instrument_function, "1.62.0", None, None),
     /// Allows the use of `no_sanitize` attribute.
     (active, no_sanitize, "1.42.0", Some(39699), None),
     /// Allows using the `non_exhaustive_omitted_patterns` lint.
@@ -370,6 +370,10 @@ pub struct BuiltinAttribute {
         experimental!(no_sanitize)
     ),
     gated!(no_coverage, Normal, template!(Word), WarnFollowing, experimental!(no_coverage)),
+    gated!(
+        no_instrument_function, Normal, template!(Word), WarnFollowing,
+        experimental!(no_instrument_function)
+    ),
 
     ungated!(
         doc, Normal, template!(List: "hidden|inline|...", NameValueStr: "string"), DuplicatesOk
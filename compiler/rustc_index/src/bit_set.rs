@@ -464,6 +464,12 @@ pub fn count(&self) -> usize {
         self.chunks.iter().map(|chunk| chunk.count()).sum()
     }
 
+    /// Iterates over the elements of `self` in increasing order.
+    #[inline]
+    pub fn iter(&self) -> ChunkedBitIter<'_, T> {
+        ChunkedBitIter::new(self)
+    }
+
     /// Returns `true` if `self` contains `elem`.
     #[inline]
     pub fn contains(&self, elem: T) -> bool {
@@ -1039,6 +1045,46 @@ fn next(&mut self) -> Option<T> {
     }
 }
 
+pub struct ChunkedBitIter<'a, T: Idx> {
+    /// The next index to look at.
+    index: usize,
+    bitset: &'a ChunkedBitSet<T>,
+}
+
+impl<'a, T: Idx> ChunkedBitIter<'a, T> {
+    #[inline]
+    fn new(bitset: &'a ChunkedBitSet<T>) -> ChunkedBitIter<'a, T> {
+        ChunkedBitIter { index: 0, bitset }
+    }
+}
+
+impl<'a, T: Idx> Iterator for ChunkedBitIter<'a, T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        while self.index < self.bitset.domain_size() {
+            let elem = T::new(self.index);
+            match self.bitset.chunks[chunk_index(elem)] {
+                Zeros(chunk_domain_size) => {
+                    // Skip to the start of the next chunk.
+                    self.index = chunk_index(elem) * CHUNK_BITS + chunk_domain_size as usize;
+                }
+                Ones(_) => {
+                    self.index += 1;
+                    return Some(elem);
+                }
+                Mixed(_, _, ref words) => {
+                    self.index += 1;
+                    let (word_index, mask) = chunk_word_index_and_mask(elem);
+                    if (words[word_index] & mask) != 0 {
+                        return Some(elem);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
 #[inline]
 fn bitwise<Op>(out_vec: &mut [Word], in_vec: &[Word], op: Op) -> bool
 where
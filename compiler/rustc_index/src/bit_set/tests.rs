@@ -342,6 +342,21 @@ fn chunked_bitset() {
     b10000b.assert_valid();
 }
 
+#[test]
+fn chunked_bitset_iter() {
+    let mut b = ChunkedBitSet::<usize>::new_empty(10000);
+    assert_eq!(b.iter().collect::<Vec<_>>(), vec![]);
+
+    // Elements in a mixed chunk, a chunk skipped as zeros, and the final partial chunk.
+    for i in [0, 7, 2047, 6000, 9999] {
+        b.insert(i);
+    }
+    assert_eq!(b.iter().collect::<Vec<_>>(), vec![0, 7, 2047, 6000, 9999]);
+
+    let b = ChunkedBitSet::<usize>::new_filled(3000);
+    assert_eq!(b.iter().collect::<Vec<_>>(), (0..3000).collect::<Vec<_>>());
+}
+
 #[test]
 fn grow() {
     let mut set: GrowableBitSet<usize> = GrowableBitSet::with_capacity(65);
@@ -653,9 +653,12 @@ macro_rules! untracked {
     untracked!(dump_dep_graph, true);
     untracked!(dump_mir, Some(String::from("abc")));
     untracked!(dump_mir_dataflow, true);
+    untracked!(dump_mir_dataflow_html, true);
     untracked!(dump_mir_dir, String::from("abc"));
     untracked!(dump_mir_exclude_pass_number, true);
     untracked!(dump_mir_graphviz, true);
+    untracked!(dump_mono_items, Some(PathBuf::from("abc")));
+    untracked!(emit_size_report, true);
     untracked!(emit_stack_sizes, true);
     untracked!(future_incompat_test, true);
     untracked!(hir_stats, true);
@@ -670,6 +673,7 @@ macro_rules! untracked {
     untracked!(ls, true);
     untracked!(macro_backtrace, true);
     untracked!(meta_stats, true);
+    untracked!(mono_why, Some(String::from("abc")));
     untracked!(nll_facts, true);
     untracked!(no_analysis, true);
     untracked!(no_interleave_lints, true);
@@ -677,6 +681,7 @@ macro_rules! untracked {
     untracked!(no_parallel_llvm, true);
     untracked!(parse_only, true);
     untracked!(perf_stats, true);
+    untracked!(pgo_report, true);
     // `pre_link_arg` is omitted because it just forwards to `pre_link_args`.
     untracked!(pre_link_args, vec![String::from("abc"), String::from("def")]);
     untracked!(profile_closures, true);
@@ -745,8 +750,10 @@ macro_rules! tracked {
     tracked!(inline_mir_hint_threshold, Some(123));
     tracked!(inline_mir_threshold, Some(123));
     tracked!(instrument_coverage, Some(InstrumentCoverage::All));
+    tracked!(instrument_functions, true);
     tracked!(instrument_mcount, true);
     tracked!(link_only, true);
+    tracked!(llvm_opt_bisect_limit, Some(10));
     tracked!(llvm_plugins, vec![String::from("plugin_name")]);
     tracked!(location_detail, LocationDetail { file: true, line: false, column: false });
     tracked!(merge_functions, Some(MergeFunctions::Disabled));
@@ -760,7 +767,9 @@ macro_rules! tracked {
     tracked!(no_unique_section_names, true);
     tracked!(no_profiler_runtime, true);
     tracked!(oom, OomStrategy::Panic);
+    tracked!(opt_bisect_cgus, Some(1));
     tracked!(osx_rpath_install_name, true);
+    tracked!(outline_cold_panics, true);
     tracked!(panic_abort_tests, true);
     tracked!(panic_in_drop, PanicStrategy::Abort);
     tracked!(pick_stable_methods_before_any_unstable, false);
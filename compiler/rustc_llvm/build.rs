@@ -227,6 +227,7 @@ fn main() {
         .file("llvm-wrapper/ArchiveWrapper.cpp")
         .file("llvm-wrapper/CoverageMappingWrapper.cpp")
         .file("llvm-wrapper/Linker.cpp")
+        .file("llvm-wrapper/ProfileDataWrapper.cpp")
         .cpp(true)
         .cpp_link_stdlib(None) // we handle this below
         .compile("llvm-wrapper");
@@ -84,6 +84,7 @@ enum LLVMRustAttribute {
   StackProtect = 32,
   NoUndef = 33,
   SanitizeMemTag = 34,
+  ShadowCallStack = 35,
 };
 
 typedef struct OpaqueRustString *RustStringRef;
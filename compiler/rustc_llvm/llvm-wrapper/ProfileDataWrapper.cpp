@@ -0,0 +1,93 @@
+#include "LLVMWrapper.h"
+#include "llvm/Analysis/ProfileSummaryInfo.h"
+#include "llvm/ProfileData/InstrProfReader.h"
+#include "llvm/ProfileData/InstrProfWriter.h"
+#include "llvm/Support/FileSystem.h"
+
+using namespace llvm;
+
+// Merges the raw (`.profraw`) or indexed (`.profdata`) instrumentation profiles
+// in `Inputs` into the indexed profile `Output`, like `llvm-profdata merge`.
+// Records that cannot be merged are dropped, and the first reason why is
+// written to `WarningOut`.
+extern "C" LLVMRustResult
+LLVMRustMergeInstrProfiles(const char *const Inputs[], size_t InputsLen,
+                           const char *Output, RustStringRef WarningOut) {
+  InstrProfWriter Writer;
+  std::string Warning;
+  for (size_t i = 0; i < InputsLen; i++) {
+    auto ReaderOrErr = InstrProfReader::create(Inputs[i]);
+    if (Error E = ReaderOrErr.takeError()) {
+      LLVMRustSetLastError(
+          (Twine(Inputs[i]) + ": " + toString(std::move(E))).str().c_str());
+      return LLVMRustResult::Failure;
+    }
+    auto Reader = std::move(ReaderOrErr.get());
+#if LLVM_VERSION_LT(14, 0)
+    if (Reader->isIRLevelProfile()) {
+      if (Error E = Writer.setIsIRLevelProfile(
+              true, Reader->hasCSIRLevelProfile())) {
+        LLVMRustSetLastError(
+            (Twine(Inputs[i]) + ": " + toString(std::move(E))).str().c_str());
+        return LLVMRustResult::Failure;
+      }
+    }
+    Writer.setInstrEntryBBEnabled(Reader->instrEntryBBEnabled());
+#else
+    if (Error E = Writer.mergeProfileKind(Reader->getProfileKind())) {
+      LLVMRustSetLastError(
+          (Twine(Inputs[i]) + ": " + toString(std::move(E))).str().c_str());
+      return LLVMRustResult::Failure;
+    }
+#endif
+    for (auto &Record : *Reader) {
+      Writer.addRecord(std::move(Record), [&](Error E) {
+        if (Warning.empty())
+          Warning = toString(std::move(E));
+        else
+          consumeError(std::move(E));
+      });
+    }
+    if (Reader->hasError()) {
+      LLVMRustSetLastError((Twine(Inputs[i]) + ": " +
+                            toString(Reader->getError())).str().c_str());
+      return LLVMRustResult::Failure;
+    }
+  }
+  RawRustStringOstream WarningOS(WarningOut);
+  WarningOS << Warning;
+
+  std::error_code EC;
+  raw_fd_ostream OS(Output, EC, sys::fs::OF_None);
+  if (EC) {
+    LLVMRustSetLastError(EC.message().c_str());
+    return LLVMRustResult::Failure;
+  }
+  if (Error E = Writer.write(OS)) {
+    LLVMRustSetLastError(toString(std::move(E)).c_str());
+    return LLVMRustResult::Failure;
+  }
+  return LLVMRustResult::Success;
+}
+
+typedef void (*LLVMRustFunctionProfileCallback)(void *, const char *, size_t,
+                                                bool, uint64_t, bool);
+
+// Calls `Callback` with the name, the profile entry count, if any, and the
+// hotness of every function defined in `M`.
+extern "C" void
+LLVMRustModuleFunctionProfiles(LLVMModuleRef M,
+                               LLVMRustFunctionProfileCallback Callback,
+                               void *CallbackPayload) {
+  Module &Mod = *unwrap(M);
+  ProfileSummaryInfo PSI(Mod);
+  for (Function &F : Mod) {
+    if (F.isDeclaration())
+      continue;
+    auto EntryCount = F.getEntryCount();
+    StringRef Name = F.getName();
+    Callback(CallbackPayload, Name.data(), Name.size(), bool(EntryCount),
+             EntryCount ? EntryCount->getCount() : 0,
+             PSI.isFunctionEntryHot(&F));
+  }
+}
@@ -229,6 +229,8 @@ static Attribute::AttrKind fromRust(LLVMRustAttribute Kind) {
     return Attribute::NoUndef;
   case SanitizeMemTag:
     return Attribute::SanitizeMemTag;
+  case ShadowCallStack:
+    return Attribute::ShadowCallStack;
   }
   report_fatal_error("bad AttributeKind");
 }
@@ -1360,6 +1362,21 @@ extern "C" void LLVMRustFreeOperandBundleDef(OperandBundleDef *Bundle) {
   delete Bundle;
 }
 
+// Replaces the call or invoke `CallRef` with one that has an additional `kcfi` operand bundle, so
+// that the callee is checked to have the KCFI type id `TypeId`.
+extern "C" LLVMValueRef LLVMRustAddKCFIOperandBundle(LLVMValueRef CallRef, uint32_t TypeId) {
+  CallBase *Call = cast<CallBase>(unwrap(CallRef));
+  SmallVector<OperandBundleDef, 2> Bundles;
+  Call->getOperandBundlesAsDefs(Bundles);
+  Value *Id = ConstantInt::get(Type::getInt32Ty(Call->getContext()), TypeId);
+  Bundles.push_back(OperandBundleDef("kcfi", Id));
+  CallBase *NewCall = CallBase::Create(Call, Bundles, Call);
+  NewCall->copyMetadata(*Call);
+  Call->replaceAllUsesWith(NewCall);
+  Call->eraseFromParent();
+  return wrap(NewCall);
+}
+
 extern "C" LLVMValueRef LLVMRustBuildCall(LLVMBuilderRef B, LLVMTypeRef Ty, LLVMValueRef Fn,
                                           LLVMValueRef *Args, unsigned NumArgs,
                                           OperandBundleDef *Bundle) {
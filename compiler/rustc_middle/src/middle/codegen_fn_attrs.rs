@@ -91,6 +91,10 @@ pub struct CodegenFnAttrFlags: u32 {
         const NO_COVERAGE               = 1 << 15;
         /// `#[used(linker)]`: indicates that LLVM nor the linker can eliminate this function.
         const USED_LINKER               = 1 << 16;
+        /// `#[no_instrument_function]`: indicates that no calls to the hooks of
+        /// `-Z instrument-functions` or `-Z instrument-mcount` should be
+        /// inserted into the function.
+        const NO_INSTRUMENT_FUNCTION    = 1 << 17;
     }
 }
 
@@ -113,6 +113,13 @@ pub enum CoverageKind {
         rhs: ExpressionOperandId,
     },
     Unreachable,
+    /// A branch region, reporting how often the condition in its code region evaluated to `true`
+    /// and to `false`. Like expressions, branch regions are only added to the coverage map and do
+    /// not generate any code.
+    Branch {
+        true_count: ExpressionOperandId,
+        false_count: ExpressionOperandId,
+    },
 }
 
 impl CoverageKind {
@@ -122,6 +129,7 @@ pub fn as_operand_id(&self) -> ExpressionOperandId {
             Counter { id, .. } => ExpressionOperandId::from(id),
             Expression { id, .. } => ExpressionOperandId::from(id),
             Unreachable => bug!("Unreachable coverage cannot be part of an expression"),
+            Branch { .. } => bug!("Branch coverage cannot be part of an expression"),
         }
     }
 
@@ -144,6 +152,9 @@ fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
                 rhs.index(),
             ),
             Unreachable => write!(fmt, "Unreachable"),
+            Branch { true_count, false_count } => {
+                write!(fmt, "Branch(true: {}, false: {})", true_count.index(), false_count.index())
+            }
         }
     }
 }
@@ -32,6 +32,7 @@
 use rustc_span::symbol::Symbol;
 use rustc_span::{Span, DUMMY_SP};
 use rustc_target::asm::InlineAsmRegOrRegClass;
+use rustc_target::spec::abi::Abi;
 
 use either::Either;
 
@@ -268,6 +269,10 @@ pub struct Body<'tcx> {
 
     pub generator: Option<Box<GeneratorInfo<'tcx>>>,
 
+    /// Cold paths outlined from this body by the `OutlineColdPanics` pass. The body calls the
+    /// path at index `i` through a function pointer to `InstanceDef::ColdPanicShim(def_id, i)`.
+    pub outlined_cold_paths: Vec<Body<'tcx>>,
+
     /// Declarations of locals.
     ///
     /// The first local is the return value pointer, followed by `arg_count`
@@ -360,6 +365,7 @@ pub fn new(
                     generator_kind,
                 })
             }),
+            outlined_cold_paths: Vec::new(),
             local_decls,
             user_type_annotations,
             arg_count,
@@ -389,6 +395,7 @@ pub fn new_cfg_only(basic_blocks: IndexVec<BasicBlock, BasicBlockData<'tcx>>) ->
             basic_blocks,
             source_scopes: IndexVec::new(),
             generator: None,
+            outlined_cold_paths: Vec::new(),
             local_decls: IndexVec::new(),
             user_type_annotations: IndexVec::new(),
             arg_count: 0,
@@ -509,6 +516,18 @@ pub fn args_iter(&self) -> impl Iterator<Item = Local> + ExactSizeIterator {
         (1..self.arg_count + 1).map(Local::new)
     }
 
+    /// Returns the signature of a path outlined by the `OutlineColdPanics` pass: it takes the
+    /// locals it reads from its parent body as arguments and never returns.
+    pub fn outlined_cold_path_sig(&self, tcx: TyCtxt<'tcx>) -> ty::FnSig<'tcx> {
+        tcx.mk_fn_sig(
+            self.args_iter().map(|arg| self.local_decls[arg].ty),
+            tcx.types.never,
+            false,
+            hir::Unsafety::Normal,
+            Abi::Rust,
+        )
+    }
+
     /// Returns an iterator over all user-defined variables and compiler-generated temporaries (all
     /// locals that are neither arguments nor the return place).
     #[inline]
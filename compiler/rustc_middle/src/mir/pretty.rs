@@ -200,6 +200,8 @@ fn dump_file_basename<'tcx>(
             }));
             s
         }
+        // Outlined cold paths share the `DefId` of the function they were outlined from.
+        ty::InstanceDef::ColdPanicShim(_, index) => format!(".cold-panic-shim-{}", index),
         _ => String::new(),
     };
 
@@ -354,7 +354,8 @@ fn super_source_scope_data(
                         ty::InstanceDef::ReifyShim(_def_id) |
                         ty::InstanceDef::Virtual(_def_id, _) |
                         ty::InstanceDef::ClosureOnceShim { call_once: _def_id, track_caller: _ } |
-                        ty::InstanceDef::DropGlue(_def_id, None) => {}
+                        ty::InstanceDef::DropGlue(_def_id, None) |
+                        ty::InstanceDef::ColdPanicShim(_def_id, _) => {}
 
                         ty::InstanceDef::FnPtrShim(_def_id, ty) |
                         ty::InstanceDef::DropGlue(_def_id, Some(ty)) |
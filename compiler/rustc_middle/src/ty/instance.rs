@@ -94,6 +94,13 @@ pub enum InstanceDef<'tcx> {
     ///
     /// The `DefId` is for `Clone::clone`, the `Ty` is the type `T` with the builtin `Clone` impl.
     CloneShim(DefId, Ty<'tcx>),
+
+    /// A path that unconditionally ends in a call to a `#[cold]` diverging function (usually a
+    /// panic), outlined from the body of a function by the `OutlineColdPanics` MIR pass.
+    ///
+    /// The `DefId` is the function the path was outlined from, and the `u32` is the index of the
+    /// outlined body in its `Body::outlined_cold_paths`. The `substs` are those of the function.
+    ColdPanicShim(DefId, u32),
 }
 
 impl<'tcx> Instance<'tcx> {
@@ -149,7 +156,8 @@ pub fn def_id(self) -> DefId {
             | InstanceDef::Intrinsic(def_id)
             | InstanceDef::ClosureOnceShim { call_once: def_id, track_caller: _ }
             | InstanceDef::DropGlue(def_id, _)
-            | InstanceDef::CloneShim(def_id, _) => def_id,
+            | InstanceDef::CloneShim(def_id, _)
+            | InstanceDef::ColdPanicShim(def_id, _) => def_id,
         }
     }
 
@@ -165,7 +173,8 @@ pub fn def_id_if_not_guaranteed_local_codegen(self) -> Option<DefId> {
             | InstanceDef::Intrinsic(..)
             | InstanceDef::ClosureOnceShim { .. }
             | InstanceDef::DropGlue(..)
-            | InstanceDef::CloneShim(..) => None,
+            | InstanceDef::CloneShim(..)
+            | InstanceDef::ColdPanicShim(..) => None,
         }
     }
 
@@ -180,7 +189,8 @@ pub fn with_opt_param(self) -> ty::WithOptConstParam<DefId> {
             | InstanceDef::Intrinsic(def_id)
             | InstanceDef::ClosureOnceShim { call_once: def_id, track_caller: _ }
             | InstanceDef::DropGlue(def_id, _)
-            | InstanceDef::CloneShim(def_id, _) => ty::WithOptConstParam::unknown(def_id),
+            | InstanceDef::CloneShim(def_id, _)
+            | InstanceDef::ColdPanicShim(def_id, _) => ty::WithOptConstParam::unknown(def_id),
         }
     }
 
@@ -265,6 +275,7 @@ pub fn has_polymorphic_mir_body(&self) -> bool {
             | InstanceDef::FnPtrShim(..)
             | InstanceDef::DropGlue(_, Some(_)) => false,
             InstanceDef::ClosureOnceShim { .. }
+            | InstanceDef::ColdPanicShim(..)
             | InstanceDef::DropGlue(..)
             | InstanceDef::Item(_)
             | InstanceDef::Intrinsic(..)
@@ -296,6 +307,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             InstanceDef::DropGlue(_, None) => write!(f, " - shim(None)"),
             InstanceDef::DropGlue(_, Some(ty)) => write!(f, " - shim(Some({}))", ty),
             InstanceDef::CloneShim(_, ty) => write!(f, " - shim({})", ty),
+            InstanceDef::ColdPanicShim(_, index) => write!(f, " - shim(cold-panic#{})", index),
         }
     }
 }
@@ -2600,6 +2600,13 @@ fn fn_sig_for_fn_abi(
         tcx: TyCtxt<'tcx>,
         param_env: ty::ParamEnv<'tcx>,
     ) -> ty::PolyFnSig<'tcx> {
+        if let ty::InstanceDef::ColdPanicShim(def_id, index) = self.def {
+            // Outlined cold paths don't have a signature of their own, only a body.
+            let body = &tcx.optimized_mir(def_id).outlined_cold_paths[index as usize];
+            let sig = body.outlined_cold_path_sig(tcx).subst(tcx, self.substs);
+            return ty::Binder::dummy(tcx.normalize_erasing_regions(param_env, sig));
+        }
+
         let ty = self.ty(tcx, param_env);
         match *ty.kind() {
             ty::FnDef(..) => {
@@ -664,6 +664,9 @@ fn lift_to_tcx(self, tcx: TyCtxt<'tcx>) -> Option<Self::Lifted> {
             ty::InstanceDef::CloneShim(def_id, ty) => {
                 Some(ty::InstanceDef::CloneShim(def_id, tcx.lift(ty)?))
             }
+            ty::InstanceDef::ColdPanicShim(def_id, index) => {
+                Some(ty::InstanceDef::ColdPanicShim(def_id, index))
+            }
         }
     }
 }
@@ -934,6 +937,7 @@ fn try_super_fold_with<F: FallibleTypeFolder<'tcx>>(
                 CloneShim(did, ty) => {
                     CloneShim(did.try_fold_with(folder)?, ty.try_fold_with(folder)?)
                 }
+                ColdPanicShim(did, i) => ColdPanicShim(did.try_fold_with(folder)?, i),
             },
         })
     }
@@ -943,9 +947,11 @@ fn super_visit_with<V: TypeVisitor<'tcx>>(&self, visitor: &mut V) -> ControlFlow
         self.substs.visit_with(visitor)?;
         match self.def {
             Item(def) => def.visit_with(visitor),
-            VtableShim(did) | ReifyShim(did) | Intrinsic(did) | Virtual(did, _) => {
-                did.visit_with(visitor)
-            }
+            VtableShim(did)
+            | ReifyShim(did)
+            | Intrinsic(did)
+            | Virtual(did, _)
+            | ColdPanicShim(did, _) => did.visit_with(visitor),
             FnPtrShim(did, ty) | CloneShim(did, ty) => {
                 did.visit_with(visitor)?;
                 ty.visit_with(visitor)
@@ -18,6 +18,7 @@
 
 use super::fmt::DebugWithContext;
 use super::graphviz;
+use super::html;
 use super::{
     visit_results, Analysis, Direction, GenKill, GenKillAnalysis, GenKillSet, JoinSemiLattice,
     ResultsCursor, ResultsVisitor,
@@ -250,7 +251,7 @@ pub fn iterate_to_fixpoint(self) -> Results<'tcx, A>
 
         let res = write_graphviz_results(tcx, &body, &results, pass_name);
         if let Err(e) = res {
-            error!("Failed to write graphviz dataflow results: {}", e);
+            error!("Failed to write dataflow results: {}", e);
         }
 
         results
@@ -260,7 +261,8 @@ pub fn iterate_to_fixpoint(self) -> Results<'tcx, A>
 // Graphviz
 
 /// Writes a DOT file containing the results of a dataflow analysis if the user requested it via
-/// `rustc_mir` attributes.
+/// `rustc_mir` attributes or `-Z dump-mir-dataflow`, and an HTML file containing the same results
+/// if the user requested it via `-Z dump-mir-dataflow-html`.
 fn write_graphviz_results<'tcx, A>(
     tcx: TyCtxt<'tcx>,
     body: &mir::Body<'tcx>,
@@ -280,6 +282,13 @@ fn write_graphviz_results<'tcx, A>(
         return Ok(());
     };
 
+    if tcx.sess.opts.debugging_opts.dump_mir_dataflow_html && dump_enabled(tcx, A::NAME, def_id) {
+        let pass_name = pass_name.unwrap_or("-----");
+        let mut file = create_dump_file(tcx, "html", None, A::NAME, &pass_name, body.source)?;
+        let title = format!("{} ({}) for {}", A::NAME, pass_name, tcx.def_path_str(def_id));
+        html::write_results(&mut file, body, results, &title)?;
+    }
+
     let mut file = match attrs.output_path(A::NAME) {
         Some(path) => {
             debug!("printing dataflow results for {:?} to {}", def_id, path.display());
@@ -384,7 +384,13 @@ fn write_statements_and_terminator(
         body: &'a Body<'tcx>,
         block: BasicBlock,
     ) -> io::Result<()> {
-        let diffs = StateDiffCollector::run(body, block, self.results.results(), self.style);
+        let diffs = StateDiffCollector::run(
+            body,
+            block,
+            self.results.results(),
+            self.style,
+            |new, old, analysis| diff_pretty(new, old, analysis),
+        );
 
         let mut befores = diffs.before.map(|v| v.into_iter());
         let mut afters = diffs.after.into_iter();
@@ -481,14 +487,17 @@ fn write_row_with_full_state(
     }
 }
 
-struct StateDiffCollector<'a, 'tcx, A>
+/// Collects the formatted state diffs caused by each statement and the terminator of a single
+/// basic block, in the order they are visited.
+pub(super) struct StateDiffCollector<'a, 'tcx, A>
 where
     A: Analysis<'tcx>,
 {
     analysis: &'a A,
     prev_state: A::Domain,
-    before: Option<Vec<String>>,
-    after: Vec<String>,
+    diff: fn(&A::Domain, &A::Domain, &A) -> String,
+    pub(super) before: Option<Vec<String>>,
+    pub(super) after: Vec<String>,
 }
 
 impl<'a, 'tcx, A> StateDiffCollector<'a, 'tcx, A>
@@ -496,15 +505,17 @@ impl<'a, 'tcx, A> StateDiffCollector<'a, 'tcx, A>
     A: Analysis<'tcx>,
     A::Domain: DebugWithContext<A>,
 {
-    fn run(
+    pub(super) fn run(
         body: &'a mir::Body<'tcx>,
         block: BasicBlock,
         results: &'a Results<'tcx, A>,
         style: OutputStyle,
+        diff: fn(&A::Domain, &A::Domain, &A) -> String,
     ) -> Self {
         let mut collector = StateDiffCollector {
             analysis: &results.analysis,
             prev_state: results.analysis.bottom_value(body),
+            diff,
             after: vec![],
             before: (style == OutputStyle::BeforeAndAfter).then_some(vec![]),
         };
@@ -550,7 +561,7 @@ fn visit_statement_before_primary_effect(
         _location: Location,
     ) {
         if let Some(before) = self.before.as_mut() {
-            before.push(diff_pretty(state, &self.prev_state, self.analysis));
+            before.push((self.diff)(state, &self.prev_state, self.analysis));
             self.prev_state.clone_from(state)
         }
     }
@@ -561,7 +572,7 @@ fn visit_statement_after_primary_effect(
         _statement: &mir::Statement<'tcx>,
         _location: Location,
     ) {
-        self.after.push(diff_pretty(state, &self.prev_state, self.analysis));
+        self.after.push((self.diff)(state, &self.prev_state, self.analysis));
         self.prev_state.clone_from(state)
     }
 
@@ -572,7 +583,7 @@ fn visit_terminator_before_primary_effect(
         _location: Location,
     ) {
         if let Some(before) = self.before.as_mut() {
-            before.push(diff_pretty(state, &self.prev_state, self.analysis));
+            before.push((self.diff)(state, &self.prev_state, self.analysis));
             self.prev_state.clone_from(state)
         }
     }
@@ -583,7 +594,7 @@ fn visit_terminator_after_primary_effect(
         _terminator: &mir::Terminator<'tcx>,
         _location: Location,
     ) {
-        self.after.push(diff_pretty(state, &self.prev_state, self.analysis));
+        self.after.push((self.diff)(state, &self.prev_state, self.analysis));
         self.prev_state.clone_from(state)
     }
 }
@@ -0,0 +1,313 @@
+//! An interactive HTML view of dataflow results.
+//!
+//! The Graphviz diagrams in `graphviz.rs` become unreadable for large bodies, so this writes a
+//! single self-contained HTML file per body instead. Every basic block is a collapsible section
+//! listing its statements together with the state diff each of them causes, and a search box
+//! filters the blocks by their MIR or their dataflow state.
+
+use std::fmt::Write as _;
+use std::io;
+
+use rustc_graphviz as dot;
+use rustc_middle::mir::{self, BasicBlock, Body};
+
+use super::fmt::{DebugDiffWithAdapter, DebugWithAdapter, DebugWithContext};
+use super::graphviz::{OutputStyle, StateDiffCollector};
+use super::{Analysis, CallReturnPlaces, Direction, Results, ResultsRefCursor};
+
+/// Writes an HTML document showing `results` for `body`.
+pub fn write_results<'tcx, A>(
+    w: &mut impl io::Write,
+    body: &Body<'tcx>,
+    results: &Results<'tcx, A>,
+    title: &str,
+) -> io::Result<()>
+where
+    A: Analysis<'tcx>,
+    A::Domain: DebugWithContext<A>,
+{
+    let title = dot::escape_html(title);
+
+    writeln!(w, "<!DOCTYPE html>")?;
+    writeln!(w, "<html>")?;
+    writeln!(w, "<head>")?;
+    writeln!(w, r#"<meta charset="utf-8">"#)?;
+    writeln!(w, "<title>{}</title>", title)?;
+    writeln!(w, "<style>{}</style>", STYLE)?;
+    writeln!(w, "</head>")?;
+    writeln!(w, "<body>")?;
+    writeln!(w, "<h1>{}</h1>", title)?;
+    writeln!(
+        w,
+        concat!(
+            r#"<div id="toolbar">"#,
+            r#"<input id="search" type="search" placeholder="Search MIR and state...">"#,
+            r#"<button id="expand">Expand all</button>"#,
+            r#"<button id="collapse">Collapse all</button>"#,
+            r#"<span id="count"></span>"#,
+            "</div>",
+        )
+    )?;
+
+    let mut results = ResultsRefCursor::new(body, results);
+    for block in body.basic_blocks().indices() {
+        write_block(w, body, &mut results, block)?;
+    }
+
+    writeln!(w, "<script>{}</script>", SCRIPT)?;
+    writeln!(w, "</body>")?;
+    writeln!(w, "</html>")
+}
+
+fn write_block<'a, 'tcx, A>(
+    w: &mut impl io::Write,
+    body: &'a Body<'tcx>,
+    results: &mut ResultsRefCursor<'a, 'a, 'tcx, A>,
+    block: BasicBlock,
+) -> io::Result<()>
+where
+    A: Analysis<'tcx>,
+    A::Domain: DebugWithContext<A>,
+{
+    let block_data = &body[block];
+    let terminator = block_data.terminator();
+
+    write!(w, r#"<details class="block" id="bb{0}" open><summary>bb{0}"#, block.index())?;
+    if block_data.is_cleanup {
+        write!(w, r#" <span class="note">(cleanup)</span>"#)?;
+    }
+    let labels = terminator.kind.fmt_successor_labels();
+    for (i, (target, label)) in terminator.successors().zip(labels.iter()).enumerate() {
+        let sep = if i == 0 { " &rarr; " } else { ", " };
+        write!(
+            w,
+            r##"{sep}<a href="#bb{target}" title="{label}">bb{target}</a>"##,
+            sep = sep,
+            target = target.index(),
+            label = dot::escape_html(label),
+        )?;
+    }
+    writeln!(w, "</summary>")?;
+
+    writeln!(w, "<table>")?;
+    writeln!(w, "<tr><th></th><th>MIR</th><th>BEFORE</th><th>AFTER</th></tr>")?;
+
+    results.seek_to_block_start(block);
+    let block_start_state = results.get().clone();
+    write_full_state_row(w, "(on start)", results.get(), results.analysis())?;
+
+    let diffs = StateDiffCollector::run(
+        body,
+        block,
+        results.results(),
+        OutputStyle::BeforeAndAfter,
+        |new, old, analysis| diff_html(new, old, analysis),
+    );
+
+    let mut befores = diffs.before.expect("`BeforeAndAfter` collects both diffs").into_iter();
+    let mut afters = diffs.after.into_iter();
+
+    let next_in_dataflow_order = |it: &mut std::vec::IntoIter<_>| {
+        if A::Direction::is_forward() { it.next().unwrap() } else { it.next_back().unwrap() }
+    };
+
+    for (i, statement) in block_data.statements.iter().enumerate() {
+        let before = next_in_dataflow_order(&mut befores);
+        let after = next_in_dataflow_order(&mut afters);
+        write_diff_row(w, &i.to_string(), &format!("{:?}", statement), &before, &after)?;
+    }
+
+    let before = next_in_dataflow_order(&mut befores);
+    let after = next_in_dataflow_order(&mut afters);
+    assert!(befores.is_empty() && afters.is_empty());
+
+    let mut terminator_str = String::new();
+    terminator.kind.fmt_head(&mut terminator_str).unwrap();
+    write_diff_row(w, "T", &terminator_str, &before, &after)?;
+
+    results.seek_to_block_end(block);
+    if results.get() != &block_start_state || A::Direction::is_backward() {
+        let after_terminator_name = match terminator.kind {
+            mir::TerminatorKind::Call { destination: Some(_), .. } => "(on unwind)",
+            _ => "(on end)",
+        };
+        write_full_state_row(w, after_terminator_name, results.get(), results.analysis())?;
+    }
+
+    // Effects that only apply along one of the outgoing edges, see the corresponding FIXME in
+    // `graphviz.rs`.
+    let (name, return_places) = match terminator.kind {
+        mir::TerminatorKind::Call { destination: Some((return_place, _)), .. } => {
+            ("(on successful return)", Some(CallReturnPlaces::Call(return_place)))
+        }
+        mir::TerminatorKind::InlineAsm { destination: Some(_), ref operands, .. } => {
+            ("(on successful return)", Some(CallReturnPlaces::InlineAsm(operands)))
+        }
+        mir::TerminatorKind::Yield { .. } => ("(on yield resume)", None),
+        _ => {
+            writeln!(w, "</table>")?;
+            return writeln!(w, "</details>");
+        }
+    };
+
+    let state_before_edge = results.get().clone();
+    results.apply_custom_effect(|analysis, state| match (&terminator.kind, return_places) {
+        (_, Some(return_places)) => analysis.apply_call_return_effect(state, block, return_places),
+        (mir::TerminatorKind::Yield { resume, resume_arg, .. }, None) => {
+            analysis.apply_yield_resume_effect(state, *resume, *resume_arg)
+        }
+        _ => unreachable!(),
+    });
+    let diff = diff_html(results.get(), &state_before_edge, results.analysis());
+    write!(w, r#"<tr class="edge"><td></td><td>{}</td><td colspan="2">"#, name)?;
+    writeln!(w, r#"<span class="diff">{}</span></td></tr>"#, diff)?;
+
+    writeln!(w, "</table>")?;
+    writeln!(w, "</details>")
+}
+
+fn write_diff_row(
+    w: &mut impl io::Write,
+    i: &str,
+    mir: &str,
+    before: &str,
+    after: &str,
+) -> io::Result<()> {
+    writeln!(
+        w,
+        concat!(
+            r#"<tr><td class="index">{i}</td><td class="mir">{mir}</td>"#,
+            r#"<td class="diff">{before}</td><td class="diff">{after}</td></tr>"#,
+        ),
+        i = i,
+        mir = dot::escape_html(mir),
+        before = before,
+        after = after,
+    )
+}
+
+fn write_full_state_row<'tcx, A>(
+    w: &mut impl io::Write,
+    name: &str,
+    state: &A::Domain,
+    analysis: &A,
+) -> io::Result<()>
+where
+    A: Analysis<'tcx>,
+    A::Domain: DebugWithContext<A>,
+{
+    let state = format!("{:?}", DebugWithAdapter { this: state, ctxt: analysis });
+    writeln!(
+        w,
+        r#"<tr class="state"><td></td><td>{}</td><td colspan="2">{}</td></tr>"#,
+        name,
+        dot::escape_html(&state),
+    )
+}
+
+/// Like `graphviz::diff_pretty`, but escapes the state and marks additions and removals with
+/// CSS classes instead of Graphviz `<font>` tags.
+fn diff_html<T, C>(new: T, old: T, ctxt: &C) -> String
+where
+    T: DebugWithContext<C>,
+{
+    if new == old {
+        return String::new();
+    }
+
+    let raw_diff = format!("{:#?}", DebugDiffWithAdapter { new, old, ctxt });
+
+    // Each change is introduced by a "Unit Separator" followed by `+` or `-`, see
+    // `DebugWithContext::fmt_diff_with`.
+    let mut html_diff = String::new();
+    let mut inside_span = false;
+    for (i, part) in dot::escape_html(&raw_diff).split('\u{001f}').enumerate() {
+        let class = match part.as_bytes().first() {
+            _ if i == 0 => None,
+            Some(b'+') => Some("add"),
+            Some(b'-') => Some("remove"),
+            _ => None,
+        };
+
+        if let Some(class) = class {
+            if inside_span {
+                html_diff.push_str("</span>");
+            }
+            write!(html_diff, r#"<span class="{}">"#, class).unwrap();
+            inside_span = true;
+        }
+
+        html_diff.push_str(part.trim_end_matches('\t'));
+    }
+
+    if inside_span {
+        html_diff.push_str("</span>");
+    }
+
+    html_diff
+}
+
+const STYLE: &str = r#"
+body { font-family: sans-serif; margin: 1em; }
+#toolbar { position: sticky; top: 0; background: white; padding: 0.5em 0; z-index: 1; }
+#search { width: 30em; }
+#count { margin-left: 1em; color: #606060; }
+details.block { border: 1px solid #a0a0a0; margin: 0.5em 0; }
+details.block > summary { background: #e0e0e0; padding: 0.2em 0.5em; font-family: monospace; cursor: pointer; }
+details.block.hidden { display: none; }
+table { border-collapse: collapse; width: 100%; font-family: monospace; }
+th { background: #f0f0f0; text-align: left; }
+td, th { border-top: 1px solid #e0e0e0; padding: 0.1em 0.5em; vertical-align: top; white-space: pre-wrap; }
+td.index { text-align: right; width: 2em; color: #606060; }
+tr.state td, tr.edge td { color: #404040; background: #f8f8f8; }
+tr.match td { background: #fff3b0; }
+.note { color: #606060; }
+.add { color: darkgreen; }
+.remove { color: red; }
+"#;
+
+const SCRIPT: &str = r#"
+(function() {
+    var blocks = Array.prototype.slice.call(document.querySelectorAll("details.block"));
+    var search = document.getElementById("search");
+    var count = document.getElementById("count");
+
+    function filter() {
+        var query = search.value.toLowerCase();
+        var shown = 0;
+        blocks.forEach(function(block) {
+            var rows = block.querySelectorAll("tr");
+            var found = query === "" || block.querySelector("summary").textContent
+                .toLowerCase().indexOf(query) !== -1;
+            Array.prototype.forEach.call(rows, function(row) {
+                var matches = query !== "" && row.textContent.toLowerCase().indexOf(query) !== -1;
+                row.classList.toggle("match", matches);
+                found = found || matches;
+            });
+            block.classList.toggle("hidden", !found);
+            if (found) {
+                shown += 1;
+                if (query !== "") {
+                    block.open = true;
+                }
+            }
+        });
+        count.textContent = query === "" ? "" : shown + " of " + blocks.length + " blocks";
+    }
+
+    search.addEventListener("input", filter);
+    document.getElementById("expand").addEventListener("click", function() {
+        blocks.forEach(function(block) { block.open = true; });
+    });
+    document.getElementById("collapse").addEventListener("click", function() {
+        blocks.forEach(function(block) { block.open = false; });
+    });
+    window.addEventListener("hashchange", function() {
+        var block = document.getElementById(window.location.hash.substring(1));
+        if (block) {
+            block.classList.remove("hidden");
+            block.open = true;
+        }
+    });
+})();
+"#;
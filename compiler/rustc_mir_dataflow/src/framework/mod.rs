@@ -42,6 +42,7 @@
 mod engine;
 pub mod fmt;
 pub mod graphviz;
+pub mod html;
 pub mod lattice;
 mod visitor;
 
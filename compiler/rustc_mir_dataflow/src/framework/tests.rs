@@ -8,6 +8,7 @@
 use rustc_middle::ty;
 use rustc_span::DUMMY_SP;
 
+use super::fmt::DebugWithContext;
 use super::*;
 
 /// Creates a `mir::Body` with a few disconnected basic blocks.
@@ -318,3 +319,51 @@ fn forward_cursor() {
     let analysis = MockAnalysis { body, dir: PhantomData::<Forward> };
     test_cursor(analysis)
 }
+
+impl<C> DebugWithContext<C> for usize {}
+
+#[test]
+fn html_results() {
+    let body = mock_body();
+    let analysis = MockAnalysis { body: &body, dir: PhantomData::<Forward> };
+    let results = Results { entry_sets: analysis.mock_entry_sets(), analysis };
+
+    let mut html = Vec::new();
+    html::write_results(&mut html, &body, &results, "mock <forward>").unwrap();
+    let html = String::from_utf8(html).unwrap();
+
+    assert!(html.contains("<title>mock &lt;forward&gt;</title>"));
+    for block in body.basic_blocks().indices() {
+        let summary =
+            format!(r#"<details class="block" id="bb{0}" open><summary>bb{0}"#, block.index());
+        assert!(html.contains(&summary), "no section for {:?}", block);
+    }
+
+    // See the table in the documentation of `MockAnalysis` for the expected state of `bb2`.
+    let bb2 = html.split(r#"id="bb2""#).nth(1).unwrap().split("</details>").next().unwrap();
+    let expected = [
+        r##" open><summary>bb2 &rarr; <a href="#bb0" title="return">bb0</a></summary>"##,
+        r#"<tr class="state"><td></td><td>(on start)</td><td colspan="2">{102}</td></tr>"#,
+        concat!(
+            r#"<tr><td class="index">0</td><td class="mir">nop</td>"#,
+            r#"<td class="diff"><span class="add">+0</span></td>"#,
+            r#"<td class="diff"><span class="add">+1</span></td></tr>"#,
+        ),
+        concat!(
+            r#"<tr><td class="index">T</td><td class="mir">_0 = _0()</td>"#,
+            r#"<td class="diff"><span class="add">+4</span></td>"#,
+            r#"<td class="diff"><span class="add">+5</span></td></tr>"#,
+        ),
+        concat!(
+            r#"<tr class="state"><td></td><td>(on unwind)</td>"#,
+            r#"<td colspan="2">{0, 1, 2, 3, 4, 5, 102}</td></tr>"#,
+        ),
+        concat!(
+            r#"<tr class="edge"><td></td><td>(on successful return)</td>"#,
+            r#"<td colspan="2"><span class="diff"></span></td></tr>"#,
+        ),
+    ];
+    for line in expected {
+        assert!(bb2.contains(line), "missing `{}` in:\n{}", line, bb2);
+    }
+}
@@ -0,0 +1,350 @@
+//! Branch regions for `-C instrument-coverage=branch`.
+//!
+//! MIR does not say which `SwitchInt` tests which operand of a `&&` or `||` expression: in value
+//! context, a logical operator tests its left operand with a `SwitchInt` that has the span of the
+//! whole operator, and its right operand is never tested on its own. So the conditions are
+//! collected from the HIR, and `SwitchInt`s are matched to them by source range. Counts that are
+//! not directly available from a `SwitchInt` are derived from the value of the whole operator:
+//!
+//! * `lhs && rhs`: `rhs` is `true` as often as the operator, and `false` as often as the operator
+//!   minus the times `lhs` was `false`.
+//! * `lhs || rhs`: `rhs` is `false` as often as the operator, and `true` as often as the operator
+//!   minus the times `lhs` was `true`.
+//!
+//! Branch regions are only reported for conditions that are not themselves `&&` or `||`
+//! expressions, like clang does for C and C++.
+//!
+//! A `match` can test its scrutinee with any number of `SwitchInt`s that don't correspond to single
+//! arms, so the arms of a `match` are not matched to tests. Instead, an arm is taken as often as
+//! its body is entered from outside of it, and not taken as often as the other arms are taken. The
+//! guards of match arms are reported like any other condition.
+
+use super::counters::CoverageCounters;
+use super::graph::{BasicCoverageBlock, BcbBranch, CoverageGraph};
+
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
+use rustc_hir as hir;
+use rustc_hir::intravisit::{self, Visitor};
+use rustc_middle::mir::coverage::*;
+use rustc_middle::mir::{self, BasicBlock, StatementKind, TerminatorKind};
+use rustc_span::{BytePos, Span};
+
+/// The source range of a condition. The MIR spans of conditions can have a different syntax
+/// context than the HIR expression (`if` conditions are marked as desugared, for example), so
+/// conditions are matched by their source range only.
+type Range = (BytePos, BytePos);
+
+fn range(span: Span) -> Range {
+    (span.lo(), span.hi())
+}
+
+/// How often a condition evaluated to `true` and to `false`.
+#[derive(Clone, Copy, Debug)]
+struct Counts {
+    true_count: ExpressionOperandId,
+    false_count: ExpressionOperandId,
+}
+
+/// A `&&` or `||` expression.
+struct LogicalOp {
+    lhs: Range,
+    rhs: Range,
+    is_and: bool,
+}
+
+/// The boolean conditions of a function body, collected from the HIR.
+#[derive(Default)]
+struct Conditions {
+    /// Conditions that are not themselves `&&` or `||` expressions.
+    leaves: FxIndexMap<Range, Span>,
+    logical_ops: FxIndexMap<Range, LogicalOp>,
+    /// The arms of `match` expressions, as the spans of their patterns and the source ranges of
+    /// their bodies.
+    matches: Vec<Vec<(Span, Range)>>,
+}
+
+impl Conditions {
+    fn add_condition(&mut self, cond: &hir::Expr<'_>) {
+        let cond = peel_drop_temps(cond);
+        match cond.kind {
+            // The operands are added when the operator itself is visited.
+            hir::ExprKind::Binary(op, ..) if is_logical(op) => {}
+            // `let` conditions are pattern matches, which are not reported as branches.
+            hir::ExprKind::Let(..) => {}
+            _ if cond.span.from_expansion() => {}
+            _ => {
+                self.leaves.insert(range(cond.span), cond.span);
+            }
+        }
+    }
+}
+
+impl<'hir> Visitor<'hir> for Conditions {
+    fn visit_expr(&mut self, expr: &'hir hir::Expr<'hir>) {
+        match expr.kind {
+            hir::ExprKind::If(cond, ..) => self.add_condition(cond),
+            hir::ExprKind::Binary(op, lhs, rhs) if is_logical(op) => {
+                let (lhs, rhs) = (peel_drop_temps(lhs), peel_drop_temps(rhs));
+                self.logical_ops.insert(
+                    range(expr.span),
+                    LogicalOp {
+                        lhs: range(lhs.span),
+                        rhs: range(rhs.span),
+                        is_and: op.node == hir::BinOpKind::And,
+                    },
+                );
+                self.add_condition(lhs);
+                self.add_condition(rhs);
+            }
+            hir::ExprKind::Match(_, arms, source) => {
+                for arm in arms {
+                    if let Some(hir::Guard::If(guard)) = arm.guard {
+                        self.add_condition(guard);
+                    }
+                }
+                // Desugared matches, like the ones of `for` loops and `?`, are not reported.
+                if source == hir::MatchSource::Normal && !expr.span.from_expansion() {
+                    self.matches.push(
+                        arms.iter().map(|arm| (arm.pat.span, range(arm.body.span))).collect(),
+                    );
+                }
+            }
+            _ => {}
+        }
+        intravisit::walk_expr(self, expr);
+    }
+}
+
+fn is_logical(op: hir::BinOp) -> bool {
+    matches!(op.node, hir::BinOpKind::And | hir::BinOpKind::Or)
+}
+
+fn peel_drop_temps<'a, 'hir>(mut expr: &'a hir::Expr<'hir>) -> &'a hir::Expr<'hir> {
+    while let hir::ExprKind::DropTemps(inner) = expr.kind {
+        expr = inner;
+    }
+    expr
+}
+
+/// Computes the branch regions of all conditions in `hir_body`, using the counters that
+/// `CoverageCounters::make_bcb_counters()` assigned to the branches of the `CoverageGraph`. Must
+/// be called before the counters are taken from the graph to be injected.
+///
+/// Returns `CoverageKind::Branch`es with the span of their condition. Expressions that were
+/// created to derive counts are added to `intermediate_expressions`.
+pub(super) fn make_branch_regions<'tcx>(
+    mir_body: &mir::Body<'tcx>,
+    hir_body: &'tcx hir::Body<'tcx>,
+    basic_coverage_blocks: &CoverageGraph,
+    coverage_counters: &mut CoverageCounters,
+    intermediate_expressions: &mut Vec<CoverageKind>,
+) -> Vec<(CoverageKind, Span)> {
+    let mut conditions = Conditions::default();
+    conditions.visit_body(hir_body);
+    if conditions.leaves.is_empty() && conditions.matches.is_empty() {
+        return Vec::new();
+    }
+
+    // Collect the `SwitchInt`s on booleans, grouped by source range.
+    let mut tests: FxIndexMap<Range, Vec<(BasicCoverageBlock, Counts)>> = FxIndexMap::default();
+    for (bcb, bcb_data) in basic_coverage_blocks.iter_enumerated() {
+        let terminator = bcb_data.terminator(mir_body);
+        let TerminatorKind::SwitchInt { switch_ty, ref targets, .. } = terminator.kind else {
+            continue;
+        };
+        if !switch_ty.is_bool() {
+            continue;
+        }
+        let mut values = targets.iter();
+        let (Some((value, value_bb)), None) = (values.next(), values.next()) else {
+            continue;
+        };
+        let (true_bb, false_bb) = if value == 0 {
+            (targets.otherwise(), value_bb)
+        } else {
+            (value_bb, targets.otherwise())
+        };
+        if let Some(counts) = branch_counts(basic_coverage_blocks, bcb, true_bb, false_bb) {
+            tests.entry(range(terminator.source_info.span)).or_default().push((bcb, counts));
+        }
+    }
+
+    let mut make_expression = |lhs, op, rhs| {
+        let expression = coverage_counters.make_expression(lhs, op, rhs, || None);
+        let operand = expression.as_operand_id();
+        intermediate_expressions.push(expression);
+        operand
+    };
+
+    // Conditions tested directly. A condition can be tested in more than one place, e.g. a match
+    // guard of an arm with an or-pattern.
+    let mut counts: FxHashMap<Range, Counts> = FxHashMap::default();
+    for (cond, cond_tests) in &tests {
+        if !conditions.leaves.contains_key(cond) {
+            continue;
+        }
+        let mut cond_tests = cond_tests.iter().map(|&(_, counts)| counts);
+        let first = cond_tests.next().unwrap();
+        let sum = cond_tests.fold(first, |sum, test| Counts {
+            true_count: make_expression(sum.true_count, Op::Add, test.true_count),
+            false_count: make_expression(sum.false_count, Op::Add, test.false_count),
+        });
+        counts.insert(*cond, sum);
+    }
+
+    // Operands of logical operators. Visit outer operators first: the value of an operator that is
+    // an operand itself is only known after visiting the operator it is an operand of.
+    let mut logical_ops = conditions.logical_ops.iter().collect::<Vec<_>>();
+    logical_ops.sort_by_key(|&(&(lo, hi), _)| (lo, std::cmp::Reverse(hi)));
+    for (op_range, op) in logical_ops {
+        // The left operand is tested first, so its test dominates the test of the whole
+        // operator, if there is one.
+        let is_dominated_by = |node, dom| basic_coverage_blocks.is_dominated_by(node, dom);
+        let (lhs_test, value_test) = match tests.get(op_range).map_or(&[][..], |t| &t[..]) {
+            &[(_, lhs)] => (lhs, None),
+            &[(a_bcb, a), (b_bcb, b)] if is_dominated_by(b_bcb, a_bcb) => (a, Some(b)),
+            &[(a_bcb, a), (b_bcb, b)] if is_dominated_by(a_bcb, b_bcb) => (b, Some(a)),
+            _ => continue,
+        };
+        if let Some(value_test) = value_test {
+            counts.entry(*op_range).or_insert(value_test);
+        }
+        counts.entry(op.lhs).or_insert(lhs_test);
+
+        let Some(&value) = counts.get(op_range) else { continue };
+        counts.entry(op.rhs).or_insert_with(|| {
+            if op.is_and {
+                Counts {
+                    true_count: value.true_count,
+                    false_count: make_expression(
+                        value.false_count,
+                        Op::Subtract,
+                        lhs_test.false_count,
+                    ),
+                }
+            } else {
+                Counts {
+                    true_count: make_expression(
+                        value.true_count,
+                        Op::Subtract,
+                        lhs_test.true_count,
+                    ),
+                    false_count: value.false_count,
+                }
+            }
+        });
+    }
+
+    let mut branch_regions = conditions
+        .leaves
+        .iter()
+        .filter_map(|(cond, &span)| {
+            let Counts { true_count, false_count } = *counts.get(cond)?;
+            Some((CoverageKind::Branch { true_count, false_count }, span))
+        })
+        .collect::<Vec<_>>();
+
+    // Match arms. The counts of all arms are needed to know how often an arm is not taken, so a
+    // `match` gets no branch regions if the entries of one of its arms can't be counted.
+    for arms in &conditions.matches {
+        let Some(arm_entries) = arms
+            .iter()
+            .map(|&(_, body)| arm_entries(mir_body, basic_coverage_blocks, body))
+            .collect::<Option<Vec<_>>>()
+        else {
+            continue;
+        };
+        let arm_counts = arm_entries
+            .into_iter()
+            .map(|entries| {
+                let (first, rest) = entries.split_first().unwrap();
+                rest.iter().fold(*first, |sum, &entry| make_expression(sum, Op::Add, entry))
+            })
+            .collect::<Vec<_>>();
+        let (first, rest) = arm_counts.split_first().unwrap();
+        let total = rest.iter().fold(*first, |sum, &count| make_expression(sum, Op::Add, count));
+        for (&(pat_span, _), &true_count) in arms.iter().zip(&arm_counts) {
+            let false_count = make_expression(total, Op::Subtract, true_count);
+            branch_regions.push((CoverageKind::Branch { true_count, false_count }, pat_span));
+        }
+    }
+    branch_regions.sort_by_key(|&(_, span)| range(span));
+    branch_regions
+}
+
+/// Returns the counters of the edges that enter the body of a match arm, from blocks that are not
+/// part of the body, if there is at least one such edge and all of them have a counter.
+fn arm_entries(
+    mir_body: &mir::Body<'_>,
+    basic_coverage_blocks: &CoverageGraph,
+    body: Range,
+) -> Option<Vec<ExpressionOperandId>> {
+    let in_body = |bcb: BasicCoverageBlock| {
+        basic_coverage_blocks[bcb].basic_blocks.iter().any(|&bb| {
+            let data = &mir_body[bb];
+            // The blocks that leave an arm when its guard is false end the storage of the arm's
+            // bindings at the end of its body.
+            data.statements
+                .iter()
+                .filter(|statement| {
+                    !matches!(
+                        statement.kind,
+                        StatementKind::StorageLive(_) | StatementKind::StorageDead(_)
+                    )
+                })
+                .map(|statement| statement.source_info.span)
+                .chain(std::iter::once(data.terminator().source_info.span))
+                .any(|span| {
+                    let (lo, hi) = range(span.source_callsite());
+                    body.0 <= lo && hi <= body.1
+                })
+        })
+    };
+
+    let mut entries = Vec::new();
+    for (to_bcb, _) in basic_coverage_blocks.iter_enumerated() {
+        if !in_body(to_bcb) {
+            continue;
+        }
+        for &from_bcb in &basic_coverage_blocks.predecessors[to_bcb] {
+            if in_body(from_bcb) {
+                continue;
+            }
+            let branch = BcbBranch::from_to(from_bcb, to_bcb, basic_coverage_blocks);
+            let counter = match branch.counter(basic_coverage_blocks) {
+                Some(counter) => counter,
+                // Edges from blocks with a single successor have no counter of their own.
+                None if basic_coverage_blocks.successors[from_bcb].len() == 1 => {
+                    basic_coverage_blocks[from_bcb].counter()?
+                }
+                None => return None,
+            };
+            entries.push(counter.as_operand_id());
+        }
+    }
+    if entries.is_empty() { None } else { Some(entries) }
+}
+
+/// Returns the counters of the edges from `from_bcb` to the blocks taken when the condition is
+/// `true` and `false`, if both have one.
+fn branch_counts(
+    basic_coverage_blocks: &CoverageGraph,
+    from_bcb: BasicCoverageBlock,
+    true_bb: BasicBlock,
+    false_bb: BasicBlock,
+) -> Option<Counts> {
+    let branch_counter = |bb| {
+        let to_bcb = basic_coverage_blocks.bcb_from_bb(bb)?;
+        if basic_coverage_blocks[to_bcb].leader_bb() != bb {
+            return None;
+        }
+        let branch = BcbBranch::from_to(from_bcb, to_bcb, basic_coverage_blocks);
+        Some((to_bcb, branch.counter(basic_coverage_blocks)?.as_operand_id()))
+    };
+    let (true_bcb, true_count) = branch_counter(true_bb)?;
+    let (false_bcb, false_count) = branch_counter(false_bb)?;
+    if true_bcb == false_bcb {
+        return None;
+    }
+    Some(Counts { true_count, false_count })
+}
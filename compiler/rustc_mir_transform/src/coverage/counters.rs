@@ -63,7 +63,7 @@ fn make_counter<F>(&mut self, debug_block_label_fn: F) -> CoverageKind
         counter
     }
 
-    fn make_expression<F>(
+    pub fn make_expression<F>(
         &mut self,
         lhs: ExpressionOperandId,
         op: Op,
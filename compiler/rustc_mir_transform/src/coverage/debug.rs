@@ -307,6 +307,11 @@ pub fn format_counter(&self, counter_kind: &CoverageKind) -> String {
                 format!("Expression({})", self.format_counter_kind(counter_kind))
             }
             CoverageKind::Unreachable { .. } => "Unreachable".to_owned(),
+            CoverageKind::Branch { true_count, false_count } => format!(
+                "Branch(true: {}, false: {})",
+                self.format_operand(true_count),
+                self.format_operand(false_count),
+            ),
         }
     }
 
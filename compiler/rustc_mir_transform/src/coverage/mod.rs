@@ -1,5 +1,6 @@
 pub mod query;
 
+mod branches;
 mod counters;
 mod debug;
 mod graph;
@@ -205,8 +206,24 @@ fn inject_counters(&'a mut self) {
             .coverage_counters
             .make_bcb_counters(&mut self.basic_coverage_blocks, &coverage_spans);
 
+        let mut branch_regions = Vec::new();
         let (result, intermediate_expressions) = match intermediate_expressions_or_error {
-            Ok(intermediate_expressions) => {
+            Ok(mut intermediate_expressions) => {
+                ////////////////////////////////////////////////////
+                // With `-C instrument-coverage=branch`, look up the counters of both branches of
+                // every boolean condition, before the counters are removed from the
+                // `CoverageGraph` to be injected below.
+                if tcx.sess.instrument_coverage_branch() {
+                    let (_, hir_body) = fn_sig_and_body(tcx, def_id);
+                    branch_regions = branches::make_branch_regions(
+                        self.mir_body,
+                        hir_body,
+                        &self.basic_coverage_blocks,
+                        &mut self.coverage_counters,
+                        &mut intermediate_expressions,
+                    );
+                }
+
                 // If debugging, add any intermediate expressions (which are not associated with any
                 // BCB) to the `debug_used_expressions` map.
                 if debug_used_expressions.is_enabled() {
@@ -277,6 +294,10 @@ fn inject_counters(&'a mut self) {
         for intermediate_expression in intermediate_expressions {
             inject_intermediate_expression(self.mir_body, intermediate_expression);
         }
+
+        ////////////////////////////////////////////////////
+        // And the branch regions, which can refer to any counter or expression.
+        self.inject_branch_regions(branch_regions);
     }
 
     /// Inject a counter for each `CoverageSpan`. There can be multiple `CoverageSpan`s for a given
@@ -330,6 +351,32 @@ fn inject_coverage_span_counters(
         }
     }
 
+    /// Inject a `Coverage` statement for each branch region. Like intermediate expressions, branch
+    /// regions are only added to the coverage map, without generating executable code.
+    fn inject_branch_regions(&mut self, branch_regions: Vec<(CoverageKind, Span)>) {
+        let source_map = self.tcx.sess.source_map();
+        let body_span = self.body_span;
+        let file_name = Symbol::intern(&self.source_file.name.prefer_remapped().to_string_lossy());
+
+        for (branch, span) in branch_regions {
+            if !body_span.contains(span) {
+                continue;
+            }
+            let code_region =
+                make_code_region(source_map, file_name, &self.source_file, span, body_span);
+            debug!("  injecting branch region {:?} at code region: {:?}", branch, code_region);
+            let data = &mut self.mir_body[mir::START_BLOCK];
+            let source_info = data.terminator().source_info;
+            data.statements.push(Statement {
+                source_info,
+                kind: StatementKind::Coverage(Box::new(Coverage {
+                    kind: branch,
+                    code_region: Some(code_region),
+                })),
+            });
+        }
+    }
+
     /// `inject_coverage_span_counters()` looped through the `CoverageSpan`s and injected the
     /// counter from the `CoverageSpan`s `BasicCoverageBlock`, removing it from the BCB in the
     /// process (via `take_counter()`).
@@ -203,7 +203,8 @@ fn check_mir_is_available(
             | InstanceDef::FnPtrShim(..)
             | InstanceDef::ClosureOnceShim { .. }
             | InstanceDef::DropGlue(..)
-            | InstanceDef::CloneShim(..) => return Ok(()),
+            | InstanceDef::CloneShim(..)
+            | InstanceDef::ColdPanicShim(..) => return Ok(()),
         }
 
         if self.tcx.is_constructor(callee_def_id) {
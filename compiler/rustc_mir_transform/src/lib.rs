@@ -69,6 +69,7 @@
 mod multiple_return_terminators;
 mod normalize_array_len;
 mod nrvo;
+mod outline_cold_panics;
 // This pass is public to allow external drivers to perform MIR cleanup
 pub mod remove_false_edges;
 mod remove_noop_landing_pads;
@@ -492,6 +493,7 @@ fn o1<T>(x: T) -> WithMinOptLevel<T> {
             &o1(simplify_branches::SimplifyConstCondition::new("final")),
             &o1(remove_noop_landing_pads::RemoveNoopLandingPads),
             &o1(simplify::SimplifyCfg::new("final")),
+            &outline_cold_panics::OutlineColdPanics,
             &nrvo::RenameReturnPlace,
             &const_debuginfo::ConstDebugInfo,
             &simplify::SimplifyLocals,
@@ -0,0 +1,366 @@
+//! Moves paths that unconditionally end in a panic out of the function that contains them.
+//!
+//! A failing `unwrap`, `expect` or `panic!` with formatting arguments is often lowered to a
+//! handful of blocks that build the panic message and then call a `#[cold]` diverging function
+//! such as `core::panicking::panic_fmt`. This pass finds such paths, moves them into a body of
+//! their own that is stored in `Body::outlined_cold_paths`, and replaces them with a call to
+//! that body through an `InstanceDef::ColdPanicShim`. Codegen emits the shim as a separate
+//! `cold` function that is never inlined, which keeps the hot path of the function small.
+//!
+//! Only monomorphic functions are handled, as the call to the shim goes through a constant
+//! function pointer, which cannot refer to a generic instance. `#[track_caller]` functions are
+//! skipped, as the caller location they pass on is an argument of the function itself.
+//! `Assert` terminators (bounds and overflow checks) are left alone, since they are already
+//! lowered to a single call with no formatting of their own.
+
+use crate::required_consts::RequiredConstsVisitor;
+use crate::simplify::remove_dead_blocks;
+use crate::MirPass;
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::{Idx, IndexVec};
+use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrFlags;
+use rustc_middle::mir::interpret::{ConstValue, Pointer, Scalar};
+use rustc_middle::mir::visit::{MutVisitor, NonUseContext, PlaceContext, Visitor};
+use rustc_middle::mir::*;
+use rustc_middle::ty::{self, Instance, TyCtxt};
+use rustc_target::abi::Size;
+
+pub struct OutlineColdPanics;
+
+impl<'tcx> MirPass<'tcx> for OutlineColdPanics {
+    fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
+        sess.opts.debugging_opts.outline_cold_panics
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        let def_id = body.source.def_id();
+        if body.source.promoted.is_some()
+            || body.generator.is_some()
+            || tcx.generics_of(def_id).requires_monomorphization(tcx)
+            || body.source.instance.requires_caller_location(tcx)
+            || tcx.codegen_fn_attrs(def_id).flags.contains(CodegenFnAttrFlags::NAKED)
+        {
+            return;
+        }
+
+        let regions = find_cold_regions(tcx, body);
+        if regions.is_empty() {
+            return;
+        }
+
+        let mut region_of = IndexVec::from_elem(None, body.basic_blocks());
+        for (index, region) in regions.iter().enumerate() {
+            for &bb in region {
+                region_of[bb] = Some(index);
+            }
+        }
+        let mut collector = LocalUseCollector {
+            region_of: &region_of,
+            current_region: None,
+            owner: IndexVec::from_elem(None, &body.local_decls),
+            shared: BitSet::new_empty(body.local_decls.len()),
+        };
+        collector.visit_body(body);
+        // The return place and the arguments are also used by the caller.
+        for local in (0..=body.arg_count).map(Local::new) {
+            collector.shared.insert(local);
+        }
+        let shared = collector.shared;
+
+        let param_env = tcx.param_env_reveal_all_normalized(def_id);
+        let substs = Instance::mono(tcx, def_id).substs;
+        let mut outlined = 0;
+        for region in regions {
+            let Some(live_ins) = live_ins(tcx, param_env, body, &region, &shared) else {
+                continue;
+            };
+            let index = body.outlined_cold_paths.len() as u32;
+            let instance = Instance { def: ty::InstanceDef::ColdPanicShim(def_id, index), substs };
+            let shim = build_shim(tcx, body, instance.def, &region, &live_ins);
+            debug!("outlining {:?} of {:?} into {}", region, def_id, instance);
+
+            let entry = region[0];
+            let exit = *region.last().unwrap();
+            let terminator = body[exit].terminator();
+            let source_info = terminator.source_info;
+            let TerminatorKind::Call { cleanup, fn_span, .. } = terminator.kind else {
+                bug!("cold region does not end in a call")
+            };
+
+            let fn_ptr_ty = tcx.mk_fn_ptr(ty::Binder::dummy(shim.outlined_cold_path_sig(tcx)));
+            let fn_ptr = Scalar::from_pointer(
+                Pointer::new(tcx.create_fn_alloc(instance), Size::ZERO),
+                &tcx,
+            );
+            let func = Operand::Constant(Box::new(Constant {
+                span: source_info.span,
+                user_ty: None,
+                literal: ConstantKind::Val(ConstValue::Scalar(fn_ptr), fn_ptr_ty),
+            }));
+
+            let entry_data = &mut body.basic_blocks_mut()[entry];
+            entry_data.statements.clear();
+            entry_data.terminator = Some(Terminator {
+                source_info,
+                kind: TerminatorKind::Call {
+                    func,
+                    args: live_ins.iter().map(|&local| Operand::Copy(local.into())).collect(),
+                    destination: None,
+                    cleanup,
+                    from_hir_call: false,
+                    fn_span,
+                },
+            });
+
+            body.outlined_cold_paths.push(shim);
+            outlined += 1;
+        }
+
+        if outlined > 0 {
+            remove_dead_blocks(tcx, body);
+            for shim in &body.outlined_cold_paths {
+                pretty::dump_mir(tcx, None, &self.name(), &"after", shim, |_, _| Ok(()));
+            }
+        }
+    }
+}
+
+/// Returns the blocks of every path that ends in a call to a cold diverging function and can
+/// only be entered through its first block, in execution order.
+fn find_cold_regions<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>) -> Vec<Vec<BasicBlock>> {
+    let predecessors = body.predecessors();
+    let mut regions = Vec::new();
+    for (bb, data) in body.basic_blocks().iter_enumerated() {
+        if data.is_cleanup {
+            continue;
+        }
+        let TerminatorKind::Call { ref func, destination: None, cleanup, .. } =
+            data.terminator().kind else {
+            continue;
+        };
+        let Some((callee, _)) = func.const_fn_def() else { continue };
+        if !tcx.codegen_fn_attrs(callee).flags.contains(CodegenFnAttrFlags::COLD) {
+            continue;
+        }
+
+        // Walk back through blocks that can only continue into the region. Calls are included as
+        // long as they unwind to the same place as the final call, which is where unwinding out
+        // of the outlined path goes.
+        let mut region = vec![bb];
+        loop {
+            let &[pred] = &predecessors[*region.last().unwrap()][..] else { break };
+            if pred == START_BLOCK || region.contains(&pred) || body[pred].is_cleanup {
+                break;
+            }
+            match body[pred].terminator().kind {
+                TerminatorKind::Goto { .. } => {}
+                TerminatorKind::Call { destination: Some(_), cleanup: pred_cleanup, .. }
+                    if pred_cleanup == cleanup => {}
+                _ => break,
+            }
+            region.push(pred);
+        }
+        region.reverse();
+
+        // Outlining a lone call does not make the function any smaller.
+        let has_work = region.len() > 1
+            || body[bb].statements.iter().any(|statement| {
+                !matches!(
+                    statement.kind,
+                    StatementKind::StorageLive(_)
+                        | StatementKind::StorageDead(_)
+                        | StatementKind::Nop
+                )
+            });
+        let has_coverage = region.iter().any(|&bb| {
+            body[bb]
+                .statements
+                .iter()
+                .any(|statement| matches!(statement.kind, StatementKind::Coverage(_)))
+        });
+        if has_work && !has_coverage {
+            regions.push(region);
+        }
+    }
+    regions
+}
+
+/// Records the locals that are used outside of a single region.
+struct LocalUseCollector<'a> {
+    region_of: &'a IndexVec<BasicBlock, Option<usize>>,
+    current_region: Option<usize>,
+    owner: IndexVec<Local, Option<usize>>,
+    shared: BitSet<Local>,
+}
+
+impl<'tcx> Visitor<'tcx> for LocalUseCollector<'_> {
+    fn visit_basic_block_data(&mut self, block: BasicBlock, data: &BasicBlockData<'tcx>) {
+        self.current_region = self.region_of[block];
+        self.super_basic_block_data(block, data);
+    }
+
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
+        if let PlaceContext::NonUse(NonUseContext::VarDebugInfo) = context {
+            return;
+        }
+        match (self.current_region, self.owner[local]) {
+            (Some(region), None) => self.owner[local] = Some(region),
+            (Some(region), Some(owner)) if region == owner => {}
+            _ => {
+                self.shared.insert(local);
+            }
+        }
+    }
+}
+
+/// Returns the locals that the region reads from the rest of the function, which become the
+/// arguments of the outlined path, or `None` if the region cannot be outlined.
+fn live_ins<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    body: &Body<'tcx>,
+    region: &[BasicBlock],
+    shared: &BitSet<Local>,
+) -> Option<Vec<Local>> {
+    let mut checker = LiveInChecker {
+        shared,
+        live_ins: BitSet::new_empty(body.local_decls.len()),
+        valid: true,
+    };
+    for &bb in region {
+        checker.visit_basic_block_data(bb, &body[bb]);
+    }
+    if !checker.valid {
+        return None;
+    }
+
+    // The outlined path gets a copy of the locals it reads, which must not be observable.
+    let live_ins: Vec<_> = checker.live_ins.iter().collect();
+    let all_copy = live_ins.iter().all(|&local| {
+        let decl = &body.local_decls[local];
+        decl.ty.is_copy_modulo_regions(tcx.at(decl.source_info.span), param_env)
+    });
+    if all_copy { Some(live_ins) } else { None }
+}
+
+/// Checks that the locals shared with the rest of the function are only read by a region.
+struct LiveInChecker<'a> {
+    shared: &'a BitSet<Local>,
+    live_ins: BitSet<Local>,
+    valid: bool,
+}
+
+impl<'tcx> Visitor<'tcx> for LiveInChecker<'_> {
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
+        if !self.shared.contains(local) {
+            return;
+        }
+        match context {
+            PlaceContext::NonMutatingUse(_) => {
+                self.live_ins.insert(local);
+            }
+            PlaceContext::NonUse(NonUseContext::StorageDead | NonUseContext::VarDebugInfo) => {}
+            _ => self.valid = false,
+        }
+    }
+}
+
+/// Builds the body of the outlined path: its arguments are the `live_ins`, followed by the
+/// locals that are only used by the region, and its blocks are those of the region.
+fn build_shim<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    instance: ty::InstanceDef<'tcx>,
+    region: &[BasicBlock],
+    live_ins: &[Local],
+) -> Body<'tcx> {
+    let mut map = IndexVec::from_elem(None, &body.local_decls);
+    let mut local_decls = IndexVec::new();
+    local_decls.push(LocalDecl::new(tcx.types.never, body.span));
+    for &local in live_ins {
+        map[local] = Some(local_decls.push(body.local_decls[local].clone().immutable()));
+    }
+    let mut temps = BitSet::new_empty(body.local_decls.len());
+    let mut collector = RegionLocals(&mut temps);
+    for &bb in region {
+        collector.visit_basic_block_data(bb, &body[bb]);
+    }
+    for local in temps.iter() {
+        if map[local].is_none() {
+            map[local] = Some(local_decls.push(body.local_decls[local].clone()));
+        }
+    }
+
+    let mut basic_blocks: IndexVec<BasicBlock, _> =
+        region.iter().map(|&bb| body[bb].clone()).collect();
+    let last = basic_blocks.last().unwrap();
+    for (bb, data) in basic_blocks.iter_enumerated_mut() {
+        // Unwinding out of the outlined path continues in the caller's cleanup.
+        match data.terminator_mut().kind {
+            TerminatorKind::Goto { ref mut target } => *target = bb + 1,
+            TerminatorKind::Call { ref mut destination, ref mut cleanup, .. } => {
+                if let Some((_, target)) = destination {
+                    *target = bb + 1;
+                } else {
+                    assert_eq!(bb, last);
+                }
+                *cleanup = None;
+            }
+            _ => bug!("unexpected terminator in cold region"),
+        }
+    }
+
+    let var_debug_info = body
+        .var_debug_info
+        .iter()
+        .filter(|info| match info.value {
+            VarDebugInfoContents::Place(place) => map[place.local].is_some(),
+            VarDebugInfoContents::Const(_) => false,
+        })
+        .cloned()
+        .collect();
+
+    let mut shim = Body::new(
+        MirSource::from_instance(instance),
+        basic_blocks,
+        body.source_scopes.clone(),
+        local_decls,
+        IndexVec::new(),
+        live_ins.len(),
+        var_debug_info,
+        body.span,
+        None,
+        body.tainted_by_errors,
+    );
+    shim.phase = body.phase;
+    LocalRenamer { tcx, map }.visit_body(&mut shim);
+
+    let mut required_consts = Vec::new();
+    RequiredConstsVisitor::new(&mut required_consts).visit_body(&shim);
+    shim.required_consts = required_consts;
+    shim
+}
+
+/// Collects every local mentioned by a region.
+struct RegionLocals<'a>(&'a mut BitSet<Local>);
+
+impl<'tcx> Visitor<'tcx> for RegionLocals<'_> {
+    fn visit_local(&mut self, &local: &Local, _: PlaceContext, _: Location) {
+        self.0.insert(local);
+    }
+}
+
+struct LocalRenamer<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    map: IndexVec<Local, Option<Local>>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for LocalRenamer<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_local(&mut self, local: &mut Local, _: PlaceContext, _: Location) {
+        *local = self.map[*local].unwrap();
+    }
+}
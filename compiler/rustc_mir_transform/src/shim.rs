@@ -78,6 +78,13 @@ fn make_shim<'tcx>(tcx: TyCtxt<'tcx>, instance: ty::InstanceDef<'tcx>) -> Body<'
             build_drop_shim(tcx, def_id, ty)
         }
         ty::InstanceDef::CloneShim(def_id, ty) => build_clone_shim(tcx, def_id, ty),
+        ty::InstanceDef::ColdPanicShim(def_id, index) => {
+            // Outlined cold paths are built by the `OutlineColdPanics` pass, from an already
+            // optimized body, and are stored in the body they were outlined from.
+            let body = tcx.optimized_mir(def_id).outlined_cold_paths[index as usize].clone();
+            debug!("make_shim({:?}) = {:?}", instance, body);
+            return body;
+        }
         ty::InstanceDef::Virtual(..) => {
             bug!("InstanceDef::Virtual ({:?}) is for direct calls only", instance)
         }
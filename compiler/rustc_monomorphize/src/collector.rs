@@ -201,6 +201,8 @@
 use rustc_span::source_map::{dummy_spanned, respan, Span, Spanned, DUMMY_SP};
 use rustc_target::abi::Size;
 use smallvec::SmallVec;
+use std::collections::hash_map::Entry;
+use std::collections::VecDeque;
 use std::iter;
 use std::ops::Range;
 use std::path::PathBuf;
@@ -268,6 +270,11 @@ pub fn with_inlining_candidates<F>(&self, source: MonoItem<'tcx>, mut f: F)
         }
     }
 
+    // Returns the items referenced by `source`.
+    fn accesses(&self, source: MonoItem<'tcx>) -> &[MonoItem<'tcx>] {
+        self.index.get(&source).map_or(&[], |range| &self.targets[range.clone()])
+    }
+
     // Internally iterate over all items and the things each accesses.
     pub fn iter_accesses<F>(&self, mut f: F)
     where
@@ -299,7 +306,7 @@ pub fn collect_crate_mono_items(
         let inlining_map: MTRef<'_, _> = &mut inlining_map;
 
         tcx.sess.time("monomorphization_collector_graph_walk", || {
-            par_iter(roots).for_each(|root| {
+            par_iter(&roots).for_each(|&root| {
                 let mut recursion_depths = DefIdMap::default();
                 collect_items_rec(
                     tcx,
@@ -313,7 +320,75 @@ pub fn collect_crate_mono_items(
         });
     }
 
-    (visited.into_inner(), inlining_map.into_inner())
+    let inlining_map = inlining_map.into_inner();
+    if let Some(pattern) = &tcx.sess.opts.debugging_opts.mono_why {
+        print_usage_chains(tcx, pattern, &roots, &inlining_map);
+    }
+
+    (visited.into_inner(), inlining_map)
+}
+
+/// Prints, for every mono item matching the `-Z mono-why` pattern, the shortest chain of uses
+/// leading to it from a root of the collection.
+fn print_usage_chains<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    pattern: &str,
+    roots: &[MonoItem<'tcx>],
+    inlining_map: &InliningMap<'tcx>,
+) {
+    // A breadth-first search starting from all roots at once finds the shortest chains.
+    let mut used_by: FxHashMap<MonoItem<'tcx>, Option<MonoItem<'tcx>>> = FxHashMap::default();
+    let mut queue = VecDeque::new();
+    for &root in roots {
+        if let Entry::Vacant(entry) = used_by.entry(root) {
+            entry.insert(None);
+            queue.push_back(root);
+        }
+    }
+    while let Some(item) = queue.pop_front() {
+        for &target in inlining_map.accesses(item) {
+            if let Entry::Vacant(entry) = used_by.entry(target) {
+                entry.insert(Some(item));
+                queue.push_back(target);
+            }
+        }
+    }
+
+    let mut matching_items: Vec<_> = used_by
+        .keys()
+        .map(|&item| (with_no_trimmed_paths!(item.to_string()), item))
+        .filter(|(name, _)| matches_pattern(name, pattern))
+        .collect();
+    matching_items.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    if matching_items.is_empty() {
+        tcx.sess.warn(&format!("no mono item matches the `-Z mono-why` pattern `{}`", pattern));
+    }
+
+    for (name, item) in matching_items {
+        let mut chain = vec![item];
+        while let Some(&Some(user)) = used_by.get(chain.last().unwrap()) {
+            chain.push(user);
+        }
+
+        println!("MONO_WHY {}", name);
+        for (i, item) in chain.iter().rev().enumerate() {
+            let prefix = if i == 0 { "root" } else { "uses" };
+            println!("    {} {}", prefix, with_no_trimmed_paths!(item.to_string()));
+        }
+    }
+}
+
+/// Returns whether `name` contains `pattern`, where each `*` of `pattern` matches any text.
+fn matches_pattern(name: &str, pattern: &str) -> bool {
+    let mut rest = name;
+    for part in pattern.split('*') {
+        match rest.find(part) {
+            Some(start) => rest = &rest[start + part.len()..],
+            None => return false,
+        }
+    }
+    true
 }
 
 // Find all non-generic items by walking the HIR. These items serve as roots to
@@ -935,7 +1010,8 @@ fn visit_instance_use<'tcx>(
         | ty::InstanceDef::ClosureOnceShim { .. }
         | ty::InstanceDef::Item(..)
         | ty::InstanceDef::FnPtrShim(..)
-        | ty::InstanceDef::CloneShim(..) => {
+        | ty::InstanceDef::CloneShim(..)
+        | ty::InstanceDef::ColdPanicShim(..) => {
             output.push(create_fn_mono_item(tcx, instance, source));
         }
     }
@@ -9,6 +9,8 @@
 #[macro_use]
 extern crate tracing;
 #[macro_use]
+extern crate rustc_macros;
+#[macro_use]
 extern crate rustc_middle;
 
 use rustc_hir::lang_items::LangItem;
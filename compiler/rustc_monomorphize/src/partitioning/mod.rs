@@ -94,17 +94,22 @@
 
 mod default;
 mod merging;
+mod stable_hash;
 
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::sync;
-use rustc_hir::def_id::DefIdSet;
+use rustc_hir::def_id::{DefIdSet, LOCAL_CRATE};
 use rustc_middle::mir::mono::MonoItem;
 use rustc_middle::mir::mono::{CodegenUnit, Linkage};
 use rustc_middle::ty::print::with_no_trimmed_paths;
 use rustc_middle::ty::query::Providers;
 use rustc_middle::ty::TyCtxt;
+use rustc_serialize::json::as_pretty_json;
 use rustc_span::symbol::Symbol;
 
+use std::fs;
+use std::path::Path;
+
 use crate::collector::InliningMap;
 use crate::collector::{self, MonoItemCollectionMode};
 
@@ -148,6 +153,7 @@ fn get_partitioner<'tcx>(tcx: TyCtxt<'tcx>) -> Box<dyn Partitioner<'tcx>> {
 
     match strategy {
         "default" => Box::new(default::DefaultPartitioning),
+        "stable-hash" => Box::new(stable_hash::StableHashPartitioning),
         _ => tcx.sess.fatal("unknown partitioning strategy"),
     }
 }
@@ -413,60 +419,149 @@ fn collect_and_partition_mono_items<'tcx>(
         })
         .collect();
 
-    if tcx.sess.opts.debugging_opts.print_mono_items.is_some() {
-        let mut item_to_cgus: FxHashMap<_, Vec<_>> = Default::default();
-
-        for cgu in codegen_units {
-            for (&mono_item, &linkage) in cgu.items() {
-                item_to_cgus.entry(mono_item).or_default().push((cgu.name(), linkage));
-            }
-        }
+    let dump_mono_items = &tcx.sess.opts.debugging_opts.dump_mono_items;
+    if tcx.sess.opts.debugging_opts.print_mono_items.is_some() || dump_mono_items.is_some() {
+        let placements = mono_item_placements(&items, codegen_units);
 
-        let mut item_keys: Vec<_> = items
-            .iter()
-            .map(|i| {
-                let mut output = with_no_trimmed_paths!(i.to_string());
+        if tcx.sess.opts.debugging_opts.print_mono_items.is_some() {
+            for (item, cgus) in &placements {
+                let mut output = item.clone();
                 output.push_str(" @@");
-                let mut empty = Vec::new();
-                let cgus = item_to_cgus.get_mut(i).unwrap_or(&mut empty);
-                cgus.sort_by_key(|(name, _)| *name);
-                cgus.dedup();
-                for &(ref cgu_name, (linkage, _)) in cgus.iter() {
+                for &(cgu_name, linkage) in cgus {
                     output.push(' ');
                     output.push_str(cgu_name.as_str());
-
-                    let linkage_abbrev = match linkage {
-                        Linkage::External => "External",
-                        Linkage::AvailableExternally => "Available",
-                        Linkage::LinkOnceAny => "OnceAny",
-                        Linkage::LinkOnceODR => "OnceODR",
-                        Linkage::WeakAny => "WeakAny",
-                        Linkage::WeakODR => "WeakODR",
-                        Linkage::Appending => "Appending",
-                        Linkage::Internal => "Internal",
-                        Linkage::Private => "Private",
-                        Linkage::ExternalWeak => "ExternalWeak",
-                        Linkage::Common => "Common",
-                    };
-
                     output.push('[');
-                    output.push_str(linkage_abbrev);
+                    output.push_str(linkage_abbrev(linkage));
                     output.push(']');
                 }
-                output
-            })
-            .collect();
-
-        item_keys.sort();
+                println!("MONO_ITEM {}", output);
+            }
+        }
 
-        for item in item_keys {
-            println!("MONO_ITEM {}", item);
+        if let Some(path) = dump_mono_items {
+            write_mono_items_dump(tcx, path, codegen_units, placements);
         }
     }
 
     (tcx.arena.alloc(mono_items), codegen_units)
 }
 
+/// Returns every mono item with the codegen units it is placed into, sorted by item and by codegen
+/// unit name.
+fn mono_item_placements<'tcx>(
+    items: &FxHashSet<MonoItem<'tcx>>,
+    codegen_units: &[CodegenUnit<'tcx>],
+) -> Vec<(String, Vec<(Symbol, Linkage)>)> {
+    let mut item_to_cgus: FxHashMap<_, Vec<_>> = Default::default();
+
+    for cgu in codegen_units {
+        for (&mono_item, &(linkage, _)) in cgu.items() {
+            item_to_cgus.entry(mono_item).or_default().push((cgu.name(), linkage));
+        }
+    }
+
+    let mut placements: Vec<_> = items
+        .iter()
+        .map(|i| {
+            let mut cgus = item_to_cgus.remove(i).unwrap_or_default();
+            cgus.sort_by_key(|(name, _)| *name);
+            cgus.dedup();
+            (with_no_trimmed_paths!(i.to_string()), cgus)
+        })
+        .collect();
+
+    placements.sort_by(|(a, _), (b, _)| a.cmp(b));
+    placements
+}
+
+fn linkage_abbrev(linkage: Linkage) -> &'static str {
+    match linkage {
+        Linkage::External => "External",
+        Linkage::AvailableExternally => "Available",
+        Linkage::LinkOnceAny => "OnceAny",
+        Linkage::LinkOnceODR => "OnceODR",
+        Linkage::WeakAny => "WeakAny",
+        Linkage::WeakODR => "WeakODR",
+        Linkage::Appending => "Appending",
+        Linkage::Internal => "Internal",
+        Linkage::Private => "Private",
+        Linkage::ExternalWeak => "ExternalWeak",
+        Linkage::Common => "Common",
+    }
+}
+
+#[derive(Encodable)]
+struct MonoItemsDump {
+    crate_name: String,
+    partitioning_strategy: String,
+    codegen_units: Vec<CodegenUnitDump>,
+    mono_items: Vec<MonoItemDump>,
+}
+
+#[derive(Encodable)]
+struct CodegenUnitDump {
+    name: String,
+    size_estimate: usize,
+    mono_items: usize,
+}
+
+#[derive(Encodable)]
+struct MonoItemDump {
+    name: String,
+    placements: Vec<PlacementDump>,
+}
+
+#[derive(Encodable)]
+struct PlacementDump {
+    codegen_unit: String,
+    linkage: String,
+}
+
+/// Writes the placements of `-Z print-mono-items` as JSON for `-Z dump-mono-items`, together with
+/// the codegen units themselves.
+fn write_mono_items_dump<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    path: &Path,
+    codegen_units: &[CodegenUnit<'tcx>],
+    placements: Vec<(String, Vec<(Symbol, Linkage)>)>,
+) {
+    let dump = MonoItemsDump {
+        crate_name: tcx.crate_name(LOCAL_CRATE).to_string(),
+        partitioning_strategy: tcx
+            .sess
+            .opts
+            .debugging_opts
+            .cgu_partitioning_strategy
+            .clone()
+            .unwrap_or_else(|| "default".to_string()),
+        codegen_units: codegen_units
+            .iter()
+            .map(|cgu| CodegenUnitDump {
+                name: cgu.name().to_string(),
+                size_estimate: cgu.size_estimate(),
+                mono_items: cgu.items().len(),
+            })
+            .collect(),
+        mono_items: placements
+            .into_iter()
+            .map(|(name, cgus)| MonoItemDump {
+                name,
+                placements: cgus
+                    .into_iter()
+                    .map(|(cgu_name, linkage)| PlacementDump {
+                        codegen_unit: cgu_name.to_string(),
+                        linkage: linkage_abbrev(linkage).to_string(),
+                    })
+                    .collect(),
+            })
+            .collect(),
+    };
+
+    if let Err(err) = fs::write(path, as_pretty_json(&dump).to_string()) {
+        tcx.sess.err(&format!("failed to write mono items to `{}`: {}", path.display(), err));
+    }
+}
+
 fn codegened_and_inlined_items<'tcx>(tcx: TyCtxt<'tcx>, (): ()) -> &'tcx DefIdSet {
     let (items, cgus) = tcx.collect_and_partition_mono_items(());
     let mut visited = DefIdSet::default();
@@ -0,0 +1,87 @@
+//! A partitioning strategy that places every root mono item into one of a fixed number of codegen
+//! units, chosen by the stable hash of the item.
+//!
+//! Unlike the default strategy, which groups items by source-level module and then merges the
+//! smallest codegen units until there are few enough of them, the codegen unit an item is placed
+//! into only depends on the item itself. Adding, removing or editing an item thus only changes the
+//! contents of the codegen unit of that item (and of the codegen units it is inlined into), which
+//! keeps the other codegen units reusable by incremental compilation. The price is that related
+//! items usually end up in different codegen units, which gives LLVM fewer inlining opportunities.
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::stable_hasher::{HashStable, StableHasher};
+use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_middle::mir::mono::{CodegenUnit, CodegenUnitNameBuilder, MonoItem};
+
+use super::default::DefaultPartitioning;
+use super::PartitioningCx;
+use crate::partitioning::{Partitioner, PostInliningPartitioning, PreInliningPartitioning};
+
+pub struct StableHashPartitioning;
+
+impl<'tcx> Partitioner<'tcx> for StableHashPartitioning {
+    fn place_root_mono_items(
+        &mut self,
+        cx: &PartitioningCx<'_, 'tcx>,
+        mono_items: &mut dyn Iterator<Item = MonoItem<'tcx>>,
+    ) -> PreInliningPartitioning<'tcx> {
+        // The default strategy computes the linkage and visibility of the roots, which do not
+        // depend on the codegen unit they are placed into.
+        let mut initial_partitioning = DefaultPartitioning.place_root_mono_items(cx, mono_items);
+
+        let mut hcx = cx.tcx.create_stable_hashing_context();
+        let cgu_name_builder = &mut CodegenUnitNameBuilder::new(cx.tcx);
+        let mut codegen_units = FxHashMap::default();
+
+        for cgu in &initial_partitioning.codegen_units {
+            for (&mono_item, &linkage) in cgu.items() {
+                let mut hasher = StableHasher::new();
+                mono_item.hash_stable(&mut hcx, &mut hasher);
+                let bucket = hasher.finish::<u64>() % cx.target_cgu_count as u64;
+
+                let codegen_unit = codegen_units.entry(bucket).or_insert_with(|| {
+                    let name = cgu_name_builder.build_cgu_name(
+                        LOCAL_CRATE,
+                        &["stable-hash"],
+                        Some(format!("{:x}", bucket)),
+                    );
+                    CodegenUnit::new(name)
+                });
+                codegen_unit.items_mut().insert(mono_item, linkage);
+            }
+        }
+
+        // If there are no roots, keep the empty codegen unit of the default strategy, so that
+        // there is at least one codegen unit.
+        if !codegen_units.is_empty() {
+            initial_partitioning.codegen_units = codegen_units.into_values().collect();
+        }
+        initial_partitioning
+    }
+
+    fn merge_codegen_units(
+        &mut self,
+        cx: &PartitioningCx<'_, 'tcx>,
+        initial_partitioning: &mut PreInliningPartitioning<'tcx>,
+    ) {
+        // Merging codegen units would make their contents depend on their sizes again, and there
+        // already are at most as many of them as requested.
+        debug_assert!(initial_partitioning.codegen_units.len() <= cx.target_cgu_count);
+    }
+
+    fn place_inlined_mono_items(
+        &mut self,
+        cx: &PartitioningCx<'_, 'tcx>,
+        initial_partitioning: PreInliningPartitioning<'tcx>,
+    ) -> PostInliningPartitioning<'tcx> {
+        DefaultPartitioning.place_inlined_mono_items(cx, initial_partitioning)
+    }
+
+    fn internalize_symbols(
+        &mut self,
+        cx: &PartitioningCx<'_, 'tcx>,
+        partitioning: &mut PostInliningPartitioning<'tcx>,
+    ) {
+        DefaultPartitioning.internalize_symbols(cx, partitioning)
+    }
+}
@@ -171,6 +171,12 @@ pub enum InstrumentCoverage {
     ExceptUnusedGenerics,
     /// `-Zunstable-options -C instrument-coverage=except-unused-functions`
     ExceptUnusedFunctions,
+    /// `-Zunstable-options -C instrument-coverage=branch`
+    ///
+    /// Like `All`, but also adds a branch region for every condition in `if`
+    /// expressions, match guards and operands of `&&` and `||`, counting how
+    /// often the condition evaluated to `true` and to `false`.
+    Branch,
     /// `-C instrument-coverage=off` (or `no`, etc.)
     Off,
 }
@@ -2432,7 +2438,8 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         (Some(_), _) if !debugging_opts.unstable_options => {
             early_error(
                 error_format,
-                "`-C instrument-coverage=except-*` requires `-Z unstable-options`",
+                "`-C instrument-coverage=except-*` and `-C instrument-coverage=branch` \
+                require `-Z unstable-options`",
             );
         }
         (None, None) => {}
@@ -122,6 +122,10 @@ pub fn instrument_coverage_except_unused_functions(&self) -> bool {
         self.cg.instrument_coverage.unwrap_or(InstrumentCoverage::Off)
             == InstrumentCoverage::ExceptUnusedFunctions
     }
+
+    pub fn instrument_coverage_branch(&self) -> bool {
+        self.cg.instrument_coverage.unwrap_or(InstrumentCoverage::Off) == InstrumentCoverage::Branch
+    }
 }
 
 top_level_options!(
@@ -378,7 +382,7 @@ mod desc {
     pub const parse_opt_panic_strategy: &str = parse_panic_strategy;
     pub const parse_oom_strategy: &str = "either `panic` or `abort`";
     pub const parse_relro_level: &str = "one of: `full`, `partial`, or `off`";
-    pub const parse_sanitizers: &str = "comma separated list of sanitizers: `address`, `cfi`, `hwaddress`, `leak`, `memory`, `memtag`, or `thread`";
+    pub const parse_sanitizers: &str = "comma separated list of sanitizers: `address`, `cfi`, `hwaddress`, `kcfi`, `leak`, `memory`, `memtag`, `shadow-call-stack`, or `thread`";
     pub const parse_sanitizer_memory_track_origins: &str = "0, 1, or 2";
     pub const parse_cfguard: &str =
         "either a boolean (`yes`, `no`, `on`, `off`, etc), `checks`, or `nochecks`";
@@ -388,7 +392,7 @@ mod desc {
     pub const parse_optimization_fuel: &str = "crate=integer";
     pub const parse_mir_spanview: &str = "`statement` (default), `terminator`, or `block`";
     pub const parse_instrument_coverage: &str =
-        "`all` (default), `except-unused-generics`, `except-unused-functions`, or `off`";
+        "`all` (default), `except-unused-generics`, `except-unused-functions`, `branch`, or `off`";
     pub const parse_unpretty: &str = "`string` or `string=string`";
     pub const parse_treat_err_as_bug: &str = "either no value or a number bigger than 0";
     pub const parse_lto: &str =
@@ -659,9 +663,11 @@ mod parse {
                 *slot |= match s {
                     "address" => SanitizerSet::ADDRESS,
                     "cfi" => SanitizerSet::CFI,
+                    "kcfi" => SanitizerSet::KCFI,
                     "leak" => SanitizerSet::LEAK,
                     "memory" => SanitizerSet::MEMORY,
                     "memtag" => SanitizerSet::MEMTAG,
+                    "shadow-call-stack" => SanitizerSet::SHADOWCALLSTACK,
                     "thread" => SanitizerSet::THREAD,
                     "hwaddress" => SanitizerSet::HWADDRESS,
                     _ => return false,
@@ -824,6 +830,7 @@ mod parse {
             "except-unused-functions" | "except_unused_functions" => {
                 InstrumentCoverage::ExceptUnusedFunctions
             }
+            "branch" => InstrumentCoverage::Branch,
             "off" | "no" | "n" | "false" | "0" => InstrumentCoverage::Off,
             _ => return false,
         });
@@ -1071,6 +1078,7 @@ mod parse {
         `=all` (implicit value)
         `=except-unused-generics`
         `=except-unused-functions`
+        `=branch`
         `=off` (default)"),
     link_arg: (/* redirected to link_args */) = ((), parse_string_push, [UNTRACKED],
         "a single extra argument to append to the linker invocation (can be used several times)"),
@@ -1118,7 +1126,8 @@ mod parse {
         parse_switch_with_opt_path, [TRACKED],
         "compile the program with profiling instrumentation"),
     profile_use: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED],
-        "use the given `.profdata` file for profile-guided optimization"),
+        "use the given `.profdata` or `.profraw` file, or the `.profdata` and `.profraw` files \
+        in the given directory, for profile-guided optimization"),
     relocation_model: Option<RelocModel> = (None, parse_relocation_model, [TRACKED],
         "control generation of position-independent code (PIC) \
         (`rustc --print relocation-models` for details)"),
@@ -1183,7 +1192,7 @@ mod parse {
     cf_protection: CFProtection = (CFProtection::None, parse_cfprotection, [TRACKED],
         "instrument control-flow architecture protection"),
     cgu_partitioning_strategy: Option<String> = (None, parse_opt_string, [TRACKED],
-        "the codegen unit partitioning strategy to use"),
+        "the codegen unit partitioning strategy to use: `default` or `stable-hash`"),
     chalk: bool = (false, parse_bool, [TRACKED],
         "enable the experimental Chalk-based trait solving engine"),
     codegen_backend: Option<String> = (None, parse_opt_string, [TRACKED],
@@ -1226,6 +1235,9 @@ mod parse {
     dump_mir_dataflow: bool = (false, parse_bool, [UNTRACKED],
         "in addition to `.mir` files, create graphviz `.dot` files with dataflow results \
         (default: no)"),
+    dump_mir_dataflow_html: bool = (false, parse_bool, [UNTRACKED],
+        "in addition to `.mir` files, create interactive `.html` files with dataflow results \
+        (default: no)"),
     dump_mir_dir: String = ("mir_dump".to_string(), parse_string, [UNTRACKED],
         "the directory the MIR is dumped into (default: `mir_dump`)"),
     dump_mir_exclude_pass_number: bool = (false, parse_bool, [UNTRACKED],
@@ -1240,6 +1252,12 @@ mod parse {
         computed `block` spans (one span encompassing a block's terminator and \
         all statements). If `-Z instrument-coverage` is also enabled, create \
         an additional `.html` file showing the computed coverage spans."),
+    dump_mono_items: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write the codegen units and the placement of every mono item into them to the given \
+        file, as JSON"),
+    emit_size_report: bool = (false, parse_bool, [UNTRACKED],
+        "write a report of the estimated and actual size of every mono item of the crate, \
+        grouped by crate and by generic definition, to `<crate>.size-report` (default: no)"),
     emit_stack_sizes: bool = (false, parse_bool, [UNTRACKED],
         "emit a section containing stack size metadata (default: no)"),
     fewer_names: Option<bool> = (None, parse_opt_bool, [TRACKED],
@@ -1291,7 +1309,11 @@ mod parse {
         `=all` (implicit value)
         `=except-unused-generics`
         `=except-unused-functions`
+        `=branch`
         `=off` (default)"),
+    instrument_functions: bool = (false, parse_bool, [TRACKED],
+        "insert calls to `__cyg_profile_func_enter` and `__cyg_profile_func_exit` on function \
+        entry and exit, like `-finstrument-functions-after-inlining` (default: no)"),
     instrument_mcount: bool = (false, parse_bool, [TRACKED],
         "insert function instrument code for mcount-based tracing (default: no)"),
     keep_hygiene_data: bool = (false, parse_bool, [UNTRACKED],
@@ -1300,6 +1322,9 @@ mod parse {
         "link native libraries in the linker invocation (default: yes)"),
     link_only: bool = (false, parse_bool, [TRACKED],
         "link the `.rlink` file generated by `-Z no-link` (default: no)"),
+    llvm_opt_bisect_limit: Option<usize> = (None, parse_opt_number, [TRACKED],
+        "only run the first N optional LLVM passes, and print each pass run or skipped, to \
+        bisect miscompilations (like LLVM's `-opt-bisect-limit`) (default: run all)"),
     llvm_plugins: Vec<String> = (Vec::new(), parse_list, [TRACKED],
         "a list LLVM plugins to enable (space separated)"),
     llvm_time_trace: bool = (false, parse_bool, [UNTRACKED],
@@ -1321,6 +1346,9 @@ mod parse {
         (default: no)"),
     mir_opt_level: Option<usize> = (None, parse_opt_number, [TRACKED],
         "MIR optimization level (0-4; default: 1 in non optimized builds and 2 in optimized builds)"),
+    mono_why: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "print the shortest chain of uses from a root to every mono item whose name matches \
+        the given pattern, in which `*` matches any text"),
     move_size_limit: Option<usize> = (None, parse_opt_number, [TRACKED],
         "the size at which the `large_assignments` lint starts to be emitted"),
     mutable_noalias: Option<bool> = (None, parse_opt_bool, [TRACKED],
@@ -1353,8 +1381,14 @@ mod parse {
         "normalize associated items in rustdoc when generating documentation"),
     oom: OomStrategy = (OomStrategy::Abort, parse_oom_strategy, [TRACKED],
         "panic strategy for out-of-memory handling"),
+    opt_bisect_cgus: Option<usize> = (None, parse_opt_number, [TRACKED],
+        "only optimize the first N codegen units of the crate, ordered by name, and print each \
+        codegen unit optimized or not, to bisect miscompilations (default: optimize all)"),
     osx_rpath_install_name: bool = (false, parse_bool, [TRACKED],
         "pass `-install_name @rpath/...` to the macOS linker (default: no)"),
+    outline_cold_panics: bool = (false, parse_bool, [TRACKED],
+        "outline code paths that unconditionally end in a panic into separate cold \
+        functions (default: no)"),
     panic_abort_tests: bool = (false, parse_bool, [TRACKED],
         "support compiling tests with panic=abort (default: no)"),
     panic_in_drop: PanicStrategy = (PanicStrategy::Unwind, parse_panic_strategy, [TRACKED],
@@ -1363,6 +1397,9 @@ mod parse {
         "parse only; do not compile, assemble, or link (default: no)"),
     perf_stats: bool = (false, parse_bool, [UNTRACKED],
         "print some performance-related statistics (default: no)"),
+    pgo_report: bool = (false, parse_bool, [UNTRACKED],
+        "print how the `-C profile-use` profile matched the functions of the crate, and which \
+        functions it considers hot (default: no)"),
     pick_stable_methods_before_any_unstable: bool = (true, parse_bool, [TRACKED],
         "try to pick stable methods first before picking any unstable methods (default: yes)"),
     plt: Option<bool> = (None, parse_opt_bool, [TRACKED],
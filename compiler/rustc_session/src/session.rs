@@ -529,6 +529,9 @@ pub fn time_passes(&self) -> bool {
     pub fn instrument_mcount(&self) -> bool {
         self.opts.debugging_opts.instrument_mcount
     }
+    pub fn instrument_functions(&self) -> bool {
+        self.opts.debugging_opts.instrument_functions
+    }
     pub fn time_llvm_passes(&self) -> bool {
         self.opts.debugging_opts.time_llvm_passes
     }
@@ -620,6 +623,12 @@ pub fn lto(&self) -> config::Lto {
             }
         }
 
+        // Crate-local ThinLTO would optimize the codegen units that
+        // `-Z opt-bisect-cgus` leaves unoptimized.
+        if self.opts.debugging_opts.opt_bisect_cgus.is_some() {
+            return config::Lto::No;
+        }
+
         // If there's only one codegen unit and LTO isn't enabled then there's
         // no need for ThinLTO so just return false.
         if self.codegen_units() == 1 {
@@ -660,6 +669,9 @@ pub fn is_nightly_build(&self) -> bool {
     pub fn is_sanitizer_cfi_enabled(&self) -> bool {
         self.opts.debugging_opts.sanitizer.contains(SanitizerSet::CFI)
     }
+    pub fn is_sanitizer_kcfi_enabled(&self) -> bool {
+        self.opts.debugging_opts.sanitizer.contains(SanitizerSet::KCFI)
+    }
     pub fn overflow_checks(&self) -> bool {
         self.opts.cg.overflow_checks.unwrap_or(self.opts.debug_assertions)
     }
@@ -1040,6 +1052,10 @@ pub fn instrument_coverage_except_unused_functions(&self) -> bool {
         self.opts.instrument_coverage_except_unused_functions()
     }
 
+    pub fn instrument_coverage_branch(&self) -> bool {
+        self.opts.instrument_coverage_branch()
+    }
+
     pub fn is_proc_macro_attr(&self, attr: &Attribute) -> bool {
         [sym::proc_macro, sym::proc_macro_attribute, sym::proc_macro_derive]
             .iter()
@@ -1358,6 +1374,19 @@ fn validate_commandline_args_with_session_available(sess: &Session) {
         );
     }
 
+    if sess.opts.debugging_opts.opt_bisect_cgus.is_some()
+        && matches!(sess.lto(), config::Lto::Fat | config::Lto::Thin)
+    {
+        sess.warn(
+            "`-Z opt-bisect-cgus` does not affect the optimizations that LTO runs on \
+            all codegen units",
+        );
+    }
+
+    if sess.opts.debugging_opts.pgo_report && sess.opts.cg.profile_use.is_none() {
+        sess.warn("`-Z pgo-report` has no effect without `-C profile-use`");
+    }
+
     // Make sure that any given profiling data actually exists so LLVM can't
     // decide to silently skip PGO.
     if let Some(ref path) = sess.opts.cg.profile_use {
@@ -1407,20 +1436,46 @@ fn validate_commandline_args_with_session_available(sess: &Session) {
             ));
         }
     }
-    // Cannot mix and match sanitizers.
-    let mut sanitizer_iter = sess.opts.debugging_opts.sanitizer.into_iter();
+    // Cannot mix and match sanitizers, except for KCFI and ShadowCallStack, which only change the
+    // generated code, have no runtime, and can be combined with each other and with the other
+    // sanitizers.
+    let combinable_sanitizers = SanitizerSet::KCFI | SanitizerSet::SHADOWCALLSTACK;
+    let mut sanitizer_iter =
+        (sess.opts.debugging_opts.sanitizer - combinable_sanitizers).into_iter();
     if let (Some(first), Some(second)) = (sanitizer_iter.next(), sanitizer_iter.next()) {
         sess.err(&format!("`-Zsanitizer={first}` is incompatible with `-Zsanitizer={second}`"));
     }
+    // LLVM CFI and KCFI check the same calls in different ways.
+    if sess.is_sanitizer_cfi_enabled() && sess.is_sanitizer_kcfi_enabled() {
+        sess.err("`-Zsanitizer=cfi` is incompatible with `-Zsanitizer=kcfi`");
+    }
 
     // Cannot enable crt-static with sanitizers on Linux
-    if sess.crt_static(None) && !sess.opts.debugging_opts.sanitizer.is_empty() {
+    if sess.crt_static(None)
+        && !(sess.opts.debugging_opts.sanitizer - combinable_sanitizers).is_empty()
+    {
         sess.err(
             "sanitizer is incompatible with statically linked libc, \
                                 disable it using `-C target-feature=-crt-static`",
         );
     }
 
+    // The shadow call stack pointer is kept in x18, so it must not be used by other code. LLVM
+    // reserves it by default on targets whose platform ABI reserves it, like Android.
+    if sess.opts.debugging_opts.sanitizer.contains(SanitizerSet::SHADOWCALLSTACK)
+        && sess.target.os != "android"
+    {
+        let reserve_x18 = sess
+            .target
+            .features
+            .split(',')
+            .chain(sess.opts.cg.target_feature.split(','))
+            .rfind(|&feature| feature == "+reserve-x18" || feature == "-reserve-x18");
+        if reserve_x18 != Some("+reserve-x18") {
+            sess.err("`-Zsanitizer=shadow-call-stack` requires `-Ctarget-feature=+reserve-x18`");
+        }
+    }
+
     // LLVM CFI requires LTO.
     if sess.is_sanitizer_cfi_enabled() {
         if sess.opts.cg.lto == config::LtoCli::Unspecified
@@ -1431,6 +1486,11 @@ fn validate_commandline_args_with_session_available(sess: &Session) {
         }
     }
 
+    // Both insert their entry hook through the same LLVM function attribute.
+    if sess.instrument_functions() && sess.instrument_mcount() {
+        sess.err("`-Zinstrument-functions` is incompatible with `-Zinstrument-mcount`");
+    }
+
     if sess.opts.debugging_opts.stack_protector != StackProtector::None {
         if !sess.target.options.supports_stack_protector {
             sess.warn(&format!(
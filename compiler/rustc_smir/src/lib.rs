@@ -0,0 +1,22 @@
+//! The Stable MIR, a read-only and versioned view of the compiler's MIR for external tools.
+//!
+//! Tools that analyze Rust code through `rustc_private` currently have to use the compiler's
+//! internal data structures directly, which change all the time. This crate instead exposes
+//! owned copies of MIR bodies, types and definition paths through the types in [`stable_mir`].
+//! Those types only change together with [`stable_mir::VERSION`], so a tool can keep working
+//! across nightly bumps as long as that version stays the same.
+//!
+//! The entry point is [`stable_mir::Context`], which is handed out by
+//! `rustc_driver::Callbacks::after_analysis_stable_mir`.
+//!
+//! This API is still unstable and may change in backwards-incompatible ways until the
+//! translation covers all of the MIR.
+
+#![doc(html_root_url = "https://doc.rust-lang.org/nightly/nightly-rustc/")]
+#![feature(let_else)]
+
+#[macro_use]
+extern crate rustc_middle;
+
+mod rustc_smir;
+pub mod stable_mir;
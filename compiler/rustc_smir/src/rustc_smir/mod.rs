@@ -0,0 +1,400 @@
+//! Translation of the compiler's internal MIR and types into the Stable MIR.
+//!
+//! Everything in here is private to this crate, so the internal data structures can change
+//! without affecting tools, as long as the translation keeps producing the same stable types.
+
+use rustc_ast::InlineAsmTemplatePiece;
+use rustc_hir as hir;
+use rustc_hir::def_id::DefId;
+use rustc_middle::mir;
+use rustc_middle::ty::adjustment::PointerCast;
+use rustc_middle::ty::subst::{GenericArgKind, SubstsRef};
+use rustc_middle::ty::{self, Ty};
+
+use crate::stable_mir::mir as smir;
+use crate::stable_mir::ty as sty;
+use crate::stable_mir::{self, Context};
+
+impl<'tcx> Context<'tcx> {
+    pub(crate) fn stable_def_id(&self, def_id: DefId) -> stable_mir::DefId {
+        let mut tables = self.tables.borrow_mut();
+        let tables = &mut *tables;
+        let index = *tables.def_id_indices.entry(def_id).or_insert_with(|| {
+            tables.def_ids.push(def_id);
+            tables.def_ids.len() - 1
+        });
+        stable_mir::DefId(index)
+    }
+
+    pub(crate) fn internal_def_id(&self, def: stable_mir::DefId) -> DefId {
+        self.tables.borrow().def_ids[def.0]
+    }
+
+    pub(crate) fn instance(&self, instance: ty::Instance<'tcx>) -> stable_mir::Instance {
+        let index = {
+            let mut tables = self.tables.borrow_mut();
+            let tables = &mut *tables;
+            *tables.instance_indices.entry(instance).or_insert_with(|| {
+                tables.instances.push(instance);
+                tables.instances.len() - 1
+            })
+        };
+        stable_mir::Instance {
+            def: self.stable_def_id(instance.def_id()),
+            args: self.generic_args(instance.substs),
+            symbol_name: self.tcx.symbol_name(instance).name.to_string(),
+            index,
+        }
+    }
+
+    pub(crate) fn body(&self, body: &mir::Body<'tcx>) -> smir::Body {
+        smir::Body {
+            blocks: body
+                .basic_blocks()
+                .iter()
+                .map(|block| smir::BasicBlock {
+                    statements: block.statements.iter().map(|s| self.statement(s)).collect(),
+                    terminator: self.terminator(block.terminator()),
+                    is_cleanup: block.is_cleanup,
+                })
+                .collect(),
+            locals: body
+                .local_decls
+                .iter()
+                .map(|decl| smir::LocalDecl {
+                    ty: self.ty(decl.ty),
+                    mutability: mutability(decl.mutability),
+                })
+                .collect(),
+            arg_count: body.arg_count,
+        }
+    }
+
+    fn statement(&self, statement: &mir::Statement<'tcx>) -> smir::Statement {
+        use mir::StatementKind::*;
+        match &statement.kind {
+            Assign(assign) => {
+                smir::Statement::Assign(self.place(&assign.0), self.rvalue(&assign.1))
+            }
+            SetDiscriminant { place, variant_index } => smir::Statement::SetDiscriminant {
+                place: self.place(place),
+                variant_index: variant_index.as_usize(),
+            },
+            Deinit(place) => smir::Statement::Deinit(self.place(place)),
+            StorageLive(local) => smir::Statement::StorageLive(local.as_usize()),
+            StorageDead(local) => smir::Statement::StorageDead(local.as_usize()),
+            CopyNonOverlapping(copy) => smir::Statement::CopyNonOverlapping {
+                src: self.operand(&copy.src),
+                dst: self.operand(&copy.dst),
+                count: self.operand(&copy.count),
+            },
+            FakeRead(..) | Retag(..) | AscribeUserType(..) | Coverage(..) | Nop => {
+                smir::Statement::Nop
+            }
+        }
+    }
+
+    fn terminator(&self, terminator: &mir::Terminator<'tcx>) -> smir::Terminator {
+        use mir::TerminatorKind::*;
+        let block = |bb: mir::BasicBlock| bb.as_usize();
+        match &terminator.kind {
+            Goto { target } => smir::Terminator::Goto { target: block(*target) },
+            SwitchInt { discr, targets, .. } => smir::Terminator::SwitchInt {
+                discr: self.operand(discr),
+                targets: targets.iter().map(|(value, target)| (value, block(target))).collect(),
+                otherwise: block(targets.otherwise()),
+            },
+            Resume => smir::Terminator::Resume,
+            Abort => smir::Terminator::Abort,
+            Return => smir::Terminator::Return,
+            Unreachable => smir::Terminator::Unreachable,
+            Drop { place, target, unwind } => smir::Terminator::Drop {
+                place: self.place(place),
+                target: block(*target),
+                unwind: unwind.map(block),
+            },
+            DropAndReplace { place, value, target, unwind } => smir::Terminator::DropAndReplace {
+                place: self.place(place),
+                value: self.operand(value),
+                target: block(*target),
+                unwind: unwind.map(block),
+            },
+            Call { func, args, destination, cleanup, .. } => smir::Terminator::Call {
+                func: self.operand(func),
+                args: args.iter().map(|arg| self.operand(arg)).collect(),
+                destination: destination.map(|(place, target)| (self.place(&place), block(target))),
+                cleanup: cleanup.map(block),
+            },
+            Assert { cond, expected, msg, target, cleanup } => smir::Terminator::Assert {
+                cond: self.operand(cond),
+                expected: *expected,
+                msg: match msg {
+                    mir::AssertKind::BoundsCheck { .. } => "index out of bounds".to_string(),
+                    _ => msg.description().to_string(),
+                },
+                target: block(*target),
+                cleanup: cleanup.map(block),
+            },
+            InlineAsm { template, destination, cleanup, .. } => smir::Terminator::InlineAsm {
+                template: InlineAsmTemplatePiece::to_string(template),
+                destination: destination.map(block),
+                cleanup: cleanup.map(block),
+            },
+            Yield { .. } | GeneratorDrop | FalseEdge { .. } | FalseUnwind { .. } => {
+                bug!("unexpected terminator in optimized MIR: {:?}", terminator.kind)
+            }
+        }
+    }
+
+    fn rvalue(&self, rvalue: &mir::Rvalue<'tcx>) -> smir::Rvalue {
+        use mir::Rvalue::*;
+        match rvalue {
+            Use(op) => smir::Rvalue::Use(self.operand(op)),
+            Repeat(op, count) => {
+                smir::Rvalue::Repeat(self.operand(op), sty::Const(count.to_string()))
+            }
+            Ref(_, kind, place) => smir::Rvalue::Ref(borrow_kind(*kind), self.place(place)),
+            ThreadLocalRef(def_id) => smir::Rvalue::ThreadLocalRef(self.stable_def_id(*def_id)),
+            AddressOf(mutbl, place) => {
+                smir::Rvalue::AddressOf(mutability(*mutbl), self.place(place))
+            }
+            Len(place) => smir::Rvalue::Len(self.place(place)),
+            Cast(kind, op, ty) => {
+                smir::Rvalue::Cast(cast_kind(*kind), self.operand(op), self.ty(*ty))
+            }
+            BinaryOp(op, operands) => smir::Rvalue::BinaryOp(
+                bin_op(*op),
+                self.operand(&operands.0),
+                self.operand(&operands.1),
+            ),
+            CheckedBinaryOp(op, operands) => smir::Rvalue::CheckedBinaryOp(
+                bin_op(*op),
+                self.operand(&operands.0),
+                self.operand(&operands.1),
+            ),
+            NullaryOp(op, ty) => {
+                let op = match op {
+                    mir::NullOp::SizeOf => smir::NullOp::SizeOf,
+                    mir::NullOp::AlignOf => smir::NullOp::AlignOf,
+                };
+                smir::Rvalue::NullaryOp(op, self.ty(*ty))
+            }
+            UnaryOp(op, operand) => {
+                let op = match op {
+                    mir::UnOp::Not => smir::UnOp::Not,
+                    mir::UnOp::Neg => smir::UnOp::Neg,
+                };
+                smir::Rvalue::UnaryOp(op, self.operand(operand))
+            }
+            Discriminant(place) => smir::Rvalue::Discriminant(self.place(place)),
+            Aggregate(kind, operands) => {
+                let kind = match **kind {
+                    mir::AggregateKind::Array(ty) => smir::AggregateKind::Array(self.ty(ty)),
+                    mir::AggregateKind::Tuple => smir::AggregateKind::Tuple,
+                    mir::AggregateKind::Adt(def_id, variant, substs, ..) => {
+                        smir::AggregateKind::Adt(
+                            self.stable_def_id(def_id),
+                            variant.as_usize(),
+                            self.generic_args(substs),
+                        )
+                    }
+                    mir::AggregateKind::Closure(def_id, substs) => smir::AggregateKind::Closure(
+                        self.stable_def_id(def_id),
+                        self.generic_args(substs),
+                    ),
+                    mir::AggregateKind::Generator(def_id, substs, _) => {
+                        smir::AggregateKind::Generator(
+                            self.stable_def_id(def_id),
+                            self.generic_args(substs),
+                        )
+                    }
+                };
+                smir::Rvalue::Aggregate(kind, operands.iter().map(|op| self.operand(op)).collect())
+            }
+            ShallowInitBox(op, ty) => smir::Rvalue::ShallowInitBox(self.operand(op), self.ty(*ty)),
+        }
+    }
+
+    fn operand(&self, operand: &mir::Operand<'tcx>) -> smir::Operand {
+        match operand {
+            mir::Operand::Copy(place) => smir::Operand::Copy(self.place(place)),
+            mir::Operand::Move(place) => smir::Operand::Move(self.place(place)),
+            mir::Operand::Constant(constant) => smir::Operand::Constant(smir::Constant {
+                ty: self.ty(constant.ty()),
+                literal: sty::Const(constant.literal.to_string()),
+            }),
+        }
+    }
+
+    fn place(&self, place: &mir::Place<'tcx>) -> smir::Place {
+        let projection = place
+            .projection
+            .iter()
+            .map(|elem| match elem {
+                mir::ProjectionElem::Deref => smir::ProjectionElem::Deref,
+                mir::ProjectionElem::Field(field, ty) => {
+                    smir::ProjectionElem::Field(field.index(), self.ty(ty))
+                }
+                mir::ProjectionElem::Index(local) => smir::ProjectionElem::Index(local.as_usize()),
+                mir::ProjectionElem::ConstantIndex { offset, min_length, from_end } => {
+                    smir::ProjectionElem::ConstantIndex { offset, min_length, from_end }
+                }
+                mir::ProjectionElem::Subslice { from, to, from_end } => {
+                    smir::ProjectionElem::Subslice { from, to, from_end }
+                }
+                mir::ProjectionElem::Downcast(_, variant) => {
+                    smir::ProjectionElem::Downcast(variant.as_usize())
+                }
+            })
+            .collect();
+        smir::Place { local: place.local.as_usize(), projection }
+    }
+
+    pub(crate) fn ty(&self, ty: Ty<'tcx>) -> sty::Ty {
+        match *ty.kind() {
+            ty::Bool => sty::Ty::Bool,
+            ty::Char => sty::Ty::Char,
+            ty::Int(int) => sty::Ty::Int(match int {
+                ty::IntTy::Isize => sty::IntTy::Isize,
+                ty::IntTy::I8 => sty::IntTy::I8,
+                ty::IntTy::I16 => sty::IntTy::I16,
+                ty::IntTy::I32 => sty::IntTy::I32,
+                ty::IntTy::I64 => sty::IntTy::I64,
+                ty::IntTy::I128 => sty::IntTy::I128,
+            }),
+            ty::Uint(uint) => sty::Ty::Uint(match uint {
+                ty::UintTy::Usize => sty::UintTy::Usize,
+                ty::UintTy::U8 => sty::UintTy::U8,
+                ty::UintTy::U16 => sty::UintTy::U16,
+                ty::UintTy::U32 => sty::UintTy::U32,
+                ty::UintTy::U64 => sty::UintTy::U64,
+                ty::UintTy::U128 => sty::UintTy::U128,
+            }),
+            ty::Float(float) => sty::Ty::Float(match float {
+                ty::FloatTy::F32 => sty::FloatTy::F32,
+                ty::FloatTy::F64 => sty::FloatTy::F64,
+            }),
+            ty::Adt(adt_def, substs) => {
+                sty::Ty::Adt(self.stable_def_id(adt_def.did()), self.generic_args(substs))
+            }
+            ty::Foreign(def_id) => sty::Ty::Foreign(self.stable_def_id(def_id)),
+            ty::Str => sty::Ty::Str,
+            ty::Array(elem, len) => {
+                sty::Ty::Array(Box::new(self.ty(elem)), sty::Const(len.to_string()))
+            }
+            ty::Slice(elem) => sty::Ty::Slice(Box::new(self.ty(elem))),
+            ty::RawPtr(ty::TypeAndMut { ty, mutbl }) => {
+                sty::Ty::RawPtr(Box::new(self.ty(ty)), mutability(mutbl))
+            }
+            ty::Ref(_, ty, mutbl) => sty::Ty::Ref(Box::new(self.ty(ty)), mutability(mutbl)),
+            ty::FnDef(def_id, substs) => {
+                sty::Ty::FnDef(self.stable_def_id(def_id), self.generic_args(substs))
+            }
+            ty::FnPtr(sig) => {
+                // Late-bound regions are erased along with all other regions.
+                let sig = sig.skip_binder();
+                sty::Ty::FnPtr(sty::FnSig {
+                    inputs: sig.inputs().iter().map(|ty| self.ty(*ty)).collect(),
+                    output: Box::new(self.ty(sig.output())),
+                    c_variadic: sig.c_variadic,
+                    unsafety: match sig.unsafety {
+                        hir::Unsafety::Normal => sty::Unsafety::Safe,
+                        hir::Unsafety::Unsafe => sty::Unsafety::Unsafe,
+                    },
+                    abi: sig.abi.name().to_string(),
+                })
+            }
+            ty::Dynamic(predicates, _) => sty::Ty::Dynamic(
+                predicates
+                    .principal_def_id()
+                    .into_iter()
+                    .chain(predicates.auto_traits())
+                    .map(|def_id| self.stable_def_id(def_id))
+                    .collect(),
+            ),
+            ty::Closure(def_id, substs) => {
+                sty::Ty::Closure(self.stable_def_id(def_id), self.generic_args(substs))
+            }
+            ty::Generator(def_id, substs, _) => {
+                sty::Ty::Generator(self.stable_def_id(def_id), self.generic_args(substs))
+            }
+            ty::GeneratorWitness(_) => sty::Ty::GeneratorWitness,
+            ty::Never => sty::Ty::Never,
+            ty::Tuple(fields) => sty::Ty::Tuple(fields.iter().map(|ty| self.ty(ty)).collect()),
+            ty::Projection(projection) => sty::Ty::Projection(
+                self.stable_def_id(projection.item_def_id),
+                self.generic_args(projection.substs),
+            ),
+            ty::Opaque(def_id, substs) => {
+                sty::Ty::Opaque(self.stable_def_id(def_id), self.generic_args(substs))
+            }
+            ty::Param(param) => sty::Ty::Param(param.name.to_string(), param.index),
+            ty::Bound(..) | ty::Placeholder(..) | ty::Infer(..) | ty::Error(_) => {
+                bug!("unexpected type in optimized MIR: {:?}", ty)
+            }
+        }
+    }
+
+    pub(crate) fn generic_args(&self, substs: SubstsRef<'tcx>) -> Vec<sty::GenericArg> {
+        substs
+            .iter()
+            .map(|arg| match arg.unpack() {
+                GenericArgKind::Lifetime(_) => sty::GenericArg::Lifetime,
+                GenericArgKind::Type(ty) => sty::GenericArg::Type(self.ty(ty)),
+                GenericArgKind::Const(ct) => sty::GenericArg::Const(sty::Const(ct.to_string())),
+            })
+            .collect()
+    }
+}
+
+fn mutability(mutbl: hir::Mutability) -> sty::Mutability {
+    match mutbl {
+        hir::Mutability::Not => sty::Mutability::Not,
+        hir::Mutability::Mut => sty::Mutability::Mut,
+    }
+}
+
+fn borrow_kind(kind: mir::BorrowKind) -> smir::BorrowKind {
+    match kind {
+        mir::BorrowKind::Shared => smir::BorrowKind::Shared,
+        mir::BorrowKind::Shallow => smir::BorrowKind::Shallow,
+        mir::BorrowKind::Unique => smir::BorrowKind::Unique,
+        mir::BorrowKind::Mut { .. } => smir::BorrowKind::Mut,
+    }
+}
+
+fn cast_kind(kind: mir::CastKind) -> smir::CastKind {
+    match kind {
+        mir::CastKind::Misc => smir::CastKind::Misc,
+        mir::CastKind::Pointer(PointerCast::ReifyFnPointer) => smir::CastKind::ReifyFnPointer,
+        mir::CastKind::Pointer(PointerCast::UnsafeFnPointer) => smir::CastKind::UnsafeFnPointer,
+        mir::CastKind::Pointer(PointerCast::ClosureFnPointer(_)) => {
+            smir::CastKind::ClosureFnPointer
+        }
+        mir::CastKind::Pointer(PointerCast::MutToConstPointer) => smir::CastKind::MutToConstPointer,
+        mir::CastKind::Pointer(PointerCast::ArrayToPointer) => smir::CastKind::ArrayToPointer,
+        mir::CastKind::Pointer(PointerCast::Unsize) => smir::CastKind::Unsize,
+    }
+}
+
+fn bin_op(op: mir::BinOp) -> smir::BinOp {
+    match op {
+        mir::BinOp::Add => smir::BinOp::Add,
+        mir::BinOp::Sub => smir::BinOp::Sub,
+        mir::BinOp::Mul => smir::BinOp::Mul,
+        mir::BinOp::Div => smir::BinOp::Div,
+        mir::BinOp::Rem => smir::BinOp::Rem,
+        mir::BinOp::BitXor => smir::BinOp::BitXor,
+        mir::BinOp::BitAnd => smir::BinOp::BitAnd,
+        mir::BinOp::BitOr => smir::BinOp::BitOr,
+        mir::BinOp::Shl => smir::BinOp::Shl,
+        mir::BinOp::Shr => smir::BinOp::Shr,
+        mir::BinOp::Eq => smir::BinOp::Eq,
+        mir::BinOp::Lt => smir::BinOp::Lt,
+        mir::BinOp::Le => smir::BinOp::Le,
+        mir::BinOp::Ne => smir::BinOp::Ne,
+        mir::BinOp::Ge => smir::BinOp::Ge,
+        mir::BinOp::Gt => smir::BinOp::Gt,
+        mir::BinOp::Offset => smir::BinOp::Offset,
+    }
+}
@@ -0,0 +1,208 @@
+//! MIR bodies as seen by the Stable MIR.
+//!
+//! Basic blocks and locals are referred to by their index into [`Body::blocks`] and
+//! [`Body::locals`], which matches the `bbN` and `_N` names used when printing MIR.
+
+use super::ty::{Const, Mutability, Ty};
+use super::DefId;
+
+pub type BasicBlockIdx = usize;
+pub type Local = usize;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Body {
+    pub blocks: Vec<BasicBlock>,
+    /// The return place is the first local, followed by the arguments, followed by all other
+    /// locals.
+    pub locals: Vec<LocalDecl>,
+    pub arg_count: usize,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LocalDecl {
+    pub ty: Ty,
+    pub mutability: Mutability,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BasicBlock {
+    pub statements: Vec<Statement>,
+    pub terminator: Terminator,
+    /// Whether this block is only executed while unwinding.
+    pub is_cleanup: bool,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Statement {
+    Assign(Place, Rvalue),
+    SetDiscriminant {
+        place: Place,
+        variant_index: usize,
+    },
+    Deinit(Place),
+    StorageLive(Local),
+    StorageDead(Local),
+    CopyNonOverlapping {
+        src: Operand,
+        dst: Operand,
+        count: Operand,
+    },
+    /// A statement without effect on the program, including statements that are only used by
+    /// the compiler's own analyses.
+    Nop,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Terminator {
+    Goto {
+        target: BasicBlockIdx,
+    },
+    SwitchInt {
+        discr: Operand,
+        targets: Vec<(u128, BasicBlockIdx)>,
+        otherwise: BasicBlockIdx,
+    },
+    Resume,
+    Abort,
+    Return,
+    Unreachable,
+    Drop {
+        place: Place,
+        target: BasicBlockIdx,
+        unwind: Option<BasicBlockIdx>,
+    },
+    DropAndReplace {
+        place: Place,
+        value: Operand,
+        target: BasicBlockIdx,
+        unwind: Option<BasicBlockIdx>,
+    },
+    Call {
+        func: Operand,
+        args: Vec<Operand>,
+        destination: Option<(Place, BasicBlockIdx)>,
+        cleanup: Option<BasicBlockIdx>,
+    },
+    Assert {
+        cond: Operand,
+        expected: bool,
+        /// The panic message, e.g. `"attempt to add with overflow"`.
+        msg: String,
+        target: BasicBlockIdx,
+        cleanup: Option<BasicBlockIdx>,
+    },
+    InlineAsm {
+        template: String,
+        destination: Option<BasicBlockIdx>,
+        cleanup: Option<BasicBlockIdx>,
+    },
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Rvalue {
+    Use(Operand),
+    Repeat(Operand, Const),
+    Ref(BorrowKind, Place),
+    ThreadLocalRef(DefId),
+    AddressOf(Mutability, Place),
+    Len(Place),
+    Cast(CastKind, Operand, Ty),
+    BinaryOp(BinOp, Operand, Operand),
+    CheckedBinaryOp(BinOp, Operand, Operand),
+    NullaryOp(NullOp, Ty),
+    UnaryOp(UnOp, Operand),
+    Discriminant(Place),
+    Aggregate(AggregateKind, Vec<Operand>),
+    ShallowInitBox(Operand, Ty),
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Operand {
+    Copy(Place),
+    Move(Place),
+    Constant(Constant),
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Constant {
+    pub ty: Ty,
+    pub literal: Const,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Place {
+    pub local: Local,
+    pub projection: Vec<ProjectionElem>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ProjectionElem {
+    Deref,
+    Field(usize, Ty),
+    Index(Local),
+    ConstantIndex { offset: u64, min_length: u64, from_end: bool },
+    Subslice { from: u64, to: u64, from_end: bool },
+    Downcast(usize),
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum AggregateKind {
+    Array(Ty),
+    Tuple,
+    /// A struct, union or enum variant, with the index of the variant.
+    Adt(DefId, usize, Vec<super::ty::GenericArg>),
+    Closure(DefId, Vec<super::ty::GenericArg>),
+    Generator(DefId, Vec<super::ty::GenericArg>),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum BorrowKind {
+    Shared,
+    Shallow,
+    Unique,
+    Mut,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum CastKind {
+    Misc,
+    ReifyFnPointer,
+    UnsafeFnPointer,
+    ClosureFnPointer,
+    MutToConstPointer,
+    ArrayToPointer,
+    Unsize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    BitXor,
+    BitAnd,
+    BitOr,
+    Shl,
+    Shr,
+    Eq,
+    Lt,
+    Le,
+    Ne,
+    Ge,
+    Gt,
+    Offset,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum NullOp {
+    SizeOf,
+    AlignOf,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum UnOp {
+    Not,
+    Neg,
+}
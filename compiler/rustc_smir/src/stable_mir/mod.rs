@@ -0,0 +1,145 @@
+//! The public interface of the Stable MIR.
+//!
+//! Everything reachable from here is plain owned data that does not borrow from the compiler,
+//! so results can be stored and compared after the compiler session has ended. The only type
+//! that refers back into the compiler is [`Context`], which translates items on demand.
+
+use std::cell::RefCell;
+use std::hash::{Hash, Hasher};
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_middle::mir::mono::MonoItem;
+use rustc_middle::ty::{self as rustc_ty, TyCtxt};
+
+pub mod mir;
+pub mod ty;
+
+/// The version of the Stable MIR types.
+///
+/// This is bumped whenever a type in this module changes in a way that could break a tool
+/// written against the previous version.
+pub const VERSION: u32 = 1;
+
+/// A handle to a definition, e.g. a function, a struct or a trait.
+///
+/// Handles are only meaningful for the [`Context`] that created them.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct DefId(pub(crate) usize);
+
+/// An item of the local crate that has a MIR body.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct CrateItem(pub DefId);
+
+/// A function that is instantiated with concrete generic arguments and will be codegened.
+///
+/// Two instances are equal if they were created by the same [`Context`] for the same function
+/// and generic arguments.
+#[derive(Clone, Debug)]
+pub struct Instance {
+    /// The definition this is an instance of. For compiler-generated code such as drop glue,
+    /// this is the definition the code was generated for.
+    pub def: DefId,
+    /// The generic arguments of the definition.
+    pub args: Vec<ty::GenericArg>,
+    /// The mangled symbol name of the instance.
+    pub symbol_name: String,
+    pub(crate) index: usize,
+}
+
+// Every compiler instance is interned once per `Context`, so its index identifies it.
+impl PartialEq for Instance {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl Eq for Instance {}
+
+impl Hash for Instance {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state)
+    }
+}
+
+/// Gives access to the Stable MIR of the crate being compiled.
+pub struct Context<'tcx> {
+    pub(crate) tcx: TyCtxt<'tcx>,
+    pub(crate) tables: RefCell<Tables<'tcx>>,
+}
+
+/// Maps the handles given out to tools back to the compiler's own data structures.
+#[derive(Default)]
+pub(crate) struct Tables<'tcx> {
+    pub(crate) def_ids: Vec<rustc_hir::def_id::DefId>,
+    /// The index of each `DefId` in `def_ids`.
+    pub(crate) def_id_indices: FxHashMap<rustc_hir::def_id::DefId, usize>,
+    pub(crate) instances: Vec<rustc_ty::Instance<'tcx>>,
+    /// The index of each instance in `instances`.
+    pub(crate) instance_indices: FxHashMap<rustc_ty::Instance<'tcx>, usize>,
+}
+
+impl<'tcx> Context<'tcx> {
+    /// Creates a context for the crate being compiled. Must be called after analysis.
+    pub fn new(tcx: TyCtxt<'tcx>) -> Self {
+        Context { tcx, tables: Default::default() }
+    }
+
+    /// The name of the crate being compiled.
+    pub fn local_crate_name(&self) -> String {
+        self.tcx.crate_name(rustc_span::def_id::LOCAL_CRATE).to_string()
+    }
+
+    /// All items of the local crate that have a MIR body: functions, methods, closures and
+    /// generators, in no particular order. Constants and statics are not included.
+    #[allow(rustc::potential_query_instability)]
+    pub fn all_local_items(&self) -> Vec<CrateItem> {
+        self.tcx
+            .mir_keys(())
+            .iter()
+            .filter(|def_id| self.tcx.def_kind(**def_id).is_fn_like())
+            .map(|def_id| CrateItem(self.stable_def_id(def_id.to_def_id())))
+            .collect()
+    }
+
+    /// The path of a definition, e.g. `std::vec::Vec::push`.
+    pub fn def_path(&self, def: DefId) -> String {
+        self.tcx.def_path_str(self.internal_def_id(def))
+    }
+
+    /// The optimized MIR of a local item, before monomorphization.
+    pub fn item_body(&self, item: CrateItem) -> mir::Body {
+        let body = self.tcx.optimized_mir(self.internal_def_id(item.0));
+        self.body(body)
+    }
+
+    /// All functions that are going to be codegened for this crate, including functions from
+    /// other crates that are instantiated with types from this one, sorted by symbol name.
+    #[allow(rustc::potential_query_instability)] // The instances are sorted below.
+    pub fn mono_instances(&self) -> Vec<Instance> {
+        let (_, codegen_units) = self.tcx.collect_and_partition_mono_items(());
+
+        let mut seen = FxHashSet::default();
+        let mut instances: Vec<_> = codegen_units
+            .iter()
+            .flat_map(|cgu| cgu.items().keys())
+            .filter_map(|item| match *item {
+                MonoItem::Fn(instance) if seen.insert(instance) => Some(instance),
+                _ => None,
+            })
+            .map(|instance| self.instance(instance))
+            .collect();
+        instances.sort_by(|a, b| a.symbol_name.cmp(&b.symbol_name));
+        instances
+    }
+
+    /// The MIR of an instance, with all generic parameters replaced by the instance's arguments.
+    pub fn instance_body(&self, instance: &Instance) -> mir::Body {
+        let instance = self.tables.borrow().instances[instance.index];
+        let body = instance.subst_mir_and_normalize_erasing_regions(
+            self.tcx,
+            rustc_ty::ParamEnv::reveal_all(),
+            self.tcx.instance_mir(instance.def).clone(),
+        );
+        self.body(&body)
+    }
+}
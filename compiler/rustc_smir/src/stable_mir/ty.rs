@@ -0,0 +1,101 @@
+//! Types as seen by the Stable MIR.
+//!
+//! Lifetimes are not part of these types: after borrow checking, the MIR only contains erased
+//! regions, so there is nothing meaningful to expose.
+
+use super::DefId;
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Ty {
+    Bool,
+    Char,
+    Int(IntTy),
+    Uint(UintTy),
+    Float(FloatTy),
+    /// A struct, enum or union, with its generic arguments.
+    Adt(DefId, Vec<GenericArg>),
+    /// An `extern type`.
+    Foreign(DefId),
+    Str,
+    /// An array with the element type and the length, which may be a generic constant.
+    Array(Box<Ty>, Const),
+    Slice(Box<Ty>),
+    RawPtr(Box<Ty>, Mutability),
+    Ref(Box<Ty>, Mutability),
+    /// The zero-sized type of a function item, e.g. the type of `foo` in `let f = foo;`.
+    FnDef(DefId, Vec<GenericArg>),
+    FnPtr(FnSig),
+    /// A trait object, with the principal trait (if any) and auto traits it is made of.
+    Dynamic(Vec<DefId>),
+    Closure(DefId, Vec<GenericArg>),
+    Generator(DefId, Vec<GenericArg>),
+    /// The types of the values held across a suspension point of a generator.
+    GeneratorWitness,
+    Never,
+    Tuple(Vec<Ty>),
+    /// An associated type that could not be normalized, e.g. `<T as Iterator>::Item`.
+    Projection(DefId, Vec<GenericArg>),
+    /// An `impl Trait` type.
+    Opaque(DefId, Vec<GenericArg>),
+    /// A generic type parameter, with its name and index in the generics of its item.
+    Param(String, u32),
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum GenericArg {
+    Lifetime,
+    Type(Ty),
+    Const(Const),
+}
+
+/// A constant, rendered the way the compiler would print it in MIR.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Const(pub String);
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct FnSig {
+    pub inputs: Vec<Ty>,
+    pub output: Box<Ty>,
+    pub c_variadic: bool,
+    pub unsafety: Unsafety,
+    /// The ABI as written in source, e.g. `"C"` or `"Rust"`.
+    pub abi: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum IntTy {
+    Isize,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum UintTy {
+    Usize,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum FloatTy {
+    F32,
+    F64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Mutability {
+    Not,
+    Mut,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Unsafety {
+    Safe,
+    Unsafe,
+}
@@ -404,6 +404,7 @@
         breakpoint,
         bridge,
         bswap,
+        c_char,
         c_str,
         c_unwind,
         c_variadic,
@@ -800,6 +801,7 @@
         item_like_imports,
         iter,
         iter_repeat,
+        kcfi,
         keyword,
         kind,
         kreg,
@@ -950,6 +952,7 @@
         no_default_passes,
         no_implicit_prelude,
         no_inline,
+        no_instrument_function,
         no_link,
         no_main,
         no_mangle,
@@ -1237,6 +1240,7 @@
         self_in_typedefs,
         self_struct_ctor,
         semitransparent,
+        shadow_call_stack,
         shl,
         shl_assign,
         should_panic,
@@ -76,6 +76,10 @@ pub(super) fn mangle<'tcx>(
         let _ = printer.write_str("{{reify-shim}}");
     }
 
+    if let ty::InstanceDef::ColdPanicShim(..) = instance.def {
+        let _ = printer.write_str("{{cold-panic-shim}}");
+    }
+
     printer.path.finish(hash)
 }
 
@@ -134,6 +138,11 @@ fn get_symbol_hash<'tcx>(
                 // Especially, `VtableShim`s and `ReifyShim`s may overlap with their original
                 // instances without this.
                 discriminant(&instance.def).hash_stable(hcx, &mut hasher);
+
+                // A function can have several outlined cold paths.
+                if let ty::InstanceDef::ColdPanicShim(_, index) = instance.def {
+                    index.hash_stable(hcx, &mut hasher);
+                }
             });
         });
     });
@@ -108,6 +108,7 @@
 use tracing::debug;
 
 mod legacy;
+mod typeid_itanium_cxx_abi;
 mod v0;
 
 pub mod test;
@@ -149,9 +150,17 @@ fn symbol_name_provider<'tcx>(tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> ty
     ty::SymbolName::new(tcx, &symbol_name)
 }
 
-/// This function computes the typeid for the given function ABI.
+/// This function computes the typeid for the given function ABI. For `extern "C"` functions, it
+/// is the typeid Clang uses for C functions of the same type.
 pub fn typeid_for_fnabi<'tcx>(tcx: TyCtxt<'tcx>, fn_abi: &FnAbi<'tcx, Ty<'tcx>>) -> String {
-    v0::mangle_typeid_for_fnabi(tcx, fn_abi)
+    typeid_itanium_cxx_abi::typeid_for_fnabi(tcx, fn_abi)
+        .unwrap_or_else(|| v0::mangle_typeid_for_fnabi(tcx, fn_abi))
+}
+
+/// This function computes the KCFI typeid for the given function ABI, which is derived from its
+/// typeid the same way Clang derives it.
+pub fn kcfi_typeid_for_fnabi<'tcx>(tcx: TyCtxt<'tcx>, fn_abi: &FnAbi<'tcx, Ty<'tcx>>) -> u32 {
+    typeid_itanium_cxx_abi::kcfi_typeid(&typeid_for_fnabi(tcx, fn_abi))
 }
 
 /// Computes the symbol name for the given instance. This function will call
@@ -203,12 +212,15 @@ fn compute_symbol_name<'tcx>(
         return tcx.item_name(def_id).to_string();
     }
 
-    if let Some(name) = attrs.export_name {
+    // Cold paths outlined from a function share its attributes, but not its symbol name.
+    let is_cold_panic_shim = matches!(instance.def, ty::InstanceDef::ColdPanicShim(..));
+
+    if let Some(name) = attrs.export_name.filter(|_| !is_cold_panic_shim) {
         // Use provided name
         return name.to_string();
     }
 
-    if attrs.flags.contains(CodegenFnAttrFlags::NO_MANGLE) {
+    if attrs.flags.contains(CodegenFnAttrFlags::NO_MANGLE) && !is_cold_panic_shim {
         // Don't mangle
         return tcx.item_name(def_id).to_string();
     }
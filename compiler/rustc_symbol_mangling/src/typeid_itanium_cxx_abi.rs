@@ -0,0 +1,299 @@
+//! Type identifiers for function ABIs that are compatible with the ones Clang uses for LLVM CFI
+//! and KCFI, so that C and C++ code compiled with `-fsanitize=cfi-icall` or `-fsanitize=kcfi` can
+//! call `extern "C"` functions defined in Rust through function pointers, and vice versa.
+//!
+//! Clang uses the Itanium C++ ABI's[1] RTTI typeinfo structure name[2] of the function type as
+//! type identifier, i.e., `_ZTS` followed by the mangled function type. For KCFI, the type
+//! identifier is the lower 32 bits of the xxHash64 of that name.
+//!
+//! Rust integer types are encoded as the C integer types of the same size and signedness that
+//! Clang would use. Type aliases are resolved by the time the type identifier is computed, so the
+//! integer type that `core::ffi::c_char` is an alias of on the target (`i8` or `u8`) is always
+//! encoded as `char`, which C interfaces use much more often than `signed char` or `unsigned char`.
+//! C functions taking a `signed char` on targets where `char` is signed, or an `unsigned char` on
+//! targets where it is unsigned, can therefore not be called through a checked function pointer.
+//!
+//! [1] https://itanium-cxx-abi.github.io/cxx-abi/abi.html#mangling-type
+//! [2] https://itanium-cxx-abi.github.io/cxx-abi/abi.html#mangling-special-vtables
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::Mutability;
+use rustc_middle::ty::{self, FloatTy, IntTy, Ty, TyCtxt, UintTy};
+use rustc_span::sym;
+use rustc_target::abi::call::{Conv, FnAbi};
+
+/// Returns the type identifier of `fn_abi` as Clang computes it for a C function type, or `None`
+/// if `fn_abi` does not use the C calling convention or has types that cannot be expressed in C.
+pub fn typeid_for_fnabi<'tcx>(tcx: TyCtxt<'tcx>, fn_abi: &FnAbi<'tcx, Ty<'tcx>>) -> Option<String> {
+    if fn_abi.conv != Conv::C {
+        return None;
+    }
+    let mut encoder = Encoder { tcx, substitutions: FxHashMap::default() };
+    let mut typeid = String::from("_ZTSF");
+    if fn_abi.ret.is_ignore() {
+        typeid.push('v');
+    } else {
+        typeid.push_str(&encoder.encode_ty(fn_abi.ret.layout.ty)?.emitted);
+    }
+    let mut has_args = false;
+    for arg in fn_abi.args[..fn_abi.fixed_count].iter().filter(|arg| !arg.is_ignore()) {
+        typeid.push_str(&encoder.encode_ty(arg.layout.ty)?.emitted);
+        has_args = true;
+    }
+    if fn_abi.c_variadic {
+        typeid.push('z');
+    } else if !has_args {
+        typeid.push('v');
+    }
+    typeid.push('E');
+    Some(typeid)
+}
+
+/// Returns the lower 32 bits of the xxHash64 of `typeid`, as Clang computes KCFI type identifiers.
+pub fn kcfi_typeid(typeid: &str) -> u32 {
+    xxhash64(typeid.as_bytes(), 0) as u32
+}
+
+/// An encoded type: `canonical` is the encoding without substitutions, which identifies the type
+/// for substitutions, and `emitted` is the encoding that goes into the type identifier.
+struct Encoded {
+    canonical: String,
+    emitted: String,
+}
+
+struct Encoder<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    /// The sequence numbers of the substitution candidates seen so far, by canonical encoding.
+    substitutions: FxHashMap<String, usize>,
+}
+
+impl<'tcx> Encoder<'tcx> {
+    fn encode_ty(&mut self, ty: Ty<'tcx>) -> Option<Encoded> {
+        let builtin = |code: &str| Some(Encoded { canonical: code.into(), emitted: code.into() });
+        let target = &self.tcx.sess.target;
+        let int_width = if target.c_int_width == "16" { 16 } else { 32 };
+        // `long` is as wide as pointers, but at least 32 bits wide, except on Windows, where it
+        // is always 32 bits wide.
+        let long_width =
+            if target.is_like_windows { 32 } else { u64::from(target.pointer_width).max(32) };
+        let signed = |bits: u64| match bits {
+            8 => "a",
+            _ if bits == int_width => "i",
+            16 => "s",
+            _ if bits == long_width => "l",
+            64 => "x",
+            128 => "n",
+            _ => unreachable!(),
+        };
+        let unsigned = |bits: u64| match bits {
+            8 => "h",
+            _ if bits == int_width => "j",
+            16 => "t",
+            _ if bits == long_width => "m",
+            64 => "y",
+            128 => "o",
+            _ => unreachable!(),
+        };
+        let pointer_width = u64::from(target.pointer_width);
+
+        if let Some(c_char) = self.tcx.get_diagnostic_item(sym::c_char) {
+            if ty == self.tcx.type_of(c_char) {
+                return builtin("c");
+            }
+        }
+
+        match *ty.kind() {
+            ty::Bool => builtin("b"),
+            ty::Int(int_ty) => builtin(signed(match int_ty {
+                IntTy::Isize => pointer_width,
+                IntTy::I8 => 8,
+                IntTy::I16 => 16,
+                IntTy::I32 => 32,
+                IntTy::I64 => 64,
+                IntTy::I128 => 128,
+            })),
+            ty::Uint(uint_ty) => builtin(unsigned(match uint_ty {
+                UintTy::Usize => pointer_width,
+                UintTy::U8 => 8,
+                UintTy::U16 => 16,
+                UintTy::U32 => 32,
+                UintTy::U64 => 64,
+                UintTy::U128 => 128,
+            })),
+            ty::Float(FloatTy::F32) => builtin("f"),
+            ty::Float(FloatTy::F64) => builtin("d"),
+            ty::Tuple(tys) if tys.is_empty() => builtin("v"),
+            ty::Never => builtin("v"),
+
+            // References are pointers in C.
+            ty::RawPtr(ty::TypeAndMut { ty: pointee, mutbl }) | ty::Ref(_, pointee, mutbl) => {
+                let pointee = self.encode_ty(pointee)?;
+                let pointee = match mutbl {
+                    Mutability::Not => self.substitutable(
+                        format!("K{}", pointee.canonical),
+                        format!("K{}", pointee.emitted),
+                    ),
+                    Mutability::Mut => pointee,
+                };
+                Some(self.substitutable(
+                    format!("P{}", pointee.canonical),
+                    format!("P{}", pointee.emitted),
+                ))
+            }
+
+            ty::FnPtr(sig) => {
+                let sig = self.tcx.erase_late_bound_regions(sig);
+                let mut canonical = String::from("F");
+                let mut emitted = String::from("F");
+                let output = self.encode_ty(sig.output())?;
+                canonical.push_str(&output.canonical);
+                emitted.push_str(&output.emitted);
+                for &input in sig.inputs() {
+                    let input = self.encode_ty(input)?;
+                    canonical.push_str(&input.canonical);
+                    emitted.push_str(&input.emitted);
+                }
+                if sig.c_variadic {
+                    canonical.push('z');
+                    emitted.push('z');
+                } else if sig.inputs().is_empty() {
+                    canonical.push('v');
+                    emitted.push('v');
+                }
+                canonical.push('E');
+                emitted.push('E');
+                let function = self.substitutable(canonical, emitted);
+                Some(self.substitutable(
+                    format!("P{}", function.canonical),
+                    format!("P{}", function.emitted),
+                ))
+            }
+
+            ty::Adt(adt_def, substs) => {
+                let did = adt_def.did();
+                if self.tcx.crate_name(did.krate) == sym::core
+                    && self.tcx.item_name(did).as_str() == "c_void"
+                {
+                    return builtin("v");
+                }
+                if adt_def.repr().transparent() {
+                    // A `#[repr(transparent)]` type is its non-zero-sized field in C.
+                    let param_env = ty::ParamEnv::reveal_all();
+                    let field = adt_def.all_fields().map(|field| field.ty(self.tcx, substs)).find(
+                        |&field_ty| {
+                            self.tcx
+                                .layout_of(param_env.and(field_ty))
+                                .map_or(true, |layout| !layout.is_zst())
+                        },
+                    )?;
+                    return self.encode_ty(field);
+                }
+                if !adt_def.repr().c() && !adt_def.is_enum() {
+                    return None;
+                }
+                // Named types are encoded with their unqualified names, like C tags.
+                let name = self.tcx.item_name(did);
+                let name = format!("{}{}", name.as_str().len(), name);
+                Some(self.substitutable(name.clone(), name))
+            }
+
+            _ => None,
+        }
+    }
+
+    /// Returns `emitted`, or a substitution if the type encoded as `canonical` was seen before.
+    fn substitutable(&mut self, canonical: String, emitted: String) -> Encoded {
+        if let Some(&seq_id) = self.substitutions.get(&canonical) {
+            let emitted =
+                if seq_id == 0 { "S_".to_string() } else { format!("S{}_", to_base36(seq_id - 1)) };
+            return Encoded { canonical, emitted };
+        }
+        let seq_id = self.substitutions.len();
+        self.substitutions.insert(canonical.clone(), seq_id);
+        Encoded { canonical, emitted }
+    }
+}
+
+fn to_base36(mut n: usize) -> String {
+    const DIGITS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    let mut digits = Vec::new();
+    loop {
+        digits.push(DIGITS[n % 36]);
+        n /= 36;
+        if n == 0 {
+            break;
+        }
+    }
+    digits.reverse();
+    String::from_utf8(digits).unwrap()
+}
+
+/// The xxHash64 hash function, as implemented by `llvm::xxHash64`.
+fn xxhash64(input: &[u8], seed: u64) -> u64 {
+    const PRIME64_1: u64 = 0x9E3779B185EBCA87;
+    const PRIME64_2: u64 = 0xC2B2AE3D27D4EB4F;
+    const PRIME64_3: u64 = 0x165667B19E3779F9;
+    const PRIME64_4: u64 = 0x85EBCA77C2B2AE63;
+    const PRIME64_5: u64 = 0x27D4EB2F165667C5;
+
+    fn round(acc: u64, input: u64) -> u64 {
+        acc.wrapping_add(input.wrapping_mul(PRIME64_2)).rotate_left(31).wrapping_mul(PRIME64_1)
+    }
+    fn merge_round(acc: u64, val: u64) -> u64 {
+        (acc ^ round(0, val)).wrapping_mul(PRIME64_1).wrapping_add(PRIME64_4)
+    }
+    fn read_u64(bytes: &[u8]) -> u64 {
+        u64::from_le_bytes(bytes[..8].try_into().unwrap())
+    }
+    fn read_u32(bytes: &[u8]) -> u64 {
+        u64::from(u32::from_le_bytes(bytes[..4].try_into().unwrap()))
+    }
+
+    let mut rest = input;
+    let mut hash = if input.len() >= 32 {
+        let mut v1 = seed.wrapping_add(PRIME64_1).wrapping_add(PRIME64_2);
+        let mut v2 = seed.wrapping_add(PRIME64_2);
+        let mut v3 = seed;
+        let mut v4 = seed.wrapping_sub(PRIME64_1);
+        while rest.len() >= 32 {
+            v1 = round(v1, read_u64(&rest[0..]));
+            v2 = round(v2, read_u64(&rest[8..]));
+            v3 = round(v3, read_u64(&rest[16..]));
+            v4 = round(v4, read_u64(&rest[24..]));
+            rest = &rest[32..];
+        }
+        let mut hash = v1
+            .rotate_left(1)
+            .wrapping_add(v2.rotate_left(7))
+            .wrapping_add(v3.rotate_left(12))
+            .wrapping_add(v4.rotate_left(18));
+        hash = merge_round(hash, v1);
+        hash = merge_round(hash, v2);
+        hash = merge_round(hash, v3);
+        merge_round(hash, v4)
+    } else {
+        seed.wrapping_add(PRIME64_5)
+    };
+    hash = hash.wrapping_add(input.len() as u64);
+
+    while rest.len() >= 8 {
+        hash ^= round(0, read_u64(rest));
+        hash = hash.rotate_left(27).wrapping_mul(PRIME64_1).wrapping_add(PRIME64_4);
+        rest = &rest[8..];
+    }
+    if rest.len() >= 4 {
+        hash ^= read_u32(rest).wrapping_mul(PRIME64_1);
+        hash = hash.rotate_left(23).wrapping_mul(PRIME64_2).wrapping_add(PRIME64_3);
+        rest = &rest[4..];
+    }
+    for &byte in rest {
+        hash ^= u64::from(byte).wrapping_mul(PRIME64_5);
+        hash = hash.rotate_left(11).wrapping_mul(PRIME64_1);
+    }
+
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(PRIME64_2);
+    hash ^= hash >> 29;
+    hash = hash.wrapping_mul(PRIME64_3);
+    hash ^ (hash >> 32)
+}
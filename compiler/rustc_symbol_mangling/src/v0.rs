@@ -41,14 +41,16 @@ pub(super) fn mangle<'tcx>(
 
     // Append `::{shim:...#0}` to shims that can coexist with a non-shim instance.
     let shim_kind = match instance.def {
-        ty::InstanceDef::VtableShim(_) => Some("vtable"),
-        ty::InstanceDef::ReifyShim(_) => Some("reify"),
+        ty::InstanceDef::VtableShim(_) => Some(("vtable", 0)),
+        ty::InstanceDef::ReifyShim(_) => Some(("reify", 0)),
+        ty::InstanceDef::ColdPanicShim(_, index) => Some(("cold_panic", index.into())),
 
         _ => None,
     };
 
-    cx = if let Some(shim_kind) = shim_kind {
-        cx.path_append_ns(|cx| cx.print_def_path(def_id, substs), 'S', 0, shim_kind).unwrap()
+    cx = if let Some((shim_kind, disambiguator)) = shim_kind {
+        cx.path_append_ns(|cx| cx.print_def_path(def_id, substs), 'S', disambiguator, shim_kind)
+            .unwrap()
     } else {
         cx.print_def_path(def_id, substs).unwrap()
     };
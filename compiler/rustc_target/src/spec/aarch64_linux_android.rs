@@ -17,6 +17,8 @@ pub fn target() -> Target {
             supported_sanitizers: SanitizerSet::CFI
                 | SanitizerSet::HWADDRESS
                 | SanitizerSet::MEMTAG
+                | SanitizerSet::SHADOWCALLSTACK
+                | SanitizerSet::KCFI
                 | SanitizerSet::ADDRESS,
             ..super::android_base::opts()
         },
@@ -5,8 +5,8 @@
 // features.
 
 use super::{
-    CodeModel, LinkerFlavor, LldFlavor, PanicStrategy, RelocModel, RelroLevel, StackProbeType,
-    Target, TargetOptions,
+    CodeModel, LinkerFlavor, LldFlavor, PanicStrategy, RelocModel, RelroLevel, SanitizerSet,
+    StackProbeType, Target, TargetOptions,
 };
 
 pub fn target() -> Target {
@@ -28,6 +28,7 @@ pub fn target() -> Target {
         disable_redzone: true,
         panic_strategy: PanicStrategy::Abort,
         code_model: Some(CodeModel::Kernel),
+        supported_sanitizers: SanitizerSet::KCFI,
         ..Default::default()
     };
     Target {
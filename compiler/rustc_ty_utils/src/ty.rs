@@ -354,7 +354,7 @@ fn instance_def_size_estimate<'tcx>(
     use ty::InstanceDef;
 
     match instance_def {
-        InstanceDef::Item(..) | InstanceDef::DropGlue(..) => {
+        InstanceDef::Item(..) | InstanceDef::DropGlue(..) | InstanceDef::ColdPanicShim(..) => {
             let mir = tcx.instance_mir(instance_def);
             mir.basic_blocks().iter().map(|bb| bb.statements.len() + 1).sum()
         }
@@ -144,10 +144,12 @@ mod c_char_definition {
             ),
             all(target_os = "fuchsia", target_arch = "aarch64")
         ))] {
+            #[rustc_diagnostic_item = "c_char"]
             pub type c_char = u8;
             pub type NonZero_c_char = crate::num::NonZeroU8;
         } else {
             // On every other target, c_char is signed.
+            #[rustc_diagnostic_item = "c_char"]
             pub type c_char = i8;
             pub type NonZero_c_char = crate::num::NonZeroI8;
         }
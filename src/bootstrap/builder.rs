@@ -474,6 +474,7 @@ macro_rules! describe {
                 tool::Cargo,
                 tool::Rls,
                 tool::RustAnalyzer,
+                tool::RustCov,
                 tool::RustDemangler,
                 tool::Rustdoc,
                 tool::Clippy,
@@ -538,6 +539,7 @@ macro_rules! describe {
                 test::Rustfmt,
                 test::Miri,
                 test::Clippy,
+                test::RustCov,
                 test::RustDemangler,
                 test::CompiletestTest,
                 test::RustdocJSStd,
@@ -587,6 +589,7 @@ macro_rules! describe {
                 dist::Rls,
                 dist::RustAnalyzer,
                 dist::Rustfmt,
+                dist::RustCov,
                 dist::RustDemangler,
                 dist::Clippy,
                 dist::Miri,
@@ -608,6 +611,7 @@ macro_rules! describe {
                 install::Rls,
                 install::RustAnalyzer,
                 install::Rustfmt,
+                install::RustCov,
                 install::RustDemangler,
                 install::Clippy,
                 install::Miri,
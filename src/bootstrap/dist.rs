@@ -1253,6 +1253,60 @@ fn run(self, builder: &Builder<'_>) -> Option<GeneratedTarball> {
     }
 }
 
+#[derive(Debug, PartialOrd, Ord, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct RustCov {
+    pub compiler: Compiler,
+    pub target: TargetSelection,
+}
+
+impl Step for RustCov {
+    type Output = Option<GeneratedTarball>;
+    const DEFAULT: bool = true;
+    const ONLY_HOSTS: bool = true;
+
+    fn should_run(run: ShouldRun<'_>) -> ShouldRun<'_> {
+        // Like `rust-demangler`, `rust-cov` is built when either it's enabled as a tool, or if
+        // `profiler = true` for the target, which is only known when running the step.
+        let default = run.builder.config.extended;
+        run.path("rust-cov").default_condition(default)
+    }
+
+    fn make_run(run: RunConfig<'_>) {
+        run.builder.ensure(RustCov {
+            compiler: run.builder.compiler_for(
+                run.builder.top_stage,
+                run.builder.config.build,
+                run.target,
+            ),
+            target: run.target,
+        });
+    }
+
+    fn run(self, builder: &Builder<'_>) -> Option<GeneratedTarball> {
+        let compiler = self.compiler;
+        let target = self.target;
+
+        // Only build this extended tool if explicitly included in `tools`, or if `profiler = true`
+        let condition = should_build_extended_tool(builder, "rust-cov")
+            || builder.config.profiler_enabled(target);
+        if builder.config.extended && !condition {
+            return None;
+        }
+
+        let rust_cov = builder
+            .ensure(tool::RustCov { compiler, target, extra_features: Vec::new() })
+            .expect("rust-cov expected to build - in-tree tool");
+
+        // Prepare the image directory
+        let mut tarball = Tarball::new(builder, "rust-cov", &target.triple);
+        tarball.set_overlay(OverlayKind::RustCov);
+        tarball.is_preview(true);
+        tarball.add_file(&rust_cov, "bin", 0o755);
+        tarball.add_legal_and_readme_to("share/doc/rust-cov");
+        Some(tarball.generate())
+    }
+}
+
 #[derive(Debug, PartialOrd, Ord, Copy, Clone, Hash, PartialEq, Eq)]
 pub struct RustDemangler {
     pub compiler: Compiler,
@@ -1366,6 +1420,7 @@ macro_rules! add_component {
         }
 
         add_component!("rust-docs" => Docs { host: target });
+        add_component!("rust-cov" => RustCov { compiler, target });
         add_component!("rust-demangler"=> RustDemangler { compiler, target });
         add_component!("cargo" => Cargo { compiler, target });
         add_component!("rustfmt" => Rustfmt { compiler, target });
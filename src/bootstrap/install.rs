@@ -206,6 +206,21 @@ fn run($sel, $builder: &Builder<'_>) {
             );
         }
     };
+    RustCov, "rust-cov", Self::should_build(_config), only_hosts: true, {
+        // Like `rust-demangler`, dist::RustCov may return None unless `profiler` is enabled for
+        // the target, or the `tools` array explicitly includes "rust-cov".
+        if let Some(tarball) = builder.ensure(dist::RustCov {
+            compiler: self.compiler,
+            target: self.target
+        }) {
+            install_sh(builder, "rust-cov", self.compiler.stage, Some(self.target), &tarball);
+        } else {
+            builder.info(
+                &format!("skipping Install RustCov stage{} ({})",
+                         self.compiler.stage, self.target),
+            );
+        }
+    };
     RustDemangler, "rust-demangler", Self::should_build(_config), only_hosts: true, {
         // Note: Even though `should_build` may return true for `extended` default tools,
         // dist::RustDemangler may still return None, unless the target-dependent `profiler` config
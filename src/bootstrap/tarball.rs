@@ -14,6 +14,7 @@ pub(crate) enum OverlayKind {
     Clippy,
     Miri,
     Rustfmt,
+    RustCov,
     RustDemangler,
     RLS,
     RustAnalyzer,
@@ -47,6 +48,9 @@ fn legal_and_readme(&self) -> &[&str] {
                 "src/tools/rustfmt/LICENSE-APACHE",
                 "src/tools/rustfmt/LICENSE-MIT",
             ],
+            OverlayKind::RustCov => {
+                &["src/tools/rust-cov/README.md", "LICENSE-APACHE", "LICENSE-MIT"]
+            }
             OverlayKind::RustDemangler => {
                 &["src/tools/rust-demangler/README.md", "LICENSE-APACHE", "LICENSE-MIT"]
             }
@@ -67,6 +71,7 @@ fn version(&self, builder: &Builder<'_>) -> String {
         match self {
             OverlayKind::Rust => builder.rust_version(),
             OverlayKind::LLVM => builder.rust_version(),
+            OverlayKind::RustCov => builder.release_num("rust-cov"),
             OverlayKind::RustDemangler => builder.release_num("rust-demangler"),
             OverlayKind::Cargo => {
                 builder.cargo_info.version(builder, &builder.release_num("cargo"))
@@ -401,6 +401,49 @@ fn run(self, builder: &Builder<'_>) {
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct RustCov {
+    stage: u32,
+    host: TargetSelection,
+}
+
+impl Step for RustCov {
+    type Output = ();
+    const ONLY_HOSTS: bool = true;
+
+    fn should_run(run: ShouldRun<'_>) -> ShouldRun<'_> {
+        run.path("src/tools/rust-cov")
+    }
+
+    fn make_run(run: RunConfig<'_>) {
+        run.builder.ensure(RustCov { stage: run.builder.top_stage, host: run.target });
+    }
+
+    /// Runs `cargo test` for rust-cov.
+    fn run(self, builder: &Builder<'_>) {
+        let stage = self.stage;
+        let host = self.host;
+        let compiler = builder.compiler(stage, host);
+
+        let mut cargo = tool::prepare_tool_cargo(
+            builder,
+            compiler,
+            Mode::ToolRustc,
+            host,
+            "test",
+            "src/tools/rust-cov",
+            SourceType::InTree,
+            &[],
+        );
+
+        cargo.arg("--").args(builder.config.cmd.test_args());
+
+        cargo.add_rustc_lib_path(builder, compiler);
+
+        builder.run(&mut cargo.into());
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct RustDemangler {
     stage: u32,
@@ -1582,14 +1582,6 @@ impl Type {
         }
     }
 
-    crate fn is_full_generic(&self) -> bool {
-        matches!(self, Type::Generic(_))
-    }
-
-    crate fn is_primitive(&self) -> bool {
-        self.primitive_type().is_some()
-    }
-
     crate fn projection(&self) -> Option<(&Type, DefId, PathSegment)> {
         if let QPath { self_type, trait_, assoc, .. } = self {
             Some((&self_type, trait_.def_id(), *assoc.clone()))
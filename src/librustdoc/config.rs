@@ -2,14 +2,14 @@
 use std::convert::TryFrom;
 use std::ffi::OsStr;
 use std::fmt;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 use std::str::FromStr;
 
 use rustc_data_structures::fx::FxHashMap;
 use rustc_session::config::{
     self, parse_crate_types_from_list, parse_externs, parse_target_triple, CrateType,
 };
-use rustc_session::config::{get_cmd_lint_options, nightly_options};
+use rustc_session::config::{get_cmd_lint_options, nightly_options, InstrumentCoverage};
 use rustc_session::config::{CodegenOptions, DebuggingOptions, ErrorOutputType, Externs};
 use rustc_session::getopts;
 use rustc_session::lint::Level;
@@ -23,6 +23,7 @@
 use crate::html::markdown::IdMap;
 use crate::html::render::StylePath;
 use crate::html::static_files;
+use crate::json::history::ReleaseHistory;
 use crate::opts;
 use crate::passes::{self, Condition};
 use crate::scrape_examples::{AllCallLocations, ScrapeExamplesOptions};
@@ -32,6 +33,8 @@
 crate enum OutputFormat {
     Json,
     Html,
+    Markdown,
+    Man,
 }
 
 impl Default for OutputFormat {
@@ -53,6 +56,8 @@ fn try_from(value: &str) -> Result<Self, Self::Error> {
         match value {
             "json" => Ok(OutputFormat::Json),
             "html" => Ok(OutputFormat::Html),
+            "markdown" => Ok(OutputFormat::Markdown),
+            "man" => Ok(OutputFormat::Man),
             _ => Err(format!("unknown output format `{}`", value)),
         }
     }
@@ -141,7 +146,7 @@ fn try_from(value: &str) -> Result<Self, Self::Error> {
     crate render_options: RenderOptions,
     /// The format that we output when rendering.
     ///
-    /// Currently used only for the `--show-coverage` option.
+    /// Currently used only for the `--show-coverage` and `--api-diff` options.
     crate output_format: OutputFormat,
     /// If this option is set to `true`, rustdoc will only run checks and not generate
     /// documentation.
@@ -150,10 +155,23 @@ fn try_from(value: &str) -> Result<Self, Self::Error> {
     crate json_unused_externs: bool,
     /// Whether to skip capturing stdout and stderr of tests.
     crate nocapture: bool,
+    /// Whether to compile compatible doctests together into a single binary per edition.
+    crate merge_doctests: bool,
+    /// Where to keep the doctest binaries and the coverage profiles of their runs, with
+    /// `-C instrument-coverage`.
+    crate doctest_coverage_dir: Option<PathBuf>,
 
     /// Configuration for scraping examples from the current crate. If this option is Some(..) then
     /// the compiler will scrape examples and not generate documentation.
     crate scrape_examples_options: Option<ScrapeExamplesOptions>,
+
+    /// The JSON output of the old version of the crate, if the public API of the crate described by
+    /// the JSON file `input` should be compared to it instead of generating documentation.
+    crate api_diff: Option<PathBuf>,
+
+    /// The public items of the previous releases of the crate, read from their JSON output, to
+    /// compute the versions since which the items are available.
+    crate release_history: Option<ReleaseHistory>,
 }
 
 impl fmt::Debug for Options {
@@ -196,7 +214,11 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             .field("run_check", &self.run_check)
             .field("no_run", &self.no_run)
             .field("nocapture", &self.nocapture)
+            .field("merge_doctests", &self.merge_doctests)
+            .field("doctest_coverage_dir", &self.doctest_coverage_dir)
             .field("scrape_examples_options", &self.scrape_examples_options)
+            .field("api_diff", &self.api_diff)
+            .field("release_history", &self.release_history)
             .finish()
     }
 }
@@ -247,9 +269,15 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     /// A file to use as the index page at the root of the output directory. Overrides
     /// `enable_index_page` to be true if set.
     crate index_page: Option<PathBuf>,
+    /// Whether to generate a workspace landing page at the root of the output directory, listing
+    /// all the documented crates along with their dependency graph. Implies `enable_index_page`.
+    crate workspace_index: bool,
     /// An optional path to use as the location of static files. If not set, uses combinations of
     /// `../` to reach the documentation root.
     crate static_root_path: Option<String>,
+    /// If present, the path of a zip archive to write the whole output directory to, so it can be
+    /// browsed offline.
+    crate offline_bundle: Option<PathBuf>,
 
     // Options specific to reading standalone Markdown files
     /// Whether to generate a table of contents on the output file when reading a standalone
@@ -593,6 +621,12 @@ fn println_condition(condition: Condition) {
             }
         }
 
+        let workspace_index = matches.opt_present("workspace-index");
+        if workspace_index && index_page.is_some() {
+            diag.struct_err("`--workspace-index` cannot be used with `--index-page`").emit();
+            return Err(1);
+        }
+
         let target = parse_target_triple(matches, error_format);
 
         let show_coverage = matches.opt_present("show-coverage");
@@ -609,9 +643,10 @@ fn println_condition(condition: Condition) {
             Some(s) => match OutputFormat::try_from(s.as_str()) {
                 Ok(out_fmt) => {
                     if !out_fmt.is_json() && show_coverage {
-                        diag.struct_err(
-                            "html output format isn't supported for the --show-coverage option",
-                        )
+                        diag.struct_err(&format!(
+                            "{} output format isn't supported for the --show-coverage option",
+                            s
+                        ))
                         .emit();
                         return Err(1);
                     }
@@ -635,7 +670,8 @@ fn println_condition(condition: Condition) {
         let markdown_css = matches.opt_strs("markdown-css");
         let markdown_playground_url = matches.opt_str("markdown-playground-url");
         let crate_version = matches.opt_str("crate-version");
-        let enable_index_page = matches.opt_present("enable-index-page") || index_page.is_some();
+        let enable_index_page =
+            matches.opt_present("enable-index-page") || index_page.is_some() || workspace_index;
         let static_root_path = matches.opt_str("static-root-path");
         let test_run_directory = matches.opt_str("test-run-directory").map(PathBuf::from);
         let persist_doctests = matches.opt_str("persist-doctests").map(PathBuf::from);
@@ -653,10 +689,31 @@ fn println_condition(condition: Condition) {
         let generate_redirect_map = matches.opt_present("generate-redirect-map");
         let show_type_layout = matches.opt_present("show-type-layout");
         let nocapture = matches.opt_present("nocapture");
+        let merge_doctests = matches.opt_present("merge-doctests");
         let generate_link_to_definition = matches.opt_present("generate-link-to-definition");
         let extern_html_root_takes_precedence =
             matches.opt_present("extern-html-root-takes-precedence");
 
+        let offline_bundle = matches.opt_str("offline-bundle").map(PathBuf::from);
+        if let Some(ref offline_bundle) = offline_bundle {
+            if show_coverage || output_format != OutputFormat::Html {
+                diag.struct_err("`--offline-bundle` can only be used with HTML output format")
+                    .emit();
+                return Err(1);
+            }
+            if static_root_path.is_some() {
+                diag.struct_err("`--offline-bundle` cannot be used with `--static-root-path`")
+                    .emit();
+                return Err(1);
+            }
+            // Neither path needs to exist yet, and either can go through symbolic links.
+            if resolve_path(offline_bundle).starts_with(resolve_path(&output)) {
+                diag.struct_err("`--offline-bundle` must be outside of the output directory")
+                    .emit();
+                return Err(1);
+            }
+        }
+
         if generate_link_to_definition && (show_coverage || output_format != OutputFormat::Html) {
             diag.struct_err(
                 "--generate-link-to-definition option can only be used with HTML output format",
@@ -665,9 +722,37 @@ fn println_condition(condition: Condition) {
             return Err(1);
         }
 
+        let doctest_coverage_dir = matches.opt_str("doctest-coverage-dir").map(PathBuf::from);
+        if doctest_coverage_dir.is_some() {
+            if !should_test {
+                diag.err("the `--test` flag must be passed to enable `--doctest-coverage-dir`");
+                return Err(1);
+            }
+            if codegen_options.instrument_coverage.unwrap_or(InstrumentCoverage::Off)
+                == InstrumentCoverage::Off
+            {
+                diag.err("`--doctest-coverage-dir` requires `-C instrument-coverage`");
+                return Err(1);
+            }
+        }
+
         let scrape_examples_options = ScrapeExamplesOptions::new(&matches, &diag)?;
         let with_examples = matches.opt_strs("with-examples");
         let call_locations = crate::scrape_examples::load_call_locations(with_examples, &diag)?;
+        let api_diff = matches.opt_str("api-diff").map(PathBuf::from);
+        let previous_releases =
+            matches.opt_strs("previous-release").into_iter().map(PathBuf::from).collect::<Vec<_>>();
+        let release_history = if previous_releases.is_empty() {
+            None
+        } else {
+            match ReleaseHistory::load(&previous_releases) {
+                Ok(release_history) => Some(release_history),
+                Err(e) => {
+                    diag.err(&format!("failed to load previous releases: {}", e));
+                    return Err(1);
+                }
+            }
+        };
 
         let (lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(matches, error_format);
 
@@ -704,6 +789,8 @@ fn println_condition(condition: Condition) {
             run_check,
             no_run,
             nocapture,
+            merge_doctests,
+            doctest_coverage_dir,
             render_options: RenderOptions {
                 output,
                 external_html,
@@ -719,7 +806,9 @@ fn println_condition(condition: Condition) {
                 enable_minification,
                 enable_index_page,
                 index_page,
+                workspace_index,
                 static_root_path,
+                offline_bundle,
                 markdown_no_toc,
                 markdown_css,
                 markdown_playground_url,
@@ -739,6 +828,8 @@ fn println_condition(condition: Condition) {
             output_format,
             json_unused_externs,
             scrape_examples_options,
+            api_diff,
+            release_history,
         })
     }
 
@@ -784,6 +875,27 @@ fn check_deprecated_options(matches: &getopts::Matches, diag: &rustc_errors::Han
     }
 }
 
+/// Returns `path` as an absolute path, with the symbolic links of the part of it that exists
+/// resolved, and without `.` and `..` components.
+fn resolve_path(path: &Path) -> PathBuf {
+    let path = match std::env::current_dir() {
+        Ok(current_dir) => current_dir.join(path),
+        Err(_) => path.to_path_buf(),
+    };
+    let Some(existing) = path.ancestors().find(|ancestor| ancestor.exists()) else { return path };
+    let Ok(mut resolved) = existing.canonicalize() else { return path };
+    for component in path.strip_prefix(existing).unwrap().components() {
+        match component {
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::CurDir => {}
+            component => resolved.push(component),
+        }
+    }
+    resolved
+}
+
 /// Extracts `--extern-html-root-url` arguments from `matches` and returns a map of crate names to
 /// the given URLs. If an `--extern-html-root-url` argument was ill-formed, returns an error
 /// describing the issue.
@@ -1,5 +1,5 @@
 use rustc_ast as ast;
-use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexMap};
 use rustc_data_structures::sync::Lrc;
 use rustc_errors::{ColorConfig, ErrorGuaranteed, FatalError};
 use rustc_hir as hir;
@@ -12,7 +12,7 @@
 use rustc_middle::ty::TyCtxt;
 use rustc_parse::maybe_new_parser_from_source_str;
 use rustc_parse::parser::attr::InnerAttrPolicy;
-use rustc_session::config::{self, CrateType, ErrorOutputType};
+use rustc_session::config::{self, CrateType, ErrorOutputType, InstrumentCoverage};
 use rustc_session::parse::ParseSess;
 use rustc_session::{lint, DiagnosticOutput, Session};
 use rustc_span::edition::Edition;
@@ -26,7 +26,7 @@
 use std::env;
 use std::io::{self, Write};
 use std::panic;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{self, Command, Stdio};
 use std::str;
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -159,7 +159,7 @@
                 let unused_extern_reports = collector.unused_extern_reports.clone();
                 let compiling_test_count = collector.compiling_test_count.load(Ordering::SeqCst);
                 let ret: Result<_, ErrorGuaranteed> =
-                    Ok((collector.tests, unused_extern_reports, compiling_test_count));
+                    Ok((collector.into_tests(), unused_extern_reports, compiling_test_count));
                 ret
             })
         })?;
@@ -264,13 +264,64 @@ enum TestFailure {
     UnexpectedRunPass,
 }
 
+/// Prints why a doctest failed, as part of the test's output.
+fn print_test_failure(err: TestFailure) {
+    match err {
+        TestFailure::CompileError => {
+            eprint!("Couldn't compile the test.");
+        }
+        TestFailure::UnexpectedCompilePass => {
+            eprint!("Test compiled successfully, but it's marked `compile_fail`.");
+        }
+        TestFailure::UnexpectedRunPass => {
+            eprint!("Test executable succeeded, but it's marked `should_panic`.");
+        }
+        TestFailure::MissingErrorCodes(codes) => {
+            eprint!("Some expected error codes were not found: {:?}", codes);
+        }
+        TestFailure::ExecutionError(err) => {
+            eprint!("Couldn't run the test: {err}");
+            if err.kind() == io::ErrorKind::PermissionDenied {
+                eprint!(" - maybe your tempdir is mounted with noexec?");
+            }
+        }
+        TestFailure::ExecutionFailure(out) => {
+            eprintln!("Test executable failed ({reason}).", reason = out.status);
+
+            // FIXME(#12309): An unfortunate side-effect of capturing the test
+            // executable's output is that the relative ordering between the test's
+            // stdout and stderr is lost. However, this is better than the
+            // alternative: if the test executable inherited the parent's I/O
+            // handles the output wouldn't be captured at all, even on success.
+            //
+            // The ordering could be preserved if the test process' stderr was
+            // redirected to stdout, but that functionality does not exist in the
+            // standard library, so it may not be portable enough.
+            let stdout = str::from_utf8(&out.stdout).unwrap_or_default();
+            let stderr = str::from_utf8(&out.stderr).unwrap_or_default();
+
+            if !stdout.is_empty() || !stderr.is_empty() {
+                eprintln!();
+
+                if !stdout.is_empty() {
+                    eprintln!("stdout:\n{stdout}");
+                }
+
+                if !stderr.is_empty() {
+                    eprintln!("stderr:\n{stderr}");
+                }
+            }
+        }
+    }
+}
+
 enum DirState {
     Temp(tempfile::TempDir),
     Perm(PathBuf),
 }
 
 impl DirState {
-    fn path(&self) -> &std::path::Path {
+    fn path(&self) -> &Path {
         match self {
             DirState::Temp(t) => t.path(),
             DirState::Perm(p) => p.as_path(),
@@ -290,28 +341,13 @@ struct UnusedExterns {
     unused_extern_names: Vec<String>,
 }
 
-fn run_test(
-    test: &str,
-    crate_name: &str,
-    line: usize,
-    rustdoc_options: RustdocOptions,
-    mut lang_string: LangString,
-    no_run: bool,
-    runtool: Option<String>,
-    runtool_args: Vec<String>,
+/// Creates the command to compile a doctest with the flags that are shared by all doctests.
+fn make_compiler_command(
+    rustdoc_options: &RustdocOptions,
     target: TargetTriple,
-    opts: &GlobalTestOptions,
     edition: Edition,
-    outdir: DirState,
-    path: PathBuf,
-    test_id: &str,
-    report_unused_externs: impl Fn(UnusedExterns),
-) -> Result<(), TestFailure> {
-    let (test, line_offset, supports_color) =
-        make_test(test, Some(crate_name), lang_string.test_harness, opts, edition, Some(test_id));
-
-    let output_file = outdir.path().join("rust_out");
-
+    supports_color: bool,
+) -> Command {
     let rustc_binary = rustdoc_options
         .test_builder
         .as_deref()
@@ -327,22 +363,10 @@ fn run_test(
             compiler.arg("--check-cfg").arg(&check_cfg);
         }
     }
-    if let Some(sysroot) = rustdoc_options.maybe_sysroot {
+    if let Some(sysroot) = &rustdoc_options.maybe_sysroot {
         compiler.arg("--sysroot").arg(sysroot);
     }
     compiler.arg("--edition").arg(&edition.to_string());
-    compiler.env("UNSTABLE_RUSTDOC_TEST_PATH", path);
-    compiler.env("UNSTABLE_RUSTDOC_TEST_LINE", format!("{}", line as isize - line_offset as isize));
-    compiler.arg("-o").arg(&output_file);
-    if lang_string.test_harness {
-        compiler.arg("--test");
-    }
-    if rustdoc_options.json_unused_externs && !lang_string.compile_fail {
-        compiler.arg("--error-format=json");
-        compiler.arg("--json").arg("unused-externs");
-        compiler.arg("-Z").arg("unstable-options");
-        compiler.arg("-W").arg("unused_crate_dependencies");
-    }
     for lib_str in &rustdoc_options.lib_strs {
         compiler.arg("-L").arg(&lib_str);
     }
@@ -356,9 +380,6 @@ fn run_test(
     for debugging_option_str in &rustdoc_options.debugging_opts_strs {
         compiler.arg("-Z").arg(&debugging_option_str);
     }
-    if no_run && !lang_string.compile_fail && rustdoc_options.persist_doctests.is_none() {
-        compiler.arg("--emit=metadata");
-    }
     compiler.arg("--target").arg(match target {
         TargetTriple::TargetTriple(s) => s,
         TargetTriple::TargetPath(path) => {
@@ -384,6 +405,47 @@ fn run_test(
             }
         }
     }
+    compiler
+}
+
+fn run_test(
+    test: &str,
+    crate_name: &str,
+    line: usize,
+    rustdoc_options: RustdocOptions,
+    mut lang_string: LangString,
+    no_run: bool,
+    runtool: Option<String>,
+    runtool_args: Vec<String>,
+    target: TargetTriple,
+    opts: &GlobalTestOptions,
+    edition: Edition,
+    outdir: DirState,
+    path: PathBuf,
+    test_id: &str,
+    report_unused_externs: impl Fn(UnusedExterns),
+) -> Result<(), TestFailure> {
+    let (test, line_offset, supports_color) =
+        make_test(test, Some(crate_name), lang_string.test_harness, opts, edition, Some(test_id));
+
+    let output_file = outdir.path().join("rust_out");
+
+    let mut compiler = make_compiler_command(&rustdoc_options, target, edition, supports_color);
+    compiler.env("UNSTABLE_RUSTDOC_TEST_PATH", path);
+    compiler.env("UNSTABLE_RUSTDOC_TEST_LINE", format!("{}", line as isize - line_offset as isize));
+    compiler.arg("-o").arg(&output_file);
+    if lang_string.test_harness {
+        compiler.arg("--test");
+    }
+    if rustdoc_options.json_unused_externs && !lang_string.compile_fail {
+        compiler.arg("--error-format=json");
+        compiler.arg("--json").arg("unused-externs");
+        compiler.arg("-Z").arg("unstable-options");
+        compiler.arg("-W").arg("unused_crate_dependencies");
+    }
+    if no_run && !lang_string.compile_fail && rustdoc_options.persist_doctests.is_none() {
+        compiler.arg("--emit=metadata");
+    }
 
     compiler.arg("-");
     compiler.stdin(Stdio::piped());
@@ -449,17 +511,47 @@ fn drop(&mut self) {
         return Ok(());
     }
 
-    // Run the code!
+    // With `--doctest-coverage-dir`, each doctest writes its coverage profile to a file named after
+    // the doctest, next to the directory of its binary.
+    let profile_file = rustdoc_options.doctest_coverage_dir.as_ref().map(|dir| {
+        let dir = env::current_dir().map(|cwd| cwd.join(dir)).unwrap_or_else(|_| dir.clone());
+        dir.join(format!("{test_id}.profraw"))
+    });
+    run_binary(
+        &output_file,
+        &[],
+        profile_file.as_deref(),
+        &rustdoc_options,
+        runtool,
+        runtool_args,
+        lang_string.should_panic,
+    )
+}
+
+/// Runs a compiled doctest, checking that it panics if and only if it's marked `should_panic`.
+fn run_binary(
+    binary: &Path,
+    args: &[String],
+    profile_file: Option<&Path>,
+    rustdoc_options: &RustdocOptions,
+    runtool: Option<String>,
+    runtool_args: Vec<String>,
+    should_panic: bool,
+) -> Result<(), TestFailure> {
     let mut cmd;
 
     if let Some(tool) = runtool {
         cmd = Command::new(tool);
         cmd.args(runtool_args);
-        cmd.arg(output_file);
+        cmd.arg(binary);
     } else {
-        cmd = Command::new(output_file);
+        cmd = Command::new(binary);
     }
-    if let Some(run_directory) = rustdoc_options.test_run_directory {
+    cmd.args(args);
+    if let Some(profile_file) = profile_file {
+        cmd.env("LLVM_PROFILE_FILE", profile_file);
+    }
+    if let Some(run_directory) = &rustdoc_options.test_run_directory {
         cmd.current_dir(run_directory);
     }
 
@@ -475,9 +567,9 @@ fn drop(&mut self) {
     match result {
         Err(e) => return Err(TestFailure::ExecutionError(e)),
         Ok(out) => {
-            if lang_string.should_panic && out.status.success() {
+            if should_panic && out.status.success() {
                 return Err(TestFailure::UnexpectedRunPass);
-            } else if !lang_string.should_panic && !out.status.success() {
+            } else if !should_panic && !out.status.success() {
                 return Err(TestFailure::ExecutionFailure(out));
             }
         }
@@ -486,6 +578,96 @@ fn drop(&mut self) {
     Ok(())
 }
 
+/// Returns whether `attr`, an attribute from `#![doc(test(attr(...)))]`, sets a lint level.
+fn is_lint_attr(attr: &str) -> bool {
+    ["allow", "warn", "deny", "forbid", "expect"].iter().any(|level| {
+        attr.strip_prefix(level).map_or(false, |rest| rest.trim_start().starts_with('('))
+    })
+}
+
+/// Transforms a test into the body of a module of a merged doctest binary (see
+/// [`make_merged_test`]), or returns `None` if it has to be compiled on its own.
+///
+/// Tests with crate attributes, `#[macro_use] extern crate` or a custom test harness can't be
+/// put in a module, and 2015 edition paths are relative to the crate root. The other tests only
+/// differ from standalone ones in what `crate::` refers to, and in that compiling them together
+/// can produce errors that compiling them on their own wouldn't: the merged binary is then
+/// dropped in favour of standalone compilation. Tests marked `standalone` are never merged, to
+/// avoid that.
+crate fn make_test_module(
+    s: &str,
+    crate_name: &str,
+    lang_string: &LangString,
+    opts: &GlobalTestOptions,
+    edition: Edition,
+    test_id: &str,
+) -> Option<String> {
+    if edition == Edition::Edition2015
+        || lang_string.compile_fail
+        || lang_string.test_harness
+        || lang_string.standalone
+    {
+        return None;
+    }
+    let (crate_attrs, _, crates) = partition_source(s, edition);
+    if crate_attrs.lines().any(|line| line.trim_start().starts_with("#![")) {
+        return None;
+    }
+    if crates.contains("macro_use") {
+        return None;
+    }
+    // The other attributes are added to the root of the merged binary instead.
+    let opts = GlobalTestOptions {
+        no_crate_inject: opts.no_crate_inject,
+        attrs: opts.attrs.iter().filter(|attr| is_lint_attr(attr)).cloned().collect(),
+    };
+    let (module, _, _) = make_test(s, Some(crate_name), false, &opts, edition, Some(test_id));
+    Some(module)
+}
+
+/// Creates a binary containing all of the given `modules`, from [`make_test_module`]. It runs
+/// the `main` function of the module whose index is given as its first argument.
+crate fn make_merged_test(opts: &GlobalTestOptions, modules: &[&str]) -> String {
+    let mut prog = String::new();
+    for attr in opts.attrs.iter().filter(|attr| !is_lint_attr(attr)) {
+        prog.push_str(&format!("#![{attr}]\n"));
+    }
+    for (i, module) in modules.iter().enumerate() {
+        prog.push_str(&format!(
+            "mod __doctest_{i} {{\n{module}\n\
+             pub(super) fn __main() -> impl std::process::Termination {{ main() }}\n}}\n"
+        ));
+    }
+    prog.push_str("fn main() -> std::process::ExitCode {\n");
+    prog.push_str("    match std::env::args().nth(1).as_deref() {\n");
+    for i in 0..modules.len() {
+        prog.push_str(&format!(
+            "        Some(\"{i}\") => std::process::Termination::report(__doctest_{i}::__main()),\n"
+        ));
+    }
+    prog.push_str("        _ => std::process::ExitCode::FAILURE,\n    }\n}\n");
+    prog
+}
+
+/// Compiles the source of a merged doctest binary into `outdir`, returning the path of the
+/// binary if it compiled.
+fn compile_merged_test(
+    source: &str,
+    rustdoc_options: &RustdocOptions,
+    edition: Edition,
+    outdir: &DirState,
+) -> Option<PathBuf> {
+    let source_file = outdir.path().join("merged_doctests.rs");
+    let output_file = outdir.path().join("rust_out");
+    std::fs::write(&source_file, source).expect("could write out test sources");
+
+    let mut compiler =
+        make_compiler_command(rustdoc_options, rustdoc_options.target.clone(), edition, false);
+    compiler.arg("-o").arg(&output_file).arg(&source_file);
+    let output = compiler.output().expect("Failed to spawn rustc process");
+    if output.status.success() { Some(output_file) } else { None }
+}
+
 /// Transforms a test into code that can be compiled into a Rust binary, and returns the number of
 /// lines before the test code begins as well as if the output stream supports colors or not.
 crate fn make_test(
@@ -884,6 +1066,18 @@ fn register_header(&mut self, _name: &str, _level: u32) {}
     visited_tests: FxHashMap<(String, usize), usize>,
     unused_extern_reports: Arc<Mutex<Vec<UnusedExterns>>>,
     compiling_test_count: AtomicUsize,
+    /// The tests that are compiled into a single binary per edition, see `--merge-doctests`.
+    mergeable_tests: FxIndexMap<Edition, Vec<MergeableTest>>,
+}
+
+/// A test that can be compiled into a merged doctest binary.
+struct MergeableTest {
+    desc: test::TestDesc,
+    /// The test as the body of a module, from [`make_test_module`].
+    module: String,
+    should_panic: bool,
+    /// Compiles and runs the test on its own, in case the merged binary doesn't compile.
+    standalone: test::TestFn,
 }
 
 impl Collector {
@@ -910,9 +1104,80 @@ impl Collector {
             visited_tests: FxHashMap::default(),
             unused_extern_reports: Default::default(),
             compiling_test_count: AtomicUsize::new(0),
+            mergeable_tests: FxIndexMap::default(),
         }
     }
 
+    /// Returns the collected tests, after compiling the ones that can be merged into a single
+    /// binary per edition. The tests of a merged binary that fails to compile are compiled on
+    /// their own instead.
+    crate fn into_tests(mut self) -> Vec<test::TestDescAndFn> {
+        for (edition, tests) in self.mergeable_tests {
+            let outdir = if let Some(mut path) = self.rustdoc_options.persist_doctests.clone() {
+                path.push(format!("merged_{edition}"));
+
+                std::fs::create_dir_all(&path)
+                    .expect("Couldn't create directory for doctest executables");
+
+                DirState::Perm(path)
+            } else {
+                DirState::Temp(
+                    TempFileBuilder::new()
+                        .prefix("rustdoctest")
+                        .tempdir()
+                        .expect("rustdoc needs a tempdir"),
+                )
+            };
+            let modules = tests.iter().map(|test| &*test.module).collect::<Vec<_>>();
+            let source = make_merged_test(&self.opts, &modules);
+            let Some(binary) =
+                compile_merged_test(&source, &self.rustdoc_options, edition, &outdir)
+            else {
+                eprintln!(
+                    "note: the merged doctests for edition {edition} failed to compile, \
+                     compiling them separately"
+                );
+                self.tests.extend(
+                    tests.into_iter().map(|test| test::TestDescAndFn {
+                        desc: test.desc,
+                        testfn: test.standalone,
+                    }),
+                );
+                continue;
+            };
+
+            // The binary is deleted along with the temporary directory once all tests ran.
+            let outdir = Arc::new(outdir);
+            for (i, test) in tests.into_iter().enumerate() {
+                let outdir = outdir.clone();
+                let binary = binary.clone();
+                let rustdoc_options = self.rustdoc_options.clone();
+                let runtool = self.rustdoc_options.runtool.clone();
+                let runtool_args = self.rustdoc_options.runtool_args.clone();
+                self.tests.push(test::TestDescAndFn {
+                    desc: test.desc,
+                    testfn: test::DynTestFn(box move || {
+                        let _outdir = outdir;
+                        let res = run_binary(
+                            &binary,
+                            &[i.to_string()],
+                            None,
+                            &rustdoc_options,
+                            runtool,
+                            runtool_args,
+                            test.should_panic,
+                        );
+                        if let Err(err) = res {
+                            print_test_failure(err);
+                            panic::resume_unwind(box ());
+                        }
+                    }),
+                });
+            }
+        }
+        self.tests
+    }
+
     fn generate_name(&self, line: usize, filename: &FileName) -> String {
         let mut item_path = self.names.join("::");
         item_path.retain(|c| c != ' ');
@@ -994,7 +1259,11 @@ fn add_test(&mut self, test: String, config: LangString, line: usize) {
                 self.visited_tests.entry((file.clone(), line)).and_modify(|v| *v += 1).or_insert(0)
             },
         );
-        let outdir = if let Some(mut path) = rustdoc_options.persist_doctests.clone() {
+        let persist_dir = rustdoc_options
+            .persist_doctests
+            .as_ref()
+            .or(rustdoc_options.doctest_coverage_dir.as_ref());
+        let outdir = if let Some(mut path) = persist_dir.cloned() {
             path.push(&test_id);
 
             std::fs::create_dir_all(&path)
@@ -1010,97 +1279,75 @@ fn add_test(&mut self, test: String, config: LangString, line: usize) {
             )
         };
 
+        let ignore = match config.ignore {
+            Ignore::All => true,
+            Ignore::None => false,
+            Ignore::Some(ref ignores) => ignores.iter().any(|s| target_str.contains(s)),
+        };
+        // The coverage of merged doctests would be reported for the source of the merged binary,
+        // instead of for the file the doctests come from.
+        let instrument_coverage =
+            rustdoc_options.codegen_options.instrument_coverage.unwrap_or(InstrumentCoverage::Off)
+                != InstrumentCoverage::Off;
+        let module = if rustdoc_options.merge_doctests
+            && !rustdoc_options.json_unused_externs
+            && !instrument_coverage
+            && !ignore
+            && !no_run
+        {
+            make_test_module(&test, &crate_name, &config, &opts, edition, &test_id)
+        } else {
+            None
+        };
+        let should_panic = config.should_panic;
+
         debug!("creating test {name}: {test}");
-        self.tests.push(test::TestDescAndFn {
-            desc: test::TestDesc {
-                name: test::DynTestName(name),
-                ignore: match config.ignore {
-                    Ignore::All => true,
-                    Ignore::None => false,
-                    Ignore::Some(ref ignores) => ignores.iter().any(|s| target_str.contains(s)),
-                },
-                ignore_message: None,
-                // compiler failures are test failures
-                should_panic: test::ShouldPanic::No,
-                compile_fail: config.compile_fail,
+        let desc = test::TestDesc {
+            name: test::DynTestName(name),
+            ignore,
+            ignore_message: None,
+            // compiler failures are test failures
+            should_panic: test::ShouldPanic::No,
+            compile_fail: config.compile_fail,
+            no_run,
+            test_type: test::TestType::DocTest,
+        };
+        let testfn = test::DynTestFn(box move || {
+            let report_unused_externs = |uext| {
+                unused_externs.lock().unwrap().push(uext);
+            };
+            let res = run_test(
+                &test,
+                &crate_name,
+                line,
+                rustdoc_options,
+                config,
                 no_run,
-                test_type: test::TestType::DocTest,
-            },
-            testfn: test::DynTestFn(box move || {
-                let report_unused_externs = |uext| {
-                    unused_externs.lock().unwrap().push(uext);
-                };
-                let res = run_test(
-                    &test,
-                    &crate_name,
-                    line,
-                    rustdoc_options,
-                    config,
-                    no_run,
-                    runtool,
-                    runtool_args,
-                    target,
-                    &opts,
-                    edition,
-                    outdir,
-                    path,
-                    &test_id,
-                    report_unused_externs,
-                );
-
-                if let Err(err) = res {
-                    match err {
-                        TestFailure::CompileError => {
-                            eprint!("Couldn't compile the test.");
-                        }
-                        TestFailure::UnexpectedCompilePass => {
-                            eprint!("Test compiled successfully, but it's marked `compile_fail`.");
-                        }
-                        TestFailure::UnexpectedRunPass => {
-                            eprint!("Test executable succeeded, but it's marked `should_panic`.");
-                        }
-                        TestFailure::MissingErrorCodes(codes) => {
-                            eprint!("Some expected error codes were not found: {:?}", codes);
-                        }
-                        TestFailure::ExecutionError(err) => {
-                            eprint!("Couldn't run the test: {err}");
-                            if err.kind() == io::ErrorKind::PermissionDenied {
-                                eprint!(" - maybe your tempdir is mounted with noexec?");
-                            }
-                        }
-                        TestFailure::ExecutionFailure(out) => {
-                            eprintln!("Test executable failed ({reason}).", reason = out.status);
-
-                            // FIXME(#12309): An unfortunate side-effect of capturing the test
-                            // executable's output is that the relative ordering between the test's
-                            // stdout and stderr is lost. However, this is better than the
-                            // alternative: if the test executable inherited the parent's I/O
-                            // handles the output wouldn't be captured at all, even on success.
-                            //
-                            // The ordering could be preserved if the test process' stderr was
-                            // redirected to stdout, but that functionality does not exist in the
-                            // standard library, so it may not be portable enough.
-                            let stdout = str::from_utf8(&out.stdout).unwrap_or_default();
-                            let stderr = str::from_utf8(&out.stderr).unwrap_or_default();
-
-                            if !stdout.is_empty() || !stderr.is_empty() {
-                                eprintln!();
-
-                                if !stdout.is_empty() {
-                                    eprintln!("stdout:\n{stdout}");
-                                }
-
-                                if !stderr.is_empty() {
-                                    eprintln!("stderr:\n{stderr}");
-                                }
-                            }
-                        }
-                    }
+                runtool,
+                runtool_args,
+                target,
+                &opts,
+                edition,
+                outdir,
+                path,
+                &test_id,
+                report_unused_externs,
+            );
 
-                    panic::resume_unwind(box ());
-                }
-            }),
+            if let Err(err) = res {
+                print_test_failure(err);
+                panic::resume_unwind(box ());
+            }
         });
+        match module {
+            Some(module) => self.mergeable_tests.entry(edition).or_default().push(MergeableTest {
+                desc,
+                module,
+                should_panic,
+                standalone: testfn,
+            }),
+            None => self.tests.push(test::TestDescAndFn { desc, testfn }),
+        }
     }
 
     fn get_line(&self) -> usize {
@@ -1,5 +1,6 @@
-use super::{make_test, GlobalTestOptions};
-use rustc_span::edition::DEFAULT_EDITION;
+use super::{make_merged_test, make_test, make_test_module, GlobalTestOptions};
+use crate::html::markdown::LangString;
+use rustc_span::edition::{Edition, DEFAULT_EDITION};
 
 #[test]
 fn make_test_basic() {
@@ -298,3 +299,74 @@ fn main() { #[allow(non_snake_case)] fn _doctest_main__some_unique_name() {
         make_test(input, None, false, &opts, DEFAULT_EDITION, Some("_some_unique_name"));
     assert_eq!((output, len), (expected, 2));
 }
+
+#[test]
+fn make_test_module_basic() {
+    // lint attributes stay with the test, other attributes go to the merged crate root
+    let opts = GlobalTestOptions {
+        no_crate_inject: false,
+        attrs: vec!["deny(warnings)".to_string(), "feature(sync)".to_string()],
+    };
+    let input = "use asdf::qwop;
+assert_eq!(2+2, 4);";
+    let expected = "#![deny(warnings)]
+extern crate r#asdf;
+fn main() { #[allow(non_snake_case)] fn _doctest_main_test() {
+use asdf::qwop;
+assert_eq!(2+2, 4);
+} _doctest_main_test() }"
+        .to_string();
+    let lang_string = LangString::default();
+    let output = make_test_module(input, "asdf", &lang_string, &opts, Edition::Edition2018, "test");
+    assert_eq!(output, Some(expected));
+}
+
+#[test]
+fn make_test_module_unmergeable() {
+    let opts = GlobalTestOptions::default();
+    let edition = Edition::Edition2018;
+    let lang_string = LangString::default();
+    let input = "#![feature(sync)]
+assert_eq!(2+2, 4);";
+    assert_eq!(make_test_module(input, "asdf", &lang_string, &opts, edition, "test"), None);
+    let input = "#[macro_use] extern crate asdf;
+assert_eq!(2+2, 4);";
+    assert_eq!(make_test_module(input, "asdf", &lang_string, &opts, edition, "test"), None);
+
+    let input = "assert_eq!(2+2, 4);";
+    let mut compile_fail = LangString::default();
+    compile_fail.compile_fail = true;
+    assert_eq!(make_test_module(input, "asdf", &compile_fail, &opts, edition, "test"), None);
+    let mut standalone = LangString::default();
+    standalone.standalone = true;
+    assert_eq!(make_test_module(input, "asdf", &standalone, &opts, edition, "test"), None);
+    let edition = Edition::Edition2015;
+    assert_eq!(make_test_module(input, "asdf", &lang_string, &opts, edition, "test"), None);
+}
+
+#[test]
+fn make_merged_test_basic() {
+    let opts = GlobalTestOptions {
+        no_crate_inject: false,
+        attrs: vec!["deny(warnings)".to_string(), "feature(sync)".to_string()],
+    };
+    let modules = ["fn main() {}", "fn main() -> Result<(), ()> { Ok(()) }"];
+    let expected = "#![feature(sync)]
+mod __doctest_0 {
+fn main() {}
+pub(super) fn __main() -> impl std::process::Termination { main() }
+}
+mod __doctest_1 {
+fn main() -> Result<(), ()> { Ok(()) }
+pub(super) fn __main() -> impl std::process::Termination { main() }
+}
+fn main() -> std::process::ExitCode {
+    match std::env::args().nth(1).as_deref() {
+        Some(\"0\") => std::process::Termination::report(__doctest_0::__main()),
+        Some(\"1\") => std::process::Termination::report(__doctest_1::__main()),
+        _ => std::process::ExitCode::FAILURE,
+    }
+}
+";
+    assert_eq!(make_merged_test(&opts, &modules), expected);
+}
@@ -15,6 +15,7 @@
 use crate::html::markdown::short_markdown_summary;
 use crate::html::render::search_index::get_function_type_for_search;
 use crate::html::render::IndexItem;
+use crate::json::history::Availability;
 
 /// This cache is used to store information about the [`clean::Crate`] being
 /// rendered in order to provide more useful documentation. This contains
@@ -83,6 +84,10 @@
     /// The version of the crate being documented, if given from the `--crate-version` flag.
     crate crate_version: Option<String>,
 
+    /// The versions in which the local items appeared and were deprecated, computed from the
+    /// previous releases given with `--previous-release`.
+    crate availability: FxHashMap<DefId, Availability>,
+
     /// Whether to document private items.
     /// This is stored in `Cache` so it doesn't need to be passed through all rustdoc functions.
     crate document_private: bool,
@@ -0,0 +1,235 @@
+//! The offline bundle of the documentation, generated with `--offline-bundle`.
+//!
+//! The bundle is a zip archive of the whole output directory, including the static files, the
+//! search index and the source pages, so the documentation can be shipped as a single file and
+//! browsed from `file://` URLs once extracted. Browsers don't let pages opened from `file://` URLs
+//! load fonts from other directories, so the fonts are embedded in the stylesheets of the bundle.
+//!
+//! The files are stored without compression, which keeps the archive easy to write and reproducible
+//! since the modification times are not kept either.
+
+use std::fs;
+use std::io::{self, Write};
+use std::lazy::SyncLazy;
+use std::path::{Path, PathBuf};
+
+use regex::{Captures, Regex};
+
+use crate::docfs::PathError;
+use crate::error::Error;
+use crate::try_err;
+
+#[cfg(test)]
+mod tests;
+
+/// Writes the bundle of the documentation in `root` to `bundle`.
+crate fn write_bundle(root: &Path, bundle: &Path) -> Result<(), Error> {
+    let mut files = Vec::new();
+    collect_files(root, &mut files)?;
+    files.sort();
+
+    let out = try_err!(fs::File::create(bundle), bundle);
+    let mut zip = ZipWriter::new(io::BufWriter::new(out));
+    for file in &files {
+        let mut data = try_err!(fs::read(file), file);
+        if file.extension().map_or(false, |ext| ext == "css") {
+            let css = String::from_utf8_lossy(&data);
+            data = embed_fonts(&css, file.parent().unwrap_or(root)).into_bytes();
+        }
+        let name = file.strip_prefix(root).unwrap_or(file);
+        let name = name.iter().map(|c| c.to_string_lossy()).collect::<Vec<_>>().join("/");
+        try_err!(zip.add_file(&name, &data), bundle);
+    }
+    try_err!(zip.finish().and_then(|mut out| out.flush()), bundle);
+    Ok(())
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), Error> {
+    for entry in try_err!(fs::read_dir(dir), dir) {
+        let path = try_err!(entry, dir).path();
+        // The lock taken while writing the shared files isn't part of the documentation.
+        if path.file_name().map_or(false, |name| name == ".lock") {
+            continue;
+        }
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+static FONT_URL_REGEX: SyncLazy<Regex> = SyncLazy::new(|| {
+    Regex::new(r#"url\(["']?([^"')/]+\.(woff2?))["']?\)"#).expect("failed to build regex")
+});
+
+/// Replaces the URLs of the fonts in `css` by `data:` URLs, when the font file is in `dir`.
+fn embed_fonts(css: &str, dir: &Path) -> String {
+    FONT_URL_REGEX
+        .replace_all(css, |captures: &Captures<'_>| match fs::read(dir.join(&captures[1])) {
+            Ok(font) => format!("url(\"data:font/{};base64,{}\")", &captures[2], base64(&font)),
+            Err(_) => captures[0].to_string(),
+        })
+        .into_owned()
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity((data.len() + 2) / 3 * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = u32::from(bytes[0]) << 16 | u32::from(bytes[1]) << 8 | u32::from(bytes[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { 0xedb8_8320 ^ (crc >> 1) } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0;
+    for &byte in data {
+        crc = CRC32_TABLE[((crc ^ u32::from(byte)) & 0xff) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+/// The modification date of the files, January 1st 1980 in the MS-DOS format.
+const DOS_DATE: u16 = 1 << 5 | 1;
+/// Version 2.0 of the zip format, or 4.5 for the archives needing the ZIP64 extensions.
+const VERSION: u16 = 20;
+const VERSION_ZIP64: u16 = 45;
+/// The file names are encoded in UTF-8.
+const UTF8_FLAG: u16 = 1 << 11;
+
+/// Writes a zip archive whose files are stored without compression. The ZIP64 extensions are used
+/// when the archive has too many files, or is too large, for the original format.
+struct ZipWriter<W: Write> {
+    out: W,
+    offset: u64,
+    entries: u64,
+    central_directory: Vec<u8>,
+}
+
+impl<W: Write> ZipWriter<W> {
+    fn new(out: W) -> Self {
+        ZipWriter { out, offset: 0, entries: 0, central_directory: Vec::new() }
+    }
+
+    fn add_file(&mut self, name: &str, data: &[u8]) -> io::Result<()> {
+        let Some(size) = u32::try_from(data.len()).ok().filter(|&size| size != u32::MAX) else {
+            return Err(io::Error::new(io::ErrorKind::Other, format!("`{}` is too large", name)));
+        };
+        let crc = crc32(data);
+
+        let mut header = Vec::with_capacity(30 + name.len());
+        header.extend_from_slice(&0x0403_4b50_u32.to_le_bytes());
+        header.extend_from_slice(&VERSION.to_le_bytes());
+        header.extend_from_slice(&UTF8_FLAG.to_le_bytes());
+        header.extend_from_slice(&0_u16.to_le_bytes()); // stored
+        header.extend_from_slice(&0_u16.to_le_bytes()); // time
+        header.extend_from_slice(&DOS_DATE.to_le_bytes());
+        header.extend_from_slice(&crc.to_le_bytes());
+        header.extend_from_slice(&size.to_le_bytes()); // compressed size
+        header.extend_from_slice(&size.to_le_bytes());
+        header.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        header.extend_from_slice(&0_u16.to_le_bytes()); // extra field length
+        header.extend_from_slice(name.as_bytes());
+        self.out.write_all(&header)?;
+        self.out.write_all(data)?;
+
+        // The offset of the local header is moved to the extra field if it doesn't fit.
+        let zip64_offset = u32::try_from(self.offset).ok().filter(|&offset| offset != u32::MAX);
+        let (version, extra_len) =
+            if zip64_offset.is_some() { (VERSION, 0_u16) } else { (VERSION_ZIP64, 12) };
+        let entry = &mut self.central_directory;
+        entry.extend_from_slice(&0x0201_4b50_u32.to_le_bytes());
+        entry.extend_from_slice(&(3 << 8 | VERSION_ZIP64).to_le_bytes()); // made by, on Unix
+        entry.extend_from_slice(&version.to_le_bytes());
+        entry.extend_from_slice(&UTF8_FLAG.to_le_bytes());
+        entry.extend_from_slice(&0_u16.to_le_bytes()); // stored
+        entry.extend_from_slice(&0_u16.to_le_bytes()); // time
+        entry.extend_from_slice(&DOS_DATE.to_le_bytes());
+        entry.extend_from_slice(&crc.to_le_bytes());
+        entry.extend_from_slice(&size.to_le_bytes()); // compressed size
+        entry.extend_from_slice(&size.to_le_bytes());
+        entry.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        entry.extend_from_slice(&extra_len.to_le_bytes());
+        entry.extend_from_slice(&0_u16.to_le_bytes()); // comment length
+        entry.extend_from_slice(&0_u16.to_le_bytes()); // disk
+        entry.extend_from_slice(&0_u16.to_le_bytes()); // internal attributes
+        entry.extend_from_slice(&(0o100644_u32 << 16).to_le_bytes()); // regular file, `rw-r--r--`
+        entry.extend_from_slice(&zip64_offset.unwrap_or(u32::MAX).to_le_bytes());
+        entry.extend_from_slice(name.as_bytes());
+        if zip64_offset.is_none() {
+            entry.extend_from_slice(&1_u16.to_le_bytes()); // ZIP64 extra field
+            entry.extend_from_slice(&8_u16.to_le_bytes());
+            entry.extend_from_slice(&self.offset.to_le_bytes());
+        }
+
+        self.offset += (header.len() + data.len()) as u64;
+        self.entries += 1;
+        Ok(())
+    }
+
+    /// Writes the central directory and returns the underlying writer.
+    fn finish(mut self) -> io::Result<W> {
+        let directory_offset = self.offset;
+        let directory_size = self.central_directory.len() as u64;
+        self.out.write_all(&self.central_directory)?;
+
+        let entries = u16::try_from(self.entries).ok().filter(|&entries| entries != u16::MAX);
+        let size = u32::try_from(directory_size).ok().filter(|&size| size != u32::MAX);
+        let offset = u32::try_from(directory_offset).ok().filter(|&offset| offset != u32::MAX);
+        let mut end = Vec::new();
+        if entries.is_none() || size.is_none() || offset.is_none() {
+            let zip64_end_offset = directory_offset + directory_size;
+            end.extend_from_slice(&0x0606_4b50_u32.to_le_bytes());
+            end.extend_from_slice(&44_u64.to_le_bytes()); // size of the rest of the record
+            end.extend_from_slice(&VERSION_ZIP64.to_le_bytes()); // made by
+            end.extend_from_slice(&VERSION_ZIP64.to_le_bytes());
+            end.extend_from_slice(&0_u32.to_le_bytes()); // disk
+            end.extend_from_slice(&0_u32.to_le_bytes()); // disk of the central directory
+            end.extend_from_slice(&self.entries.to_le_bytes()); // entries on this disk
+            end.extend_from_slice(&self.entries.to_le_bytes());
+            end.extend_from_slice(&directory_size.to_le_bytes());
+            end.extend_from_slice(&directory_offset.to_le_bytes());
+
+            end.extend_from_slice(&0x0706_4b50_u32.to_le_bytes());
+            end.extend_from_slice(&0_u32.to_le_bytes()); // disk of the ZIP64 end record
+            end.extend_from_slice(&zip64_end_offset.to_le_bytes());
+            end.extend_from_slice(&1_u32.to_le_bytes()); // number of disks
+        }
+        end.extend_from_slice(&0x0605_4b50_u32.to_le_bytes());
+        end.extend_from_slice(&0_u16.to_le_bytes()); // disk
+        end.extend_from_slice(&0_u16.to_le_bytes()); // disk of the central directory
+        end.extend_from_slice(&entries.unwrap_or(u16::MAX).to_le_bytes()); // entries on this disk
+        end.extend_from_slice(&entries.unwrap_or(u16::MAX).to_le_bytes());
+        end.extend_from_slice(&size.unwrap_or(u32::MAX).to_le_bytes());
+        end.extend_from_slice(&offset.unwrap_or(u32::MAX).to_le_bytes());
+        end.extend_from_slice(&0_u16.to_le_bytes()); // comment length
+        self.out.write_all(&end)?;
+        Ok(self.out)
+    }
+}
@@ -0,0 +1,87 @@
+use std::fs;
+
+use super::{base64, crc32, embed_fonts, ZipWriter};
+
+#[test]
+fn test_base64() {
+    assert_eq!(base64(b""), "");
+    assert_eq!(base64(b"f"), "Zg==");
+    assert_eq!(base64(b"fo"), "Zm8=");
+    assert_eq!(base64(b"foo"), "Zm9v");
+    assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    assert_eq!(base64(&[0xff, 0xfe, 0xfd, 0xfc]), "//79/A==");
+}
+
+#[test]
+fn test_crc32() {
+    assert_eq!(crc32(b""), 0);
+    assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+}
+
+#[test]
+fn test_embed_fonts() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("Font.woff2"), b"foo").unwrap();
+    let css = "@font-face {src: url(\"Font.woff2\") format(\"woff2\"), url(\"Font.woff\");}\
+               .arrow {background: url(\"down-arrow.svg\");}";
+    assert_eq!(
+        embed_fonts(css, dir.path()),
+        "@font-face {src: url(\"data:font/woff2;base64,Zm9v\") format(\"woff2\"), \
+         url(\"Font.woff\");}.arrow {background: url(\"down-arrow.svg\");}"
+    );
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+#[test]
+fn test_zip_writer() {
+    let mut zip = ZipWriter::new(Vec::new());
+    zip.add_file("index.html", b"<html>").unwrap();
+    zip.add_file("foo/struct.Foo.html", b"").unwrap();
+    let zip = zip.finish().unwrap();
+
+    // The first local header, followed by the content of the file.
+    assert_eq!(u32_at(&zip, 0), 0x0403_4b50);
+    assert_eq!(u32_at(&zip, 14), crc32(b"<html>"));
+    assert_eq!(u32_at(&zip, 22), 6);
+    assert_eq!(u16_at(&zip, 26), 10);
+    assert_eq!(&zip[30..46], b"index.html<html>");
+
+    // The end of central directory record.
+    let end = zip.len() - 22;
+    assert_eq!(u32_at(&zip, end), 0x0605_4b50);
+    assert_eq!(u16_at(&zip, end + 10), 2);
+    let directory_size = u32_at(&zip, end + 12) as usize;
+    let directory_offset = u32_at(&zip, end + 16) as usize;
+    assert_eq!(directory_offset + directory_size, end);
+    assert_eq!(u32_at(&zip, directory_offset), 0x0201_4b50);
+    assert_eq!(u32_at(&zip, directory_offset + 42), 0);
+    assert_eq!(&zip[directory_offset + 46..directory_offset + 56], b"index.html");
+}
+
+#[test]
+fn test_zip_writer_zip64() {
+    let mut zip = ZipWriter::new(Vec::new());
+    for i in 0..=u16::MAX {
+        zip.add_file(&i.to_string(), b"").unwrap();
+    }
+    let zip = zip.finish().unwrap();
+
+    // The end of central directory record can't hold the number of files.
+    let end = zip.len() - 22;
+    assert_eq!(u32_at(&zip, end), 0x0605_4b50);
+    assert_eq!(u16_at(&zip, end + 10), u16::MAX);
+    // So it's preceded by the ZIP64 end of central directory record and its locator.
+    let locator = end - 20;
+    assert_eq!(u32_at(&zip, locator), 0x0706_4b50);
+    let zip64_end = u32_at(&zip, locator + 8) as usize;
+    assert_eq!(zip64_end, locator - 56);
+    assert_eq!(u32_at(&zip, zip64_end), 0x0606_4b50);
+    assert_eq!(u32_at(&zip, zip64_end + 32), u32::from(u16::MAX) + 1);
+}
@@ -146,13 +146,13 @@ impl ErrorCodes {
 /// Controls whether a line will be hidden or shown in HTML output.
 ///
 /// All lines are used in documentation tests.
-enum Line<'a> {
+crate enum Line<'a> {
     Hidden(&'a str),
     Shown(Cow<'a, str>),
 }
 
 impl<'a> Line<'a> {
-    fn for_html(self) -> Option<Cow<'a, str>> {
+    crate fn for_html(self) -> Option<Cow<'a, str>> {
         match self {
             Line::Shown(l) => Some(l),
             Line::Hidden(_) => None,
@@ -172,7 +172,7 @@ fn for_code(self) -> Cow<'a, str> {
 // is done in the single # case. This inconsistency seems okay, if non-ideal. In
 // order to fix it we'd have to iterate to find the first non-# character, and
 // then reallocate to remove it; which would make us return a String.
-fn map_line(s: &str) -> Line<'_> {
+crate fn map_line(s: &str) -> Line<'_> {
     let trimmed = s.trim();
     if trimmed.starts_with("##") {
         Line::Shown(Cow::Owned(s.replacen("##", "#", 1)))
@@ -841,6 +841,7 @@ fn error_invalid_codeblock_attr(&self, msg: &str, help: &str) {
     crate ignore: Ignore,
     crate rust: bool,
     crate test_harness: bool,
+    crate standalone: bool,
     crate compile_fail: bool,
     crate error_codes: Vec<String>,
     crate edition: Option<Edition>,
@@ -862,6 +863,7 @@ fn default() -> Self {
             ignore: Ignore::None,
             rust: true,
             test_harness: false,
+            standalone: false,
             compile_fail: false,
             error_codes: Vec::new(),
             edition: None,
@@ -870,7 +872,7 @@ fn default() -> Self {
 }
 
 impl LangString {
-    fn parse_without_check(
+    crate fn parse_without_check(
         string: &str,
         allow_error_code_check: ErrorCodes,
         enable_per_target_ignores: bool,
@@ -945,6 +947,10 @@ fn parse(
                     data.test_harness = true;
                     seen_rust_tags = !seen_other_tags || seen_rust_tags;
                 }
+                "standalone" => {
+                    data.standalone = true;
+                    seen_rust_tags = !seen_other_tags || seen_rust_tags;
+                }
                 "compile_fail" => {
                     data.compile_fail = true;
                     seen_rust_tags = !seen_other_tags || seen_rust_tags;
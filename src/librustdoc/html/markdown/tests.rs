@@ -64,6 +64,7 @@ fn t(lg: LangString) {
     t(LangString { original: "should_panic".into(), should_panic: true, ..Default::default() });
     t(LangString { original: "no_run".into(), no_run: true, ..Default::default() });
     t(LangString { original: "test_harness".into(), test_harness: true, ..Default::default() });
+    t(LangString { original: "standalone".into(), standalone: true, ..Default::default() });
     t(LangString {
         original: "compile_fail".into(),
         no_run: true,
@@ -1,3 +1,4 @@
+crate mod bundle;
 crate mod escape;
 crate mod format;
 crate mod highlight;
@@ -14,6 +14,7 @@
 use rustc_span::{sym, Symbol};
 
 use super::print_item::{full_path, item_path, print_item};
+use super::relative_links::RelativeLinks;
 use super::search_index::build_index;
 use super::write_shared::write_shared;
 use super::{
@@ -28,6 +29,7 @@
 use crate::formats::cache::Cache;
 use crate::formats::item_type::ItemType;
 use crate::formats::FormatRenderer;
+use crate::html::bundle::write_bundle;
 use crate::html::escape::Escape;
 use crate::html::format::{join_with_double_colon, Buffer};
 use crate::html::markdown::{self, plain_text_summary, ErrorCodes, IdMap};
@@ -104,6 +106,8 @@
     /// Optional path string to be used to load static files on output pages. If not set, uses
     /// combinations of `../` to reach the documentation root.
     crate static_root_path: Option<String>,
+    /// The zip archive to write the output directory to once the documentation is generated.
+    offline_bundle: Option<PathBuf>,
     /// The fs handle we are working with.
     crate fs: DocFS,
     pub(super) codes: ErrorCodes,
@@ -124,6 +128,8 @@
     crate cache: Cache,
 
     crate call_locations: AllCallLocations,
+    /// The relative links of the rendered documentation, checked once all the files are written.
+    pub(super) relative_links: RefCell<RelativeLinks>,
 }
 
 impl SharedContext<'_> {
@@ -400,6 +406,7 @@ fn init(
             extension_css,
             resource_suffix,
             static_root_path,
+            offline_bundle,
             unstable_features,
             generate_redirect_map,
             show_type_layout,
@@ -480,6 +487,7 @@ fn init(
             style_files,
             resource_suffix,
             static_root_path,
+            offline_bundle,
             fs: DocFS::new(sender),
             codes: ErrorCodes::from(unstable_features.is_nightly_build()),
             playground,
@@ -490,6 +498,7 @@ fn init(
             span_correspondance_map: matches,
             cache,
             call_locations,
+            relative_links: Default::default(),
         };
 
         // Add the default themes to the `Vec` of stylepaths
@@ -620,8 +629,11 @@ fn after_krate(&mut self) -> Result<(), Error> {
         Rc::get_mut(&mut self.shared).unwrap().fs.close();
         let nb_errors =
             self.shared.errors.iter().map(|err| self.tcx().sess.struct_err(&err).emit()).count();
+        self.shared.relative_links.take().check(self.tcx(), &self.dst);
         if nb_errors > 0 {
             Err(Error::new(io::Error::new(io::ErrorKind::Other, "I/O error"), ""))
+        } else if let Some(ref offline_bundle) = self.shared.offline_bundle {
+            write_bundle(&self.dst, offline_bundle)
         } else {
             Ok(())
         }
@@ -650,7 +662,10 @@ fn mod_item_in(&mut self, item: &clean::Item) -> Result<(), Error> {
         if !buf.is_empty() {
             self.shared.ensure_dir(&self.dst)?;
             let joint_dst = self.dst.join("index.html");
+            scx.relative_links.borrow_mut().finish_page(Some(&joint_dst));
             scx.fs.write(joint_dst, buf)?;
+        } else {
+            scx.relative_links.borrow_mut().finish_page(None);
         }
 
         // Render sidebar-items.js used throughout this module.
@@ -694,6 +709,7 @@ fn item(&mut self, item: clean::Item) -> Result<(), Error> {
             let file_name = &item_path(item_type, name.as_str());
             self.shared.ensure_dir(&self.dst)?;
             let joint_dst = self.dst.join(file_name);
+            self.shared.relative_links.borrow_mut().finish_page(Some(&joint_dst));
             self.shared.fs.write(joint_dst, buf)?;
 
             if !self.render_redirect_pages {
@@ -715,6 +731,8 @@ fn item(&mut self, item: clean::Item) -> Result<(), Error> {
                     self.shared.fs.write(redir_dst, v)?;
                 }
             }
+        } else {
+            self.shared.relative_links.borrow_mut().finish_page(None);
         }
         Ok(())
     }
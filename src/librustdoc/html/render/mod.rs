@@ -30,7 +30,9 @@
 
 mod context;
 mod print_item;
+mod relative_links;
 mod span_map;
+mod workspace;
 mod write_shared;
 
 crate use self::context::*;
@@ -561,6 +563,8 @@ fn document_full_inner(
 ) {
     if let Some(s) = item.collapsed_doc_value() {
         debug!("Doc block: =====\n{}\n=====", s);
+        let links = item.links(cx);
+        cx.shared.relative_links.borrow_mut().record(cx.tcx(), item, &s, &links);
         if is_collapsible {
             w.write_str(
                 "<details class=\"rustdoc-toggle top-doc\" open>\
@@ -568,10 +572,10 @@ fn document_full_inner(
                      <span>Expand description</span>\
                 </summary>",
             );
-            render_markdown(w, cx, &s, item.links(cx), heading_offset);
+            render_markdown(w, cx, &s, links, heading_offset);
             w.write_str("</details>");
         } else {
-            render_markdown(w, cx, &s, item.links(cx), heading_offset);
+            render_markdown(w, cx, &s, links, heading_offset);
         }
     }
 
@@ -631,6 +635,11 @@ fn short_item_info(
     {
         // We display deprecation messages for #[deprecated] and #[rustc_deprecated]
         // but only display the future-deprecation messages for #[rustc_deprecated].
+        // The version isn't required by `#[deprecated]`, but previous releases may tell it.
+        let since = since.or_else(|| {
+            let did = item.def_id.as_def_id()?;
+            cx.cache().availability.get(&did)?.deprecated_since
+        });
         let mut message = if let Some(since) = since {
             let since = since.as_str();
             if !stability::deprecation_in_effect(&depr) {
@@ -911,6 +920,15 @@ fn assoc_method(
     )
 }
 
+/// Returns the version since which `item` is stable or, for crates without stability attributes,
+/// the first release containing it according to the releases given with `--previous-release`.
+fn available_since(item: &clean::Item, cx: &Context<'_>) -> Option<Symbol> {
+    item.stable_since(cx.tcx()).or_else(|| {
+        let did = item.def_id.as_def_id()?;
+        cx.cache().availability.get(&did)?.since
+    })
+}
+
 /// Writes a span containing the versions at which an item became stable and/or const-stable. For
 /// example, if the item became stable at 1.0.0, and const-stable at 1.45.0, this function would
 /// write a span containing "1.0.0 (const: 1.45.0)".
@@ -927,6 +945,7 @@ fn assoc_method(
 /// consequence of the above rules.
 fn render_stability_since_raw(
     w: &mut Buffer,
+    cx: &Context<'_>,
     ver: Option<Symbol>,
     const_stability: Option<ConstStability>,
     containing_ver: Option<Symbol>,
@@ -939,7 +958,11 @@ fn render_stability_since_raw(
 
     if let Some(ver) = stable_version {
         stability.push_str(&ver.as_str());
-        title.push_str(&format!("Stable since Rust version {}", ver));
+        if cx.cache().availability.is_empty() {
+            title.push_str(&format!("Stable since Rust version {}", ver));
+        } else {
+            title.push_str(&format!("Available since version {}", ver));
+        }
     }
 
     let const_title_and_stability = match const_stability {
@@ -1744,9 +1767,10 @@ fn render_rightside(
     let mut rightside = Buffer::new();
     let has_stability = render_stability_since_raw(
         &mut rightside,
-        item.stable_since(tcx),
+        cx,
+        available_since(item, cx),
         const_stability,
-        containing_item.stable_since(tcx),
+        available_since(containing_item, cx),
         const_stable_since,
     );
     let mut srclink = Buffer::empty_from(w);
@@ -2482,7 +2506,7 @@ fn sidebar_enum(cx: &Context<'_>, buf: &mut Buffer, it: &clean::Item, e: &clean:
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-enum ItemSection {
+crate enum ItemSection {
     Reexports,
     PrimitiveTypes,
     Modules,
@@ -2511,7 +2535,7 @@ enum ItemSection {
 }
 
 impl ItemSection {
-    const ALL: &'static [Self] = {
+    crate const ALL: &'static [Self] = {
         use ItemSection::*;
         // NOTE: The order here affects the order in the UI.
         &[
@@ -2573,7 +2597,7 @@ fn id(self) -> &'static str {
         }
     }
 
-    fn name(self) -> &'static str {
+    crate fn name(self) -> &'static str {
         match self {
             Self::Reexports => "Re-exports",
             Self::Modules => "Modules",
@@ -2604,7 +2628,7 @@ fn name(self) -> &'static str {
     }
 }
 
-fn item_ty_to_section(ty: ItemType) -> ItemSection {
+crate fn item_ty_to_section(ty: ItemType) -> ItemSection {
     match ty {
         ItemType::ExternCrate | ItemType::Import => ItemSection::Reexports,
         ItemType::Module => ItemSection::Modules,
@@ -16,7 +16,7 @@
 use rustc_target::abi::{Layout, Primitive, TagEncoding, Variants};
 
 use super::{
-    collect_paths_for_type, document, ensure_trailing_slash, item_ty_to_section,
+    available_since, collect_paths_for_type, document, ensure_trailing_slash, item_ty_to_section,
     notable_traits_decl, render_assoc_item, render_assoc_items, render_attributes_in_code,
     render_attributes_in_pre, render_impl, render_stability_since_raw, write_srclink,
     AssocItemLink, Context, ImplRenderingParameters,
@@ -97,7 +97,8 @@ pub(super) fn print_item(cx: &Context<'_>, item: &clean::Item, buf: &mut Buffer,
     let mut stability_since_raw = Buffer::new();
     render_stability_since_raw(
         &mut stability_since_raw,
-        item.stable_since(cx.tcx()),
+        cx,
+        available_since(item, cx),
         item.const_stability(cx.tcx()),
         None,
         None,
@@ -686,7 +687,7 @@ fn trait_item(w: &mut Buffer, cx: &Context<'_>, m: &clean::Item, t: &clean::Item
         write!(w, "<div id=\"{}\" class=\"method has-srclink\">", id);
         write!(w, "<div class=\"rightside\">");
 
-        let has_stability = render_stability_since(w, m, t, cx.tcx());
+        let has_stability = render_stability_since(w, m, t, cx);
         if has_stability {
             w.write_str(" · ");
         }
@@ -1142,7 +1143,7 @@ fn item_enum(w: &mut Buffer, cx: &Context<'_>, it: &clean::Item, e: &clean::Enum
                 w.write_str(")");
             }
             w.write_str("</code>");
-            render_stability_since(w, variant, it, cx.tcx());
+            render_stability_since(w, variant, it, cx);
             w.write_str("</h3>");
 
             use crate::clean::Variant;
@@ -1478,13 +1479,15 @@ fn render_stability_since(
     w: &mut Buffer,
     item: &clean::Item,
     containing_item: &clean::Item,
-    tcx: TyCtxt<'_>,
+    cx: &Context<'_>,
 ) -> bool {
+    let tcx = cx.tcx();
     render_stability_since_raw(
         w,
-        item.stable_since(tcx),
+        cx,
+        available_since(item, cx),
         item.const_stability(tcx),
-        containing_item.stable_since(tcx),
+        available_since(containing_item, cx),
         containing_item.const_stable_since(tcx),
     )
 }
@@ -0,0 +1,180 @@
+//! Checks the relative links of the documentation against the generated files, for the
+//! `broken_relative_links` lint.
+//!
+//! Unlike the other documentation lints, this one can't run as a pass over the cleaned crate: the
+//! anchors of a page are only known once it has been rendered, and the files a link can point to
+//! only exist once the whole crate has been documented. So the links are collected while the
+//! documentation of the items is rendered, and checked against the output directory at the end.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use pulldown_cmark::{Event, LinkType, Parser, Tag};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_hir::HirId;
+use rustc_middle::ty::TyCtxt;
+use rustc_session::lint::Level;
+use rustc_span::Span;
+
+use crate::clean::{self, RenderedLink};
+use crate::core::DocContext;
+use crate::html::markdown::main_body_opts;
+use crate::lint::BROKEN_RELATIVE_LINKS;
+use crate::passes::source_span_for_markdown_range;
+
+#[cfg(test)]
+mod tests;
+
+/// A relative link found in the documentation of a local item.
+struct RelativeLink {
+    hir_id: HirId,
+    span: Span,
+    /// The destination of the link, as written in the documentation.
+    dest: String,
+}
+
+/// The relative links of the rendered pages.
+#[derive(Default)]
+crate struct RelativeLinks {
+    /// The links of the page being rendered.
+    pending: Vec<RelativeLink>,
+    /// The links of the pages that have been written, along with the path of their page.
+    links: Vec<(PathBuf, RelativeLink)>,
+}
+
+impl RelativeLinks {
+    /// Collects the relative links in `dox`, the documentation of `item` which is being rendered.
+    /// Links which were resolved as intra-doc links are skipped.
+    crate fn record(
+        &mut self,
+        tcx: TyCtxt<'_>,
+        item: &clean::Item,
+        dox: &str,
+        links: &[RenderedLink],
+    ) {
+        let Some(hir_id) = DocContext::as_local_hir_id(tcx, item.def_id) else { return };
+        if tcx.lint_level_at_node(BROKEN_RELATIVE_LINKS, hir_id).0 == Level::Allow {
+            return;
+        }
+        for (event, range) in Parser::new_ext(dox, main_body_opts()).into_offset_iter() {
+            let Event::Start(Tag::Link(kind, dest, _)) = event else { continue };
+            if matches!(kind, LinkType::Autolink | LinkType::Email)
+                || split_relative_url(&dest).is_none()
+                || links.iter().any(|link| *link.original_text == *dest)
+            {
+                continue;
+            }
+            let span = source_span_for_markdown_range(tcx, dox, &range, &item.attrs)
+                .unwrap_or_else(|| item.attr_span(tcx));
+            self.pending.push(RelativeLink { hir_id, span, dest: dest.to_string() });
+        }
+    }
+
+    /// Attaches the links collected since the previous page to `page`, the file in which they
+    /// were written. The links are dropped if the page wasn't written.
+    crate fn finish_page(&mut self, page: Option<&Path>) {
+        match page {
+            Some(page) => {
+                self.links.extend(self.pending.drain(..).map(|link| (page.to_path_buf(), link)))
+            }
+            None => self.pending.clear(),
+        }
+    }
+
+    /// Reports the links pointing to files that don't exist, or to anchors that aren't in the
+    /// page they point to. This must be called once all the files have been written to `root`,
+    /// the output directory.
+    crate fn check(self, tcx: TyCtxt<'_>, root: &Path) {
+        let mut pages: FxHashMap<PathBuf, FxHashSet<String>> = FxHashMap::default();
+        let mut reported = FxHashSet::default();
+        // The same documentation can be rendered in several pages, but it's only reported once.
+        for (page, link) in self.links {
+            if reported.contains(&(link.span, link.dest.clone())) {
+                continue;
+            }
+            let Some((path, fragment)) = split_relative_url(&link.dest) else { continue };
+            let target = if path.is_empty() {
+                page
+            } else {
+                normalize_path(&page.parent().unwrap_or(root).join(path))
+            };
+            let display = target.strip_prefix(root).unwrap_or(&target).display().to_string();
+            let note = if !target.exists() {
+                format!("`{}` doesn't exist", display)
+            } else if let Some(fragment) = fragment {
+                let file = if target.is_dir() { target.join("index.html") } else { target };
+                if !matches!(file.extension(), Some(ext) if ext == "html" || ext == "htm") {
+                    continue;
+                }
+                let ids = pages.entry(file).or_insert_with_key(|file| {
+                    fs::read_to_string(file).map(|html| collect_ids(&html)).unwrap_or_default()
+                });
+                if ids.contains(fragment) {
+                    continue;
+                }
+                format!("there is no element with the id `{}` in `{}`", fragment, display)
+            } else {
+                continue;
+            };
+            tcx.struct_span_lint_hir(BROKEN_RELATIVE_LINKS, link.hir_id, link.span, |lint| {
+                lint.build(&format!("unresolved link to `{}`", link.dest)).note(&note).emit()
+            });
+            reported.insert((link.span, link.dest));
+        }
+    }
+}
+
+/// Splits a relative URL into its path, which is empty for a link to an anchor of the same page,
+/// and its fragment. Returns `None` for absolute URLs, and for destinations which look like the
+/// path of an item since those are checked by `broken_intra_doc_links`.
+fn split_relative_url(dest: &str) -> Option<(&str, Option<&str>)> {
+    if dest.starts_with('/') || has_scheme(dest) {
+        return None;
+    }
+    let (path, fragment) = match dest.split_once('#') {
+        Some((path, fragment)) => (path, Some(fragment).filter(|f| !f.is_empty())),
+        None => (dest, None),
+    };
+    let path = path.split('?').next().unwrap_or_default();
+    if path.is_empty() && fragment.is_none() {
+        return None;
+    }
+    if !path.is_empty() && !path.contains(|c| c == '/' || c == '.') {
+        return None;
+    }
+    Some((path, fragment))
+}
+
+/// Returns `true` if `url` starts with a scheme, like `https:` or `mailto:`.
+fn has_scheme(url: &str) -> bool {
+    let Some((scheme, _)) = url.split_once(':') else { return false };
+    scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+/// Removes the `.` and `..` components of `path` without accessing the file system.
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(normalized.components().next_back(), Some(Component::Normal(_))) =>
+            {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Collects the values of the `id` attributes of an HTML page.
+fn collect_ids(html: &str) -> FxHashSet<String> {
+    html.match_indices(" id=\"")
+        .filter_map(|(start, attr)| {
+            let value = &html[start + attr.len()..];
+            value.find('"').map(|end| value[..end].to_string())
+        })
+        .collect()
+}
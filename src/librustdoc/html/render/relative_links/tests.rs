@@ -0,0 +1,52 @@
+use std::path::{Path, PathBuf};
+
+use super::{collect_ids, normalize_path, split_relative_url};
+
+#[test]
+fn test_split_relative_url() {
+    assert_eq!(split_relative_url("#examples"), Some(("", Some("examples"))));
+    assert_eq!(split_relative_url("struct.Foo.html"), Some(("struct.Foo.html", None)));
+    assert_eq!(
+        split_relative_url("../foo/index.html#method.new"),
+        Some(("../foo/index.html", Some("method.new")))
+    );
+    assert_eq!(split_relative_url("CHANGELOG.md?plain=1"), Some(("CHANGELOG.md", None)));
+    assert_eq!(split_relative_url("examples/"), Some(("examples/", None)));
+}
+
+#[test]
+fn test_split_relative_url_ignored() {
+    // Absolute URLs.
+    assert_eq!(split_relative_url("https://example.com/foo.html"), None);
+    assert_eq!(split_relative_url("mailto:someone@example.com"), None);
+    assert_eq!(split_relative_url("/foo/index.html"), None);
+    assert_eq!(split_relative_url("//example.com/foo.html"), None);
+    // Links to the top of the page.
+    assert_eq!(split_relative_url("#"), None);
+    assert_eq!(split_relative_url(""), None);
+    // Item paths, checked as intra-doc links.
+    assert_eq!(split_relative_url("Foo"), None);
+    assert_eq!(split_relative_url("crate::Foo"), None);
+    assert_eq!(split_relative_url("Foo#method.new"), None);
+}
+
+#[test]
+fn test_normalize_path() {
+    assert_eq!(
+        normalize_path(Path::new("doc/foo/./../bar/x.html")),
+        PathBuf::from("doc/bar/x.html")
+    );
+    assert_eq!(normalize_path(Path::new("doc/../../README.md")), PathBuf::from("../README.md"));
+    assert_eq!(normalize_path(Path::new("../../x")), PathBuf::from("../../x"));
+}
+
+#[test]
+fn test_collect_ids() {
+    let ids = collect_ids(
+        "<h2 id=\"examples\" class=\"section-header\">Examples</h2>\
+         <div data-id=\"nope\"></div><h3 id=\"method.new\">",
+    );
+    let mut ids = ids.into_iter().collect::<Vec<_>>();
+    ids.sort();
+    assert_eq!(ids, ["examples", "method.new"]);
+}
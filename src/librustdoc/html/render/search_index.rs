@@ -2,6 +2,7 @@
 use std::collections::BTreeMap;
 
 use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::TraitBoundModifier;
 use rustc_middle::ty::TyCtxt;
 use rustc_span::symbol::{kw, Symbol};
 use serde::ser::{Serialize, SerializeStruct, Serializer};
@@ -220,192 +221,126 @@ fn get_index_type_name(clean_type: &clean::Type) -> Option<Symbol> {
             let path = &bounds[0].trait_;
             Some(path.segments.last().unwrap().name)
         }
-        // We return an empty name because we don't care about the generic name itself.
-        clean::Generic(_) => Some(kw::Empty),
+        clean::Generic(name) => Some(name),
         clean::Primitive(ref p) => Some(p.as_sym()),
         clean::BorrowedRef { ref type_, .. } => get_index_type_name(type_),
-        clean::BareFunction(_)
-        | clean::Tuple(_)
-        | clean::Slice(_)
-        | clean::Array(_, _)
-        | clean::RawPointer(_, _)
-        | clean::QPath { .. }
-        | clean::Infer
-        | clean::ImplTrait(_) => None,
+        clean::Tuple(_) | clean::Slice(_) | clean::Array(_, _) | clean::RawPointer(_, _) => {
+            clean_type.primitive_type().map(|p| p.as_sym())
+        }
+        // `impl Trait` is an anonymous type parameter.
+        clean::ImplTrait(_) => Some(kw::Empty),
+        clean::BareFunction(_) | clean::QPath { .. } | clean::Infer => None,
     }
 }
 
-/// The point of this function is to replace bounds with types.
-///
-/// i.e. `[T, U]` when you have the following bounds: `T: Display, U: Option<T>` will return
-/// `[Display, Option]`. If a type parameter has no trait bound, it is discarded.
+/// Converts a type into its search index representation, keeping all of its generic arguments.
 ///
-/// Important note: It goes through generics recursively. So if you have
-/// `T: Option<Result<(), ()>>`, it'll go into `Option` and then into `Result`.
-#[instrument(level = "trace", skip(tcx, res, cache))]
-fn add_generics_and_bounds_as_types<'tcx>(
+/// Type parameters are stored as [`ItemType::Generic`] entries named after the parameter, with
+/// their trait bounds (both inline and from `where` clauses) as generics. This allows the search
+/// to unify the same type parameter across the inputs and the output of a function. `impl Trait`
+/// is stored as a type parameter with an empty name, and associated type bindings such as the
+/// `u8` in `Iterator<Item = u8>` are stored as generic arguments of the trait.
+#[instrument(level = "trace", skip(tcx, cache))]
+fn get_index_type_with_generics<'tcx>(
     generics: &Generics,
     arg: &Type,
     tcx: TyCtxt<'tcx>,
     recurse: usize,
-    res: &mut Vec<TypeWithKind>,
     cache: &Cache,
-) {
-    fn insert_ty(
-        res: &mut Vec<TypeWithKind>,
-        tcx: TyCtxt<'_>,
-        ty: Type,
-        mut generics: Vec<TypeWithKind>,
-        cache: &Cache,
-    ) {
-        let is_full_generic = ty.is_full_generic();
-        let generics_empty = generics.is_empty();
-
-        if is_full_generic {
-            if generics_empty {
-                // This is a type parameter with no trait bounds (for example: `T` in
-                // `fn f<T>(p: T)`, so not useful for the rustdoc search because we would end up
-                // with an empty type with an empty name. Let's just discard it.
-                return;
-            } else if generics.len() == 1 {
-                // In this case, no need to go through an intermediate state if the type parameter
-                // contains only one trait bound.
-                //
-                // For example:
-                //
-                // `fn foo<T: Display>(r: Option<T>) {}`
-                //
-                // In this case, it would contain:
-                //
-                // ```
-                // [{
-                //     name: "option",
-                //     generics: [{
-                //         name: "",
-                //         generics: [
-                //             name: "Display",
-                //             generics: []
-                //         }]
-                //     }]
-                // }]
-                // ```
-                //
-                // After removing the intermediate (unnecessary) type parameter, it'll become:
-                //
-                // ```
-                // [{
-                //     name: "option",
-                //     generics: [{
-                //         name: "Display",
-                //         generics: []
-                //     }]
-                // }]
-                // ```
-                //
-                // To be noted that it can work if there is ONLY ONE trait bound, otherwise we still
-                // need to keep it as is!
-                res.push(generics.pop().unwrap());
-                return;
-            }
-        }
-        let mut index_ty = get_index_type(&ty, generics);
-        if index_ty.name.as_ref().map(|s| s.is_empty() && generics_empty).unwrap_or(true) {
-            return;
-        }
-        if is_full_generic {
-            // We remove the name of the full generic because we have no use for it.
-            index_ty.name = Some(String::new());
-            res.push(TypeWithKind::from((index_ty, ItemType::Generic)));
-        } else if let Some(kind) = ty.def_id(cache).map(|did| tcx.def_kind(did).into()) {
-            res.push(TypeWithKind::from((index_ty, kind)));
-        } else if ty.is_primitive() {
-            // This is a primitive, let's store it as such.
-            res.push(TypeWithKind::from((index_ty, ItemType::Primitive)));
-        }
-    }
-
+) -> Option<TypeWithKind> {
     if recurse >= 10 {
         // FIXME: remove this whole recurse thing when the recursion bug is fixed
         // See #59502 for the original issue.
-        return;
+        return None;
     }
 
-    // If this argument is a type parameter and not a trait bound or a type, we need to look
-    // for its bounds.
-    if let Type::Generic(arg_s) = *arg {
-        // First we check if the bounds are in a `where` predicate...
-        if let Some(where_pred) = generics.where_predicates.iter().find(|g| match g {
-            WherePredicate::BoundPredicate { ty, .. } => ty.def_id(cache) == arg.def_id(cache),
-            _ => false,
-        }) {
-            let mut ty_generics = Vec::new();
-            let bounds = where_pred.get_bounds().unwrap_or_else(|| &[]);
-            for bound in bounds.iter() {
-                if let GenericBound::TraitBound(poly_trait, _) = bound {
-                    for param_def in poly_trait.generic_params.iter() {
-                        match &param_def.kind {
-                            clean::GenericParamDefKind::Type { default: Some(ty), .. } => {
-                                add_generics_and_bounds_as_types(
-                                    generics,
-                                    ty,
-                                    tcx,
-                                    recurse + 1,
-                                    &mut ty_generics,
-                                    cache,
-                                )
-                            }
-                            _ => {}
-                        }
-                    }
-                }
-            }
-            insert_ty(res, tcx, arg.clone(), ty_generics, cache);
+    let (kind, ty_generics) = match *arg {
+        // References are transparent for the search.
+        Type::BorrowedRef { ref type_, .. } => {
+            return get_index_type_with_generics(generics, type_, tcx, recurse, cache);
         }
-        // Otherwise we check if the trait bounds are "inlined" like `T: Option<u32>`...
-        if let Some(bound) = generics.params.iter().find(|g| g.is_type() && g.name == arg_s) {
-            let mut ty_generics = Vec::new();
-            for bound in bound.get_bounds().unwrap_or(&[]) {
-                if let Some(path) = bound.get_trait_path() {
-                    let ty = Type::Path { path };
-                    add_generics_and_bounds_as_types(
-                        generics,
-                        &ty,
-                        tcx,
-                        recurse + 1,
-                        &mut ty_generics,
-                        cache,
-                    );
+        Type::Generic(arg_s) => {
+            // The bounds can be either "inlined" like `T: Iterator`, or in a `where` clause.
+            let inline_bounds = generics
+                .params
+                .iter()
+                .filter(|g| g.is_type() && g.name == arg_s)
+                .flat_map(|g| g.get_bounds().unwrap_or(&[]));
+            let where_bounds = generics.where_predicates.iter().flat_map(|pred| match pred {
+                WherePredicate::BoundPredicate { ty: Type::Generic(ty_s), bounds, .. }
+                    if *ty_s == arg_s =>
+                {
+                    &bounds[..]
                 }
-            }
-            insert_ty(res, tcx, arg.clone(), ty_generics, cache);
+                _ => &[][..],
+            });
+            let bounds = get_bounds_as_types(inline_bounds.chain(where_bounds));
+            // Type parameters appearing in the bounds are not expanded again, otherwise
+            // `T: Iterator<Item = T>` would never end.
+            let no_bounds = Generics::default();
+            (ItemType::Generic, get_index_types(&no_bounds, bounds.iter(), tcx, recurse, cache))
         }
-    } else {
-        // This is not a type parameter. So for example if we have `T, U: Option<T>`, and we're
-        // looking at `Option`, we enter this "else" condition, otherwise if it's `T`, we don't.
-        //
-        // So in here, we can add it directly and look for its own type parameters (so for `Option`,
-        // we will look for them but not for `T`).
-        let mut ty_generics = Vec::new();
-        if let Some(arg_generics) = arg.generics() {
-            for gen in arg_generics.iter() {
-                add_generics_and_bounds_as_types(
-                    generics,
-                    gen,
-                    tcx,
-                    recurse + 1,
-                    &mut ty_generics,
-                    cache,
-                );
-            }
+        Type::ImplTrait(ref bounds) => {
+            let bounds = get_bounds_as_types(bounds.iter());
+            (ItemType::Generic, get_index_types(generics, bounds.iter(), tcx, recurse, cache))
         }
-        insert_ty(res, tcx, arg.clone(), ty_generics, cache);
-    }
+        Type::Path { ref path } => (
+            tcx.def_kind(path.def_id()).into(),
+            get_index_types(generics, get_path_generics(path), tcx, recurse, cache),
+        ),
+        Type::DynTrait(ref bounds, _) => (
+            ItemType::Trait,
+            get_index_types(generics, get_path_generics(&bounds[0].trait_), tcx, recurse, cache),
+        ),
+        Type::Tuple(ref types) => {
+            (ItemType::Primitive, get_index_types(generics, types.iter(), tcx, recurse, cache))
+        }
+        Type::Slice(ref type_) | Type::Array(ref type_, _) | Type::RawPointer(_, ref type_) => (
+            ItemType::Primitive,
+            get_index_types(generics, std::iter::once(&**type_), tcx, recurse, cache),
+        ),
+        Type::Primitive(_) => (ItemType::Primitive, Vec::new()),
+        Type::BareFunction(_) | Type::QPath { .. } | Type::Infer => return None,
+    };
+    Some(TypeWithKind::from((get_index_type(arg, ty_generics), kind)))
+}
+
+/// Converts the generic arguments of a type, see [`get_index_type_with_generics`].
+fn get_index_types<'a, 'tcx>(
+    generics: &Generics,
+    types: impl Iterator<Item = &'a Type>,
+    tcx: TyCtxt<'tcx>,
+    recurse: usize,
+    cache: &Cache,
+) -> Vec<TypeWithKind> {
+    types
+        .filter_map(|ty| get_index_type_with_generics(generics, ty, tcx, recurse + 1, cache))
+        .collect()
+}
+
+/// Returns the traits of the given bounds as types, ignoring `?Sized`-like bounds.
+fn get_bounds_as_types<'a>(bounds: impl Iterator<Item = &'a GenericBound>) -> Vec<Type> {
+    bounds
+        .filter(|bound| !matches!(bound, GenericBound::TraitBound(_, TraitBoundModifier::Maybe)))
+        .filter_map(|bound| bound.get_trait_path())
+        .map(|path| Type::Path { path })
+        .collect()
+}
+
+/// Returns the generic type arguments of the last segment of `path`, followed by the types of
+/// its associated type bindings (the `u8` in `Iterator<Item = u8>`).
+fn get_path_generics(path: &clean::Path) -> impl Iterator<Item = &Type> {
+    let bindings = path.bindings().unwrap_or(&[]).iter().filter_map(|binding| match binding.kind {
+        clean::TypeBindingKind::Equality { term: clean::Term::Type(ref ty) } => Some(ty),
+        _ => None,
+    });
+    path.generics().unwrap_or_default().into_iter().chain(bindings)
 }
 
 /// Return the full list of types when bounds have been resolved.
 ///
 /// i.e. `fn foo<A: Display, B: Option<A>>(x: u32, y: B)` will return
-/// `[u32, Display, Option]`.
+/// `[u32, B<Option<A>>]`.
 fn get_fn_inputs_and_outputs<'tcx>(
     func: &Function,
     tcx: TyCtxt<'tcx>,
@@ -414,33 +349,19 @@ fn get_fn_inputs_and_outputs<'tcx>(
     let decl = &func.decl;
     let generics = &func.generics;
 
-    let mut all_types = Vec::new();
-    for arg in decl.inputs.values.iter() {
-        if arg.type_.is_self_type() {
-            continue;
-        }
-        let mut args = Vec::new();
-        add_generics_and_bounds_as_types(generics, &arg.type_, tcx, 0, &mut args, cache);
-        if !args.is_empty() {
-            all_types.extend(args);
-        } else {
-            if let Some(kind) = arg.type_.def_id(cache).map(|did| tcx.def_kind(did).into()) {
-                all_types.push(TypeWithKind::from((get_index_type(&arg.type_, vec![]), kind)));
-            }
-        }
-    }
+    let all_types = decl
+        .inputs
+        .values
+        .iter()
+        .filter(|arg| !arg.type_.is_self_type())
+        .filter_map(|arg| get_index_type_with_generics(generics, &arg.type_, tcx, 0, cache))
+        .collect();
 
-    let mut ret_types = Vec::new();
-    match decl.output {
+    let ret_types = match decl.output {
         FnRetTy::Return(ref return_type) => {
-            add_generics_and_bounds_as_types(generics, return_type, tcx, 0, &mut ret_types, cache);
-            if ret_types.is_empty() {
-                if let Some(kind) = return_type.def_id(cache).map(|did| tcx.def_kind(did).into()) {
-                    ret_types.push(TypeWithKind::from((get_index_type(return_type, vec![]), kind)));
-                }
-            }
+            get_index_type_with_generics(generics, return_type, tcx, 0, cache).into_iter().collect()
         }
-        _ => {}
+        _ => Vec::new(),
     };
     (all_types, ret_types)
 }
@@ -0,0 +1,243 @@
+//! The workspace landing page, generated at the root of the output directory with
+//! `--workspace-index`.
+//!
+//! Every crate documented in this mode stores its description and its dependencies in
+//! `crates.js`. This file is shared by all the crates of the output directory, so the landing page
+//! can show all of them along with their dependency graph, and the search can rank the results
+//! from the dependencies of the current crate before the ones from unrelated crates.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use serde::{Deserialize, Serialize};
+
+use crate::html::escape::Escape;
+use crate::html::render::ensure_trailing_slash;
+
+#[cfg(test)]
+mod tests;
+
+/// Information about a crate documented with `--workspace-index`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+crate struct WorkspaceCrate {
+    /// The summary of the crate-level documentation, as in the search index.
+    crate doc: String,
+    /// The crates this crate directly depends on. They are not necessarily documented.
+    crate deps: Vec<String>,
+}
+
+/// Reads the workspace crates stored in the contents of an existing `crates.js`, except `krate`
+/// which is being documented again.
+crate fn parse_workspace_crates(contents: &str, krate: &str) -> BTreeMap<String, WorkspaceCrate> {
+    contents
+        .lines()
+        .filter(|line| line.starts_with('"'))
+        .filter_map(|line| {
+            let entry = format!("{{{}}}", line.strip_suffix(',').unwrap_or(line));
+            serde_json::from_str::<BTreeMap<String, WorkspaceCrate>>(&entry).ok()
+        })
+        .flatten()
+        .filter(|(name, _)| name != krate)
+        .collect()
+}
+
+/// Generates the part of `crates.js` storing the workspace crates. There is one crate per line so
+/// that [`parse_workspace_crates`] can read them back.
+crate fn workspace_crates_js(crates: &BTreeMap<String, WorkspaceCrate>) -> String {
+    let entries = crates
+        .iter()
+        .map(|(name, info)| {
+            format!(
+                "{}:{}",
+                serde_json::to_string(name).unwrap(),
+                serde_json::to_string(info).unwrap()
+            )
+        })
+        .collect::<Vec<_>>();
+    format!("window.WORKSPACE_CRATES = {{\n{}\n}};", entries.join(",\n"))
+}
+
+/// Sorts the crates in rows for the dependency graph: every crate is in a row above the rows of
+/// all its dependencies. In every row, crates are placed below the crates depending on them as
+/// much as possible, to limit the crossings of edges.
+crate fn dependency_graph_rows<'a>(
+    krates: &'a [String],
+    crates: &'a BTreeMap<String, WorkspaceCrate>,
+) -> Vec<Vec<&'a str>> {
+    let documented = krates.iter().map(|k| k.as_str()).collect::<FxHashSet<_>>();
+    let deps = |krate: &str| -> Vec<&'a str> {
+        crates.get(krate).map_or_else(Vec::new, |info| {
+            info.deps.iter().map(|d| d.as_str()).filter(|d| documented.contains(d)).collect()
+        })
+    };
+
+    // The level of a crate is the length of the longest path to a crate without dependencies.
+    fn level<'a>(
+        krate: &'a str,
+        deps: &dyn Fn(&str) -> Vec<&'a str>,
+        levels: &mut FxHashMap<&'a str, usize>,
+    ) -> usize {
+        if let Some(&level) = levels.get(krate) {
+            return level;
+        }
+        // Crate graphs can't have cycles, but this data is read from files on disk.
+        levels.insert(krate, 0);
+        let level =
+            deps(krate).into_iter().map(|dep| level(dep, deps, levels) + 1).max().unwrap_or(0);
+        levels.insert(krate, level);
+        level
+    }
+    let mut levels = FxHashMap::default();
+    for krate in krates {
+        level(krate, &deps, &mut levels);
+    }
+
+    let max_level = levels.values().copied().max().unwrap_or(0);
+    let mut rows = vec![Vec::new(); max_level + 1];
+    for krate in krates {
+        rows[max_level - levels[krate.as_str()]].push(krate.as_str());
+    }
+
+    // The position of each crate in the rows that are already sorted.
+    let mut positions: FxHashMap<&str, f64> = FxHashMap::default();
+    for row in &mut rows {
+        let barycenter = |krate: &str| -> f64 {
+            let dependents = positions
+                .iter()
+                .filter(|(dependent, _)| deps(dependent).contains(&krate))
+                .map(|(_, &pos)| pos)
+                .collect::<Vec<_>>();
+            if dependents.is_empty() {
+                f64::MAX
+            } else {
+                dependents.iter().sum::<f64>() / dependents.len() as f64
+            }
+        };
+        let mut keyed = row.iter().map(|&krate| (barycenter(krate), krate)).collect::<Vec<_>>();
+        keyed.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap().then(a.1.cmp(b.1)));
+        *row = keyed.into_iter().map(|(_, krate)| krate).collect();
+        let len = row.len() as f64;
+        for (i, &krate) in row.iter().enumerate() {
+            positions.insert(krate, (i as f64 + 0.5) / len);
+        }
+    }
+    rows
+}
+
+const CHAR_WIDTH: usize = 8;
+const NODE_PADDING: usize = 10;
+const NODE_HEIGHT: usize = 24;
+const ROW_HEIGHT: usize = 64;
+const NODE_GAP: usize = 16;
+
+/// Renders the dependency graph of the documented crates as an inline SVG image, where every crate
+/// links to its documentation.
+crate fn render_dependency_graph(
+    krates: &[String],
+    crates: &BTreeMap<String, WorkspaceCrate>,
+) -> String {
+    let rows = dependency_graph_rows(krates, crates);
+    let node_width = |krate: &str| krate.chars().count() * CHAR_WIDTH + 2 * NODE_PADDING;
+    let row_width = |row: &[&str]| {
+        row.iter().map(|k| node_width(k)).sum::<usize>() + NODE_GAP * row.len().saturating_sub(1)
+    };
+    let width = rows.iter().map(|row| row_width(row)).max().unwrap_or(0) + 2 * NODE_GAP;
+    let height = rows.len() * ROW_HEIGHT - (ROW_HEIGHT - NODE_HEIGHT) + 2 * NODE_GAP;
+
+    // The top left corner of every node.
+    let mut nodes = FxHashMap::default();
+    for (i, row) in rows.iter().enumerate() {
+        let mut x = (width - row_width(row)) / 2;
+        for &krate in row {
+            nodes.insert(krate, (x, NODE_GAP + i * ROW_HEIGHT));
+            x += node_width(krate) + NODE_GAP;
+        }
+    }
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" \
+            viewBox=\"0 0 {0} {1}\" role=\"img\" aria-label=\"Dependency graph\">\
+         <defs><marker id=\"dependency-arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" \
+            markerWidth=\"6\" markerHeight=\"6\" orient=\"auto\">\
+            <path d=\"M 0 0 L 10 5 L 0 10 z\"/></marker></defs>",
+        width, height
+    );
+    for krate in rows.iter().flatten() {
+        let (x, y) = nodes[krate];
+        let deps = crates.get(*krate).map(|info| &info.deps[..]).unwrap_or_default();
+        for dep in deps {
+            if let Some(&(dep_x, dep_y)) = nodes.get(dep.as_str()) {
+                write!(
+                    svg,
+                    "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" \
+                        marker-end=\"url(#dependency-arrow)\"/>",
+                    x + node_width(krate) / 2,
+                    y + NODE_HEIGHT,
+                    dep_x + node_width(dep) / 2,
+                    dep_y
+                )
+                .unwrap();
+            }
+        }
+    }
+    for krate in rows.iter().flatten() {
+        let (x, y) = nodes[krate];
+        write!(
+            svg,
+            "<a href=\"{href}index.html\">\
+                <rect x=\"{x}\" y=\"{y}\" width=\"{width}\" height=\"{height}\" rx=\"3\"/>\
+                <text x=\"{text_x}\" y=\"{text_y}\" text-anchor=\"middle\" \
+                    dominant-baseline=\"central\">{name}</text>\
+             </a>",
+            href = ensure_trailing_slash(krate),
+            x = x,
+            y = y,
+            width = node_width(krate),
+            height = NODE_HEIGHT,
+            text_x = x + node_width(krate) / 2,
+            text_y = y + NODE_HEIGHT / 2,
+            name = Escape(krate),
+        )
+        .unwrap();
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Renders the content of the workspace landing page.
+crate fn render_workspace_page(
+    krates: &[String],
+    crates: &BTreeMap<String, WorkspaceCrate>,
+) -> String {
+    let mut content = format!(
+        "<h1 class=\"fqn\"><span class=\"in-band\">Workspace</span></h1>\
+         <h2 id=\"dependency-graph\" class=\"small-section-header\">\
+            Dependency graph<a href=\"#dependency-graph\" class=\"anchor\"></a>\
+         </h2>\
+         <div class=\"workspace-graph\">{}</div>\
+         <h2 id=\"crates\" class=\"small-section-header\">\
+            Crates<a href=\"#crates\" class=\"anchor\"></a>\
+         </h2>\
+         <div class=\"item-table\">",
+        render_dependency_graph(krates, crates)
+    );
+    for krate in krates {
+        let doc = crates.get(krate).map(|info| info.doc.as_str()).unwrap_or_default();
+        write!(
+            content,
+            "<div class=\"item-row\">\
+                <div class=\"item-left module-item\">\
+                    <a class=\"mod\" href=\"{}index.html\">{}</a>\
+                </div>\
+                <div class=\"item-right docblock-short\">{}</div>\
+             </div>",
+            ensure_trailing_slash(krate),
+            Escape(krate),
+            doc
+        )
+        .unwrap();
+    }
+    content.push_str("</div>");
+    content
+}
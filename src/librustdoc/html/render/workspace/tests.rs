@@ -0,0 +1,54 @@
+use std::collections::BTreeMap;
+
+use super::{dependency_graph_rows, parse_workspace_crates, workspace_crates_js, WorkspaceCrate};
+
+fn workspace(crates: &[(&str, &[&str])]) -> BTreeMap<String, WorkspaceCrate> {
+    crates
+        .iter()
+        .map(|(name, deps)| {
+            let deps = deps.iter().map(|d| d.to_string()).collect();
+            (name.to_string(), WorkspaceCrate { doc: format!("The {} crate.", name), deps })
+        })
+        .collect()
+}
+
+#[test]
+fn test_workspace_crates_roundtrip() {
+    let crates = workspace(&[("app", &["core_lib", "std"]), ("core_lib", &["std"])]);
+    let js =
+        format!("window.ALL_CRATES = [\"app\",\"core_lib\"];\n{}", workspace_crates_js(&crates));
+    assert_eq!(parse_workspace_crates(&js, "other"), crates);
+
+    // The crate being documented is not read back.
+    let mut expected = crates.clone();
+    expected.remove("app");
+    assert_eq!(parse_workspace_crates(&js, "app"), expected);
+}
+
+#[test]
+fn test_parse_workspace_crates_without_workspace() {
+    assert!(parse_workspace_crates("window.ALL_CRATES = [\"foo\"];", "bar").is_empty());
+}
+
+#[test]
+fn test_dependency_graph_rows() {
+    let crates = workspace(&[
+        ("app", &["cli", "server"]),
+        ("cli", &["util"]),
+        ("server", &["util", "db"]),
+        ("db", &["std"]),
+        ("util", &[]),
+        ("tool", &[]),
+    ]);
+    let krates = crates.keys().cloned().collect::<Vec<_>>();
+    let rows = dependency_graph_rows(&krates, &crates);
+    assert_eq!(rows, vec![vec!["app"], vec!["cli", "server"], vec!["util", "db", "tool"]]);
+}
+
+#[test]
+fn test_dependency_graph_rows_cycle() {
+    let crates = workspace(&[("a", &["b"]), ("b", &["a"])]);
+    let krates = crates.keys().cloned().collect::<Vec<_>>();
+    let rows = dependency_graph_rows(&krates, &crates);
+    assert_eq!(rows.iter().map(|row| row.len()).sum::<usize>(), 2);
+}
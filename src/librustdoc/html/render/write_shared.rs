@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::ffi::OsStr;
 use std::fmt::Write;
 use std::fs::{self, File};
@@ -11,11 +12,13 @@
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use serde::Serialize;
 
+use super::workspace::{self, WorkspaceCrate};
 use super::{collect_paths_for_type, ensure_trailing_slash, Context, BASIC_KEYWORDS};
 use crate::clean::Crate;
 use crate::config::{EmitType, RenderOptions};
 use crate::docfs::PathError;
 use crate::error::Error;
+use crate::html::markdown::short_markdown_summary;
 use crate::html::{layout, static_files};
 use crate::{try_err, try_none};
 
@@ -452,9 +455,40 @@ fn to_json_string(&self) -> String {
         Ok(v.into_bytes())
     })?;
 
+    // Keep the workspace crates stored by previous invocations, even if this crate isn't
+    // documented with `--workspace-index`.
+    let crates_js = SharedResource::InvocationSpecific { basename: "crates.js" }.path(cx);
+    let mut workspace_crates = if crates_js.exists() {
+        let contents = try_err!(fs::read_to_string(&crates_js), &crates_js);
+        workspace::parse_workspace_crates(&contents, krate.name(cx.tcx()).as_str())
+    } else {
+        BTreeMap::new()
+    };
+    if options.workspace_index {
+        let tcx = cx.tcx();
+        let doc = krate.module.doc_value().map_or_else(String::new, |s| {
+            short_markdown_summary(&s, &krate.module.link_names(cx.cache()))
+        });
+        let deps = tcx
+            .crates(())
+            .iter()
+            .filter(|&&cnum| tcx.extern_crate(cnum.as_def_id()).map_or(false, |e| e.is_direct()))
+            .map(|&cnum| tcx.crate_name(cnum).to_string())
+            .sorted()
+            .collect();
+        workspace_crates.insert(krate.name(tcx).to_string(), WorkspaceCrate { doc, deps });
+    }
+
     write_crate("crates.js", &|| {
-        let krates = krates.iter().map(|k| format!("\"{}\"", k)).join(",");
-        Ok(format!("window.ALL_CRATES = [{}];", krates).into_bytes())
+        let mut v = format!(
+            "window.ALL_CRATES = [{}];",
+            krates.iter().map(|k| format!("\"{}\"", k)).join(",")
+        );
+        if !workspace_crates.is_empty() {
+            v.push('\n');
+            v.push_str(&workspace::workspace_crates_js(&workspace_crates));
+        }
+        Ok(v.into_bytes())
     })?;
 
     if options.enable_index_page {
@@ -465,6 +499,24 @@ fn to_json_string(&self) -> String {
 
             crate::markdown::render(&index_page, md_opts, cx.shared.edition())
                 .map_err(|e| Error::new(e, &index_page))?;
+        } else if options.workspace_index {
+            let dst = cx.dst.join("index.html");
+            let page = layout::Page {
+                title: "Workspace",
+                css_class: "mod",
+                root_path: "./",
+                static_root_path: cx.shared.static_root_path.as_deref(),
+                description: "Workspace crates and their dependency graph",
+                keywords: BASIC_KEYWORDS,
+                resource_suffix: &cx.shared.resource_suffix,
+                extra_scripts: &[],
+                static_extra_scripts: &[],
+            };
+            // The landing page doesn't belong to any crate.
+            let layout = layout::Layout { krate: String::new(), ..cx.shared.layout.clone() };
+            let content = workspace::render_workspace_page(&krates, &workspace_crates);
+            let v = layout::render(&layout, &page, "", content, &cx.shared.style_files);
+            cx.shared.fs.write(dst, v)?;
         } else {
             let dst = cx.dst.join("index.html");
             let page = layout::Page {
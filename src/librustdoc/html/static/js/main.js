@@ -939,6 +939,9 @@ function hideThemeButtonState() {
              and <code>const</code>.",
             "Search functions by type signature (e.g., <code>vec -&gt; usize</code> or \
              <code>* -&gt; vec</code>)",
+            "Use single uppercase letters for generic types in type signatures (e.g., \
+             <code>Vec&lt;T&gt; -&gt; Option&lt;T&gt;</code>), and <code>impl</code> to look for \
+             trait bounds (e.g., <code>impl Iterator&lt;Item = u8&gt;</code>)",
             "Search multiple things at once by splitting your query with comma (e.g., \
              <code>str,u8</code> or <code>String,struct:Vec,test</code>)",
             "You can look for items with an exact name by putting double quotes around \
@@ -31,11 +31,14 @@ var itemTypes = [
     "attr",
     "derive",
     "traitalias",
+    "generic",
 ];
 
 // used for special search precedence
 var TY_PRIMITIVE = itemTypes.indexOf("primitive");
 var TY_KEYWORD = itemTypes.indexOf("keyword");
+// type parameters of functions in the search index
+var TY_GENERIC = itemTypes.indexOf("generic");
 
 // In the search display, allows to switch between tabs.
 function printTab(nb) {
@@ -226,6 +229,25 @@ window.initSearch = function(rawSearchIndex) {
             return out;
         }
 
+        /**
+         * Returns 0 for the current crate, 1 for the crates it depends on when the documentation
+         * was generated with `--workspace-index`, and 2 for the other crates.
+         *
+         * @param {string} crate
+         * @return {integer}
+         */
+        function crateDistance(crate) {
+            if (crate === window.currentCrate) {
+                return 0;
+            }
+            var workspace = window.WORKSPACE_CRATES;
+            if (workspace && hasOwnPropertyRustdoc(workspace, window.currentCrate) &&
+                workspace[window.currentCrate].deps.indexOf(crate) !== -1) {
+                return 1;
+            }
+            return 2;
+        }
+
         function sortResults(results, isType) {
             var ar = [];
             for (var entry in results) {
@@ -256,9 +278,9 @@ window.initSearch = function(rawSearchIndex) {
                 b = (bbb.lev);
                 if (a !== b) { return a - b; }
 
-                // sort by crate (non-current crate goes later)
-                a = (aaa.item.crate !== window.currentCrate);
-                b = (bbb.item.crate !== window.currentCrate);
+                // sort by crate (current crate first, then its dependencies, then the others)
+                a = crateDistance(aaa.item.crate);
+                b = crateDistance(bbb.item.crate);
                 if (a !== b) { return a - b; }
 
                 // sort by item name length (longer goes later)
@@ -342,47 +364,60 @@ window.initSearch = function(rawSearchIndex) {
             };
         }
 
+        /**
+         * Returns the names a generic argument of the search index can be matched with. The name
+         * of a type parameter is meaningless, so it is matched with the names of its bounds.
+         *
+         * @param {Array} gen
+         *
+         * @return {Array<string>}
+         */
+        function genericNames(gen) {
+            if (gen[1] !== TY_GENERIC) {
+                return [gen[NAME]];
+            }
+            if (gen.length <= GENERICS_DATA) {
+                return [];
+            }
+            return gen[GENERICS_DATA].map(function(bound) {
+                return bound[NAME];
+            });
+        }
+
         function checkGenerics(obj, val) {
             // The names match, but we need to be sure that all generics kinda
             // match as well.
-            var tmp_lev, elem_name;
+            var tmp_lev, y;
             if (val.generics.length > 0) {
                 if (obj.length > GENERICS_DATA &&
                       obj[GENERICS_DATA].length >= val.generics.length) {
-                    var elems = Object.create(null);
-                    var elength = obj[GENERICS_DATA].length;
-                    for (var x = 0; x < elength; ++x) {
-                        if (!elems[obj[GENERICS_DATA][x][NAME]]) {
-                            elems[obj[GENERICS_DATA][x][NAME]] = 0;
-                        }
-                        elems[obj[GENERICS_DATA][x][NAME]] += 1;
-                    }
+                    var elems = obj[GENERICS_DATA].map(genericNames);
                     var total = 0;
                     var done = 0;
                     // We need to find the type that matches the most to remove it in order
                     // to move forward.
                     var vlength = val.generics.length;
-                    for (x = 0; x < vlength; ++x) {
+                    for (var x = 0; x < vlength; ++x) {
                         var lev = MAX_LEV_DISTANCE + 1;
                         var firstGeneric = val.generics[x];
-                        var match = null;
-                        if (elems[firstGeneric]) {
-                            match = firstGeneric;
-                            lev = 0;
-                        } else {
-                            for (elem_name in elems) {
-                                tmp_lev = levenshtein(elem_name, firstGeneric);
+                        var match = -1;
+                        for (y = 0; y < elems.length && lev !== 0; ++y) {
+                            if (elems[y].indexOf(firstGeneric) !== -1) {
+                                match = y;
+                                lev = 0;
+                            }
+                        }
+                        for (y = 0; y < elems.length && lev !== 0; ++y) {
+                            for (var z = 0; z < elems[y].length; ++z) {
+                                tmp_lev = levenshtein(elems[y][z], firstGeneric);
                                 if (tmp_lev < lev) {
                                     lev = tmp_lev;
-                                    match = elem_name;
+                                    match = y;
                                 }
                             }
                         }
-                        if (match !== null) {
-                            elems[match] -= 1;
-                            if (elems[match] == 0) {
-                                delete elems[match];
-                            }
+                        if (match !== -1) {
+                            elems.splice(match, 1);
                             total += lev;
                             done += 1;
                         } else {
@@ -409,26 +444,37 @@ window.initSearch = function(rawSearchIndex) {
         function checkType(obj, val, literalSearch) {
             var lev_distance = MAX_LEV_DISTANCE + 1;
             var tmp_lev = MAX_LEV_DISTANCE + 1;
-            var len, x, firstGeneric;
+            var len, x, y, firstGeneric;
+            if (obj[1] === TY_GENERIC) {
+                // The name of a type parameter is meaningless, so only its bounds are checked.
+                if (obj.length > GENERICS_DATA) {
+                    len = obj[GENERICS_DATA].length;
+                    for (x = 0; x < len; ++x) {
+                        tmp_lev = Math.min(
+                            checkType(obj[GENERICS_DATA][x], val, literalSearch),
+                            tmp_lev
+                        );
+                    }
+                }
+                return tmp_lev;
+            }
             if (obj[NAME] === val.name) {
                 if (literalSearch) {
                     if (val.generics && val.generics.length !== 0) {
                         if (obj.length > GENERICS_DATA &&
                              obj[GENERICS_DATA].length > 0) {
-                            var elems = Object.create(null);
-                            len = obj[GENERICS_DATA].length;
-                            for (x = 0; x < len; ++x) {
-                                if (!elems[obj[GENERICS_DATA][x][NAME]]) {
-                                    elems[obj[GENERICS_DATA][x][NAME]] = 0;
-                                }
-                                elems[obj[GENERICS_DATA][x][NAME]] += 1;
-                            }
+                            var elems = obj[GENERICS_DATA].map(genericNames);
 
                             len = val.generics.length;
                             for (x = 0; x < len; ++x) {
                                 firstGeneric = val.generics[x];
-                                if (elems[firstGeneric]) {
-                                    elems[firstGeneric] -= 1;
+                                y = 0;
+                                while (y < elems.length &&
+                                       elems[y].indexOf(firstGeneric) === -1) {
+                                    y += 1;
+                                }
+                                if (y < elems.length) {
+                                    elems.splice(y, 1);
                                 } else {
                                     // Something wasn't found and this is a literal search so
                                     // abort and return a "failing" distance.
@@ -457,7 +503,7 @@ window.initSearch = function(rawSearchIndex) {
                       obj.length > GENERICS_DATA && obj[GENERICS_DATA].length > 0) {
                     found = obj[GENERICS_DATA].some(
                         function(gen) {
-                            return gen[NAME] === val.name;
+                            return genericNames(gen).indexOf(val.name) !== -1;
                         });
                 }
                 return found ? 0 : MAX_LEV_DISTANCE + 1;
@@ -473,7 +519,10 @@ window.initSearch = function(rawSearchIndex) {
                 // We can check if the type we're looking for is inside the generics!
                 var olength = obj[GENERICS_DATA].length;
                 for (x = 0; x < olength; ++x) {
-                    tmp_lev = Math.min(levenshtein(obj[GENERICS_DATA][x][NAME], val.name), tmp_lev);
+                    var names = genericNames(obj[GENERICS_DATA][x]);
+                    for (y = 0; y < names.length; ++y) {
+                        tmp_lev = Math.min(levenshtein(names[y], val.name), tmp_lev);
+                    }
                 }
                 if (tmp_lev !== 0) {
                     // If we didn't find a good enough result, we go check inside the generics of
@@ -549,6 +598,357 @@ window.initSearch = function(rawSearchIndex) {
             return literalSearch ? MAX_LEV_DISTANCE + 1 : lev_distance;
         }
 
+        /**
+         * Parses a type signature query like `Vec<T>, usize -> Option<T>`. Every type is
+         * represented as an object with the following fields:
+         *
+         * * `name`: the lowercased name of the type, without its path.
+         * * `generics`: the parsed generic arguments. Associated type bindings like the `u8` in
+         *   `Iterator<Item = u8>` are generic arguments too.
+         * * `isGeneric`: whether this is a type variable (a single uppercase letter, optionally
+         *   followed by digits, like `T` or `U2`), which can be unified with the type parameters
+         *   of functions.
+         * * `bounds`: if not `null`, the type is `impl A + B` and must be a type parameter with
+         *   all these bounds.
+         * * `wildcard`: whether this is `*`, which matches any type.
+         *
+         * References, lifetimes and the `impl` and `dyn` keywords are ignored.
+         *
+         * @param {string} raw - The query, without its type filter.
+         *
+         * @return {{inputs: Array<Object>|null, output: Array<Object>|null}|null} - `inputs`
+         *     and `output` are `null` if they match anything. Returns `null` if the query is
+         *     not a valid type signature.
+         */
+        function parseTypeSignature(raw) {
+            function isIdentChar(c) {
+                return c === "_" || c.toLowerCase() !== c.toUpperCase() || (c >= "0" && c <= "9");
+            }
+
+            var tokens = [];
+            var pos = 0;
+            while (pos < raw.length) {
+                var c = raw.charAt(pos);
+                var end = pos + 1;
+                if (raw.substr(pos, 2) === "->" || raw.substr(pos, 2) === "::") {
+                    end = pos + 2;
+                } else if (isIdentChar(c) || c === "'") {
+                    while (end < raw.length && isIdentChar(raw.charAt(end))) {
+                        end += 1;
+                    }
+                    if (c === "'") {
+                        // Lifetimes are ignored.
+                        pos = end;
+                        continue;
+                    }
+                } else if (c === " " || c === "\t") {
+                    pos = end;
+                    continue;
+                } else if ("<>,=&()[];*+!".indexOf(c) === -1) {
+                    return null;
+                }
+                tokens.push(raw.substring(pos, end));
+                pos = end;
+            }
+            pos = 0;
+
+            function peek() {
+                return pos < tokens.length ? tokens[pos] : null;
+            }
+            function eat(token) {
+                if (peek() === token) {
+                    pos += 1;
+                    return true;
+                }
+                return false;
+            }
+            function isIdent(token) {
+                return token !== null && (token === "_" ||
+                    token.charAt(0).toLowerCase() !== token.charAt(0).toUpperCase());
+            }
+            function makeType(name, generics) {
+                return {
+                    name: name,
+                    generics: generics,
+                    isGeneric: false,
+                    bounds: null,
+                    wildcard: false,
+                };
+            }
+            function parseList(close) {
+                var list = [];
+                while (!eat(close)) {
+                    if (list.length !== 0 && !eat(",")) {
+                        return null;
+                    }
+                    if (eat(close)) {
+                        break;
+                    }
+                    // Skip the name of associated type bindings, like `Item` in `Item = u8`.
+                    if (isIdent(peek()) && tokens[pos + 1] === "=") {
+                        pos += 2;
+                    }
+                    var ty = parseType();
+                    if (ty === null) {
+                        return null;
+                    }
+                    list.push(ty);
+                }
+                return list;
+            }
+            function parseType() {
+                var token = peek();
+                var ty, generics;
+                pos += 1;
+                if (token === "&") {
+                    eat("mut");
+                    return parseType();
+                } else if (token === "*") {
+                    if (eat("const") || eat("mut")) {
+                        ty = parseType();
+                        return ty === null ? null : makeType("pointer", [ty]);
+                    }
+                    ty = makeType("*", []);
+                    ty.wildcard = true;
+                    return ty;
+                } else if (token === "!") {
+                    return makeType("never", []);
+                } else if (token === "[") {
+                    ty = parseType();
+                    if (ty === null) {
+                        return null;
+                    }
+                    if (eat(";")) {
+                        while (peek() !== null && peek() !== "]") {
+                            pos += 1;
+                        }
+                        return eat("]") ? makeType("array", [ty]) : null;
+                    }
+                    return eat("]") ? makeType("slice", [ty]) : null;
+                } else if (token === "(") {
+                    generics = parseList(")");
+                    if (generics === null) {
+                        return null;
+                    } else if (generics.length === 0) {
+                        return makeType("unit", []);
+                    } else if (generics.length === 1 && tokens[pos - 2] !== ",") {
+                        // This is just a parenthesized type.
+                        return generics[0];
+                    }
+                    return makeType("tuple", generics);
+                } else if (token === "impl" || token === "dyn") {
+                    var bounds = [];
+                    do {
+                        ty = parseType();
+                        if (ty === null) {
+                            return null;
+                        }
+                        bounds.push(ty);
+                    } while (eat("+"));
+                    if (bounds.length === 1) {
+                        return bounds[0];
+                    }
+                    ty = makeType("", []);
+                    ty.bounds = bounds;
+                    return ty;
+                } else if (!isIdent(token)) {
+                    return null;
+                }
+                // Only the last segment of a path is kept.
+                while (eat("::")) {
+                    token = peek();
+                    if (!isIdent(token)) {
+                        return null;
+                    }
+                    pos += 1;
+                }
+                generics = eat("<") ? parseList(">") : [];
+                if (generics === null) {
+                    return null;
+                }
+                ty = makeType(token.toLowerCase(), generics);
+                ty.isGeneric = generics.length === 0 && token.length > 0 &&
+                    token.charAt(0) >= "A" && token.charAt(0) <= "Z" &&
+                    token.substr(1).split("").every(function(d) {
+                        return d >= "0" && d <= "9";
+                    });
+                return ty;
+            }
+            function parseSide(last) {
+                if (pos === last) {
+                    return [];
+                } else if (pos + 1 === last && tokens[pos] === "*") {
+                    pos += 1;
+                    return null;
+                }
+                var list = [];
+                while (pos < last) {
+                    if (list.length !== 0 && !eat(",")) {
+                        return undefined;
+                    }
+                    var ty = parseType();
+                    if (ty === null || pos > last) {
+                        return undefined;
+                    }
+                    list.push(ty);
+                }
+                return list;
+            }
+
+            var arrow = tokens.indexOf("->");
+            var inputs = parseSide(arrow === -1 ? tokens.length : arrow);
+            var output = [];
+            if (arrow !== -1) {
+                pos = arrow + 1;
+                output = parseSide(tokens.length);
+            }
+            if (inputs === undefined || output === undefined ||
+                    (output !== null && output.length > 1)) {
+                return null;
+            }
+            return {
+                inputs: inputs,
+                output: output,
+            };
+        }
+
+        /**
+         * Returns a copy of `bindings` where the type variable `name` of the query is bound to
+         * the type parameter `key` of the function.
+         */
+        function bindGeneric(bindings, name, key) {
+            var newBindings = {
+                vars: Object.assign({}, bindings.vars),
+                keys: Object.assign({}, bindings.keys),
+            };
+            newBindings.vars[name] = key;
+            // `impl Trait` arguments have no name and can't be told apart.
+            if (key !== "") {
+                newBindings.keys[key] = name;
+            }
+            return newBindings;
+        }
+
+        /**
+         * Unifies the query type `queryType` with the search index type `indexType`. Since
+         * type variables can be bound in different ways, this function calls `next` with the
+         * resulting bindings and the distance of the match for every possible unification.
+         *
+         * The index type is "unboxed" if needed: `u8` matches `Option<u8>` or
+         * `Result<u8, E>` too, with a higher distance.
+         *
+         * @param {Object} queryType
+         * @param {Array} indexType
+         * @param {{vars: Object, keys: Object}} bindings
+         * @param {function({vars: Object, keys: Object}, integer)} next
+         */
+        function unifyType(queryType, indexType, bindings, next) {
+            unifyTypeDirect(queryType, indexType, bindings, next);
+            if (indexType[1] !== TY_GENERIC && indexType.length > GENERICS_DATA) {
+                var len = indexType[GENERICS_DATA].length;
+                for (var x = 0; x < len; ++x) {
+                    unifyType(queryType, indexType[GENERICS_DATA][x], bindings,
+                        function(newBindings, lev) {
+                            next(newBindings, lev + 1);
+                        });
+                }
+            }
+        }
+
+        function unifyTypeDirect(queryType, indexType, bindings, next) {
+            var x, len;
+            if (queryType.wildcard) {
+                next(bindings, 0);
+            } else if (queryType.isGeneric) {
+                // A type variable can only be unified with a type parameter, and always with the
+                // same one.
+                if (indexType[1] !== TY_GENERIC) {
+                    return;
+                }
+                var key = indexType[NAME];
+                if (bindings.vars[queryType.name] !== undefined) {
+                    if (bindings.vars[queryType.name] === key) {
+                        next(bindings, 0);
+                    }
+                } else if (bindings.keys[key] === undefined) {
+                    next(bindGeneric(bindings, queryType.name, key), 0);
+                }
+            } else if (queryType.bounds !== null) {
+                if (indexType[1] === TY_GENERIC && indexType.length > GENERICS_DATA) {
+                    unifyAll(queryType.bounds, indexType[GENERICS_DATA], bindings, next);
+                }
+            } else if (indexType[1] === TY_GENERIC) {
+                // A type parameter of the function can be any type implementing its bounds.
+                if (indexType.length > GENERICS_DATA) {
+                    len = indexType[GENERICS_DATA].length;
+                    for (x = 0; x < len; ++x) {
+                        unifyTypeDirect(queryType, indexType[GENERICS_DATA][x], bindings, next);
+                    }
+                }
+            } else if (queryType.name === indexType[NAME]) {
+                unifyAll(
+                    queryType.generics,
+                    indexType.length > GENERICS_DATA ? indexType[GENERICS_DATA] : [],
+                    bindings,
+                    next
+                );
+            }
+        }
+
+        /**
+         * Unifies every type of `queryTypes` with a different type of `indexTypes`, in any
+         * order. `next` is called with the sum of the distances.
+         */
+        function unifyAll(queryTypes, indexTypes, bindings, next) {
+            var used = [];
+            function unifyFrom(x, bindings, total) {
+                if (x === queryTypes.length) {
+                    next(bindings, total);
+                    return;
+                }
+                for (var y = 0, len = indexTypes.length; y < len; ++y) {
+                    if (used[y]) {
+                        continue;
+                    }
+                    used[y] = true;
+                    unifyType(queryTypes[x], indexTypes[y], bindings, function(newBindings, lev) {
+                        unifyFrom(x + 1, newBindings, total + lev);
+                    });
+                    used[y] = false;
+                }
+            }
+            unifyFrom(0, bindings, 0);
+        }
+
+        /**
+         * This function checks if the function `obj` matches the parsed type signature `query`.
+         * `inputs` and `output` are the query types to look for, or `null` if anything can
+         * match.
+         *
+         * @return {integer} - Returns the distance of the best match. If there is no match,
+         *                     returns `MAX_LEV_DISTANCE + 1`.
+         */
+        function checkSignature(obj, inputs, output) {
+            var type = obj.type;
+            var lev_distance = MAX_LEV_DISTANCE + 1;
+            var fnInputs = type[INPUTS_DATA];
+            var fnOutput = type.length > OUTPUT_DATA ? type[OUTPUT_DATA] : [];
+            if (typeof fnOutput[0] === "string") {
+                fnOutput = [fnOutput];
+            }
+            var emptyBindings = {vars: {}, keys: {}};
+            unifyAll(inputs || [], fnInputs, emptyBindings, function(bindings, inputs_lev) {
+                unifyAll(output || [], fnOutput, bindings, function(_, output_lev) {
+                    lev_distance = Math.min(inputs_lev + output_lev, lev_distance);
+                });
+            });
+            if (lev_distance <= MAX_LEV_DISTANCE && inputs !== null) {
+                // Functions with fewer unmatched arguments go first.
+                lev_distance += Math.min(fnInputs.length - inputs.length, 9) / 10;
+            }
+            return lev_distance;
+        }
+
         function checkPath(contains, lastElem, ty) {
             if (contains.length === 0) {
                 return 0;
@@ -713,12 +1113,17 @@ window.initSearch = function(rawSearchIndex) {
 
         // quoted values mean literal search
         var nSearchWords = searchWords.length;
-        var i, it;
+        var i;
         var ty;
         var fullId;
         var returned;
         var in_args;
         var len;
+        var isArrowQuery = val.search("->") > -1;
+        var signature = null;
+        if (isArrowQuery || /^(impl|dyn)\s/.test(valLower)) {
+            signature = parseTypeSignature(query.query);
+        }
         if ((val.charAt(0) === "\"" || val.charAt(0) === "'") &&
             val.charAt(val.length - 1) === val.charAt(0))
         {
@@ -743,57 +1148,38 @@ window.initSearch = function(rawSearchIndex) {
             query.output = val;
             query.search = val;
         // searching by type
-        } else if (val.search("->") > -1) {
-            var trimmer = function(s) { return s.trim(); };
-            var parts = val.split("->").map(trimmer);
-            var input = parts[0];
-            // sort inputs so that order does not matter
-            var inputs = input.split(",").map(trimmer).sort();
-            for (i = 0, len = inputs.length; i < len; ++i) {
-                inputs[i] = extractGenerics(inputs[i]);
-            }
-            var output = extractGenerics(parts[1]);
+        } else if (signature !== null) {
+            var inputs = signature.inputs;
+            var output = signature.output;
 
             for (i = 0; i < nSearchWords; ++i) {
-                if (filterCrates !== null && searchIndex[i].crate !== filterCrates) {
-                    continue;
-                }
-                var type = searchIndex[i].type;
                 ty = searchIndex[i];
-                if (!type) {
+                if (!ty.type || (filterCrates !== null && ty.crate !== filterCrates) ||
+                        !typePassesFilter(typeFilter, ty.ty)) {
                     continue;
                 }
                 fullId = ty.id;
 
-                returned = checkReturned(ty, output, true, NO_TYPE_FILTER);
-                if (output.name === "*" || returned === 0) {
-                    in_args = false;
-                    var is_module = false;
-
-                    if (input === "*") {
-                        is_module = true;
-                    } else {
-                        var firstNonZeroDistance = 0;
-                        for (it = 0, len = inputs.length; it < len; it++) {
-                            var distance = checkType(type, inputs[it], true);
-                            if (distance > 0) {
-                                firstNonZeroDistance = distance;
-                                break;
-                            }
-                        }
-                        in_args = firstNonZeroDistance;
+                if (!isArrowQuery) {
+                    // Something like `impl Iterator<Item = u8>`: look for it in the arguments
+                    // and in the return type separately.
+                    in_args = checkSignature(ty, inputs, null);
+                    returned = checkSignature(ty, null, inputs);
+                } else {
+                    in_args = MAX_LEV_DISTANCE + 1;
+                    returned = MAX_LEV_DISTANCE + 1;
+                    if (output === null || output.length !== 0) {
+                        returned = checkSignature(ty, null, output);
                     }
-                    addIntoResults(true, results_in_args, fullId, i, -1, in_args);
-                    addIntoResults(true, results_returned, fullId, i, -1, returned);
-                    if (is_module) {
-                        addIntoResults(true, results, fullId, i, -1, 0);
+                    if (inputs === null || inputs.length !== 0) {
+                        in_args = checkSignature(ty, inputs, output);
                     }
                 }
+                addIntoResults(false, results_in_args, fullId, i, -1, in_args);
+                addIntoResults(false, results_returned, fullId, i, -1, returned);
             }
-            query.inputs = inputs.map(function(input) {
-                return input.name;
-            });
-            query.output = output.name;
+            query.inputs = inputs;
+            query.output = output;
         } else {
             query.inputs = [val];
             query.output = val;
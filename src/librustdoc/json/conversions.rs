@@ -6,6 +6,7 @@
 
 use std::convert::From;
 use std::fmt;
+use std::iter;
 
 use rustc_ast::ast;
 use rustc_hir::{def::CtorKind, def_id::DefId};
@@ -40,6 +41,13 @@ pub(super) fn convert_item(&self, item: clean::Item) -> Option<Item> {
             .map(rustc_ast_pretty::pprust::attribute_to_string)
             .collect();
         let span = item.span(self.tcx);
+        let availability = item
+            .def_id
+            .as_def_id()
+            .and_then(|did| self.cache.availability.get(&did))
+            .copied()
+            .unwrap_or_default();
+        let available_since = item.stable_since(self.tcx).or(availability.since);
         let clean::Item { name, attrs: _, kind: _, visibility, def_id, cfg: _ } = item;
         let inner = match *item.kind {
             clean::StrippedItem(_) => return None,
@@ -53,7 +61,12 @@ pub(super) fn convert_item(&self, item: clean::Item) -> Option<Item> {
             visibility: self.convert_visibility(visibility),
             docs,
             attrs,
-            deprecation: deprecation.map(from_deprecation),
+            deprecation: deprecation.map(|mut deprecation| {
+                // The version isn't required by `#[deprecated]`, but previous releases may tell it.
+                deprecation.since = deprecation.since.or(availability.deprecated_since);
+                from_deprecation(deprecation)
+            }),
+            available_since: available_since.map(|since| since.to_string()),
             inner,
             links,
         })
@@ -256,7 +269,11 @@ fn from_tcx(struct_: clean::Struct, tcx: TyCtxt<'_>) -> Self {
             generics: generics.into_tcx(tcx),
             fields_stripped,
             fields: ids(fields),
-            impls: Vec::new(), // Added in JsonRenderer::item
+            // Added in JsonRenderer::item
+            impls: Vec::new(),
+            auto_trait_impls: Vec::new(),
+            blanket_impls: Vec::new(),
+            layout: None,
         }
     }
 }
@@ -268,7 +285,11 @@ fn from_tcx(struct_: clean::Union, tcx: TyCtxt<'_>) -> Self {
             generics: generics.into_tcx(tcx),
             fields_stripped,
             fields: ids(fields),
-            impls: Vec::new(), // Added in JsonRenderer::item
+            // Added in JsonRenderer::item
+            impls: Vec::new(),
+            auto_trait_impls: Vec::new(),
+            blanket_impls: Vec::new(),
+            layout: None,
         }
     }
 }
@@ -584,7 +605,11 @@ fn from_tcx(enum_: clean::Enum, tcx: TyCtxt<'_>) -> Self {
             generics: generics.into_tcx(tcx),
             variants_stripped,
             variants: ids(variants),
-            impls: Vec::new(), // Added in JsonRenderer::item
+            // Added in JsonRenderer::item
+            impls: Vec::new(),
+            auto_trait_impls: Vec::new(),
+            blanket_impls: Vec::new(),
+            layout: None,
         }
     }
 }
@@ -598,6 +623,9 @@ fn from_tcx(struct_: clean::VariantStruct, _tcx: TyCtxt<'_>) -> Self {
             fields_stripped,
             fields: ids(fields),
             impls: Vec::new(),
+            auto_trait_impls: Vec::new(),
+            blanket_impls: Vec::new(),
+            layout: None,
         }
     }
 }
@@ -625,25 +653,33 @@ fn from_tcx(variant: clean::Variant, tcx: TyCtxt<'_>) -> Self {
 }
 
 impl FromWithTcx<clean::Import> for Import {
-    fn from_tcx(import: clean::Import, _tcx: TyCtxt<'_>) -> Self {
+    fn from_tcx(import: clean::Import, tcx: TyCtxt<'_>) -> Self {
         use clean::ImportKind::*;
         match import.kind {
             Simple(s) => Import {
                 source: import.source.path.whole_name(),
                 name: s.to_string(),
                 id: import.source.did.map(ItemId::from).map(from_item_id),
+                def_path: import.source.did.map(|did| from_def_path(did, tcx)),
                 glob: false,
             },
             Glob => Import {
                 source: import.source.path.whole_name(),
                 name: import.source.path.last().to_string(),
                 id: import.source.did.map(ItemId::from).map(from_item_id),
+                def_path: import.source.did.map(|did| from_def_path(did, tcx)),
                 glob: true,
             },
         }
     }
 }
 
+/// The path of the definition of an item, starting with the name of the crate defining it.
+fn from_def_path(did: DefId, tcx: TyCtxt<'_>) -> Vec<String> {
+    let names = tcx.def_path(did).data.into_iter().filter_map(|elem| elem.data.get_opt_name());
+    iter::once(tcx.crate_name(did.krate)).chain(names).map(|name| name.to_string()).collect()
+}
+
 impl FromWithTcx<clean::ProcMacro> for ProcMacro {
     fn from_tcx(mac: clean::ProcMacro, _tcx: TyCtxt<'_>) -> Self {
         ProcMacro {
@@ -0,0 +1,1102 @@
+//! Comparison of the public APIs of two versions of a crate, for `--api-diff`.
+//!
+//! Both versions are read from the output of `--output-format json`. Items are matched by the path
+//! under which they are public, since the IDs of the JSON output are not stable between
+//! compilations. Every difference is classified as a major (breaking) or minor change following
+//! the rules of [the Cargo book](https://doc.rust-lang.org/cargo/reference/semver.html).
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustdoc_json_types::{
+    Abi, Crate, FnDecl, GenericArg, GenericArgs, GenericBound, GenericParamDef,
+    GenericParamDefKind, Generics, Header, Id, Import, Item, ItemEnum, MacroKind, Term,
+    TraitBoundModifier, Type, TypeBindingKind, Variant, Visibility, WherePredicate,
+    FORMAT_VERSION,
+};
+use serde::Serialize;
+
+use crate::config::OutputFormat;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Severity {
+    Major,
+    Minor,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, Serialize)]
+struct Change {
+    severity: Severity,
+    kind: ChangeKind,
+    /// The path of the item, or `<Type as Trait>` for trait implementations.
+    path: String,
+    item_kind: &'static str,
+    description: String,
+    /// The declarations of the item before and after the change, for changed items.
+    old: Option<String>,
+    new: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Report {
+    crate_name: String,
+    old_version: Option<String>,
+    new_version: Option<String>,
+    major_changes: usize,
+    minor_changes: usize,
+    changes: Vec<Change>,
+}
+
+/// Compares the crates described by the JSON files `old` and `new`, and prints the changes to
+/// stdout. Returns an error if a file cannot be read or if there are major changes.
+crate fn run(old: &Path, new: &Path, output_format: OutputFormat) -> Result<(), String> {
+    let old = load(old)?;
+    let new = load(new)?;
+    let old_api = Api::new(&old);
+    let new_api = Api::new(&new);
+
+    let mut changes = diff(&old_api, &new_api);
+    changes.sort_by(|a, b| (a.severity, &a.path).cmp(&(b.severity, &b.path)));
+    let major_changes = changes.iter().filter(|change| change.severity == Severity::Major).count();
+    let report = Report {
+        crate_name: new_api.crate_name.clone(),
+        old_version: old.crate_version.clone(),
+        new_version: new.crate_version.clone(),
+        major_changes,
+        minor_changes: changes.len() - major_changes,
+        changes,
+    };
+
+    match output_format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?)
+        }
+        OutputFormat::Html | OutputFormat::Markdown | OutputFormat::Man => {
+            print!("{}", render_report(&report))
+        }
+    }
+
+    if major_changes > 0 {
+        Err(format!(
+            "the public API of `{}` has {} breaking change{}",
+            report.crate_name,
+            major_changes,
+            if major_changes == 1 { "" } else { "s" }
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+crate fn load(path: &Path) -> Result<Crate, String> {
+    let json = fs::read_to_string(path)
+        .map_err(|e| format!("failed to read `{}`: {}", path.display(), e))?;
+    let krate: Crate = serde_json::from_str(&json).map_err(|e| {
+        // Report an unsupported format version rather than the first field that doesn't parse.
+        let format_version = serde_json::from_str::<serde_json::Value>(&json)
+            .ok()
+            .and_then(|value| value.get("format_version")?.as_u64());
+        match format_version {
+            Some(version) if version != u64::from(FORMAT_VERSION) => format!(
+                "`{}` uses rustdoc JSON format version {}, but only version {} is supported",
+                path.display(),
+                version,
+                FORMAT_VERSION
+            ),
+            _ => format!("failed to parse `{}`: {}", path.display(), e),
+        }
+    })?;
+    if krate.format_version != FORMAT_VERSION {
+        return Err(format!(
+            "`{}` uses rustdoc JSON format version {}, but only version {} is supported",
+            path.display(),
+            krate.format_version,
+            FORMAT_VERSION
+        ));
+    }
+    Ok(krate)
+}
+
+fn render_report(report: &Report) -> String {
+    let mut out = String::new();
+    let version = |version: &Option<String>| match version {
+        Some(version) => format!(" {}", version),
+        None => String::new(),
+    };
+    let _ = writeln!(
+        out,
+        "API changes of `{}`{} -> `{}`{}: {} major, {} minor",
+        report.crate_name,
+        version(&report.old_version),
+        report.crate_name,
+        version(&report.new_version),
+        report.major_changes,
+        report.minor_changes,
+    );
+    for change in &report.changes {
+        let severity = match change.severity {
+            Severity::Major => "major",
+            Severity::Minor => "minor",
+        };
+        let _ = writeln!(out, "{}: {}", severity, change.description);
+        if let Some(old) = &change.old {
+            let _ = writeln!(out, "    old: {}", old);
+        }
+        if let Some(new) = &change.new {
+            let _ = writeln!(out, "    new: {}", new);
+        }
+    }
+    let summary = if report.major_changes > 0 {
+        "these changes are breaking and need a new major version (or a new minor version for \
+        versions 0.y.z)"
+    } else if report.minor_changes > 0 {
+        "these changes are backwards compatible and need a new minor version (or a new patch \
+        version for versions 0.y.z)"
+    } else {
+        "the public API is unchanged"
+    };
+    let _ = writeln!(out, "{}", summary);
+    out
+}
+
+/// An item of the public API, or a member of one, like a field or a trait implementation.
+crate struct Entry<'a> {
+    kind: &'static str,
+    /// The path of the item this one is a member of, if any.
+    parent: Option<String>,
+    /// The declaration of the item, as printed in reports.
+    signature: String,
+    /// The declaration of the item without the names of function arguments and with generic
+    /// parameters named by their position, since renaming them doesn't affect users of the item.
+    /// The item is changed if this is.
+    compared: String,
+    /// The item, or the import for re-exports of items of other crates.
+    crate item: &'a Item,
+    /// The type and the trait of trait implementations.
+    crate implementation: Option<(String, String)>,
+}
+
+/// The public API of a crate.
+crate struct Api<'a> {
+    krate: &'a Crate,
+    crate_name: String,
+    /// The shortest public path of every local item.
+    public_paths: FxHashMap<&'a Id, String>,
+    crate entries: BTreeMap<String, Entry<'a>>,
+    /// The generic parameters of the type, the trait or the inherent implementation whose members
+    /// are being added, which are in scope in their declarations.
+    outer_params: &'a [GenericParamDef],
+    /// The positional names of the generic parameters in scope, while rendering the declaration
+    /// that is compared. Function argument names are left out while this is set.
+    positional_names: RefCell<Option<FxHashMap<String, String>>>,
+}
+
+impl<'a> Api<'a> {
+    crate fn new(krate: &'a Crate) -> Api<'a> {
+        let root = &krate.index[&krate.root];
+        let crate_name = root.name.clone().unwrap_or_default();
+
+        let mut items = Vec::new();
+        let mut modules = FxHashSet::default();
+        collect_module_items(krate, root, &crate_name, &mut modules, &mut items);
+
+        let mut public_paths: FxHashMap<&Id, String> = FxHashMap::default();
+        for (path, item) in &items {
+            let shorter = |old: &String| {
+                (old.matches("::").count(), old) > (path.matches("::").count(), path)
+            };
+            if public_paths.get(&item.id).map_or(true, shorter) {
+                public_paths.insert(&item.id, path.clone());
+            }
+        }
+
+        let mut api = Api {
+            krate,
+            crate_name,
+            public_paths,
+            entries: BTreeMap::new(),
+            outer_params: &[],
+            positional_names: RefCell::new(None),
+        };
+        for (path, item) in items {
+            api.add_item(path, item, None);
+        }
+        api
+    }
+
+    fn add_item(&mut self, path: String, item: &'a Item, parent: Option<String>) {
+        let Some(kind) = item_kind(&item.inner) else { return };
+        let signature = self.signature(item);
+        let compared = self.compared_signature(item);
+        self.entries.insert(
+            path.clone(),
+            Entry { kind, parent, signature, compared, item, implementation: None },
+        );
+
+        match &item.inner {
+            ItemEnum::Struct(rustdoc_json_types::Struct { generics, fields, impls, .. })
+            | ItemEnum::Union(rustdoc_json_types::Union { generics, fields, impls, .. }) => {
+                self.outer_params = &generics.params;
+                for field in self.members(fields) {
+                    if field.visibility == Visibility::Public {
+                        self.add_member(&path, field);
+                    }
+                }
+                self.outer_params = &[];
+                self.add_impls(&path, impls);
+            }
+            ItemEnum::Enum(enum_) => {
+                // The fields of struct variants are added with the variants.
+                self.outer_params = &enum_.generics.params;
+                for variant in self.members(&enum_.variants) {
+                    self.add_member(&path, variant);
+                }
+                self.outer_params = &[];
+                self.add_impls(&path, &enum_.impls);
+            }
+            ItemEnum::Variant(Variant::Struct(fields)) => {
+                for field in self.members(fields) {
+                    self.add_member(&path, field);
+                }
+            }
+            ItemEnum::Trait(trait_) => {
+                self.outer_params = &trait_.generics.params;
+                for trait_item in self.members(&trait_.items) {
+                    self.add_member(&path, trait_item);
+                }
+                self.outer_params = &[];
+                self.add_impls(&path, &trait_.implementations);
+            }
+            _ => {}
+        }
+    }
+
+    fn add_member(&mut self, parent: &str, item: &'a Item) {
+        if let Some(name) = &item.name {
+            self.add_item(format!("{}::{}", parent, name), item, Some(parent.to_string()));
+        }
+    }
+
+    fn add_impls(&mut self, parent: &str, impls: &'a [Id]) {
+        for impl_item in self.members(impls) {
+            let ItemEnum::Impl(impl_) = &impl_item.inner else { continue };
+            // Blanket implementations follow from other implementations, and negative ones can
+            // only be written on nightly.
+            if impl_.blanket_impl.is_some() || impl_.negative {
+                continue;
+            }
+            match &impl_.trait_ {
+                None => {
+                    self.outer_params = &impl_.generics.params;
+                    for item in self.members(&impl_.items) {
+                        if item.visibility == Visibility::Public {
+                            self.add_member(parent, item);
+                        }
+                    }
+                    self.outer_params = &[];
+                }
+                Some(trait_) => {
+                    let for_ = self.ty(&impl_.for_);
+                    let trait_ = self.ty(trait_);
+                    let kind = if impl_.synthetic { "auto trait impl" } else { "trait impl" };
+                    self.entries.insert(
+                        format!("<{} as {}>", for_, trait_),
+                        Entry {
+                            kind,
+                            parent: Some(parent.to_string()),
+                            signature: self.signature(impl_item),
+                            compared: self.compared_signature(impl_item),
+                            item: impl_item,
+                            implementation: Some((for_, trait_)),
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    fn members(&self, ids: &'a [Id]) -> impl Iterator<Item = &'a Item> + 'a {
+        let index = &self.krate.index;
+        ids.iter().filter_map(move |id| index.get(id))
+    }
+
+    /// Returns the path to print for an item referenced by a type.
+    fn path(&self, id: &Id, name: &str) -> String {
+        if let Some(path) = self.public_paths.get(id) {
+            path.clone()
+        } else if let Some(summary) = self.krate.paths.get(id) {
+            summary.path.join("::")
+        } else {
+            name.to_string()
+        }
+    }
+
+    /// Returns the declaration of an item that is compared between versions, see
+    /// `Entry::compared`.
+    fn compared_signature(&self, item: &Item) -> String {
+        let own_params = generics(&item.inner).map_or(&[][..], |generics| &generics.params);
+        let positional_names = self
+            .outer_params
+            .iter()
+            .chain(own_params)
+            // Synthetic parameters are never named.
+            .filter(|param| {
+                !matches!(param.kind, GenericParamDefKind::Type { synthetic: true, .. })
+            })
+            .enumerate()
+            .map(|(i, param)| {
+                let name = match param.kind {
+                    GenericParamDefKind::Lifetime { .. } => format!("'{}", i),
+                    _ => format!("#{}", i),
+                };
+                (param.name.clone(), name)
+            })
+            .collect();
+        *self.positional_names.borrow_mut() = Some(positional_names);
+        let signature = self.signature(item);
+        *self.positional_names.borrow_mut() = None;
+        signature
+    }
+
+    /// Returns the name to print for a generic parameter or a lifetime.
+    fn param_name(&self, name: &str) -> String {
+        match &*self.positional_names.borrow() {
+            Some(names) => names.get(name).map_or_else(|| name.to_string(), |name| name.clone()),
+            None => name.to_string(),
+        }
+    }
+
+    /// Returns the declaration of an item, without its attributes and documentation.
+    fn signature(&self, item: &Item) -> String {
+        let name = item.name.as_deref().unwrap_or("_");
+        match &item.inner {
+            ItemEnum::Module(_) => format!("mod {}", name),
+            ItemEnum::ExternCrate { name, rename } => match rename {
+                Some(rename) => format!("extern crate {} as {}", name, rename),
+                None => format!("extern crate {}", name),
+            },
+            ItemEnum::Import(import) => format!("use {}", import.source),
+            ItemEnum::Union(union_) => {
+                format!("union {}{}", name, self.generics_with_where(&union_.generics))
+            }
+            ItemEnum::Struct(struct_) => {
+                let body = match struct_.struct_type {
+                    rustdoc_json_types::StructType::Plain => " { .. }",
+                    rustdoc_json_types::StructType::Tuple => "(..)",
+                    rustdoc_json_types::StructType::Unit => ";",
+                };
+                format!("struct {}{}{}", name, self.generics_with_where(&struct_.generics), body)
+            }
+            ItemEnum::StructField(ty) => format!("{}: {}", name, self.ty(ty)),
+            ItemEnum::Enum(enum_) => {
+                format!("enum {}{}", name, self.generics_with_where(&enum_.generics))
+            }
+            ItemEnum::Variant(Variant::Plain) => name.to_string(),
+            ItemEnum::Variant(Variant::Tuple(types)) => {
+                format!("{}({})", name, self.list(types.iter().map(|ty| self.ty(ty))))
+            }
+            ItemEnum::Variant(Variant::Struct(fields)) => {
+                let fields = self.members(fields).map(|field| self.signature(field));
+                format!("{} {{ {} }}", name, self.list(fields))
+            }
+            ItemEnum::Function(function) => {
+                self.function(name, &function.header, &function.generics, &function.decl)
+            }
+            ItemEnum::Method(method) => {
+                self.function(name, &method.header, &method.generics, &method.decl)
+            }
+            ItemEnum::Trait(trait_) => {
+                let mut signature = String::new();
+                if trait_.is_unsafe {
+                    signature.push_str("unsafe ");
+                }
+                if trait_.is_auto {
+                    signature.push_str("auto ");
+                }
+                signature.push_str("trait ");
+                signature.push_str(name);
+                signature.push_str(&self.generic_params(&trait_.generics.params));
+                if !trait_.bounds.is_empty() {
+                    signature.push_str(": ");
+                    signature.push_str(&self.bounds(&trait_.bounds));
+                }
+                signature.push_str(&self.where_clause(&trait_.generics.where_predicates));
+                signature
+            }
+            ItemEnum::TraitAlias(alias) => format!(
+                "trait {}{} = {}",
+                name,
+                self.generics_with_where(&alias.generics),
+                self.bounds(&alias.params)
+            ),
+            ItemEnum::Impl(impl_) => {
+                let mut signature = String::new();
+                if impl_.is_unsafe {
+                    signature.push_str("unsafe ");
+                }
+                signature.push_str("impl");
+                signature.push_str(&self.generic_params(&impl_.generics.params));
+                signature.push(' ');
+                if let Some(trait_) = &impl_.trait_ {
+                    signature.push_str(&self.ty(trait_));
+                    signature.push_str(" for ");
+                }
+                signature.push_str(&self.ty(&impl_.for_));
+                signature.push_str(&self.where_clause(&impl_.generics.where_predicates));
+                // The associated types of trait implementations are part of the API, like
+                // `Iterator::Item`.
+                let assoc_types: Vec<_> = self
+                    .members(&impl_.items)
+                    .filter(|item| matches!(item.inner, ItemEnum::AssocType { .. }))
+                    .map(|item| self.signature(item))
+                    .collect();
+                if !assoc_types.is_empty() {
+                    signature.push_str(&format!(" {{ {}; }}", assoc_types.join("; ")));
+                }
+                signature
+            }
+            ItemEnum::Typedef(typedef) => format!(
+                "type {}{} = {}",
+                name,
+                self.generics_with_where(&typedef.generics),
+                self.ty(&typedef.type_)
+            ),
+            ItemEnum::OpaqueTy(opaque) => format!(
+                "type {}{} = impl {}",
+                name,
+                self.generics_with_where(&opaque.generics),
+                self.bounds(&opaque.bounds)
+            ),
+            ItemEnum::Constant(constant) => format!("const {}: {}", name, self.ty(&constant.type_)),
+            ItemEnum::Static(static_) => format!(
+                "static {}{}: {}",
+                if static_.mutable { "mut " } else { "" },
+                name,
+                self.ty(&static_.type_)
+            ),
+            ItemEnum::ForeignType => format!("extern type {}", name),
+            ItemEnum::Macro(_) => format!("macro_rules! {}", name),
+            ItemEnum::ProcMacro(proc_macro) => {
+                let kind = match proc_macro.kind {
+                    MacroKind::Bang => "proc_macro",
+                    MacroKind::Attr => "proc_macro_attribute",
+                    MacroKind::Derive => "proc_macro_derive",
+                };
+                if proc_macro.helpers.is_empty() {
+                    format!("#[{}] {}", kind, name)
+                } else {
+                    format!("#[{}({}, attributes({}))]", kind, name, proc_macro.helpers.join(", "))
+                }
+            }
+            ItemEnum::PrimitiveType(name) => name.clone(),
+            ItemEnum::AssocConst { type_, .. } => format!("const {}: {}", name, self.ty(type_)),
+            ItemEnum::AssocType { generics, bounds, default } => {
+                let mut signature =
+                    format!("type {}{}", name, self.generic_params(&generics.params));
+                if !bounds.is_empty() {
+                    signature.push_str(": ");
+                    signature.push_str(&self.bounds(bounds));
+                }
+                signature.push_str(&self.where_clause(&generics.where_predicates));
+                if let Some(default) = default {
+                    signature.push_str(" = ");
+                    signature.push_str(&self.ty(default));
+                }
+                signature
+            }
+        }
+    }
+
+    fn function(&self, name: &str, header: &Header, generics: &Generics, decl: &FnDecl) -> String {
+        format!(
+            "{}fn {}{}{}{}",
+            render_header(header),
+            name,
+            self.generic_params(&generics.params),
+            self.fn_decl(decl),
+            self.where_clause(&generics.where_predicates)
+        )
+    }
+
+    fn fn_decl(&self, decl: &FnDecl) -> String {
+        let without_names = self.positional_names.borrow().is_some();
+        let mut inputs: Vec<_> = decl
+            .inputs
+            .iter()
+            .map(|(name, ty)| {
+                if without_names { self.ty(ty) } else { format!("{}: {}", name, self.ty(ty)) }
+            })
+            .collect();
+        if decl.c_variadic {
+            inputs.push("...".to_string());
+        }
+        let mut out = format!("({})", inputs.join(", "));
+        if let Some(output) = &decl.output {
+            out.push_str(" -> ");
+            out.push_str(&self.ty(output));
+        }
+        out
+    }
+
+    fn generics_with_where(&self, generics: &Generics) -> String {
+        format!(
+            "{}{}",
+            self.generic_params(&generics.params),
+            self.where_clause(&generics.where_predicates)
+        )
+    }
+
+    fn generic_params(&self, params: &[GenericParamDef]) -> String {
+        let params: Vec<_> = params
+            .iter()
+            // Synthetic parameters are the `impl Trait` types of arguments.
+            .filter(|param| {
+                !matches!(param.kind, GenericParamDefKind::Type { synthetic: true, .. })
+            })
+            .map(|param| self.generic_param(param))
+            .collect();
+        if params.is_empty() { String::new() } else { format!("<{}>", params.join(", ")) }
+    }
+
+    fn generic_param(&self, param: &GenericParamDef) -> String {
+        match &param.kind {
+            GenericParamDefKind::Lifetime { outlives } if outlives.is_empty() => {
+                self.param_name(&param.name)
+            }
+            GenericParamDefKind::Lifetime { outlives } => {
+                let outlives: Vec<_> = outlives.iter().map(|name| self.param_name(name)).collect();
+                format!("{}: {}", self.param_name(&param.name), outlives.join(" + "))
+            }
+            GenericParamDefKind::Type { bounds, default, .. } => {
+                let mut out = self.param_name(&param.name);
+                if !bounds.is_empty() {
+                    out.push_str(": ");
+                    out.push_str(&self.bounds(bounds));
+                }
+                if let Some(default) = default {
+                    out.push_str(" = ");
+                    out.push_str(&self.ty(default));
+                }
+                out
+            }
+            GenericParamDefKind::Const { type_, default } => {
+                let mut out = format!("const {}: {}", self.param_name(&param.name), self.ty(type_));
+                if let Some(default) = default {
+                    out.push_str(" = ");
+                    out.push_str(default);
+                }
+                out
+            }
+        }
+    }
+
+    fn where_clause(&self, predicates: &[WherePredicate]) -> String {
+        if predicates.is_empty() {
+            return String::new();
+        }
+        let predicates = predicates.iter().map(|predicate| match predicate {
+            WherePredicate::BoundPredicate { type_, bounds } => {
+                format!("{}: {}", self.ty(type_), self.bounds(bounds))
+            }
+            WherePredicate::RegionPredicate { lifetime, bounds } => {
+                format!("{}: {}", self.param_name(lifetime), self.bounds(bounds))
+            }
+            WherePredicate::EqPredicate { lhs, rhs } => {
+                format!("{} == {}", self.ty(lhs), self.term(rhs))
+            }
+        });
+        format!(" where {}", self.list(predicates))
+    }
+
+    fn bounds(&self, bounds: &[GenericBound]) -> String {
+        bounds.iter().map(|bound| self.bound(bound)).collect::<Vec<_>>().join(" + ")
+    }
+
+    fn bound(&self, bound: &GenericBound) -> String {
+        match bound {
+            GenericBound::TraitBound { trait_, generic_params, modifier } => {
+                let mut out = String::new();
+                if !generic_params.is_empty() {
+                    let params = generic_params.iter().map(|param| self.generic_param(param));
+                    let _ = write!(out, "for<{}> ", self.list(params));
+                }
+                match modifier {
+                    TraitBoundModifier::None => {}
+                    TraitBoundModifier::Maybe => out.push('?'),
+                    TraitBoundModifier::MaybeConst => out.push_str("~const "),
+                }
+                out.push_str(&self.ty(trait_));
+                out
+            }
+            GenericBound::Outlives(lifetime) => self.param_name(lifetime),
+        }
+    }
+
+    fn term(&self, term: &Term) -> String {
+        match term {
+            Term::Type(ty) => self.ty(ty),
+            Term::Constant(constant) => constant.expr.clone(),
+        }
+    }
+
+    fn generic_args(&self, args: &GenericArgs) -> String {
+        match args {
+            GenericArgs::AngleBracketed { args, bindings } => {
+                let args = args.iter().map(|arg| match arg {
+                    GenericArg::Lifetime(lifetime) => self.param_name(lifetime),
+                    GenericArg::Type(ty) => self.ty(ty),
+                    GenericArg::Const(constant) => constant.expr.clone(),
+                    GenericArg::Infer => "_".to_string(),
+                });
+                let bindings = bindings.iter().map(|binding| {
+                    let name = format!("{}{}", binding.name, self.generic_args(&binding.args));
+                    match &binding.binding {
+                        TypeBindingKind::Equality(term) => {
+                            format!("{} = {}", name, self.term(term))
+                        }
+                        TypeBindingKind::Constraint(bounds) => {
+                            format!("{}: {}", name, self.bounds(bounds))
+                        }
+                    }
+                });
+                let args: Vec<_> = args.chain(bindings).collect();
+                if args.is_empty() { String::new() } else { format!("<{}>", args.join(", ")) }
+            }
+            GenericArgs::Parenthesized { inputs, output } => {
+                let mut out = format!("({})", self.list(inputs.iter().map(|ty| self.ty(ty))));
+                if let Some(output) = output {
+                    out.push_str(" -> ");
+                    out.push_str(&self.ty(output));
+                }
+                out
+            }
+        }
+    }
+
+    fn ty(&self, ty: &Type) -> String {
+        match ty {
+            Type::ResolvedPath { name, id, args, param_names } => {
+                let mut out = self.path(id, name);
+                if let Some(args) = args {
+                    out.push_str(&self.generic_args(args));
+                }
+                if !param_names.is_empty() {
+                    out = format!("dyn {} + {}", out, self.bounds(param_names));
+                }
+                out
+            }
+            Type::Generic(name) => self.param_name(name),
+            Type::Primitive(name) => name.clone(),
+            Type::FunctionPointer(pointer) => {
+                let mut out = String::new();
+                if !pointer.generic_params.is_empty() {
+                    let params = pointer.generic_params.iter().map(|p| self.generic_param(p));
+                    let _ = write!(out, "for<{}> ", self.list(params));
+                }
+                let _ = write!(
+                    out,
+                    "{}fn{}",
+                    render_header(&pointer.header),
+                    self.fn_decl(&pointer.decl)
+                );
+                out
+            }
+            Type::Tuple(types) if types.len() == 1 => format!("({},)", self.ty(&types[0])),
+            Type::Tuple(types) => format!("({})", self.list(types.iter().map(|ty| self.ty(ty)))),
+            Type::Slice(ty) => format!("[{}]", self.ty(ty)),
+            Type::Array { type_, len } => format!("[{}; {}]", self.ty(type_), len),
+            Type::ImplTrait(bounds) => format!("impl {}", self.bounds(bounds)),
+            Type::Infer => "_".to_string(),
+            Type::RawPointer { mutable, type_ } => {
+                format!("*{} {}", if *mutable { "mut" } else { "const" }, self.ty(type_))
+            }
+            Type::BorrowedRef { lifetime, mutable, type_ } => {
+                let mut out = "&".to_string();
+                if let Some(lifetime) = lifetime {
+                    out.push_str(&self.param_name(lifetime));
+                    out.push(' ');
+                }
+                if *mutable {
+                    out.push_str("mut ");
+                }
+                out.push_str(&self.ty(type_));
+                out
+            }
+            Type::QualifiedPath { name, args, self_type, trait_ } => format!(
+                "<{} as {}>::{}{}",
+                self.ty(self_type),
+                self.ty(trait_),
+                name,
+                self.generic_args(args)
+            ),
+        }
+    }
+
+    fn list(&self, items: impl Iterator<Item = String>) -> String {
+        items.collect::<Vec<_>>().join(", ")
+    }
+}
+
+fn render_header(header: &Header) -> String {
+    let mut out = String::new();
+    if header.const_ {
+        out.push_str("const ");
+    }
+    if header.async_ {
+        out.push_str("async ");
+    }
+    if header.unsafe_ {
+        out.push_str("unsafe ");
+    }
+    let (abi, unwind) = match &header.abi {
+        Abi::Rust => return out,
+        Abi::C { unwind } => ("C", *unwind),
+        Abi::Cdecl { unwind } => ("cdecl", *unwind),
+        Abi::Stdcall { unwind } => ("stdcall", *unwind),
+        Abi::Fastcall { unwind } => ("fastcall", *unwind),
+        Abi::Aapcs { unwind } => ("aapcs", *unwind),
+        Abi::Win64 { unwind } => ("win64", *unwind),
+        Abi::SysV64 { unwind } => ("sysv64", *unwind),
+        Abi::System { unwind } => ("system", *unwind),
+        Abi::Other(abi) => {
+            let _ = write!(out, "extern {} ", abi);
+            return out;
+        }
+    };
+    let _ = write!(out, "extern \"{}{}\" ", abi, if unwind { "-unwind" } else { "" });
+    out
+}
+
+/// Collects the public items of a module and of its public submodules, with their paths. Items
+/// re-exported from other crates are returned as their imports, since they are not in the index.
+fn collect_module_items<'a>(
+    krate: &'a Crate,
+    module: &'a Item,
+    path: &str,
+    // The modules being visited, since glob imports can form cycles.
+    modules: &mut FxHashSet<&'a Id>,
+    items: &mut Vec<(String, &'a Item)>,
+) {
+    let ItemEnum::Module(module_inner) = &module.inner else { return };
+    if !modules.insert(&module.id) {
+        return;
+    }
+    for item in module_inner.items.iter().filter_map(|id| krate.index.get(id)) {
+        if krate.includes_private && item.visibility != Visibility::Public {
+            continue;
+        }
+        match &item.inner {
+            ItemEnum::Import(Import { source, name, id, glob, .. }) => {
+                let target = id.as_ref().and_then(|id| krate.index.get(id));
+                match target {
+                    Some(target) if *glob => {
+                        collect_module_items(krate, target, path, modules, items);
+                    }
+                    Some(target) => {
+                        let target_path = format!("{}::{}", path, name);
+                        items.push((target_path.clone(), target));
+                        collect_module_items(krate, target, &target_path, modules, items);
+                    }
+                    // Re-exports of items of other crates are part of the API too, but there is
+                    // nothing to compare except for what they re-export.
+                    None if *glob => items.push((format!("{}::{}::*", path, source), item)),
+                    None => items.push((format!("{}::{}", path, name), item)),
+                }
+            }
+            ItemEnum::Impl(_) => {}
+            _ => {
+                let Some(name) = &item.name else { continue };
+                let item_path = format!("{}::{}", path, name);
+                items.push((item_path.clone(), item));
+                collect_module_items(krate, item, &item_path, modules, items);
+            }
+        }
+    }
+    modules.remove(&module.id);
+}
+
+fn item_kind(inner: &ItemEnum) -> Option<&'static str> {
+    Some(match inner {
+        ItemEnum::Module(_) => "module",
+        ItemEnum::ExternCrate { .. } => "extern crate",
+        ItemEnum::Import(_) => "re-export",
+        ItemEnum::Union(_) => "union",
+        ItemEnum::Struct(_) => "struct",
+        ItemEnum::StructField(_) => "field",
+        ItemEnum::Enum(_) => "enum",
+        ItemEnum::Variant(_) => "variant",
+        ItemEnum::Function(_) => "function",
+        ItemEnum::Trait(_) => "trait",
+        ItemEnum::TraitAlias(_) => "trait alias",
+        ItemEnum::Method(_) => "method",
+        ItemEnum::Impl(_) => return None,
+        ItemEnum::Typedef(_) => "type alias",
+        ItemEnum::OpaqueTy(_) => "opaque type",
+        ItemEnum::Constant(_) => "constant",
+        ItemEnum::Static(_) => "static",
+        ItemEnum::ForeignType => "foreign type",
+        ItemEnum::Macro(_) => "macro",
+        ItemEnum::ProcMacro(_) => "proc macro",
+        ItemEnum::PrimitiveType(_) => "primitive type",
+        ItemEnum::AssocConst { .. } => "associated constant",
+        ItemEnum::AssocType { .. } => "associated type",
+    })
+}
+
+/// Returns the generics of an item, if it can have any.
+fn generics(inner: &ItemEnum) -> Option<&Generics> {
+    match inner {
+        ItemEnum::Union(rustdoc_json_types::Union { generics, .. })
+        | ItemEnum::Struct(rustdoc_json_types::Struct { generics, .. })
+        | ItemEnum::Enum(rustdoc_json_types::Enum { generics, .. })
+        | ItemEnum::Function(rustdoc_json_types::Function { generics, .. })
+        | ItemEnum::Method(rustdoc_json_types::Method { generics, .. })
+        | ItemEnum::Trait(rustdoc_json_types::Trait { generics, .. })
+        | ItemEnum::TraitAlias(rustdoc_json_types::TraitAlias { generics, .. })
+        | ItemEnum::Impl(rustdoc_json_types::Impl { generics, .. })
+        | ItemEnum::Typedef(rustdoc_json_types::Typedef { generics, .. })
+        | ItemEnum::OpaqueTy(rustdoc_json_types::OpaqueTy { generics, .. })
+        | ItemEnum::AssocType { generics, .. } => Some(generics),
+        _ => None,
+    }
+}
+
+fn is_non_exhaustive(item: &Item) -> bool {
+    item.attrs.iter().any(|attr| attr.contains("non_exhaustive"))
+}
+
+/// Returns whether adding a field or a variant to a struct or an enum is a breaking change,
+/// because code outside of the crate can construct or exhaustively match it.
+fn is_exhaustive(item: &Item) -> bool {
+    match &item.inner {
+        ItemEnum::Struct(struct_) => !struct_.fields_stripped && !is_non_exhaustive(item),
+        ItemEnum::Enum(_) | ItemEnum::Variant(_) => !is_non_exhaustive(item),
+        _ => false,
+    }
+}
+
+/// Returns whether implementations of a trait need to define this trait item.
+fn is_required_trait_item(item: &Item) -> bool {
+    match &item.inner {
+        ItemEnum::Method(method) => !method.has_body,
+        ItemEnum::AssocConst { default, .. } => default.is_none(),
+        ItemEnum::AssocType { default, .. } => default.is_none(),
+        _ => false,
+    }
+}
+
+/// Returns how a type implementing a trait is described, like "`Foo` now implements `Send`".
+fn describe_impl(entry: &Entry<'_>, verb: &str) -> Option<String> {
+    let (for_, trait_) = entry.implementation.as_ref()?;
+    let auto = if entry.kind == "auto trait impl" { "auto trait " } else { "" };
+    Some(format!("`{}` {} {}`{}`", for_, verb, auto, trait_))
+}
+
+fn diff(old: &Api<'_>, new: &Api<'_>) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    for (path, old_entry) in &old.entries {
+        match new.entries.get(path) {
+            Some(new_entry) => compare(path, old_entry, new_entry, &mut changes),
+            // Only report the removal of the outermost removed item.
+            None if old_entry.parent.as_ref().map_or(false, |p| !new.entries.contains_key(p)) => {}
+            None => changes.push(Change {
+                severity: Severity::Major,
+                kind: ChangeKind::Removed,
+                path: path.clone(),
+                item_kind: old_entry.kind,
+                description: describe_impl(old_entry, "no longer implements")
+                    .unwrap_or_else(|| format!("removed {} `{}`", old_entry.kind, path)),
+                old: Some(old_entry.signature.clone()),
+                new: None,
+            }),
+        }
+    }
+
+    for (path, new_entry) in &new.entries {
+        if old.entries.contains_key(path) {
+            continue;
+        }
+        let old_parent = match &new_entry.parent {
+            Some(parent) => match old.entries.get(parent) {
+                Some(old_parent) => Some(old_parent.item),
+                // Only report the addition of the outermost added item.
+                None => continue,
+            },
+            None => None,
+        };
+        let item = new_entry.item;
+        let (severity, reason) = match old_parent {
+            Some(parent) if is_exhaustive(parent) => match (&item.inner, &parent.inner) {
+                (ItemEnum::StructField(_), ItemEnum::Struct(_)) => {
+                    (Severity::Major, " to a struct without private fields")
+                }
+                (ItemEnum::StructField(_), _) => {
+                    (Severity::Major, " to a variant that is not `#[non_exhaustive]`")
+                }
+                (ItemEnum::Variant(_), _) => {
+                    (Severity::Major, " to an enum that is not `#[non_exhaustive]`")
+                }
+                _ => (Severity::Minor, ""),
+            },
+            Some(Item { inner: ItemEnum::Trait(_), .. }) if is_required_trait_item(item) => {
+                (Severity::Major, " without a default to a trait")
+            }
+            _ => (Severity::Minor, ""),
+        };
+        changes.push(Change {
+            severity,
+            kind: ChangeKind::Added,
+            path: path.clone(),
+            item_kind: new_entry.kind,
+            description: describe_impl(new_entry, "now implements")
+                .unwrap_or_else(|| format!("added {} `{}`{}", new_entry.kind, path, reason)),
+            old: None,
+            new: Some(new_entry.signature.clone()),
+        });
+    }
+
+    changes
+}
+
+fn compare(path: &str, old: &Entry<'_>, new: &Entry<'_>, changes: &mut Vec<Change>) {
+    let mut change = |severity, description: String, signatures: bool| {
+        changes.push(Change {
+            severity,
+            kind: ChangeKind::Changed,
+            path: path.to_string(),
+            item_kind: new.kind,
+            description,
+            old: signatures.then(|| old.signature.clone()),
+            new: signatures.then(|| new.signature.clone()),
+        })
+    };
+
+    if old.kind != new.kind {
+        let description = format!("`{}` changed from {} to {}", path, old.kind, new.kind);
+        change(Severity::Major, description, true);
+        return;
+    }
+
+    match (&old.item.inner, &new.item.inner) {
+        (ItemEnum::Function(old_fn), ItemEnum::Function(new_fn)) => {
+            compare_headers(path, new.kind, &old_fn.header, &new_fn.header, &mut change);
+        }
+        (ItemEnum::Method(old_fn), ItemEnum::Method(new_fn)) => {
+            compare_headers(path, new.kind, &old_fn.header, &new_fn.header, &mut change);
+            if old_fn.has_body && !new_fn.has_body {
+                let description = format!("method `{}` no longer has a default", path);
+                change(Severity::Major, description, false);
+            } else if !old_fn.has_body && new_fn.has_body {
+                change(Severity::Minor, format!("method `{}` now has a default", path), false);
+            }
+        }
+        (ItemEnum::Struct(old_struct), ItemEnum::Struct(new_struct)) => {
+            if !old_struct.fields_stripped && new_struct.fields_stripped {
+                let description = format!("struct `{}` now has private fields", path);
+                change(Severity::Major, description, false);
+            } else if old_struct.fields_stripped && !new_struct.fields_stripped {
+                let description = format!("struct `{}` no longer has private fields", path);
+                change(Severity::Minor, description, false);
+            }
+        }
+        (ItemEnum::Constant(old_const), ItemEnum::Constant(new_const)) => {
+            let value = |constant: &rustdoc_json_types::Constant| {
+                constant.value.clone().unwrap_or_else(|| constant.expr.clone())
+            };
+            if value(old_const) != value(new_const) {
+                let description = format!(
+                    "changed the value of constant `{}` from `{}` to `{}`",
+                    path,
+                    value(old_const),
+                    value(new_const)
+                );
+                change(Severity::Minor, description, false);
+            }
+        }
+        (
+            ItemEnum::AssocConst { default: old_default, .. },
+            ItemEnum::AssocConst { default: new_default, .. },
+        ) => match (old_default, new_default) {
+            (Some(_), None) => {
+                let description = format!("associated constant `{}` no longer has a default", path);
+                change(Severity::Major, description, false);
+            }
+            (None, Some(_)) => {
+                let description = format!("associated constant `{}` now has a default", path);
+                change(Severity::Minor, description, false);
+            }
+            _ => {}
+        },
+        _ => {}
+    }
+
+    // Changes of the headers of functions are reported by `compare_headers`, so compare the rest
+    // of their signatures.
+    let without_header = |entry: &'_ Entry<'_>| match &entry.item.inner {
+        ItemEnum::Function(function) => render_header(&function.header).len(),
+        ItemEnum::Method(method) => render_header(&method.header).len(),
+        _ => 0,
+    };
+    if old.compared[without_header(old)..] != new.compared[without_header(new)..] {
+        let description = match &new.implementation {
+            Some((for_, trait_)) => {
+                format!("changed the implementation of `{}` for `{}`", trait_, for_)
+            }
+            None => format!("changed the declaration of {} `{}`", new.kind, path),
+        };
+        change(Severity::Major, description, true);
+    }
+
+    if is_non_exhaustive(new.item) && !is_non_exhaustive(old.item) {
+        let description = format!("{} `{}` is now `#[non_exhaustive]`", new.kind, path);
+        change(Severity::Major, description, false);
+    } else if is_non_exhaustive(old.item) && !is_non_exhaustive(new.item) {
+        let description = format!("{} `{}` is no longer `#[non_exhaustive]`", new.kind, path);
+        change(Severity::Minor, description, false);
+    }
+
+    if old.item.deprecation.is_none() && new.item.deprecation.is_some() {
+        change(Severity::Minor, format!("deprecated {} `{}`", new.kind, path), false);
+    }
+}
+
+fn compare_headers(
+    path: &str,
+    kind: &str,
+    old: &Header,
+    new: &Header,
+    change: &mut impl FnMut(Severity, String, bool),
+) {
+    let mut qualifier = |name: &str, old: bool, new: bool, added: Severity, removed: Severity| {
+        if !old && new {
+            change(added, format!("{} `{}` is now `{}`", kind, path, name), true);
+        } else if old && !new {
+            change(removed, format!("{} `{}` is no longer `{}`", kind, path, name), true);
+        }
+    };
+    // Callers of a function that became `const` or stopped being `unsafe` are not affected.
+    qualifier("const", old.const_, new.const_, Severity::Minor, Severity::Major);
+    qualifier("unsafe", old.unsafe_, new.unsafe_, Severity::Major, Severity::Minor);
+    // Making a function `async` or not changes its return type either way.
+    qualifier("async", old.async_, new.async_, Severity::Major, Severity::Major);
+    if old.abi != new.abi {
+        change(Severity::Major, format!("changed the ABI of {} `{}`", kind, path), true);
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,278 @@
+// The maps of `rustdoc_json_types::Crate` and `Item` are the default `HashMap`.
+#![allow(rustc::default_hash_types)]
+
+use std::collections::HashMap;
+
+use rustdoc_json_types::{
+    Abi, Crate, FnDecl, Function, GenericParamDef, GenericParamDefKind, Generics, Header, Id, Impl,
+    Import, Item, ItemEnum, Module, Struct, StructType, Type, Visibility, FORMAT_VERSION,
+};
+
+use super::{diff, Api, Severity};
+
+fn item(id: &str, name: Option<&str>, inner: ItemEnum) -> Item {
+    Item {
+        id: Id(id.to_string()),
+        crate_id: 0,
+        name: name.map(|name| name.to_string()),
+        span: None,
+        visibility: Visibility::Public,
+        docs: None,
+        links: HashMap::new(),
+        attrs: Vec::new(),
+        deprecation: None,
+        available_since: None,
+        inner,
+    }
+}
+
+/// Returns a crate named `krate` whose root module contains the items `module_items`.
+fn krate(module_items: &[&str], items: Vec<Item>) -> Crate {
+    let module = Module {
+        is_crate: true,
+        items: module_items.iter().map(|id| Id(id.to_string())).collect(),
+    };
+    let mut index: HashMap<_, _> = items.into_iter().map(|item| (item.id.clone(), item)).collect();
+    index.insert(Id("0:0".to_string()), item("0:0", Some("krate"), ItemEnum::Module(module)));
+    Crate {
+        root: Id("0:0".to_string()),
+        crate_version: None,
+        includes_private: false,
+        index,
+        paths: HashMap::new(),
+        external_crates: HashMap::new(),
+        format_version: FORMAT_VERSION,
+    }
+}
+
+fn function(const_: bool) -> ItemEnum {
+    function_with_header(Header { const_, unsafe_: false, async_: false, abi: Abi::Rust })
+}
+
+fn function_with_header(header: Header) -> ItemEnum {
+    ItemEnum::Function(Function {
+        decl: FnDecl { inputs: Vec::new(), output: None, c_variadic: false },
+        generics: Generics::default(),
+        header,
+    })
+}
+
+fn ids_with_prefix(ids: &[&str], prefix: &str) -> Vec<Id> {
+    ids.iter().filter(|id| id.starts_with(prefix)).map(|id| Id(id.to_string())).collect()
+}
+
+fn plain_struct(fields_stripped: bool, fields: &[&str], impls: &[&str]) -> ItemEnum {
+    ItemEnum::Struct(Struct {
+        struct_type: StructType::Plain,
+        generics: Generics::default(),
+        fields_stripped,
+        fields: fields.iter().map(|id| Id(id.to_string())).collect(),
+        impls: impls.iter().map(|id| Id(id.to_string())).collect(),
+        auto_trait_impls: ids_with_prefix(impls, "a:"),
+        blanket_impls: ids_with_prefix(impls, "b:"),
+        layout: None,
+    })
+}
+
+fn auto_trait_impl(trait_: (&str, &str), for_: (&str, &str)) -> ItemEnum {
+    let path = |(id, name): (&str, &str)| Type::ResolvedPath {
+        name: name.to_string(),
+        id: Id(id.to_string()),
+        args: None,
+        param_names: Vec::new(),
+    };
+    ItemEnum::Impl(Impl {
+        is_unsafe: false,
+        generics: Generics::default(),
+        provided_trait_methods: Vec::new(),
+        trait_: Some(path(trait_)),
+        for_: path(for_),
+        items: Vec::new(),
+        negative: false,
+        synthetic: true,
+        blanket_impl: None,
+    })
+}
+
+fn changes(old: &Crate, new: &Crate) -> Vec<(Severity, String)> {
+    let mut changes: Vec<_> = diff(&Api::new(old), &Api::new(new))
+        .into_iter()
+        .map(|change| (change.severity, change.description))
+        .collect();
+    changes.sort();
+    changes
+}
+
+#[test]
+fn removed_function() {
+    let old = krate(
+        &["0:1", "0:2"],
+        vec![item("0:1", Some("foo"), function(false)), item("0:2", Some("bar"), function(false))],
+    );
+    let new = krate(&["0:1"], vec![item("0:1", Some("foo"), function(false))]);
+    assert_eq!(
+        changes(&old, &new),
+        vec![(Severity::Major, "removed function `krate::bar`".to_string())]
+    );
+    assert_eq!(
+        changes(&new, &old),
+        vec![(Severity::Minor, "added function `krate::bar`".to_string())]
+    );
+}
+
+#[test]
+fn const_function() {
+    let old = krate(&["0:1"], vec![item("0:1", Some("foo"), function(false))]);
+    let new = krate(&["0:1"], vec![item("0:1", Some("foo"), function(true))]);
+    assert_eq!(
+        changes(&old, &new),
+        vec![(Severity::Minor, "function `krate::foo` is now `const`".to_string())]
+    );
+    assert_eq!(
+        changes(&new, &old),
+        vec![(Severity::Major, "function `krate::foo` is no longer `const`".to_string())]
+    );
+}
+
+#[test]
+fn async_function() {
+    let async_header = Header { const_: false, unsafe_: false, async_: true, abi: Abi::Rust };
+    let old = krate(&["0:1"], vec![item("0:1", Some("foo"), function_with_header(async_header))]);
+    let new = krate(&["0:1"], vec![item("0:1", Some("foo"), function(false))]);
+    assert_eq!(
+        changes(&old, &new),
+        vec![(Severity::Major, "function `krate::foo` is no longer `async`".to_string())]
+    );
+    assert_eq!(
+        changes(&new, &old),
+        vec![(Severity::Major, "function `krate::foo` is now `async`".to_string())]
+    );
+}
+
+#[test]
+fn added_field() {
+    let field =
+        |id, name| item(id, Some(name), ItemEnum::StructField(Type::Primitive("u8".to_string())));
+
+    // Adding a field to a struct that can be constructed outside of the crate is breaking.
+    let old = krate(
+        &["0:1"],
+        vec![item("0:1", Some("Foo"), plain_struct(false, &["0:2"], &[])), field("0:2", "a")],
+    );
+    let new = krate(
+        &["0:1"],
+        vec![
+            item("0:1", Some("Foo"), plain_struct(false, &["0:2", "0:3"], &[])),
+            field("0:2", "a"),
+            field("0:3", "b"),
+        ],
+    );
+    assert_eq!(
+        changes(&old, &new),
+        vec![(
+            Severity::Major,
+            "added field `krate::Foo::b` to a struct without private fields".to_string()
+        )]
+    );
+
+    // A struct with private fields cannot be.
+    let old = krate(
+        &["0:1"],
+        vec![item("0:1", Some("Foo"), plain_struct(true, &["0:2"], &[])), field("0:2", "a")],
+    );
+    let new = krate(
+        &["0:1"],
+        vec![
+            item("0:1", Some("Foo"), plain_struct(true, &["0:2", "0:3"], &[])),
+            field("0:2", "a"),
+            field("0:3", "b"),
+        ],
+    );
+    assert_eq!(
+        changes(&old, &new),
+        vec![(Severity::Minor, "added field `krate::Foo::b`".to_string())]
+    );
+}
+
+#[test]
+fn lost_auto_trait() {
+    let old = krate(
+        &["0:1"],
+        vec![
+            item("0:1", Some("Foo"), plain_struct(true, &[], &["a:1"])),
+            item("a:1", None, auto_trait_impl(("1:1", "Send"), ("0:1", "Foo"))),
+        ],
+    );
+    let new = krate(&["0:1"], vec![item("0:1", Some("Foo"), plain_struct(true, &[], &[]))]);
+    assert_eq!(
+        changes(&old, &new),
+        vec![(Severity::Major, "`krate::Foo` no longer implements auto trait `Send`".to_string())]
+    );
+}
+
+#[test]
+fn moved_and_reexported() {
+    // Moving an item into a private module and re-exporting it from its old location doesn't
+    // change the API.
+    let old = krate(&["0:1"], vec![item("0:1", Some("foo"), function(false))]);
+    let import = Import {
+        source: "inner::foo".to_string(),
+        name: "foo".to_string(),
+        id: Some(Id("0:1".to_string())),
+        def_path: Some(vec!["krate".to_string(), "inner".to_string(), "foo".to_string()]),
+        glob: false,
+    };
+    let new = krate(
+        &["0:2"],
+        vec![
+            item("0:1", Some("foo"), function(false)),
+            item("0:2", None, ItemEnum::Import(import)),
+        ],
+    );
+    assert_eq!(changes(&old, &new), vec![]);
+}
+
+#[test]
+fn renamed_argument_and_generic_parameter() {
+    // `fn foo<param>(arg: param, y: ty)`
+    let generic_function = |param: &str, arg: &str, ty: Type| {
+        ItemEnum::Function(Function {
+            decl: FnDecl {
+                inputs: vec![
+                    (arg.to_string(), Type::Generic(param.to_string())),
+                    ("y".to_string(), ty),
+                ],
+                output: None,
+                c_variadic: false,
+            },
+            generics: Generics {
+                params: vec![GenericParamDef {
+                    name: param.to_string(),
+                    kind: GenericParamDefKind::Type {
+                        bounds: Vec::new(),
+                        default: None,
+                        synthetic: false,
+                    },
+                }],
+                where_predicates: Vec::new(),
+            },
+            header: Header { const_: false, unsafe_: false, async_: false, abi: Abi::Rust },
+        })
+    };
+    let u8_ = || Type::Primitive("u8".to_string());
+
+    // Renaming an argument or a generic parameter is not a change.
+    let old = krate(&["0:1"], vec![item("0:1", Some("foo"), generic_function("T", "x", u8_()))]);
+    let new = krate(&["0:1"], vec![item("0:1", Some("foo"), generic_function("U", "z", u8_()))]);
+    assert_eq!(changes(&old, &new), vec![]);
+
+    // Using the generic parameter for another argument is.
+    let new = krate(
+        &["0:1"],
+        vec![item("0:1", Some("foo"), generic_function("U", "x", Type::Generic("U".to_string())))],
+    );
+    assert_eq!(
+        changes(&old, &new),
+        vec![(Severity::Major, "changed the declaration of function `krate::foo`".to_string())]
+    );
+}
@@ -0,0 +1,225 @@
+//! The versions in which the items of a crate appeared, for `--previous-release`.
+//!
+//! The previous releases are read from the output of `--output-format json`. Like for
+//! `--api-diff`, items are matched by the paths under which they are public, since the IDs of the
+//! JSON output are not stable between compilations.
+
+use std::cmp::Ordering;
+use std::path::PathBuf;
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::symbol::Symbol;
+
+use super::diff::{load, Api};
+use crate::clean;
+use crate::formats::cache::Cache;
+
+#[cfg(test)]
+mod tests;
+
+/// The versions in which an item of the documented crate appeared and was deprecated.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+crate struct Availability {
+    /// The first release containing the item.
+    crate since: Option<Symbol>,
+    /// The release since which the item is deprecated, if it is.
+    crate deprecated_since: Option<Symbol>,
+}
+
+/// What the previous releases tell about a public path.
+#[derive(Clone, Debug, PartialEq)]
+struct PathHistory {
+    /// The first release in which the path is public.
+    since: String,
+    /// The first release of the latest run of releases in which the item at this path is
+    /// deprecated, if it is deprecated in the latest release containing it.
+    deprecated_since: Option<String>,
+}
+
+/// The public items of the previous releases of a crate.
+#[derive(Clone, Debug, Default)]
+crate struct ReleaseHistory {
+    paths: FxHashMap<String, PathHistory>,
+}
+
+impl ReleaseHistory {
+    /// Reads the JSON output of previous releases, which can be given in any order. Every file must
+    /// have been generated with `--crate-version`, and with the current `FORMAT_VERSION`: older
+    /// releases have to be documented again after the format changes.
+    crate fn load(paths: &[PathBuf]) -> Result<ReleaseHistory, String> {
+        let mut releases = paths
+            .iter()
+            .map(|path| {
+                let krate = load(path)?;
+                match &krate.crate_version {
+                    Some(version) => Ok((version.clone(), krate)),
+                    None => Err(format!(
+                        "`{}` has no crate version, it must be generated with `--crate-version`",
+                        path.display()
+                    )),
+                }
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        releases.sort_by(|(a, _), (b, _)| compare_versions(a, b));
+
+        let mut history = ReleaseHistory::default();
+        for (version, krate) in &releases {
+            history.add_release(version, &Api::new(krate));
+        }
+        Ok(history)
+    }
+
+    /// Adds a release, which must be more recent than the ones that were already added.
+    fn add_release(&mut self, version: &str, api: &Api<'_>) {
+        for (path, entry) in &api.entries {
+            // Trait implementations don't have pages of their own.
+            if entry.implementation.is_some() {
+                continue;
+            }
+            let history = self.paths.entry(path.clone()).or_insert_with(|| PathHistory {
+                since: version.to_string(),
+                deprecated_since: None,
+            });
+            if entry.item.deprecation.is_none() {
+                history.deprecated_since = None;
+            } else if history.deprecated_since.is_none() {
+                history.deprecated_since = Some(version.to_string());
+            }
+        }
+    }
+
+    /// Computes the availability of the public items of `krate`. Items that aren't in any previous
+    /// release appeared in the version being documented, if it was given with `--crate-version`.
+    crate fn availability(
+        &self,
+        krate: &clean::Crate,
+        cache: &Cache,
+        tcx: TyCtxt<'_>,
+    ) -> FxHashMap<DefId, Availability> {
+        let mut collector = AvailabilityCollector {
+            tcx,
+            cache,
+            history: self,
+            version: cache.crate_version.as_deref().map(Symbol::intern),
+            availability: FxHashMap::default(),
+        };
+        if let clean::ModuleItem(module) = &*krate.module.kind {
+            let crate_name = krate.name(tcx);
+            collector.visit_items(&module.items, crate_name.as_str());
+        }
+        collector.availability
+    }
+}
+
+/// Compares two versions like `1.10.0` and `1.9.0` component by component. Pre-releases like
+/// `1.0.0-beta.1` come before the corresponding release.
+crate fn compare_versions(a: &str, b: &str) -> Ordering {
+    fn components(version: &str) -> impl Iterator<Item = (u64, &str)> {
+        version.split('.').map(|component| {
+            let digits = component.find(|c: char| !c.is_ascii_digit()).unwrap_or(component.len());
+            (component[..digits].parse().unwrap_or(0), &component[digits..])
+        })
+    }
+    let (a, a_pre) = a.split_once('-').map_or((a, None), |(a, pre)| (a, Some(pre)));
+    let (b, b_pre) = b.split_once('-').map_or((b, None), |(b, pre)| (b, Some(pre)));
+    components(a).cmp(components(b)).then_with(|| match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a_pre), Some(b_pre)) => components(a_pre).cmp(components(b_pre)),
+    })
+}
+
+/// Walks the public items of a crate along with their paths, the way [`Api`] does for the JSON
+/// output.
+struct AvailabilityCollector<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    cache: &'a Cache,
+    history: &'a ReleaseHistory,
+    version: Option<Symbol>,
+    availability: FxHashMap<DefId, Availability>,
+}
+
+impl AvailabilityCollector<'_, '_> {
+    fn visit_items(&mut self, items: &[clean::Item], parent: &str) {
+        for item in items {
+            if self.cache.document_private && !item.visibility.is_public() {
+                continue;
+            }
+            self.visit_item(item, parent);
+        }
+    }
+
+    fn visit_public_members(&mut self, items: &[clean::Item], parent: &str) {
+        for item in items.iter().filter(|item| item.visibility.is_public()) {
+            self.visit_item(item, parent);
+        }
+    }
+
+    fn visit_item(&mut self, item: &clean::Item, parent: &str) {
+        let (Some(name), Some(def_id)) = (item.name, item.def_id.as_def_id()) else { return };
+        let path = format!("{}::{}", parent, name);
+        match &*item.kind {
+            clean::StrippedItem(..) | clean::ImportItem(..) | clean::ImplItem(..) => return,
+            clean::ModuleItem(module) => self.visit_items(&module.items, &path),
+            clean::StructItem(clean::Struct { fields, .. })
+            | clean::UnionItem(clean::Union { fields, .. }) => {
+                self.visit_public_members(fields, &path);
+                self.visit_inherent_impls(def_id, &path);
+            }
+            clean::EnumItem(enum_) => {
+                for variant in &enum_.variants {
+                    self.visit_item(variant, &path);
+                }
+                self.visit_inherent_impls(def_id, &path);
+            }
+            clean::VariantItem(clean::Variant::Struct(variant)) => {
+                for field in &variant.fields {
+                    self.visit_item(field, &path);
+                }
+            }
+            clean::TraitItem(trait_) => {
+                for trait_item in &trait_.items {
+                    self.visit_item(trait_item, &path);
+                }
+            }
+            _ => {}
+        }
+        self.record(item, def_id, &path);
+    }
+
+    fn visit_inherent_impls(&mut self, def_id: DefId, path: &str) {
+        let cache = self.cache;
+        for impl_ in cache.impls.get(&def_id).into_iter().flatten() {
+            if impl_.inner_impl().trait_.is_none() {
+                self.visit_public_members(&impl_.inner_impl().items, path);
+            }
+        }
+    }
+
+    fn record(&mut self, item: &clean::Item, def_id: DefId, path: &str) {
+        let history = self.history.paths.get(path);
+        let since = history.map(|history| Symbol::intern(&history.since)).or(self.version);
+        let deprecated_since = if item.deprecation(self.tcx).is_some() {
+            history
+                .and_then(|history| history.deprecated_since.as_deref())
+                .map(Symbol::intern)
+                .or(self.version)
+        } else {
+            None
+        };
+        // An item can be public under several paths, in which case the oldest one wins.
+        let older = |a: Option<Symbol>, b: Option<Symbol>| match (a, b) {
+            (Some(a), Some(b)) => {
+                Some(if compare_versions(a.as_str(), b.as_str()).is_le() { a } else { b })
+            }
+            (a, b) => a.or(b),
+        };
+        let availability =
+            self.availability.entry(def_id).or_insert(Availability { since, deprecated_since });
+        availability.since = older(availability.since, since);
+        availability.deprecated_since = older(availability.deprecated_since, deprecated_since);
+    }
+}
@@ -0,0 +1,87 @@
+// The maps of `rustdoc_json_types::Crate` and `Item` are the default `HashMap`.
+#![allow(rustc::default_hash_types)]
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use rustdoc_json_types::{
+    Abi, Crate, Deprecation, FnDecl, Function, Generics, Header, Id, Item, ItemEnum, Module,
+    Visibility, FORMAT_VERSION,
+};
+
+use super::{compare_versions, Api, PathHistory, ReleaseHistory};
+
+fn function(id: &str, name: &str, deprecated: bool) -> Item {
+    Item {
+        id: Id(id.to_string()),
+        crate_id: 0,
+        name: Some(name.to_string()),
+        span: None,
+        visibility: Visibility::Public,
+        docs: None,
+        links: HashMap::new(),
+        attrs: Vec::new(),
+        deprecation: deprecated.then(|| Deprecation { since: None, note: None }),
+        available_since: None,
+        inner: ItemEnum::Function(Function {
+            decl: FnDecl { inputs: Vec::new(), output: None, c_variadic: false },
+            generics: Generics::default(),
+            header: Header { const_: false, unsafe_: false, async_: false, abi: Abi::Rust },
+        }),
+    }
+}
+
+/// Returns a release of a crate named `krate` whose root module contains `items`.
+fn release(version: &str, items: Vec<Item>) -> Crate {
+    let module =
+        Module { is_crate: true, items: items.iter().map(|item| item.id.clone()).collect() };
+    let mut root = function("0:0", "krate", false);
+    root.inner = ItemEnum::Module(module);
+    let mut index: HashMap<_, _> = items.into_iter().map(|item| (item.id.clone(), item)).collect();
+    index.insert(root.id.clone(), root);
+    Crate {
+        root: Id("0:0".to_string()),
+        crate_version: Some(version.to_string()),
+        includes_private: false,
+        index,
+        paths: HashMap::new(),
+        external_crates: HashMap::new(),
+        format_version: FORMAT_VERSION,
+    }
+}
+
+fn history(path: &str, since: &str, deprecated_since: Option<&str>) -> (String, PathHistory) {
+    let deprecated_since = deprecated_since.map(|version| version.to_string());
+    (path.to_string(), PathHistory { since: since.to_string(), deprecated_since })
+}
+
+#[test]
+fn test_compare_versions() {
+    assert_eq!(compare_versions("1.9.0", "1.10.0"), Ordering::Less);
+    assert_eq!(compare_versions("0.2.1", "0.2.1"), Ordering::Equal);
+    assert_eq!(compare_versions("2.0.0", "1.99.99"), Ordering::Greater);
+    assert_eq!(compare_versions("1.0.0-beta.2", "1.0.0"), Ordering::Less);
+    assert_eq!(compare_versions("1.0.0-beta.2", "1.0.0-beta.10"), Ordering::Less);
+    assert_eq!(compare_versions("1.0.0-alpha", "1.0.0-beta"), Ordering::Less);
+}
+
+#[test]
+fn test_release_history() {
+    let releases = [
+        release("0.1.0", vec![function("0:1", "foo", false)]),
+        release("0.2.0", vec![function("0:1", "foo", true), function("0:2", "bar", false)]),
+        release("0.3.0", vec![function("0:1", "foo", true), function("0:2", "bar", true)]),
+        // Deprecations can be reverted.
+        release("0.4.0", vec![function("0:1", "foo", true), function("0:2", "bar", false)]),
+    ];
+    let mut release_history = ReleaseHistory::default();
+    for krate in &releases {
+        release_history.add_release(krate.crate_version.as_ref().unwrap(), &Api::new(krate));
+    }
+    assert_eq!(
+        release_history.paths,
+        [history("krate::foo", "0.1.0", Some("0.2.0")), history("krate::bar", "0.2.0", None)]
+            .into_iter()
+            .collect()
+    );
+}
@@ -5,6 +5,8 @@
 //! docs for usage and details.
 
 mod conversions;
+crate mod diff;
+crate mod history;
 
 use std::cell::RefCell;
 use std::fs::{create_dir_all, File};
@@ -96,6 +98,35 @@ fn get_impls(&mut self, id: DefId) -> Vec<types::Id> {
             .unwrap_or_default()
     }
 
+    /// Returns the impls synthesized by rustdoc for a type whose ID matches `kind`. They are also
+    /// part of the impls returned by [`get_impls`](Self::get_impls), which adds them to the index.
+    fn get_synthetic_impls(
+        &self,
+        id: DefId,
+        kind: impl Fn(clean::ItemId) -> bool,
+    ) -> Vec<types::Id> {
+        self.cache
+            .impls
+            .get(&id)
+            .into_iter()
+            .flatten()
+            .map(|i| i.impl_item.def_id)
+            .filter(|&id| id.is_local() && kind(id))
+            .map(from_item_id)
+            .collect()
+    }
+
+    /// Computes the layout of a type, which is only possible if it doesn't depend on the generic
+    /// parameters of the type, like for `struct W<T>(u8, PhantomData<T>)`.
+    fn get_layout(&self, id: DefId) -> Option<types::Layout> {
+        let param_env = self.tcx.param_env(id);
+        let layout = self.tcx.layout_of(param_env.and(self.tcx.type_of(id))).ok()?.layout;
+        Some(types::Layout {
+            size: if layout.abi().is_unsized() { None } else { Some(layout.size().bytes()) },
+            align: layout.align().abi.bytes(),
+        })
+    }
+
     fn get_trait_items(&mut self) -> Vec<(types::Id, types::Item)> {
         Rc::clone(&self.cache)
             .traits
@@ -130,6 +161,7 @@ fn get_trait_items(&mut self) -> Vec<(types::Id, types::Item)> {
                             links: Default::default(),
                             attrs: Default::default(),
                             deprecation: Default::default(),
+                            available_since: Default::default(),
                         },
                     ))
                 } else {
@@ -178,14 +210,41 @@ fn item(&mut self, item: clean::Item) -> Result<(), Error> {
 
         let id = item.def_id;
         if let Some(mut new_item) = self.convert_item(item) {
-            if let types::ItemEnum::Trait(ref mut t) = new_item.inner {
-                t.implementations = self.get_trait_implementors(id.expect_def_id())
-            } else if let types::ItemEnum::Struct(ref mut s) = new_item.inner {
-                s.impls = self.get_impls(id.expect_def_id())
-            } else if let types::ItemEnum::Enum(ref mut e) = new_item.inner {
-                e.impls = self.get_impls(id.expect_def_id())
-            } else if let types::ItemEnum::Union(ref mut u) = new_item.inner {
-                u.impls = self.get_impls(id.expect_def_id())
+            match new_item.inner {
+                types::ItemEnum::Trait(ref mut t) => {
+                    t.implementations = self.get_trait_implementors(id.expect_def_id())
+                }
+                types::ItemEnum::Struct(types::Struct {
+                    ref mut impls,
+                    ref mut auto_trait_impls,
+                    ref mut blanket_impls,
+                    ref mut layout,
+                    ..
+                })
+                | types::ItemEnum::Enum(types::Enum {
+                    ref mut impls,
+                    ref mut auto_trait_impls,
+                    ref mut blanket_impls,
+                    ref mut layout,
+                    ..
+                })
+                | types::ItemEnum::Union(types::Union {
+                    ref mut impls,
+                    ref mut auto_trait_impls,
+                    ref mut blanket_impls,
+                    ref mut layout,
+                    ..
+                }) => {
+                    let def_id = id.expect_def_id();
+                    *impls = self.get_impls(def_id);
+                    *auto_trait_impls = self
+                        .get_synthetic_impls(def_id, |id| matches!(id, clean::ItemId::Auto { .. }));
+                    *blanket_impls = self.get_synthetic_impls(def_id, |id| {
+                        matches!(id, clean::ItemId::Blanket { .. })
+                    });
+                    *layout = self.get_layout(def_id);
+                }
+                _ => {}
             }
             let removed = self.index.borrow_mut().insert(from_item_id(id), new_item.clone());
 
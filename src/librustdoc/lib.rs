@@ -126,6 +126,7 @@ macro_rules! map {
 mod markdown;
 mod passes;
 mod scrape_examples;
+mod text;
 mod theme;
 mod visit;
 mod visit_ast;
@@ -495,6 +496,14 @@ fn opts() -> Vec<RustcOptGroup> {
         unstable("enable-index-page", |o| {
             o.optflagmulti("", "enable-index-page", "To enable generation of the index page")
         }),
+        unstable("workspace-index", |o| {
+            o.optflag(
+                "",
+                "workspace-index",
+                "Generate a landing page listing all the documented crates and their dependency \
+                 graph",
+            )
+        }),
         unstable("static-root-path", |o| {
             o.optopt(
                 "",
@@ -504,6 +513,14 @@ fn opts() -> Vec<RustcOptGroup> {
                 "PATH",
             )
         }),
+        unstable("offline-bundle", |o| {
+            o.optopt(
+                "",
+                "offline-bundle",
+                "Write the output directory to a zip archive which can be browsed offline",
+                "PATH",
+            )
+        }),
         unstable("disable-per-crate-search", |o| {
             o.optflagmulti(
                 "",
@@ -577,6 +594,21 @@ fn opts() -> Vec<RustcOptGroup> {
         unstable("nocapture", |o| {
             o.optflag("", "nocapture", "Don't capture stdout and stderr of tests")
         }),
+        unstable("merge-doctests", |o| {
+            o.optflag(
+                "",
+                "merge-doctests",
+                "Compile compatible doctests into a single binary instead of one binary each",
+            )
+        }),
+        unstable("doctest-coverage-dir", |o| {
+            o.optopt(
+                "",
+                "doctest-coverage-dir",
+                "Keep doctest binaries and the coverage profiles of their runs in this directory",
+                "PATH",
+            )
+        }),
         unstable("generate-link-to-definition", |o| {
             o.optflag(
                 "",
@@ -611,6 +643,24 @@ fn opts() -> Vec<RustcOptGroup> {
                 "path to function call information (for displaying examples in the documentation)",
             )
         }),
+        unstable("previous-release", |o| {
+            o.optmulti(
+                "",
+                "previous-release",
+                "JSON output of a previous release of the crate, used to show the version in which \
+                each item appeared",
+                "PATH",
+            )
+        }),
+        unstable("api-diff", |o| {
+            o.optopt(
+                "",
+                "api-diff",
+                "compare the public API of the crate described by the JSON input to an older \
+                version of it, and report the changes",
+                "PATH",
+            )
+        }),
         // deprecated / removed options
         stable("plugin-path", |o| {
             o.optmulti(
@@ -738,9 +788,13 @@ fn run_renderer<'tcx, T: formats::FormatRenderer<'tcx>>(
     }
 }
 
-fn main_options(options: config::Options) -> MainResult {
+fn main_options(mut options: config::Options) -> MainResult {
     let diag = core::new_handler(options.error_format, None, &options.debugging_opts);
 
+    if let Some(old) = &options.api_diff {
+        return wrap_return(&diag, json::diff::run(old, &options.input, options.output_format));
+    }
+
     match (options.should_test, options.markdown_input()) {
         (true, true) => return wrap_return(&diag, markdown::test(options)),
         (true, false) => return doctest::run(options),
@@ -766,6 +820,7 @@ fn main_options(options: config::Options) -> MainResult {
     // then generated from the cleaned AST of the crate. This runs all the
     // plug/cleaning passes.
     let crate_version = options.crate_version.clone();
+    let release_history = options.release_history.take();
 
     let output_format = options.output_format;
     // FIXME: fix this clone (especially render_options)
@@ -827,6 +882,9 @@ fn main_options(options: config::Options) -> MainResult {
                 }
 
                 cache.crate_version = crate_version;
+                if let Some(release_history) = release_history {
+                    cache.availability = release_history.availability(&krate, &cache, tcx);
+                }
 
                 if show_coverage {
                     // if we ran coverage, bail early, we don't need to also generate docs at this point
@@ -845,6 +903,22 @@ fn main_options(options: config::Options) -> MainResult {
                     config::OutputFormat::Json => sess.time("render_json", || {
                         run_renderer::<json::JsonRenderer<'_>>(krate, render_opts, cache, tcx)
                     }),
+                    config::OutputFormat::Markdown => sess.time("render_markdown", || {
+                        run_renderer::<text::TextRenderer<'_, text::Markdown>>(
+                            krate,
+                            render_opts,
+                            cache,
+                            tcx,
+                        )
+                    }),
+                    config::OutputFormat::Man => sess.time("render_man", || {
+                        run_renderer::<text::TextRenderer<'_, text::Man>>(
+                            krate,
+                            render_opts,
+                            cache,
+                            tcx,
+                        )
+                    }),
                 }
             })
         })
@@ -169,6 +169,17 @@ macro_rules! declare_rustdoc_lint {
    "codeblock could not be parsed as valid Rust or is empty"
 }
 
+declare_rustdoc_lint! {
+    /// The `broken_relative_links` lint detects relative links pointing to
+    /// files or anchors which don't exist in the generated documentation. This
+    /// is a `rustdoc` only lint, see the documentation in the [rustdoc book].
+    ///
+    /// [rustdoc book]: ../../../rustdoc/lints.html#broken_relative_links
+    BROKEN_RELATIVE_LINKS,
+    Allow,
+    "detects relative links to files or anchors missing from the generated documentation"
+}
+
 crate static RUSTDOC_LINTS: Lazy<Vec<&'static Lint>> = Lazy::new(|| {
     vec![
         BROKEN_INTRA_DOC_LINKS,
@@ -180,6 +191,7 @@ macro_rules! declare_rustdoc_lint {
         INVALID_HTML_TAGS,
         BARE_URLS,
         MISSING_CRATE_LEVEL_DOCS,
+        BROKEN_RELATIVE_LINKS,
     ]
 });
 
@@ -0,0 +1,589 @@
+//! Plain-text rendering of declarations, for the Markdown and man page backends.
+//!
+//! This mirrors what `html::format` prints in alternate (`{:#}`) mode, but without needing an
+//! HTML rendering context, since the text backends have no links inside code.
+
+use rustc_hir as hir;
+use rustc_hir::def::CtorKind;
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_span::hygiene::MacroKind;
+use rustc_target::spec::abi::Abi;
+
+use crate::clean::{self, SelfTy};
+use crate::html::format::{print_abi_with_space, print_constness_with_space, PrintWithSpace};
+
+fn comma_sep(items: impl Iterator<Item = String>) -> String {
+    items.collect::<Vec<_>>().join(", ")
+}
+
+crate fn print_lifetime(lifetime: &clean::Lifetime) -> String {
+    lifetime.0.to_string()
+}
+
+crate fn print_type(ty: &clean::Type, tcx: TyCtxt<'_>) -> String {
+    match ty {
+        clean::Type::Path { path } => print_path(path, path.is_assoc_ty(), tcx),
+        clean::Type::DynTrait(bounds, lifetime) => {
+            let mut out = format!(
+                "dyn {}",
+                bounds.iter().map(|b| print_poly_trait(b, tcx)).collect::<Vec<_>>().join(" + ")
+            );
+            if let Some(lifetime) = lifetime {
+                out.push_str(" + ");
+                out.push_str(&print_lifetime(lifetime));
+            }
+            out
+        }
+        clean::Type::Generic(name) => name.to_string(),
+        clean::Type::Primitive(prim) => prim.as_sym().to_string(),
+        clean::Type::BareFunction(decl) => {
+            let mut out = String::new();
+            if !decl.generic_params.is_empty() {
+                let params = decl.generic_params.iter().map(|p| print_generic_param(p, tcx));
+                out.push_str(&format!("for<{}> ", comma_sep(params)));
+            }
+            format!(
+                "{}{}{:#}fn{}",
+                out,
+                decl.unsafety.print_with_space(),
+                print_abi_with_space(decl.abi),
+                print_fn_decl(&decl.decl, tcx)
+            )
+        }
+        clean::Type::Tuple(types) if types.len() == 1 => {
+            format!("({},)", print_type(&types[0], tcx))
+        }
+        clean::Type::Tuple(types) => {
+            format!("({})", comma_sep(types.iter().map(|ty| print_type(ty, tcx))))
+        }
+        clean::Type::Slice(ty) => format!("[{}]", print_type(ty, tcx)),
+        clean::Type::Array(ty, len) => format!("[{}; {}]", print_type(ty, tcx), len),
+        clean::Type::RawPointer(mutability, ty) => {
+            let mutability = match mutability {
+                hir::Mutability::Mut => "mut",
+                hir::Mutability::Not => "const",
+            };
+            format!("*{} {}", mutability, print_type(ty, tcx))
+        }
+        clean::Type::BorrowedRef { lifetime, mutability, type_ } => {
+            let lifetime = match lifetime {
+                Some(lifetime) => format!("{} ", print_lifetime(lifetime)),
+                None => String::new(),
+            };
+            let inner = match &**type_ {
+                clean::Type::DynTrait(bounds, trait_lifetime)
+                    if bounds.len() > 1 || trait_lifetime.is_some() =>
+                {
+                    format!("({})", print_type(type_, tcx))
+                }
+                _ => print_type(type_, tcx),
+            };
+            format!("&{}{}{}", lifetime, mutability.print_with_space(), inner)
+        }
+        clean::Type::ImplTrait(bounds) => format!("impl {}", print_generic_bounds(bounds, tcx)),
+        clean::Type::QPath { assoc, self_type, trait_, self_def_id } => {
+            let should_show_cast = !trait_.segments.is_empty()
+                && self_def_id
+                    .zip(Some(trait_.def_id()))
+                    .map_or(!self_type.is_self_type(), |(id, trait_)| id != trait_);
+            let self_type = if should_show_cast {
+                format!("<{} as {}>", print_type(self_type, tcx), print_path(trait_, false, tcx))
+            } else {
+                print_type(self_type, tcx)
+            };
+            format!("{}::{}{}", self_type, assoc.name, print_generic_args(&assoc.args, tcx))
+        }
+        clean::Type::Infer => "_".to_string(),
+    }
+}
+
+/// Prints a path as its last segment, or as all of its segments for paths to associated types
+/// like `T::Output`.
+fn print_path(path: &clean::Path, print_all: bool, tcx: TyCtxt<'_>) -> String {
+    let segments =
+        if print_all { &path.segments[..] } else { &path.segments[path.segments.len() - 1..] };
+    segments
+        .iter()
+        .map(|segment| format!("{}{}", segment.name, print_generic_args(&segment.args, tcx)))
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+fn print_generic_args(args: &clean::GenericArgs, tcx: TyCtxt<'_>) -> String {
+    match args {
+        clean::GenericArgs::AngleBracketed { args, bindings } => {
+            let args = args.iter().map(|arg| match arg {
+                clean::GenericArg::Lifetime(lifetime) => print_lifetime(lifetime),
+                clean::GenericArg::Type(ty) => print_type(ty, tcx),
+                clean::GenericArg::Const(constant) => constant.expr(tcx),
+                clean::GenericArg::Infer => "_".to_string(),
+            });
+            let bindings = bindings.iter().map(|binding| {
+                let name = format!(
+                    "{}{}",
+                    binding.assoc.name,
+                    print_generic_args(&binding.assoc.args, tcx)
+                );
+                match &binding.kind {
+                    clean::TypeBindingKind::Equality { term } => {
+                        format!("{} = {}", name, print_term(term, tcx))
+                    }
+                    clean::TypeBindingKind::Constraint { bounds } if bounds.is_empty() => name,
+                    clean::TypeBindingKind::Constraint { bounds } => {
+                        format!("{}: {}", name, print_generic_bounds(bounds, tcx))
+                    }
+                }
+            });
+            let args: Vec<_> = args.chain(bindings).collect();
+            if args.is_empty() { String::new() } else { format!("<{}>", args.join(", ")) }
+        }
+        clean::GenericArgs::Parenthesized { inputs, output } => {
+            let mut out = format!("({})", comma_sep(inputs.iter().map(|ty| print_type(ty, tcx))));
+            if let Some(output) = output {
+                out.push_str(" -> ");
+                out.push_str(&print_type(output, tcx));
+            }
+            out
+        }
+    }
+}
+
+fn print_term(term: &clean::Term, tcx: TyCtxt<'_>) -> String {
+    match term {
+        clean::Term::Type(ty) => print_type(ty, tcx),
+        clean::Term::Constant(constant) => constant.expr(tcx),
+    }
+}
+
+fn print_poly_trait(poly_trait: &clean::PolyTrait, tcx: TyCtxt<'_>) -> String {
+    let mut out = String::new();
+    if !poly_trait.generic_params.is_empty() {
+        let params = poly_trait.generic_params.iter().map(|p| print_generic_param(p, tcx));
+        out.push_str(&format!("for<{}> ", comma_sep(params)));
+    }
+    out.push_str(&print_path(&poly_trait.trait_, false, tcx));
+    out
+}
+
+crate fn print_generic_bounds(bounds: &[clean::GenericBound], tcx: TyCtxt<'_>) -> String {
+    bounds
+        .iter()
+        .map(|bound| match bound {
+            clean::GenericBound::TraitBound(poly_trait, modifier) => {
+                let modifier = match modifier {
+                    hir::TraitBoundModifier::None => "",
+                    hir::TraitBoundModifier::Maybe => "?",
+                    hir::TraitBoundModifier::MaybeConst => "~const ",
+                };
+                format!("{}{}", modifier, print_poly_trait(poly_trait, tcx))
+            }
+            clean::GenericBound::Outlives(lifetime) => print_lifetime(lifetime),
+        })
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+fn print_generic_param(param: &clean::GenericParamDef, tcx: TyCtxt<'_>) -> String {
+    match &param.kind {
+        clean::GenericParamDefKind::Lifetime { outlives } if outlives.is_empty() => {
+            param.name.to_string()
+        }
+        clean::GenericParamDefKind::Lifetime { outlives } => format!(
+            "{}: {}",
+            param.name,
+            outlives.iter().map(print_lifetime).collect::<Vec<_>>().join(" + ")
+        ),
+        clean::GenericParamDefKind::Type { bounds, default, .. } => {
+            let mut out = param.name.to_string();
+            if !bounds.is_empty() {
+                out.push_str(": ");
+                out.push_str(&print_generic_bounds(bounds, tcx));
+            }
+            if let Some(default) = default {
+                out.push_str(" = ");
+                out.push_str(&print_type(default, tcx));
+            }
+            out
+        }
+        clean::GenericParamDefKind::Const { ty, default, .. } => {
+            let mut out = format!("const {}: {}", param.name, print_type(ty, tcx));
+            if let Some(default) = default {
+                out.push_str(" = ");
+                out.push_str(default);
+            }
+            out
+        }
+    }
+}
+
+/// Prints the generic parameters of an item, like `<'a, T: Clone>`, leaving out the synthetic
+/// parameters of `impl Trait` arguments.
+crate fn print_generic_params(generics: &clean::Generics, tcx: TyCtxt<'_>) -> String {
+    let params: Vec<_> = generics
+        .params
+        .iter()
+        .filter(|param| !param.is_synthetic_type_param())
+        .map(|param| print_generic_param(param, tcx))
+        .collect();
+    if params.is_empty() { String::new() } else { format!("<{}>", params.join(", ")) }
+}
+
+/// Prints the where clause of an item on its own lines, or nothing if it has none.
+crate fn print_where_clause(generics: &clean::Generics, tcx: TyCtxt<'_>) -> String {
+    if generics.where_predicates.is_empty() {
+        return String::new();
+    }
+    let predicates = generics.where_predicates.iter().map(|predicate| match predicate {
+        clean::WherePredicate::BoundPredicate { ty, bounds, bound_params } => {
+            let mut out = String::new();
+            if !bound_params.is_empty() {
+                let params = bound_params.iter().map(print_lifetime);
+                out.push_str(&format!("for<{}> ", comma_sep(params)));
+            }
+            out.push_str(&print_type(ty, tcx));
+            out.push(':');
+            if !bounds.is_empty() {
+                out.push(' ');
+                out.push_str(&print_generic_bounds(bounds, tcx));
+            }
+            out
+        }
+        clean::WherePredicate::RegionPredicate { lifetime, bounds } => {
+            format!("{}: {}", print_lifetime(lifetime), print_generic_bounds(bounds, tcx))
+        }
+        clean::WherePredicate::EqPredicate { lhs, rhs } => {
+            format!("{} == {}", print_type(lhs, tcx), print_term(rhs, tcx))
+        }
+    });
+    predicates
+        .map(|predicate| format!("\n    {},", predicate))
+        .fold("\nwhere".to_string(), |a, b| a + &b)
+}
+
+crate fn print_fn_decl(decl: &clean::FnDecl, tcx: TyCtxt<'_>) -> String {
+    let mut args: Vec<_> = decl
+        .inputs
+        .values
+        .iter()
+        .map(|arg| match arg.to_self() {
+            Some(SelfTy::SelfValue) => "self".to_string(),
+            Some(SelfTy::SelfBorrowed(lifetime, mutability)) => format!(
+                "&{}{}self",
+                lifetime.map(|lt| format!("{} ", print_lifetime(&lt))).unwrap_or_default(),
+                mutability.print_with_space()
+            ),
+            Some(SelfTy::SelfExplicit(ty)) => format!("self: {}", print_type(&ty, tcx)),
+            None => format!("{}: {}", arg.name, print_type(&arg.type_, tcx)),
+        })
+        .collect();
+    if decl.c_variadic {
+        args.push("...".to_string());
+    }
+    let mut out = format!("({})", args.join(", "));
+    if let clean::FnRetTy::Return(ty) = &decl.output {
+        out.push_str(" -> ");
+        out.push_str(&print_type(ty, tcx));
+    }
+    out
+}
+
+fn print_visibility(item: &clean::Item, tcx: TyCtxt<'_>) -> String {
+    match item.def_id.as_def_id() {
+        Some(def_id) => item.visibility.to_src_with_space(tcx, def_id).to_string(),
+        None if item.visibility.is_public() => "pub ".to_string(),
+        None => String::new(),
+    }
+}
+
+fn print_function(
+    item: &clean::Item,
+    function: &clean::Function,
+    visibility: bool,
+    tcx: TyCtxt<'_>,
+) -> String {
+    let header = item.fn_header(tcx).unwrap_or(hir::FnHeader {
+        unsafety: hir::Unsafety::Normal,
+        constness: hir::Constness::NotConst,
+        asyncness: hir::IsAsync::NotAsync,
+        abi: Abi::Rust,
+    });
+    let decl = match header.asyncness {
+        hir::IsAsync::Async => clean::FnDecl {
+            output: function.decl.sugared_async_return_type(),
+            ..function.decl.clone()
+        },
+        hir::IsAsync::NotAsync => function.decl.clone(),
+    };
+    format!(
+        "{}{}{}{}{:#}fn {}{}{}{}",
+        if visibility { print_visibility(item, tcx) } else { String::new() },
+        print_constness_with_space(&header.constness, item.const_stability(tcx)),
+        header.asyncness.print_with_space(),
+        header.unsafety.print_with_space(),
+        print_abi_with_space(header.abi),
+        item.name.unwrap(),
+        print_generic_params(&function.generics, tcx),
+        print_fn_decl(&decl, tcx),
+        print_where_clause(&function.generics, tcx)
+    )
+}
+
+/// Prints the header of an implementation, like `impl<T: Clone> Clone for Foo<T>`.
+crate fn print_impl(impl_: &clean::Impl, tcx: TyCtxt<'_>) -> String {
+    let mut out = format!(
+        "{}impl{} ",
+        impl_.unsafety.print_with_space(),
+        print_generic_params(&impl_.generics, tcx)
+    );
+    if let Some(trait_) = &impl_.trait_ {
+        if impl_.polarity == ty::ImplPolarity::Negative {
+            out.push('!');
+        }
+        out.push_str(&print_path(trait_, false, tcx));
+        out.push_str(" for ");
+    }
+    match impl_.kind.as_blanket_ty() {
+        Some(ty) => out.push_str(&print_type(ty, tcx)),
+        None => out.push_str(&print_type(&impl_.for_, tcx)),
+    }
+    out.push_str(&print_where_clause(&impl_.generics, tcx));
+    out
+}
+
+fn print_fields(fields: &[clean::Item], stripped: bool, tcx: TyCtxt<'_>) -> String {
+    let mut out = String::new();
+    for field in fields {
+        if let clean::StructFieldItem(ty) = &*field.kind {
+            out.push_str(&format!(
+                "    {}{}: {},\n",
+                print_visibility(field, tcx),
+                field.name.unwrap(),
+                print_type(ty, tcx)
+            ));
+        }
+    }
+    if stripped || fields.iter().any(|field| field.is_stripped()) {
+        out.push_str("    /* private fields */\n");
+    }
+    out
+}
+
+fn print_tuple_fields(fields: &[clean::Item], tcx: TyCtxt<'_>) -> String {
+    comma_sep(fields.iter().map(|field| match &*field.kind {
+        clean::StructFieldItem(ty) => {
+            format!("{}{}", print_visibility(field, tcx), print_type(ty, tcx))
+        }
+        _ => "_".to_string(),
+    }))
+}
+
+/// Prints the declaration of an item, or returns `None` for items without one, like modules.
+crate fn print_declaration(item: &clean::Item, tcx: TyCtxt<'_>) -> Option<String> {
+    let name = item.name?;
+    let visibility = print_visibility(item, tcx);
+    Some(match &*item.kind {
+        clean::FunctionItem(function) | clean::ForeignFunctionItem(function) => {
+            print_function(item, function, true, tcx)
+        }
+        clean::StructItem(struct_) => {
+            let generics = print_generic_params(&struct_.generics, tcx);
+            let where_clause = print_where_clause(&struct_.generics, tcx);
+            match struct_.struct_type {
+                CtorKind::Fictive => format!(
+                    "{}struct {}{}{} {{\n{}}}",
+                    visibility,
+                    name,
+                    generics,
+                    where_clause,
+                    print_fields(&struct_.fields, struct_.fields_stripped, tcx)
+                ),
+                CtorKind::Fn => format!(
+                    "{}struct {}{}({}){};",
+                    visibility,
+                    name,
+                    generics,
+                    print_tuple_fields(&struct_.fields, tcx),
+                    where_clause
+                ),
+                CtorKind::Const => {
+                    format!("{}struct {}{}{};", visibility, name, generics, where_clause)
+                }
+            }
+        }
+        clean::UnionItem(union_) => format!(
+            "{}union {}{}{} {{\n{}}}",
+            visibility,
+            name,
+            print_generic_params(&union_.generics, tcx),
+            print_where_clause(&union_.generics, tcx),
+            print_fields(&union_.fields, union_.fields_stripped, tcx)
+        ),
+        clean::EnumItem(enum_) => {
+            let mut variants = String::new();
+            for variant in &enum_.variants {
+                if let Some(variant) = print_variant(variant, tcx) {
+                    variants.push_str(&format!("    {},\n", variant));
+                }
+            }
+            if enum_.variants_stripped {
+                variants.push_str("    // some variants omitted\n");
+            }
+            format!(
+                "{}enum {}{}{} {{\n{}}}",
+                visibility,
+                name,
+                print_generic_params(&enum_.generics, tcx),
+                print_where_clause(&enum_.generics, tcx),
+                variants
+            )
+        }
+        clean::TypedefItem(typedef) => format!(
+            "{}type {}{}{} = {};",
+            visibility,
+            name,
+            print_generic_params(&typedef.generics, tcx),
+            print_where_clause(&typedef.generics, tcx),
+            print_type(&typedef.type_, tcx)
+        ),
+        clean::OpaqueTyItem(opaque) => format!(
+            "{}type {}{}{} = impl {};",
+            visibility,
+            name,
+            print_generic_params(&opaque.generics, tcx),
+            print_where_clause(&opaque.generics, tcx),
+            print_generic_bounds(&opaque.bounds, tcx)
+        ),
+        clean::StaticItem(static_) | clean::ForeignStaticItem(static_) => format!(
+            "{}static {}{}: {};",
+            visibility,
+            static_.mutability.print_with_space(),
+            name,
+            print_type(&static_.type_, tcx)
+        ),
+        clean::ConstantItem(constant) => {
+            let value = if constant.is_literal(tcx) {
+                constant.expr(tcx)
+            } else {
+                constant.value(tcx).unwrap_or_else(|| constant.expr(tcx))
+            };
+            format!(
+                "{}const {}: {} = {};",
+                visibility,
+                name,
+                print_type(&constant.type_, tcx),
+                value
+            )
+        }
+        clean::TraitItem(trait_) => {
+            let mut out = format!(
+                "{}{}{}trait {}{}",
+                visibility,
+                trait_.unsafety.print_with_space(),
+                if trait_.is_auto { "auto " } else { "" },
+                name,
+                print_generic_params(&trait_.generics, tcx)
+            );
+            if !trait_.bounds.is_empty() {
+                out.push_str(": ");
+                out.push_str(&print_generic_bounds(&trait_.bounds, tcx));
+            }
+            out.push_str(&print_where_clause(&trait_.generics, tcx));
+            out.push_str(" {\n");
+            for trait_item in &trait_.items {
+                if let Some(declaration) = print_assoc_item(trait_item, false, tcx) {
+                    let body = match &*trait_item.kind {
+                        clean::MethodItem(..) => " { ... }",
+                        _ => ";",
+                    };
+                    out.push_str(&format!("    {}{}\n", declaration, body));
+                }
+            }
+            out.push('}');
+            out
+        }
+        clean::TraitAliasItem(alias) => format!(
+            "{}trait {}{}{} = {};",
+            visibility,
+            name,
+            print_generic_params(&alias.generics, tcx),
+            print_where_clause(&alias.generics, tcx),
+            print_generic_bounds(&alias.bounds, tcx)
+        ),
+        clean::ForeignTypeItem => format!("{}type {};", visibility, name),
+        clean::MacroItem(macro_) => macro_.source.clone(),
+        clean::ProcMacroItem(proc_macro) => match proc_macro.kind {
+            MacroKind::Bang => format!("{}!() {{ /* proc-macro */ }}", name),
+            MacroKind::Attr => format!("#[{}]", name),
+            MacroKind::Derive if proc_macro.helpers.is_empty() => {
+                format!("#[derive({})]", name)
+            }
+            MacroKind::Derive => format!(
+                "#[derive({})]\n// helper attributes: {}",
+                name,
+                comma_sep(proc_macro.helpers.iter().map(|helper| format!("#[{}]", helper)))
+            ),
+        },
+        _ => return None,
+    })
+}
+
+fn print_variant(variant: &clean::Item, tcx: TyCtxt<'_>) -> Option<String> {
+    let name = variant.name?;
+    Some(match &*variant.kind {
+        clean::VariantItem(clean::Variant::CLike) => name.to_string(),
+        clean::VariantItem(clean::Variant::Tuple(fields)) => {
+            format!("{}({})", name, print_tuple_fields(fields, tcx))
+        }
+        clean::VariantItem(clean::Variant::Struct(struct_)) => {
+            let fields = struct_.fields.iter().filter_map(|field| match &*field.kind {
+                clean::StructFieldItem(ty) => {
+                    Some(format!("{}: {}", field.name?, print_type(ty, tcx)))
+                }
+                _ => None,
+            });
+            format!("{} {{ {} }}", name, comma_sep(fields))
+        }
+        _ => return None,
+    })
+}
+
+/// Prints an item of a trait or of an implementation, without a body. Items of trait
+/// implementations and of traits have no visibility.
+crate fn print_assoc_item(
+    item: &clean::Item,
+    visibility: bool,
+    tcx: TyCtxt<'_>,
+) -> Option<String> {
+    let name = item.name?;
+    Some(match &*item.kind {
+        clean::TyMethodItem(function) | clean::MethodItem(function, _) => {
+            print_function(item, function, visibility, tcx)
+        }
+        clean::TyAssocConstItem(ty) => format!("const {}: {}", name, print_type(ty, tcx)),
+        clean::AssocConstItem(ty, constant) => {
+            format!("const {}: {} = {}", name, print_type(ty, tcx), constant.expr(tcx))
+        }
+        clean::TyAssocTypeItem(generics, bounds) => {
+            let mut out = format!("type {}{}", name, print_generic_params(generics, tcx));
+            if !bounds.is_empty() {
+                out.push_str(": ");
+                out.push_str(&print_generic_bounds(bounds, tcx));
+            }
+            out.push_str(&print_where_clause(generics, tcx));
+            out
+        }
+        clean::AssocTypeItem(typedef, _) => format!(
+            "type {}{}{} = {}",
+            name,
+            print_generic_params(&typedef.generics, tcx),
+            print_where_clause(&typedef.generics, tcx),
+            print_type(&typedef.type_, tcx)
+        ),
+        clean::StructFieldItem(ty) => {
+            let visibility = if visibility { print_visibility(item, tcx) } else { String::new() };
+            format!("{}{}: {}", visibility, name, print_type(ty, tcx))
+        }
+        clean::VariantItem(_) => print_variant(item, tcx)?,
+        _ => return None,
+    })
+}
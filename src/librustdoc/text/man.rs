@@ -0,0 +1,203 @@
+//! Conversion of documentation Markdown to roff, for man pages.
+//!
+//! Only the small subset of the `man` macro package that every `man` implementation supports is
+//! used: `.SH`/`.SS` for headings, `.PP`/`.IP` for paragraphs and list items, `.RS`/`.RE` for
+//! indentation and `.nf`/`.fi` for code blocks.
+
+use pulldown_cmark::{Event, Parser, Tag};
+
+use crate::html::markdown::main_body_opts;
+
+/// The prefix of the `href` of links to other man pages, followed by the name of the page.
+crate const MAN_LINK_PREFIX: &str = "man:";
+
+/// Accumulates roff source, escaping text so it can't be mistaken for requests.
+#[derive(Default)]
+crate struct Roff {
+    out: String,
+}
+
+impl Roff {
+    crate fn new() -> Roff {
+        Roff::default()
+    }
+
+    crate fn into_string(self) -> String {
+        self.out
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.out.is_empty() || self.out.ends_with('\n')
+    }
+
+    /// Writes a request like `.SH`, on its own line. The arguments are written as they are, so
+    /// any text in them must go through [`escape_arg`] first.
+    crate fn request(&mut self, request: &str, args: &[&str]) {
+        if !self.at_line_start() {
+            self.out.push('\n');
+        }
+        self.out.push_str(request);
+        for arg in args {
+            self.out.push_str(" \"");
+            self.out.push_str(arg);
+            self.out.push('"');
+        }
+        self.out.push('\n');
+    }
+
+    /// Writes running text. Newlines are kept, but leading whitespace on a line is dropped
+    /// unless `verbatim` is set, since roff would otherwise start a new paragraph.
+    crate fn text(&mut self, text: &str, verbatim: bool) {
+        for c in text.chars() {
+            if self.at_line_start() {
+                match c {
+                    ' ' | '\t' if !verbatim => continue,
+                    '.' | '\'' => self.out.push_str("\\&"),
+                    _ => {}
+                }
+            }
+            self.push_escaped(c);
+        }
+    }
+
+    /// Writes a font change like `\fB`, which doesn't count as text.
+    crate fn font(&mut self, font: &str) {
+        self.out.push_str(font);
+    }
+
+    fn push_escaped(&mut self, c: char) {
+        match c {
+            '\\' => self.out.push_str("\\e"),
+            '-' => self.out.push_str("\\-"),
+            _ => self.out.push(c),
+        }
+    }
+}
+
+/// Escapes `text` so it can be used as a quoted argument of a request.
+crate fn escape_arg(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\e"),
+            '-' => out.push_str("\\-"),
+            '"' => out.push_str("\\(dq"),
+            '\n' => out.push(' '),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Converts the Markdown `md` to roff, adding the man pages it links to to `see_also`.
+crate fn render(roff: &mut Roff, md: &str, see_also: &mut Vec<String>) {
+    // The target of each link being rendered, or `None` for links to other man pages.
+    let mut links: Vec<Option<String>> = Vec::new();
+    // The next number of each ordered list being rendered, or `None` for bulleted lists.
+    let mut lists: Vec<Option<u64>> = Vec::new();
+    let mut in_code_block = false;
+    // Whether the next paragraph is the first one of a list item, which needs no request.
+    let mut item_start = false;
+
+    for event in Parser::new_ext(md, main_body_opts()) {
+        match event {
+            Event::Start(Tag::Paragraph) => {
+                if item_start {
+                    item_start = false;
+                } else if lists.is_empty() {
+                    roff.request(".PP", &[]);
+                } else {
+                    roff.request(".IP", &["", "2"]);
+                }
+            }
+            Event::Start(Tag::Heading(..)) => roff.request(".SS", &[]),
+            Event::End(Tag::Heading(..)) => {
+                // `.SS` takes the rest of the line as its title: move the text to it.
+                let title_start = roff.out.rfind(".SS\n").unwrap() + ".SS".len();
+                let title = roff.out.split_off(title_start);
+                roff.out.push(' ');
+                roff.out.push_str(title.trim());
+                roff.out.push('\n');
+            }
+            Event::Start(Tag::BlockQuote) => roff.request(".RS", &["4"]),
+            Event::End(Tag::BlockQuote) => roff.request(".RE", &[]),
+            Event::Start(Tag::CodeBlock(_)) => {
+                item_start = false;
+                roff.request(".RS", &["4"]);
+                roff.request(".nf", &[]);
+                in_code_block = true;
+            }
+            Event::End(Tag::CodeBlock(_)) => {
+                roff.request(".fi", &[]);
+                roff.request(".RE", &[]);
+                in_code_block = false;
+            }
+            Event::Start(Tag::List(start)) => {
+                if !lists.is_empty() {
+                    roff.request(".RS", &["2"]);
+                }
+                lists.push(start);
+            }
+            Event::End(Tag::List(_)) => {
+                lists.pop();
+                if !lists.is_empty() {
+                    roff.request(".RE", &[]);
+                }
+            }
+            Event::Start(Tag::Item) => {
+                match lists.last_mut() {
+                    Some(Some(number)) => {
+                        roff.request(".IP", &[&format!("{}.", number), "4"]);
+                        *number += 1;
+                    }
+                    _ => roff.request(".IP", &["\\(bu", "2"]),
+                }
+                item_start = true;
+            }
+            Event::End(Tag::Item) => item_start = false,
+            Event::Start(Tag::Emphasis) => roff.font("\\fI"),
+            Event::Start(Tag::Strong) => roff.font("\\fB"),
+            Event::End(Tag::Emphasis | Tag::Strong) => roff.font("\\fP"),
+            Event::Start(Tag::Link(_, dest, _)) => match dest.strip_prefix(MAN_LINK_PREFIX) {
+                Some(page) => {
+                    if !see_also.iter().any(|p| p == page) {
+                        see_also.push(page.to_string());
+                    }
+                    roff.font("\\fI");
+                    links.push(None);
+                }
+                None => links.push(Some(dest.to_string())),
+            },
+            Event::End(Tag::Link(..)) => match links.pop() {
+                Some(None) => roff.font("\\fP"),
+                Some(Some(dest)) if !dest.is_empty() && !dest.starts_with('#') => {
+                    roff.text(&format!(" <{}>", dest), false)
+                }
+                _ => {}
+            },
+            Event::Start(Tag::TableRow | Tag::TableHead) if !roff.at_line_start() => {
+                roff.request(".br", &[])
+            }
+            Event::End(Tag::TableRow | Tag::TableHead) => roff.request(".br", &[]),
+            Event::End(Tag::TableCell) => roff.text("\t", true),
+            Event::Text(text) => roff.text(&text, in_code_block),
+            Event::Code(code) => {
+                roff.font("\\fB");
+                roff.text(&code, true);
+                roff.font("\\fP");
+            }
+            Event::SoftBreak => roff.text("\n", false),
+            Event::HardBreak => roff.request(".br", &[]),
+            Event::Rule => roff.request(".sp", &[]),
+            Event::FootnoteReference(name) => roff.text(&format!("[{}]", name), false),
+            Event::TaskListMarker(checked) => {
+                roff.text(if checked { "[x] " } else { "[ ] " }, false)
+            }
+            // Raw HTML has no equivalent in roff.
+            _ => {}
+        }
+    }
+    if !roff.at_line_start() {
+        roff.out.push('\n');
+    }
+}
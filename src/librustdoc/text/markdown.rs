@@ -0,0 +1,204 @@
+//! Rewriting of documentation Markdown for the text backends.
+//!
+//! The HTML backend renders documentation by transforming the stream of pulldown-cmark events.
+//! Since the text backends output Markdown again, they instead edit the source text: every
+//! transformation below records a replacement for a range of the original Markdown, and the
+//! replacements are applied in order at the end.
+
+use std::ops::Range;
+
+use pulldown_cmark::{BrokenLink, CodeBlockKind, Event, LinkType, Parser, Tag};
+
+use crate::clean::RenderedLink;
+use crate::html::markdown::{main_body_opts, map_line, ErrorCodes, LangString};
+
+/// A link being rewritten, with the range of its text once any of it has been seen.
+type PendingLink<'a> = (Range<usize>, &'a RenderedLink, LinkType, Option<Range<usize>>);
+
+/// Rewrites the documentation `md` of an item so it can be embedded in a Markdown page:
+///
+/// * intra-doc links are replaced by inline links to the `href` of the matching `links`, or by
+///   their text if the `href` is empty,
+/// * Rust code blocks lose their hidden (`# `) lines and get an explicit `rust` language,
+/// * headings are moved `heading_offset` levels down, so they nest below the page's own headings.
+crate fn rewrite(md: &str, links: &[RenderedLink], heading_offset: u32) -> String {
+    if md.is_empty() {
+        return String::new();
+    }
+
+    let mut replacer = |broken_link: BrokenLink<'_>| {
+        links
+            .iter()
+            .find(|link| link.original_text.as_str() == &*broken_link.reference)
+            .map(|link| (link.href.as_str().into(), link.new_text.as_str().into()))
+    };
+    let parser = Parser::new_with_broken_link_callback(md, main_body_opts(), Some(&mut replacer));
+
+    let mut edits: Vec<(Range<usize>, String)> = Vec::new();
+    let mut current_link: Option<PendingLink<'_>> = None;
+    let mut code_block: Option<(Range<usize>, bool, String)> = None;
+    let mut heading: Option<(Range<usize>, u32, Option<Range<usize>>)> = None;
+
+    for (event, range) in parser.into_offset_iter() {
+        // Everything between the start and the end of a link or a heading is part of its text.
+        if let Some((link_range, _, _, text)) = &mut current_link {
+            if *link_range != range {
+                extend(text, &range);
+            }
+        }
+        if let Some((heading_range, _, text)) = &mut heading {
+            if *heading_range != range {
+                extend(text, &range);
+            }
+        }
+
+        match event {
+            Event::Start(Tag::Link(kind, dest, _)) if current_link.is_none() => {
+                let link = match kind {
+                    // These were resolved by the broken link callback above.
+                    LinkType::ShortcutUnknown
+                    | LinkType::CollapsedUnknown
+                    | LinkType::ReferenceUnknown => {
+                        links.iter().find(|link| *link.href == *dest && !link.href.is_empty())
+                    }
+                    _ => links.iter().find(|link| *link.original_text == *dest),
+                };
+                if let Some(link) = link {
+                    current_link = Some((range, link, kind, None));
+                }
+                continue;
+            }
+            Event::End(Tag::Link(..)) if current_link.as_ref().map_or(false, |l| l.0 == range) => {
+                let (range, link, kind, text) = current_link.take().unwrap();
+                let text = text.map_or("", |text| &md[text]);
+                let text = match kind {
+                    LinkType::ShortcutUnknown | LinkType::CollapsedUnknown
+                        if text == link.original_text =>
+                    {
+                        if text.starts_with('`') && !link.new_text.starts_with('`') {
+                            format!("`{}`", link.new_text)
+                        } else {
+                            link.new_text.clone()
+                        }
+                    }
+                    _ => text.to_string(),
+                };
+                let replacement =
+                    if link.href.is_empty() { text } else { format!("[{}]({})", text, link.href) };
+                edits.push((range, replacement));
+                continue;
+            }
+            Event::Start(Tag::CodeBlock(ref kind)) => {
+                let (is_rust, indented) = match kind {
+                    CodeBlockKind::Fenced(lang) => {
+                        (LangString::parse_without_check(lang, ErrorCodes::No, false).rust, false)
+                    }
+                    CodeBlockKind::Indented => (true, true),
+                };
+                if is_rust {
+                    code_block = Some((range, indented, String::new()));
+                }
+                continue;
+            }
+            Event::Text(ref text) if code_block.is_some() => {
+                code_block.as_mut().unwrap().2.push_str(text);
+                continue;
+            }
+            Event::End(Tag::CodeBlock(_)) => {
+                if let Some((range, indented, code)) = code_block.take() {
+                    edits.push(rust_code_block(md, range, indented, &code));
+                }
+                continue;
+            }
+            Event::Start(Tag::Heading(level, ..)) if heading.is_none() => {
+                heading = Some((range, level as u32 + heading_offset, None));
+                continue;
+            }
+            Event::End(Tag::Heading(..)) if heading.as_ref().map_or(false, |h| h.0 == range) => {
+                let (range, level, text) = heading.take().unwrap();
+                let prefix = format!("{} ", "#".repeat(level.min(6) as usize));
+                let suffix = if md[range.clone()].ends_with('\n') { "\n" } else { "" };
+                match text {
+                    Some(text) => {
+                        // Keep the text itself untouched, since it may contain links that are
+                        // rewritten separately.
+                        edits.push((range.start..text.start, prefix));
+                        edits.push((text.end..range.end, suffix.to_string()));
+                    }
+                    None => edits.push((range, format!("{}{}", prefix.trim_end(), suffix))),
+                }
+                continue;
+            }
+            _ => {}
+        }
+    }
+
+    edits.sort_by_key(|(range, _)| range.start);
+    let mut out = String::with_capacity(md.len());
+    let mut last = 0;
+    for (range, replacement) in edits {
+        // Code blocks can't contain links or headings, so edits never overlap; be defensive
+        // anyway rather than panicking on odd input.
+        if range.start < last {
+            continue;
+        }
+        out.push_str(&md[last..range.start]);
+        out.push_str(&replacement);
+        last = range.end;
+    }
+    out.push_str(&md[last..]);
+    out
+}
+
+fn extend(text: &mut Option<Range<usize>>, range: &Range<usize>) {
+    *text = Some(match text.take() {
+        Some(text) => text.start.min(range.start)..text.end.max(range.end),
+        None => range.clone(),
+    });
+}
+
+/// Renders the fenced Rust code block that replaces the one at `range` in `md`, without its
+/// hidden lines. Returns the range to replace along with the new block.
+fn rust_code_block(
+    md: &str,
+    mut range: Range<usize>,
+    indented: bool,
+    code: &str,
+) -> (Range<usize>, String) {
+    let lines: Vec<_> = code.lines().filter_map(|line| map_line(line).for_html()).collect();
+    let longest_fence = lines
+        .iter()
+        .flat_map(|line| line.split(|c| c != '`'))
+        .map(|backticks| backticks.len())
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(longest_fence.max(2) + 1);
+
+    // Keep the block aligned with whatever it is nested in, like a list item. An indented code
+    // block is itself indented by four more columns, which a fenced block must not be.
+    let line_start = md[..range.start].rfind('\n').map_or(0, |i| i + 1);
+    let mut width = md[line_start..range.start].chars().count();
+    let mut out = String::new();
+    if indented && md[line_start..range.start].chars().all(|c| c == ' ') {
+        width = width.saturating_sub(4);
+        range.start = line_start;
+        out.push_str(&" ".repeat(width));
+    }
+    let indent = " ".repeat(width);
+
+    out.push_str(&fence);
+    out.push_str("rust\n");
+    for line in lines {
+        if !line.is_empty() {
+            out.push_str(&indent);
+        }
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out.push_str(&indent);
+    out.push_str(&fence);
+    if md[range.clone()].ends_with('\n') {
+        out.push('\n');
+    }
+    (range, out)
+}
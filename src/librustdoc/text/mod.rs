@@ -0,0 +1,544 @@
+//! Rustdoc's Markdown and man page backends.
+//!
+//! Both backends write one page per item, laid out like the HTML output. Pages are first built
+//! as a [`Page`] of Markdown fragments, with intra-doc links already pointing at other pages of
+//! the same output, and then written out by a [`TextFormat`]: as is for Markdown, or converted
+//! to roff for man pages.
+
+mod format;
+mod man;
+mod markdown;
+#[cfg(test)]
+mod tests;
+
+use std::fs::{create_dir_all, File};
+use std::io::Write;
+use std::marker::PhantomData;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::{DefIdTree, TyCtxt};
+use rustc_span::hygiene::MacroKind;
+use rustc_span::Symbol;
+
+use crate::clean::types::ExternalLocation;
+use crate::clean::{self, RenderedLink};
+use crate::config::RenderOptions;
+use crate::docfs::PathError;
+use crate::error::Error;
+use crate::formats::cache::Cache;
+use crate::formats::item_type::ItemType;
+use crate::formats::FormatRenderer;
+use crate::html::format::{href_relative_parts, join_with_double_colon};
+use crate::html::markdown::plain_text_summary;
+use crate::html::render::{item_ty_to_section, ItemSection};
+use crate::try_err;
+
+/// A page of documentation, with all of its text in Markdown.
+crate struct Page {
+    /// What the page documents, like `Struct`.
+    kind: &'static str,
+    /// The full path of the item, like `krate::module::Foo`.
+    path: String,
+    ty: ItemType,
+    summary: String,
+    declaration: Option<String>,
+    docs: String,
+    sections: Vec<(&'static str, String)>,
+}
+
+/// The output format of a [`TextRenderer`].
+crate trait TextFormat: Clone {
+    fn descr() -> &'static str;
+
+    /// Returns the path of the page of the item at `fqp`, relative to the output directory.
+    fn page_path(fqp: &[Symbol], ty: ItemType) -> PathBuf;
+
+    /// Returns the link to the page of the item at `fqp` from a page of the module `current`,
+    /// pointing at `fragment` on that page if the format supports it.
+    fn href(fqp: &[Symbol], ty: ItemType, current: &[Symbol], fragment: &str) -> String;
+
+    /// Renders `page`, for the crate at `version` if it has one.
+    fn render(page: &Page, version: Option<&str>) -> String;
+}
+
+/// Markdown pages, at the same paths as the HTML output but with a `.md` extension.
+#[derive(Clone)]
+crate struct Markdown;
+
+impl TextFormat for Markdown {
+    fn descr() -> &'static str {
+        "markdown"
+    }
+
+    fn page_path(fqp: &[Symbol], ty: ItemType) -> PathBuf {
+        let mut path: PathBuf = module_fqp(fqp, ty).iter().map(|s| s.as_str()).collect();
+        path.push(page_file_name(fqp, ty));
+        path
+    }
+
+    fn href(fqp: &[Symbol], ty: ItemType, current: &[Symbol], fragment: &str) -> String {
+        let mut href = String::new();
+        for part in href_relative_parts(module_fqp(fqp, ty), current) {
+            href.push_str(part.as_str());
+            href.push('/');
+        }
+        href.push_str(&page_file_name(fqp, ty));
+        href.push_str(fragment);
+        href
+    }
+
+    fn render(page: &Page, _version: Option<&str>) -> String {
+        let mut out = format!("# {} `{}`\n\n", page.kind, page.path);
+        if let Some(declaration) = &page.declaration {
+            out.push_str(&format!("```rust\n{}\n```\n\n", declaration));
+        }
+        if !page.docs.is_empty() {
+            out.push_str(page.docs.trim_end());
+            out.push_str("\n\n");
+        }
+        for (title, body) in &page.sections {
+            out.push_str(&format!("## {}\n\n{}\n\n", title, body.trim_end()));
+        }
+        out.truncate(out.trim_end().len());
+        out.push('\n');
+        out
+    }
+}
+
+/// Man pages in section 3, named after the type and the full path of their item.
+#[derive(Clone)]
+crate struct Man;
+
+impl Man {
+    /// The name of the man page of an item: its type followed by its path, joined with `.` rather
+    /// than `::` since `:` can't be used in file names on Windows. The type tells apart items of
+    /// different namespaces with the same path, like a module and a function.
+    fn page_name(path: &str, ty: ItemType) -> String {
+        format!("{}.{}", ty, path.replace("::", "."))
+    }
+}
+
+impl TextFormat for Man {
+    fn descr() -> &'static str {
+        "man"
+    }
+
+    fn page_path(fqp: &[Symbol], ty: ItemType) -> PathBuf {
+        let name = Man::page_name(&join_with_double_colon(fqp), ty);
+        PathBuf::from("man3").join(format!("{}.3", name))
+    }
+
+    fn href(fqp: &[Symbol], ty: ItemType, _current: &[Symbol], _fragment: &str) -> String {
+        format!("{}{}", man::MAN_LINK_PREFIX, Man::page_name(&join_with_double_colon(fqp), ty))
+    }
+
+    fn render(page: &Page, version: Option<&str>) -> String {
+        let crate_name = page.path.split("::").next().unwrap();
+        let source = match version {
+            Some(version) => format!("{} {}", crate_name, version),
+            None => crate_name.to_string(),
+        };
+        let mut see_also = Vec::new();
+        let mut roff = man::Roff::new();
+        roff.request(
+            ".TH",
+            &[
+                &man::escape_arg(&page.path),
+                "3",
+                "",
+                &man::escape_arg(&source),
+                "Rust Library Documentation",
+            ],
+        );
+        roff.request(".SH", &["NAME"]);
+        if page.summary.is_empty() {
+            roff.text(&page.path, false);
+        } else {
+            roff.text(&format!("{} - {}", page.path, page.summary), false);
+        }
+        if let Some(declaration) = &page.declaration {
+            roff.request(".SH", &["SYNOPSIS"]);
+            roff.request(".nf", &[]);
+            roff.text(declaration, true);
+            roff.request(".fi", &[]);
+        }
+        if !page.docs.is_empty() {
+            roff.request(".SH", &["DESCRIPTION"]);
+            man::render(&mut roff, &page.docs, &mut see_also);
+        }
+        for (title, body) in &page.sections {
+            roff.request(".SH", &[&man::escape_arg(&title.to_uppercase())]);
+            man::render(&mut roff, body, &mut see_also);
+        }
+        let name = Man::page_name(&page.path, page.ty);
+        see_also.retain(|see_also| *see_also != name);
+        if !see_also.is_empty() {
+            roff.request(".SH", &["SEE ALSO"]);
+            for (i, page) in see_also.iter().enumerate() {
+                if i > 0 {
+                    roff.text(", ", false);
+                }
+                roff.font("\\fB");
+                roff.text(page, false);
+                roff.font("\\fR");
+                roff.text("(3)", false);
+            }
+        }
+        roff.into_string()
+    }
+}
+
+/// The module of the item at `fqp`: the item itself for modules, which get their own directory.
+fn module_fqp(fqp: &[Symbol], ty: ItemType) -> &[Symbol] {
+    if ty == ItemType::Module { fqp } else { &fqp[..fqp.len() - 1] }
+}
+
+fn page_file_name(fqp: &[Symbol], ty: ItemType) -> String {
+    match ty {
+        ItemType::Module => "index.md".to_string(),
+        _ => format!("{}.{}.md", ty, fqp.last().unwrap()),
+    }
+}
+
+/// Renders a crate to one [`TextFormat`] page per item.
+#[derive(Clone)]
+crate struct TextRenderer<'tcx, F: TextFormat> {
+    tcx: TyCtxt<'tcx>,
+    cache: Rc<Cache>,
+    /// The directory the pages are written to.
+    out_path: PathBuf,
+    /// The path of the module being rendered.
+    current: Vec<Symbol>,
+    /// Whether the module being rendered is stripped, in which case none of its items get a
+    /// page, like for the HTML output.
+    stripped: bool,
+    format: PhantomData<F>,
+}
+
+impl<'tcx, F: TextFormat> TextRenderer<'tcx, F> {
+    fn write_page(&self, item: &clean::Item, fqp: &[Symbol], ty: ItemType) -> Result<(), Error> {
+        let Some(page) = self.page(item, fqp, ty) else { return Ok(()) };
+        let path = self.out_path.join(F::page_path(fqp, ty));
+        try_err!(create_dir_all(path.parent().unwrap()), &path);
+        let mut file = try_err!(File::create(&path), &path);
+        try_err!(
+            file.write_all(F::render(&page, self.cache.crate_version.as_deref()).as_bytes()),
+            &path
+        );
+        Ok(())
+    }
+
+    /// Returns the link to `did` from the pages of the current module, or an empty string if it
+    /// has no page of its own.
+    fn href(&self, did: DefId, fragment: &str) -> String {
+        // Associated items and variants are documented on their parent's page.
+        let did = match self.tcx.def_kind(did) {
+            DefKind::AssocTy | DefKind::AssocFn | DefKind::AssocConst | DefKind::Variant => {
+                self.tcx.parent(did).unwrap()
+            }
+            _ => did,
+        };
+        let Some((fqp, ty)) =
+            self.cache.paths.get(&did).or_else(|| self.cache.external_paths.get(&did))
+        else { return String::new() };
+        if !did.is_local() {
+            match self.cache.extern_locations.get(&did.krate) {
+                Some(ExternalLocation::Remote(url)) => {
+                    let mut href = url.trim_end_matches('/').to_string();
+                    for part in module_fqp(fqp, *ty) {
+                        href.push('/');
+                        href.push_str(part.as_str());
+                    }
+                    match ty {
+                        ItemType::Module => href.push_str("/index.html"),
+                        _ => href.push_str(&format!("/{}.{}.html", ty, fqp.last().unwrap())),
+                    }
+                    href.push_str(fragment);
+                    return href;
+                }
+                Some(ExternalLocation::Local) => {}
+                Some(ExternalLocation::Unknown) | None => return String::new(),
+            }
+        }
+        F::href(fqp, *ty, &self.current, fragment)
+    }
+
+    /// Returns the intra-doc links of `item`, pointing at pages of this format.
+    fn links(&self, item: &clean::Item) -> Vec<RenderedLink> {
+        let Some(links) = self.cache.intra_doc_links.get(&item.def_id) else { return Vec::new() };
+        links
+            .iter()
+            .map(|link| {
+                let mut fragment = String::new();
+                if let Some(link_fragment) = &link.fragment {
+                    link_fragment.render(&mut fragment, self.tcx).unwrap();
+                }
+                RenderedLink {
+                    original_text: link.link.clone(),
+                    new_text: link.link_text.clone(),
+                    href: self.href(link.did, &fragment),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the documentation of `item`, with its headings `heading_offset` levels down.
+    fn docs(&self, item: &clean::Item, heading_offset: u32) -> String {
+        let docs = item.collapsed_doc_value().unwrap_or_default();
+        markdown::rewrite(&docs, &self.links(item), heading_offset)
+    }
+
+    /// Renders the documentation of an item listed on the page of its parent, below a heading at
+    /// `level` with an anchor the intra-doc links to the item can point at.
+    fn member(&self, out: &mut String, item: &clean::Item, level: usize, visibility: bool) {
+        let Some(declaration) = format::print_assoc_item(item, visibility, self.tcx)
+        else { return };
+        out.push_str(&format!(
+            "{} <a id=\"{}.{}\"></a>`{}`\n\n",
+            "#".repeat(level),
+            item.type_(),
+            item.name.unwrap(),
+            one_line(&declaration)
+        ));
+        let docs = self.docs(item, level as u32);
+        if !docs.is_empty() {
+            out.push_str(docs.trim_end());
+            out.push_str("\n\n");
+        }
+    }
+
+    fn page(&self, item: &clean::Item, fqp: &[Symbol], ty: ItemType) -> Option<Page> {
+        let kind = match &*item.kind {
+            clean::ModuleItem(_) if item.is_crate() => "Crate",
+            clean::ModuleItem(_) => "Module",
+            clean::FunctionItem(_) | clean::ForeignFunctionItem(_) => "Function",
+            clean::TraitItem(_) => "Trait",
+            clean::StructItem(_) => "Struct",
+            clean::UnionItem(_) => "Union",
+            clean::EnumItem(_) => "Enum",
+            clean::TypedefItem(_) => "Type Definition",
+            clean::MacroItem(_) => "Macro",
+            clean::ProcMacroItem(proc_macro) => match proc_macro.kind {
+                MacroKind::Bang => "Macro",
+                MacroKind::Attr => "Attribute Macro",
+                MacroKind::Derive => "Derive Macro",
+            },
+            clean::PrimitiveItem(_) => "Primitive Type",
+            clean::StaticItem(_) | clean::ForeignStaticItem(_) => "Static",
+            clean::ConstantItem(_) => "Constant",
+            clean::ForeignTypeItem => "Foreign Type",
+            clean::KeywordItem(_) => "Keyword",
+            clean::OpaqueTyItem(_) => "Opaque Type",
+            clean::TraitAliasItem(_) => "Trait Alias",
+            // Nothing else gets a page.
+            _ => return None,
+        };
+        let docs = item.collapsed_doc_value().unwrap_or_default();
+        let mut page = Page {
+            kind,
+            path: join_with_double_colon(fqp),
+            ty,
+            summary: plain_text_summary(&docs),
+            declaration: format::print_declaration(item, self.tcx),
+            docs: self.docs(item, 1),
+            sections: Vec::new(),
+        };
+
+        match &*item.kind {
+            clean::ModuleItem(module) => self.module_sections(&mut page, module),
+            clean::StructItem(clean::Struct { fields, .. })
+            | clean::UnionItem(clean::Union { fields, .. }) => {
+                let mut out = String::new();
+                for field in fields.iter().filter(|field| !field.is_stripped()) {
+                    self.member(&mut out, field, 3, true);
+                }
+                push_section(&mut page, "Fields", out);
+            }
+            clean::EnumItem(enum_) => {
+                let mut out = String::new();
+                for variant in enum_.variants.iter().filter(|variant| !variant.is_stripped()) {
+                    self.member(&mut out, variant, 3, false);
+                }
+                push_section(&mut page, "Variants", out);
+            }
+            clean::TraitItem(trait_) => {
+                let sections: [(&str, fn(&clean::Item) -> bool); 4] = [
+                    ("Associated Types", |item| {
+                        item.is_ty_associated_type() || item.is_associated_type()
+                    }),
+                    ("Associated Constants", |item| {
+                        item.is_ty_associated_const() || item.is_associated_const()
+                    }),
+                    ("Required Methods", |item| item.is_ty_method()),
+                    ("Provided Methods", |item| item.is_method()),
+                ];
+                for (title, filter) in sections {
+                    let mut out = String::new();
+                    for trait_item in trait_.items.iter().filter(|item| filter(item)) {
+                        self.member(&mut out, trait_item, 3, false);
+                    }
+                    push_section(&mut page, title, out);
+                }
+                let mut out = String::new();
+                let implementors =
+                    item.def_id.as_def_id().and_then(|did| self.cache.implementors.get(&did));
+                for implementor in implementors.into_iter().flatten() {
+                    out.push_str(&format!(
+                        "* `{}`\n",
+                        one_line(&format::print_impl(implementor.inner_impl(), self.tcx))
+                    ));
+                }
+                push_section(&mut page, "Implementors", out);
+            }
+            _ => {}
+        }
+
+        let impls = item.def_id.as_def_id().and_then(|did| self.cache.impls.get(&did));
+        let (inherent, trait_impls): (Vec<_>, Vec<_>) =
+            impls.into_iter().flatten().partition(|impl_| impl_.inner_impl().trait_.is_none());
+        let mut out = String::new();
+        for impl_ in inherent {
+            out.push_str(&format!(
+                "### `{}`\n\n",
+                one_line(&format::print_impl(impl_.inner_impl(), self.tcx))
+            ));
+            let docs = self.docs(&impl_.impl_item, 3);
+            if !docs.is_empty() {
+                out.push_str(docs.trim_end());
+                out.push_str("\n\n");
+            }
+            for impl_item in impl_.inner_impl().items.iter().filter(|item| !item.is_stripped()) {
+                self.member(&mut out, impl_item, 4, true);
+            }
+        }
+        push_section(&mut page, "Implementations", out);
+        let trait_sections: [(&str, fn(&clean::ImplKind) -> bool); 3] = [
+            ("Trait Implementations", |kind| !kind.is_auto() && !kind.is_blanket()),
+            ("Auto Trait Implementations", clean::ImplKind::is_auto),
+            ("Blanket Implementations", clean::ImplKind::is_blanket),
+        ];
+        for (title, filter) in trait_sections {
+            let mut out = String::new();
+            for impl_ in trait_impls.iter().filter(|impl_| filter(&impl_.inner_impl().kind)) {
+                out.push_str(&format!(
+                    "* `{}`\n",
+                    one_line(&format::print_impl(impl_.inner_impl(), self.tcx))
+                ));
+            }
+            push_section(&mut page, title, out);
+        }
+
+        Some(page)
+    }
+
+    /// Lists the items of a module, grouped like on its HTML page.
+    fn module_sections(&self, page: &mut Page, module: &clean::Module) {
+        let items: Vec<_> =
+            module.items.iter().filter(|item| item.name.is_some() && !item.is_stripped()).collect();
+        for &section in ItemSection::ALL {
+            // Re-exported items are documented where they are re-exported, if they are inlined.
+            if section == ItemSection::Reexports {
+                continue;
+            }
+            let mut out = String::new();
+            for item in items.iter().filter(|item| item_ty_to_section(item.type_()) == section) {
+                let name = item.name.unwrap();
+                let mut fqp = self.current.clone();
+                fqp.push(name);
+                let summary = plain_text_summary(&item.collapsed_doc_value().unwrap_or_default());
+                out.push_str(&format!(
+                    "* [`{}`]({})",
+                    name,
+                    F::href(&fqp, item.type_(), &self.current, "")
+                ));
+                if !summary.is_empty() {
+                    out.push_str(": ");
+                    out.push_str(&summary);
+                }
+                out.push('\n');
+            }
+            push_section(page, section.name(), out);
+        }
+    }
+}
+
+fn push_section(page: &mut Page, title: &'static str, body: String) {
+    if !body.is_empty() {
+        page.sections.push((title, body));
+    }
+}
+
+/// Joins the lines of a multi-line declaration, like one with a where clause, into one line.
+fn one_line(declaration: &str) -> String {
+    let line = declaration.lines().map(str::trim).collect::<Vec<_>>().join(" ");
+    line.trim_end_matches(',').to_string()
+}
+
+impl<'tcx, F: TextFormat> FormatRenderer<'tcx> for TextRenderer<'tcx, F> {
+    fn descr() -> &'static str {
+        F::descr()
+    }
+
+    const RUN_ON_MODULE: bool = true;
+
+    fn init(
+        krate: clean::Crate,
+        options: RenderOptions,
+        cache: Cache,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<(Self, clean::Crate), Error> {
+        debug!("Initializing {} renderer", F::descr());
+        Ok((
+            TextRenderer {
+                tcx,
+                cache: Rc::new(cache),
+                out_path: options.output,
+                current: Vec::new(),
+                stripped: false,
+                format: PhantomData,
+            },
+            krate,
+        ))
+    }
+
+    fn make_child_renderer(&self) -> Self {
+        self.clone()
+    }
+
+    fn item(&mut self, item: clean::Item) -> Result<(), Error> {
+        if self.stripped || item.is_stripped() {
+            return Ok(());
+        }
+        let mut fqp = self.current.clone();
+        fqp.push(item.name.unwrap());
+        self.write_page(&item, &fqp, item.type_())
+    }
+
+    fn mod_item_in(&mut self, item: &clean::Item) -> Result<(), Error> {
+        // Stripped modules are still recursed into, since they can contain implementations of
+        // public types, but neither they nor their items get pages.
+        self.stripped |= item.is_stripped();
+        self.current.push(item.name.unwrap());
+        if self.stripped {
+            return Ok(());
+        }
+        let fqp = self.current.clone();
+        self.write_page(item, &fqp, ItemType::Module)
+    }
+
+    fn mod_item_out(&mut self) -> Result<(), Error> {
+        self.current.pop();
+        Ok(())
+    }
+
+    fn after_krate(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn cache(&self) -> &Cache {
+        &self.cache
+    }
+}
@@ -0,0 +1,138 @@
+use std::path::PathBuf;
+
+use rustc_span::{create_default_session_globals_then, Symbol};
+
+use super::man::{self, Roff};
+use super::markdown::rewrite;
+use super::{Man, TextFormat};
+use crate::clean::RenderedLink;
+use crate::formats::item_type::ItemType;
+
+fn link(original_text: &str, new_text: &str, href: &str) -> RenderedLink {
+    RenderedLink {
+        original_text: original_text.to_string(),
+        new_text: new_text.to_string(),
+        href: href.to_string(),
+    }
+}
+
+#[test]
+fn rewrite_intra_doc_links() {
+    let links = [
+        link("`Foo`", "Foo", "struct.Foo.md"),
+        link("crate::bar", "crate::bar", "fn.bar.md#examples"),
+        link("fn@baz", "baz", ""),
+        link("mod@qux", "qux", "qux/index.md"),
+    ];
+    let md = "See [`Foo`], [the *bar* function](crate::bar), [baz][b] and [mod@qux].\n\n\
+              [b]: fn@baz\n";
+    assert_eq!(
+        rewrite(md, &links, 0),
+        "See [`Foo`](struct.Foo.md), [the *bar* function](fn.bar.md#examples), baz and \
+         [qux](qux/index.md).\n\n[b]: fn@baz\n"
+    );
+    // Links that aren't intra-doc links are left alone.
+    let md = "[Rust](https://www.rust-lang.org) and <https://docs.rs>";
+    assert_eq!(rewrite(md, &links, 0), md);
+}
+
+#[test]
+fn rewrite_code_blocks() {
+    let md = "```\n# fn main() {\nlet x = 1;\n## attr\n# }\n```\n\n\
+              ```text\n# not hidden\n```\n\n    # use std::fmt;\n    let y = 2;\n";
+    assert_eq!(
+        rewrite(md, &[], 0),
+        "```rust\nlet x = 1;\n# attr\n```\n\n```text\n# not hidden\n```\n\n\
+         ```rust\nlet y = 2;\n```\n"
+    );
+
+    // Code blocks stay nested in their list items.
+    let md = "* item\n\n  ```ignore\n  # let a = 0;\n  a + 1;\n  ```\n";
+    assert_eq!(rewrite(md, &[], 0), "* item\n\n  ```rust\n  a + 1;\n  ```\n");
+}
+
+#[test]
+fn rewrite_headings() {
+    let links = [link("Foo", "Foo", "struct.Foo.md")];
+    let md = "# Examples\n\nText\n\nSetext [Foo]\n------\n\n###### Deep\n";
+    assert_eq!(
+        rewrite(md, &links, 2),
+        "### Examples\n\nText\n\n#### Setext [Foo](struct.Foo.md)\n\n###### Deep\n"
+    );
+}
+
+fn to_roff(md: &str) -> (String, Vec<String>) {
+    let mut roff = Roff::new();
+    let mut see_also = Vec::new();
+    man::render(&mut roff, md, &mut see_also);
+    (roff.into_string(), see_also)
+}
+
+#[test]
+fn roff_escaping() {
+    let mut roff = Roff::new();
+    roff.text("a-b \\ c\n.starts with a dot\n'and a quote\n   indented", false);
+    assert_eq!(roff.into_string(), "a\\-b \\e c\n\\&.starts with a dot\n\\&'and a quote\nindented");
+    assert_eq!(man::escape_arg("say \"hi\"\nnow"), "say \\(dqhi\\(dq now");
+}
+
+#[test]
+fn roff_blocks() {
+    let md = "Some *emphasis*, **strength** and `code`.\n\n\
+              # Examples\n\n\
+              ```rust\nlet x = -1;\n```\n\n\
+              * one\n* two\n\n\
+              1. first\n2. second\n";
+    assert_eq!(
+        to_roff(md).0,
+        ".PP\nSome \\fIemphasis\\fP, \\fBstrength\\fP and \\fBcode\\fP.\n\
+         .SS Examples\n\
+         .RS \"4\"\n.nf\nlet x = \\-1;\n.fi\n.RE\n\
+         .IP \"\\(bu\" \"2\"\none\n.IP \"\\(bu\" \"2\"\ntwo\n\
+         .IP \"1.\" \"4\"\nfirst\n.IP \"2.\" \"4\"\nsecond\n"
+    );
+}
+
+#[test]
+fn roff_links() {
+    let md = "See [`Foo`](man:krate::Foo), [the book](https://doc.rust-lang.org/book) and \
+              [`Foo`](man:krate::Foo) again.";
+    let (roff, see_also) = to_roff(md);
+    assert_eq!(
+        roff,
+        ".PP\nSee \\fI\\fBFoo\\fP\\fP, the book <https://doc.rust\\-lang.org/book> and \
+         \\fI\\fBFoo\\fP\\fP again.\n"
+    );
+    assert_eq!(see_also, vec!["krate::Foo".to_string()]);
+}
+
+#[test]
+fn man_page_names() {
+    create_default_session_globals_then(|| {
+        let fqp = |path: &str| path.split("::").map(Symbol::intern).collect::<Vec<_>>();
+        // Items of different namespaces can share a path, but not a man page.
+        let items = [
+            ("krate::foo", ItemType::Module),
+            ("krate::foo", ItemType::Function),
+            ("krate::vec", ItemType::Macro),
+            ("krate::vec", ItemType::Module),
+            ("krate::Foo", ItemType::Struct),
+            ("krate::Foo", ItemType::Function),
+        ];
+        let pages: Vec<_> =
+            items.iter().map(|&(path, ty)| Man::page_path(&fqp(path), ty)).collect();
+        let expected = [
+            "man3/mod.krate.foo.3",
+            "man3/fn.krate.foo.3",
+            "man3/macro.krate.vec.3",
+            "man3/mod.krate.vec.3",
+            "man3/struct.krate.Foo.3",
+            "man3/fn.krate.Foo.3",
+        ];
+        assert_eq!(pages, expected.iter().map(PathBuf::from).collect::<Vec<_>>());
+        assert_eq!(
+            Man::href(&fqp("krate::vec"), ItemType::Macro, &fqp("krate"), "#examples"),
+            "man:macro.krate.vec"
+        );
+    })
+}
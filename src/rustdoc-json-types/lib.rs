@@ -9,12 +9,13 @@
 use serde::{Deserialize, Serialize};
 
 /// rustdoc format-version.
-pub const FORMAT_VERSION: u32 = 14;
+pub const FORMAT_VERSION: u32 = 16;
 
 /// A `Crate` is the root of the emitted JSON blob. It contains all type/documentation information
 /// about the language items in the local crate, as well as info about external items to allow
 /// tools to find or link to them.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct Crate {
     /// The id of the root [`Module`] item of the local crate.
     pub root: Id,
@@ -35,6 +36,7 @@ pub struct Crate {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct ExternalCrate {
     pub name: String,
     pub html_root_url: Option<String>,
@@ -45,6 +47,7 @@ pub struct ExternalCrate {
 /// question, or can be used by a tool that takes the json output of multiple crates to find
 /// the actual item definition with all the relevant info.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct ItemSummary {
     /// Can be used to look up the name and html_root_url of the crate this item came from in the
     /// `external_crates` map.
@@ -57,6 +60,7 @@ pub struct ItemSummary {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct Item {
     /// The unique identifier of this item. Can be used to find this item in various mappings.
     pub id: Id,
@@ -78,12 +82,18 @@ pub struct Item {
     pub links: HashMap<String, Id>,
     /// Stringified versions of the attributes on this item (e.g. `"#[inline]"`)
     pub attrs: Vec<String>,
+    /// The deprecation of this item. If the attribute doesn't give the version since which the
+    /// item is deprecated, it is computed like `available_since`.
     pub deprecation: Option<Deprecation>,
+    /// The version since which this item is stable, or the first release of the crate containing
+    /// it according to the releases passed with `--previous-release`.
+    pub available_since: Option<String>,
     #[serde(flatten)]
     pub inner: ItemEnum,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct Span {
     /// The path to the source file for this span relative to the path `rustdoc` was invoked with.
     pub filename: PathBuf,
@@ -94,12 +104,14 @@ pub struct Span {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct Deprecation {
     pub since: Option<String>,
     pub note: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum Visibility {
     Public,
@@ -116,6 +128,7 @@ pub enum Visibility {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum GenericArgs {
     /// <'a, 32, B: Copy, C = u32>
@@ -125,6 +138,7 @@ pub enum GenericArgs {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum GenericArg {
     Lifetime(String),
@@ -134,6 +148,7 @@ pub enum GenericArg {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct Constant {
     #[serde(rename = "type")]
     pub type_: Type,
@@ -143,6 +158,7 @@ pub struct Constant {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct TypeBinding {
     pub name: String,
     pub args: GenericArgs,
@@ -150,6 +166,7 @@ pub struct TypeBinding {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum TypeBindingKind {
     Equality(Term),
@@ -157,9 +174,11 @@ pub enum TypeBindingKind {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct Id(pub String);
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum ItemKind {
     Module,
@@ -190,6 +209,7 @@ pub enum ItemKind {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 #[serde(tag = "kind", content = "inner", rename_all = "snake_case")]
 pub enum ItemEnum {
     Module(Module),
@@ -242,37 +262,78 @@ pub enum ItemEnum {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct Module {
     pub is_crate: bool,
     pub items: Vec<Id>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct Union {
     pub generics: Generics,
     pub fields_stripped: bool,
     pub fields: Vec<Id>,
+    /// All the impls of this type, including the ones listed in `auto_trait_impls` and
+    /// `blanket_impls`.
     pub impls: Vec<Id>,
+    /// The impls of auto traits like `Send` and `Sync` synthesized by rustdoc for this type.
+    pub auto_trait_impls: Vec<Id>,
+    /// The blanket impls, like `impl<T> From<T> for T`, which apply to this type.
+    pub blanket_impls: Vec<Id>,
+    /// The memory layout of this type, if it could be computed.
+    pub layout: Option<Layout>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct Struct {
     pub struct_type: StructType,
     pub generics: Generics,
     pub fields_stripped: bool,
     pub fields: Vec<Id>,
+    /// All the impls of this type, including the ones listed in `auto_trait_impls` and
+    /// `blanket_impls`.
     pub impls: Vec<Id>,
+    /// The impls of auto traits like `Send` and `Sync` synthesized by rustdoc for this type.
+    pub auto_trait_impls: Vec<Id>,
+    /// The blanket impls, like `impl<T> From<T> for T`, which apply to this type.
+    pub blanket_impls: Vec<Id>,
+    /// The memory layout of this type, if it could be computed.
+    pub layout: Option<Layout>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct Enum {
     pub generics: Generics,
     pub variants_stripped: bool,
     pub variants: Vec<Id>,
+    /// All the impls of this type, including the ones listed in `auto_trait_impls` and
+    /// `blanket_impls`.
     pub impls: Vec<Id>,
+    /// The impls of auto traits like `Send` and `Sync` synthesized by rustdoc for this type.
+    pub auto_trait_impls: Vec<Id>,
+    /// The blanket impls, like `impl<T> From<T> for T`, which apply to this type.
+    pub blanket_impls: Vec<Id>,
+    /// The memory layout of this type, if it could be computed.
+    pub layout: Option<Layout>,
 }
 
+/// The memory layout of a type on the target of the documentation. It can only be computed for
+/// types whose layout doesn't depend on their generic parameters, and is unstable except for
+/// types with certain `repr` attributes.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+pub struct Layout {
+    /// The size in bytes, absent for unsized types.
+    pub size: Option<u64>,
+    /// The alignment in bytes.
+    pub align: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 #[serde(tag = "variant_kind", content = "variant_inner")]
 pub enum Variant {
@@ -282,6 +343,7 @@ pub enum Variant {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum StructType {
     Plain,
@@ -290,6 +352,7 @@ pub enum StructType {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct Header {
     #[serde(rename = "const")]
     pub const_: bool,
@@ -301,6 +364,7 @@ pub struct Header {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub enum Abi {
     // We only have a concrete listing here for stable ABI's because their are so many
     // See rustc_ast_passes::feature_gate::PostExpansionVisitor::check_abi for the list
@@ -317,6 +381,7 @@ pub enum Abi {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct Function {
     pub decl: FnDecl,
     pub generics: Generics,
@@ -324,6 +389,7 @@ pub struct Function {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct Method {
     pub decl: FnDecl,
     pub generics: Generics,
@@ -332,18 +398,21 @@ pub struct Method {
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct Generics {
     pub params: Vec<GenericParamDef>,
     pub where_predicates: Vec<WherePredicate>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct GenericParamDef {
     pub name: String,
     pub kind: GenericParamDefKind,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum GenericParamDefKind {
     Lifetime {
@@ -385,6 +454,7 @@ pub enum GenericParamDefKind {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum WherePredicate {
     BoundPredicate {
@@ -403,6 +473,7 @@ pub enum WherePredicate {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum GenericBound {
     TraitBound {
@@ -416,6 +487,7 @@ pub enum GenericBound {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum TraitBoundModifier {
     None,
@@ -424,6 +496,7 @@ pub enum TraitBoundModifier {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum Term {
     Type(Type),
@@ -431,6 +504,7 @@ pub enum Term {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 #[serde(tag = "kind", content = "inner")]
 pub enum Type {
@@ -485,6 +559,7 @@ pub enum Type {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct FunctionPointer {
     pub decl: FnDecl,
     pub generic_params: Vec<GenericParamDef>,
@@ -492,6 +567,7 @@ pub struct FunctionPointer {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct FnDecl {
     pub inputs: Vec<(String, Type)>,
     pub output: Option<Type>,
@@ -499,6 +575,7 @@ pub struct FnDecl {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct Trait {
     pub is_auto: bool,
     pub is_unsafe: bool,
@@ -509,12 +586,14 @@ pub struct Trait {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct TraitAlias {
     pub generics: Generics,
     pub params: Vec<GenericBound>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct Impl {
     pub is_unsafe: bool,
     pub generics: Generics,
@@ -530,6 +609,7 @@ pub struct Impl {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub struct Import {
     /// The full path being imported.
@@ -539,17 +619,22 @@ pub struct Import {
     pub name: String,
     /// The ID of the item being imported.
     pub id: Option<Id>, // FIXME is this actually ever None?
+    /// The path of the definition of the item being imported, starting with the name of the crate
+    /// defining it: `["alloc", "vec", "Vec"]` for `use std::vec::Vec;`.
+    pub def_path: Option<Vec<String>>,
     /// Whether this import uses a glob: `use source::*;`
     pub glob: bool,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct ProcMacro {
     pub kind: MacroKind,
     pub helpers: Vec<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum MacroKind {
     /// A bang macro `foo!()`.
@@ -561,6 +646,7 @@ pub enum MacroKind {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct Typedef {
     #[serde(rename = "type")]
     pub type_: Type,
@@ -568,12 +654,14 @@ pub struct Typedef {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct OpaqueTy {
     pub bounds: Vec<GenericBound>,
     pub generics: Generics,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct Static {
     #[serde(rename = "type")]
     pub type_: Type,
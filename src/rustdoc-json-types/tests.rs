@@ -1,3 +1,5 @@
+use expect_test::expect_file;
+
 use super::*;
 
 #[test]
@@ -8,6 +10,9 @@ fn test_struct_info_roundtrip() {
         fields_stripped: false,
         fields: vec![],
         impls: vec![],
+        auto_trait_impls: vec![],
+        blanket_impls: vec![],
+        layout: Some(Layout { size: Some(8), align: 4 }),
     });
 
     let struct_json = serde_json::to_string(&s).unwrap();
@@ -24,6 +29,9 @@ fn test_union_info_roundtrip() {
         fields_stripped: false,
         fields: vec![],
         impls: vec![],
+        auto_trait_impls: vec![],
+        blanket_impls: vec![],
+        layout: Some(Layout { size: Some(8), align: 4 }),
     });
 
     let union_json = serde_json::to_string(&u).unwrap();
@@ -32,3 +40,10 @@ fn test_union_info_roundtrip() {
 
     assert_eq!(u, de_u);
 }
+
+#[test]
+fn test_schema_is_up_to_date() {
+    // Run with `UPDATE_EXPECT=1` to regenerate the schema after changing the types.
+    let schema = serde_json::to_string_pretty(&schemars::schema_for!(Crate)).unwrap();
+    expect_file!["schema.json"].assert_eq(&format!("{}\n", schema));
+}
@@ -0,0 +1,21 @@
+// compile-flags: -Z instrument-functions -Copt-level=0
+
+#![crate_type = "lib"]
+#![feature(no_instrument_function)]
+
+// CHECK-LABEL: define{{.*}}void @instrumented()
+// CHECK: call void @__cyg_profile_func_enter(i8* bitcast ({{.*}} @instrumented to i8*)
+// CHECK: call void @__cyg_profile_func_exit(i8* bitcast ({{.*}} @instrumented to i8*)
+// CHECK-NEXT: ret void
+#[no_mangle]
+pub fn instrumented() {}
+
+// CHECK-LABEL: define{{.*}}void @not_instrumented()
+// CHECK-NOT: call void @__cyg_profile_func_{{enter|exit}}
+// CHECK: ret void
+#[no_mangle]
+#[no_instrument_function]
+pub fn not_instrumented() {}
+
+// The attributes are consumed by the pass that inserts the calls.
+// CHECK-NOT: instrument-function-entry
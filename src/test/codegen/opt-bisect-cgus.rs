@@ -0,0 +1,18 @@
+// Checks that `-Z opt-bisect-cgus` leaves the codegen units after the limit unoptimized, even
+// when optimizations are enabled.
+//
+// compile-flags: -O -Ccodegen-units=1 -Zopt-bisect-cgus=0
+
+#![crate_type = "lib"]
+
+#[inline]
+fn helper(x: u32) -> u32 {
+    x.wrapping_add(1)
+}
+
+// CHECK-LABEL: @caller
+#[no_mangle]
+pub fn caller(x: u32) -> u32 {
+    // CHECK: call {{.*}}helper
+    helper(x)
+}
@@ -0,0 +1,25 @@
+// Checks that `-Z outline-cold-panics` moves the formatting of panic messages out of the
+// function that may panic and into a separate cold function.
+//
+// compile-flags: -O -Z outline-cold-panics
+
+#![crate_type = "lib"]
+
+// The outlined path is emitted before the function it was taken from.
+// CHECK: define internal {{.*}}void @[[SHIM:[^(]+cold.panic.shim[^(]+]](
+// CHECK-SAME: #[[COLD:[0-9]+]]
+// CHECK: call {{.*}}panic_fmt
+
+// CHECK-LABEL: @checked_get(
+#[no_mangle]
+pub fn checked_get(data: &[u64], index: usize, limit: usize) -> u64 {
+    // CHECK-NOT: panic_fmt
+    // CHECK: call {{.*}}void @[[SHIM]](
+    if index >= limit {
+        panic!("index {} out of the allowed range {} (len {})", index, limit, data.len());
+    }
+    // CHECK: ret i64
+    data[index]
+}
+
+// CHECK: attributes #[[COLD]] = {{.*}}cold{{.*}}noinline
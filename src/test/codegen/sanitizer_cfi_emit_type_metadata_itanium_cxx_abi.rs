@@ -0,0 +1,28 @@
+// Verifies that the type identifiers of `extern "C"` functions are the ones Clang uses for C
+// functions of the same type.
+//
+// needs-sanitizer-cfi
+// only-x86_64
+// ignore-windows
+// compile-flags: -Clto -Cno-prepopulate-passes -Zsanitizer=cfi
+
+#![crate_type="lib"]
+
+use std::os::raw::c_char;
+
+pub extern "C" fn foo(f: extern "C" fn(i32) -> i32, arg: i32) -> i32 {
+    // CHECK-LABEL: define{{.*}}foo
+    // CHECK-SAME:  !type ![[TYPE1:[0-9]+]]
+    // CHECK:       call i1 @llvm.type.test({{.*}}, metadata !"_ZTSFiiE")
+    f(arg)
+}
+
+pub extern "C" fn bar(f: extern "C" fn(*const c_char, *const c_char), arg: *const c_char) {
+    // CHECK-LABEL: define{{.*}}bar
+    // CHECK-SAME:  !type ![[TYPE2:[0-9]+]]
+    // CHECK:       call i1 @llvm.type.test({{.*}}, metadata !"_ZTSFvPKcS0_E")
+    f(arg, arg)
+}
+
+// CHECK: ![[TYPE1]] = !{i64 0, !"_ZTSFiPFiiEiE"}
+// CHECK: ![[TYPE2]] = !{i64 0, !"_ZTSFvPFvPKcS0_ES0_E"}
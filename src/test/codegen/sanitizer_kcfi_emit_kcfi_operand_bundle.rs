@@ -0,0 +1,23 @@
+// Verifies that KCFI type metadata for functions and operand bundles for indirect calls are
+// emitted.
+//
+// needs-sanitizer-kcfi
+// min-llvm-version: 16
+// compile-flags: -Cno-prepopulate-passes -Zsanitizer=kcfi
+
+#![crate_type="lib"]
+
+pub fn foo(f: fn(i32) -> i32, arg: i32) -> i32 {
+    // CHECK-LABEL: define{{.*}}foo{{.*}}!kcfi_type ![[TYPE1:[0-9]+]]
+    // CHECK:       call{{.*}}(i32 {{.*}}) [ "kcfi"(i32 -605831916) ]
+    f(arg)
+}
+
+pub extern "C" fn bar(f: extern "C" fn(i32) -> i32, arg: i32) -> i32 {
+    // CHECK-LABEL: define{{.*}}bar{{.*}}!kcfi_type ![[TYPE2:[0-9]+]]
+    // CHECK:       call{{.*}}(i32 {{.*}}) [ "kcfi"(i32 329620) ]
+    f(arg)
+}
+
+// CHECK: ![[TYPE1]] = !{i32 -1200930345}
+// CHECK: !{i32 8, !"kcfi", i32 1}
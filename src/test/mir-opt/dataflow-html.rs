@@ -0,0 +1,20 @@
+// Test the HTML output of a dataflow analysis whose domain is a `ChunkedBitSet`.
+// compile-flags: -Z dump-mir-dataflow-html
+
+struct S;
+
+impl Drop for S {
+    fn drop(&mut self) {}
+}
+
+// EMIT_MIR dataflow_html.f.maybe_init.elaborate_drops.html
+fn f(c: bool) {
+    let s = S;
+    if c {
+        std::mem::drop(s);
+    }
+}
+
+fn main() {
+    f(true);
+}
@@ -0,0 +1,16 @@
+// compile-flags: -Z outline-cold-panics
+// Checks that a path ending in a panic, including the formatting of its message, is moved into
+// a separate body, and that bounds checks are left alone.
+
+// EMIT_MIR outline_cold_panics.checked_get.OutlineColdPanics.diff
+// EMIT_MIR outline_cold_panics.checked_get.cold-panic-shim-0.OutlineColdPanics.after.mir
+pub fn checked_get(data: &[u64], index: usize, limit: usize) -> u64 {
+    if index >= limit {
+        panic!("index {} out of the allowed range {}", index, limit);
+    }
+    data[index]
+}
+
+fn main() {
+    checked_get(&[1, 2, 3], 1, 2);
+}
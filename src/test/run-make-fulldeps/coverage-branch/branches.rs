@@ -0,0 +1,27 @@
+fn classify(x: u32, y: bool) -> u32 {
+    if x > 10 && y {
+        1
+    } else if x == 0 || y {
+        2
+    } else {
+        3
+    }
+}
+
+fn describe(x: u32) -> u32 {
+    match x {
+        0 => 0,
+        n if n > 10 => 1,
+        _ => 2,
+    }
+}
+
+fn main() {
+    let mut total = 0;
+    for (x, y) in [(20, true), (20, false), (0, false), (5, true), (5, false)] {
+        total += classify(x, y) + describe(x);
+    }
+    if total != 17 {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,12 @@
+#[inline(never)]
+pub fn generic_function<T: Default + PartialEq>(value: T) -> bool {
+    value == T::default()
+}
+
+fn inlined_function(x: u64) -> u64 {
+    x + 1
+}
+
+pub fn large_function(x: u64, y: u32, z: u8) -> bool {
+    generic_function(inlined_function(x)) || generic_function(y) || generic_function(z)
+}
@@ -0,0 +1,26 @@
+pub mod a {
+    pub fn unchanged(x: u32) -> u32 {
+        x.wrapping_mul(3)
+    }
+}
+
+pub mod b {
+    pub fn also_unchanged(x: &[u8]) -> usize {
+        x.iter().filter(|&&b| b == 0).count()
+    }
+
+    pub fn generic<T: Copy>(x: T) -> (T, T) {
+        (x, x)
+    }
+
+    pub fn instantiate(x: u8) -> (u8, u8) {
+        generic(x)
+    }
+}
+
+#[cfg(added)]
+pub mod c {
+    pub fn added(x: u64) -> u64 {
+        super::a::unchanged(x as u32) as u64 + 1
+    }
+}
@@ -0,0 +1,13 @@
+#![crate_type = "lib"]
+
+// The test command of the bisection treats inlining `helper` into `caller` as a miscompilation.
+
+#[inline]
+fn helper(x: u32) -> u32 {
+    x.wrapping_add(1)
+}
+
+#[no_mangle]
+pub fn caller(x: u32) -> u32 {
+    helper(x)
+}
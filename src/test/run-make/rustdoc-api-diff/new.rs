@@ -0,0 +1,23 @@
+pub struct Config {
+    pub verbose: bool,
+    pub color: bool,
+}
+
+impl Config {
+    pub fn new() -> Config {
+        Config { verbose: false, color: false }
+    }
+}
+
+pub const fn parse(input: &str) -> Config {
+    Config { verbose: input.is_empty(), color: false }
+}
+
+pub const LIMIT: u32 = 20;
+
+#[non_exhaustive]
+pub enum Mode {
+    Fast,
+    Slow,
+    Auto,
+}
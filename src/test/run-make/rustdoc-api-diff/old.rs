@@ -0,0 +1,16 @@
+pub struct Config {
+    pub verbose: bool,
+}
+
+pub fn parse(input: &str) -> Config {
+    Config { verbose: input.is_empty() }
+}
+
+pub fn helper() {}
+
+pub const LIMIT: u32 = 10;
+
+pub enum Mode {
+    Fast,
+    Slow,
+}
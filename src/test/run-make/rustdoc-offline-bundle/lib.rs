@@ -0,0 +1,9 @@
+// @has bundled/index.html
+// @has bundled/struct.Foo.html
+// @has search-index.js
+// @has rustdoc.css
+// @has src/bundled/lib.rs.html
+// @has settings.html
+
+/// A documented struct.
+pub struct Foo;
@@ -0,0 +1,31 @@
+// @has since/fn.old.html '//span[@class="since"]' '0.1.0'
+// @has - '//span[@class="since"]/@title' 'Available since version 0.1.0'
+// @has - '//div[@class="stab deprecated"]' 'Deprecated since 0.2.0'
+#[deprecated]
+pub fn old() {}
+
+// @has since/fn.added.html '//span[@class="since"]' '0.2.0'
+// @!has - '//div[@class="stab deprecated"]'
+pub fn added() {}
+
+// Items that aren't in any previous release appeared in the current one.
+// @has since/fn.brand_new.html '//span[@class="since"]' '0.3.0'
+pub fn brand_new() {}
+
+// @has since/struct.Config.html '//span[@class="since"]' '0.1.0'
+// @has - '//section[@id="method.new"]//span[@class="since"]' '0.2.0'
+// The version of members is omitted when it is the same as the version of the type.
+// @!has - '//section[@id="method.verbose"]//span[@class="since"]'
+pub struct Config {
+    pub verbose: bool,
+}
+
+impl Config {
+    pub fn new() -> Config {
+        Config { verbose: false }
+    }
+
+    pub fn verbose(&self) -> bool {
+        self.verbose
+    }
+}
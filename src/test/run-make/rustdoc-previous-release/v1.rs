@@ -0,0 +1,11 @@
+pub fn old() {}
+
+pub struct Config {
+    pub verbose: bool,
+}
+
+impl Config {
+    pub fn verbose(&self) -> bool {
+        self.verbose
+    }
+}
@@ -0,0 +1,18 @@
+#[deprecated]
+pub fn old() {}
+
+pub fn added() {}
+
+pub struct Config {
+    pub verbose: bool,
+}
+
+impl Config {
+    pub fn new() -> Config {
+        Config { verbose: false }
+    }
+
+    pub fn verbose(&self) -> bool {
+        self.verbose
+    }
+}
@@ -0,0 +1,40 @@
+//! A crate documented as Markdown and man pages.
+
+/// Items grouped in a module.
+pub mod shapes {
+    /// A point in the plane.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use textout::shapes::Point;
+    /// let origin = Point::default();
+    /// ```
+    #[derive(Default)]
+    pub struct Point {
+        /// The horizontal coordinate.
+        pub x: i32,
+        /// The vertical coordinate.
+        pub y: i32,
+    }
+
+    impl Point {
+        /// Moves the point, see [`distance`](crate::distance).
+        pub fn shift(&mut self, dx: i32, dy: i32) {
+            self.x += dx;
+            self.y += dy;
+        }
+    }
+}
+
+/// Returns the distance between two [`Point`]s, after [`shapes::Point::shift`].
+///
+/// [`Point`]: shapes::Point
+pub fn distance(a: &shapes::Point, b: &shapes::Point) -> f64 {
+    (((a.x - b.x).pow(2) + (a.y - b.y).pow(2)) as f64).sqrt()
+}
+
+/// Returns the names of the shapes, with the same path as the `shapes` module.
+pub fn shapes() -> &'static [&'static str] {
+    &["Point"]
+}
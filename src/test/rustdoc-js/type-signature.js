@@ -0,0 +1,119 @@
+// exact-check
+
+const QUERY = [
+    'Vec<T> -> Option<T>',
+    '&[T] -> Option<&T>',
+    'impl Iterator<Item = u8>',
+    '-> impl Iterator<Item = u8>',
+    'str -> Result<Foo, String>',
+    '&str -> Foo',
+    'T, U -> (U, T)',
+    'T, U -> T',
+    'Trait -> Bar<T>',
+    'u8 -> u8',
+    '* -> option',
+];
+
+const EXPECTED = [
+    {
+        'in_args': [
+            { 'path': 'type_signature', 'name': 'first' },
+        ],
+        'returned': [
+            { 'path': 'type_signature', 'name': 'last' },
+            { 'path': 'type_signature', 'name': 'first' },
+        ],
+    },
+    {
+        'in_args': [
+            { 'path': 'type_signature', 'name': 'last' },
+        ],
+        'returned': [
+            { 'path': 'type_signature', 'name': 'last' },
+            { 'path': 'type_signature', 'name': 'first' },
+        ],
+    },
+    {
+        'in_args': [
+            { 'path': 'type_signature', 'name': 'sum' },
+        ],
+        'returned': [
+            { 'path': 'type_signature', 'name': 'to_bytes' },
+        ],
+    },
+    {
+        'in_args': [],
+        'returned': [
+            { 'path': 'type_signature', 'name': 'to_bytes' },
+        ],
+    },
+    {
+        'in_args': [
+            { 'path': 'type_signature', 'name': 'parse' },
+        ],
+        'returned': [
+            { 'path': 'type_signature', 'name': 'parse' },
+        ],
+    },
+    // `Foo` is found inside of the `Result`.
+    {
+        'in_args': [
+            { 'path': 'type_signature', 'name': 'parse' },
+        ],
+        'returned': [
+            { 'path': 'type_signature', 'name': 'parse' },
+        ],
+    },
+    {
+        'in_args': [
+            { 'path': 'type_signature', 'name': 'swap' },
+        ],
+        'returned': [
+            { 'path': 'type_signature', 'name': 'swap' },
+        ],
+    },
+    // `swap` returns a `T` inside of a tuple.
+    {
+        'in_args': [
+            { 'path': 'type_signature', 'name': 'keep' },
+            { 'path': 'type_signature', 'name': 'swap' },
+        ],
+        'returned': [
+            { 'path': 'type_signature', 'name': 'keep' },
+            { 'path': 'type_signature', 'name': 'to_bytes' },
+            { 'path': 'type_signature', 'name': 'last' },
+            { 'path': 'type_signature', 'name': 'swap' },
+            { 'path': 'type_signature', 'name': 'bound' },
+            { 'path': 'type_signature', 'name': 'first' },
+        ],
+    },
+    // `T` is unified with a type parameter bound by `Trait`.
+    {
+        'in_args': [
+            { 'path': 'type_signature', 'name': 'bound' },
+        ],
+        'returned': [
+            { 'path': 'type_signature', 'name': 'bound' },
+        ],
+    },
+    {
+        'in_args': [
+            { 'path': 'type_signature', 'name': 'wrap' },
+        ],
+        'returned': [
+            { 'path': 'type_signature', 'name': 'wrap' },
+        ],
+    },
+    {
+        'in_args': [
+            { 'path': 'type_signature', 'name': 'last' },
+            { 'path': 'type_signature', 'name': 'wrap' },
+            { 'path': 'type_signature', 'name': 'first' },
+        ],
+        'returned': [
+            { 'path': 'type_signature', 'name': 'last' },
+            { 'path': 'type_signature', 'name': 'wrap' },
+            { 'path': 'type_signature', 'name': 'first' },
+        ],
+    },
+];
@@ -0,0 +1,15 @@
+pub struct Foo;
+pub struct Bar<T>(T);
+pub trait Trait {}
+
+pub fn first<T>(_v: Vec<T>) -> Option<T> { loop {} }
+pub fn last<T>(_v: &[T]) -> Option<&T> { loop {} }
+pub fn wrap(_x: u8) -> Option<u8> { loop {} }
+
+pub fn to_bytes(_s: &str) -> impl Iterator<Item = u8> { [].into_iter() }
+pub fn sum<I: Iterator<Item = u8>>(_iter: I) -> u32 { loop {} }
+pub fn parse(_s: &str) -> Result<Foo, String> { loop {} }
+
+pub fn swap<T, U>(_a: T, _b: U) -> (U, T) { loop {} }
+pub fn keep<T, U>(_a: T, _b: U) -> T { loop {} }
+pub fn bound<T: Trait>(_t: T) -> Bar<T> { loop {} }
@@ -0,0 +1,21 @@
+// Auto trait and blanket implementations are listed separately from the other implementations.
+
+#![no_std]
+
+pub trait Local {}
+
+impl<T> Local for T {}
+
+// @set auto_impl = synthetic.json "$.index[*][?(@.name=='Foo')].inner.auto_trait_impls[0]"
+// @has - "$.index[*][?(@.name=='Foo')].inner.impls[*]" $auto_impl
+// @!has - "$.index[*][?(@.name=='Foo')].inner.blanket_impls[*]" $auto_impl
+// @set blanket_impl = - "$.index[*][?(@.name=='Foo')].inner.blanket_impls[0]"
+// @has - "$.index[*][?(@.name=='Foo')].inner.impls[*]" $blanket_impl
+// @!has - "$.index[*][?(@.name=='Foo')].inner.auto_trait_impls[*]" $blanket_impl
+
+// Only the implementations of `Send`, `Sync`, `Unpin`, `UnwindSafe` and `RefUnwindSafe` are
+// synthetic.
+// @count - "$.index[*][?(@.name=='Foo')].inner.auto_trait_impls[*]" 5
+// @count - "$.index[*][?(@.inner.synthetic==true)].id" 5
+// @has - "$.index[*][?(@.inner.synthetic==true)].inner.trait.inner.name" '"Send"'
+pub struct Foo;
@@ -0,0 +1,16 @@
+// ignore-tidy-linelength
+
+// edition:2018
+
+#![no_core]
+#![feature(no_core)]
+
+pub mod inner {
+    pub struct Public;
+}
+
+// @is def_path.json "$.index[*][?(@.inner.name=='NewName')].inner.def_path" '["def_path", "inner", "Public"]'
+pub use inner::Public as NewName;
+
+// @is - "$.index[*][?(@.inner.glob==true)].inner.def_path" '["def_path", "inner"]'
+pub use inner::*;
@@ -0,0 +1,34 @@
+#![no_std]
+
+use core::marker::PhantomData;
+
+// @is layout.json "$.index[*][?(@.name=='Pair')].inner.layout.size" 8
+// @is - "$.index[*][?(@.name=='Pair')].inner.layout.align" 4
+#[repr(C)]
+pub struct Pair {
+    pub a: u32,
+    pub b: u32,
+}
+
+// @is - "$.index[*][?(@.name=='Small')].inner.layout.size" 1
+// @is - "$.index[*][?(@.name=='Small')].inner.layout.align" 1
+#[repr(u8)]
+pub enum Small {
+    A,
+    B,
+}
+
+// @is - "$.index[*][?(@.name=='Slice')].inner.layout.size" null
+// @is - "$.index[*][?(@.name=='Slice')].inner.layout.align" 1
+pub struct Slice {
+    pub data: [u8],
+}
+
+// The layout of generic types depends on their parameters.
+// @is - "$.index[*][?(@.name=='Wrapper')].inner.layout" null
+pub struct Wrapper<T>(pub T);
+
+// Unless it doesn't.
+// @is - "$.index[*][?(@.name=='Tagged')].inner.layout.size" 1
+// @is - "$.index[*][?(@.name=='Tagged')].inner.layout.align" 1
+pub struct Tagged<T>(pub u8, PhantomData<T>);
@@ -0,0 +1,24 @@
+#![deny(rustdoc::broken_relative_links)]
+
+//! The [struct](struct.Foo.html), its [constructor](struct.Foo.html#method.new) and the
+//! [missing module](missing/index.html).
+
+/// # Examples
+///
+/// See the [examples](#examples), the [crate](index.html) and the [module](inner/).
+/// There is no [anchor](#nope) here.
+/// Neither is there a [page](../missing.html) or a [section](index.html#nope).
+/// The [intra-doc links](Foo::new), [with anchors](Foo#method.new) and
+/// [URLs](https://example.com/missing.html) are not checked.
+pub struct Foo;
+
+impl Foo {
+    /// Creates a [`Foo`](#method.new), or a [`Bar`](#method.bar).
+    pub fn new() -> Foo {
+        Foo
+    }
+}
+
+pub mod inner {
+    //! Back to the [crate](../index.html#structs).
+}
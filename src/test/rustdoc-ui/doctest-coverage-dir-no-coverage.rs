@@ -0,0 +1,6 @@
+// test the behavior of the --doctest-coverage-dir flag without -C instrument-coverage
+
+// compile-flags:--test -Z unstable-options --doctest-coverage-dir=coverage
+// error-pattern: requires `-C instrument-coverage`
+
+pub fn f() {}
@@ -0,0 +1,6 @@
+// test the behavior of the --doctest-coverage-dir flag without the --test flag
+
+// compile-flags:-Z unstable-options -C instrument-coverage --doctest-coverage-dir=coverage
+// error-pattern: the `--test` flag must be passed
+
+pub fn f() {}
@@ -0,0 +1,23 @@
+// edition:2018
+// compile-flags:--test --test-args=--test-threads=1 -Z unstable-options --merge-doctests
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+// check-pass
+
+// `crate::` refers to the merged binary rather than to the doctest, so the doctests of this file
+// are compiled separately.
+
+/// ```
+/// struct Meters(u32);
+///
+/// fn main() {
+///     let m = crate::Meters(5);
+///     assert_eq!(m.0, 5);
+/// }
+/// ```
+pub fn meters() {}
+
+/// ```
+/// assert!(true);
+/// ```
+pub fn other() {}
@@ -0,0 +1,32 @@
+// edition:2018
+// compile-flags:--test --test-args=--test-threads=1 -Z unstable-options --merge-doctests
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+// check-pass
+
+//! ```
+//! assert_eq!(1 + 1, 2);
+//! ```
+
+/// ```should_panic
+/// panic!("expected");
+/// ```
+pub fn panics() {}
+
+/// ```
+/// let n: u32 = "42".parse()?;
+/// assert_eq!(n, 42);
+/// # Ok::<(), std::num::ParseIntError>(())
+/// ```
+pub fn result() {}
+
+/// ```compile_fail
+/// let x: u32 = "not a number";
+/// ```
+pub fn compile_fail() {}
+
+/// ```standalone
+/// // The merged binary is passed the index of the test to run.
+/// assert_eq!(std::env::args().count(), 1);
+/// ```
+pub fn standalone() {}
@@ -0,0 +1,6 @@
+// test that --offline-bundle can't be combined with --static-root-path
+
+// compile-flags:-Z unstable-options --offline-bundle docs.zip --static-root-path /cache/
+// error-pattern: cannot be used with `--static-root-path`
+
+pub fn f() {}
@@ -0,0 +1,7 @@
+// test that --workspace-index can't be combined with a custom --index-page
+
+// compile-flags:-Z unstable-options --workspace-index
+// compile-flags:--index-page {{src-base}}/workspace-index-with-index-page.rs
+// error-pattern: cannot be used with `--index-page`
+
+pub fn f() {}
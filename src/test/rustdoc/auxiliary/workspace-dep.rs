@@ -0,0 +1,5 @@
+// compile-flags: -Z unstable-options --workspace-index
+
+//! Utilities shared by the workspace.
+
+pub struct Dep;
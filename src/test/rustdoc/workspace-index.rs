@@ -0,0 +1,22 @@
+// aux-build:workspace-dep.rs
+// build-aux-docs
+// compile-flags: -Z unstable-options --workspace-index
+
+//! The main crate of the workspace.
+
+#![crate_name = "foo"]
+
+extern crate workspace_dep;
+
+// @has foo/../index.html
+// @has - '//span[@class="in-band"]' 'Workspace'
+// @has - '//div[@class="workspace-graph"]//a[@href="foo/index.html"]' 'foo'
+// @has - '//div[@class="workspace-graph"]//a[@href="workspace_dep/index.html"]' 'workspace_dep'
+// @count - '//div[@class="workspace-graph"]//line' 1
+// @has - '//div[@class="item-table"]//a[@href="foo/index.html"]' 'foo'
+// @has - '//div[@class="item-right docblock-short"]' 'The main crate of the workspace.'
+// @has - '//div[@class="item-right docblock-short"]' 'Utilities shared by the workspace.'
+
+// @has foo/../crates.js '"foo":{"doc":"The main crate of the workspace.","deps":["std","workspace_dep"]}'
+// @has foo/../crates.js '"workspace_dep":{"doc":"Utilities shared by the workspace.","deps":["std"]}'
+pub use workspace_dep::Dep;
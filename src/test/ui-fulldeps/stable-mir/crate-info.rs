@@ -0,0 +1,91 @@
+// run-pass
+// Test that users are able to use the Stable MIR API to retrieve information about a crate.
+
+// ignore-cross-compile
+// ignore-stage1
+// ignore-remote
+
+#![feature(rustc_private)]
+
+extern crate rustc_driver;
+extern crate rustc_smir;
+
+use rustc_driver::Compilation;
+use rustc_smir::stable_mir::mir::{Statement, Terminator};
+use rustc_smir::stable_mir::ty::Ty;
+use rustc_smir::stable_mir::{self, Context};
+use std::io::Write;
+
+const CRATE_NAME: &str = "input";
+
+struct StableMirCalls {
+    checked: bool,
+}
+
+impl rustc_driver::Callbacks for StableMirCalls {
+    fn after_analysis_stable_mir(&mut self, cx: &Context<'_>) -> Compilation {
+        assert_eq!(stable_mir::VERSION, 1);
+        assert_eq!(cx.local_crate_name(), CRATE_NAME);
+
+        let items = cx.all_local_items();
+        let paths: Vec<_> = items.iter().map(|item| cx.def_path(item.0)).collect();
+        assert!(paths.contains(&"foo".to_string()), "{:?}", paths);
+        assert!(paths.contains(&"identity".to_string()), "{:?}", paths);
+
+        let foo = items.iter().find(|item| cx.def_path(item.0) == "foo").unwrap();
+        let body = cx.item_body(*foo);
+        assert_eq!(body.arg_count, 1);
+        assert_eq!(body.locals[0].ty, Ty::Bool);
+        assert!(body.blocks.iter().any(|block| block.terminator == Terminator::Return));
+        assert!(body.blocks.iter().any(|block| {
+            block.statements.iter().any(|statement| matches!(statement, Statement::Assign(..)))
+        }));
+
+        let instances = cx.mono_instances();
+        let identity = instances
+            .iter()
+            .find(|instance| cx.def_path(instance.def) == "identity")
+            .expect("`identity::<u8>` is instantiated by `foo`");
+        let body = cx.instance_body(identity);
+        assert_eq!(body.locals[0].ty, Ty::Uint(stable_mir::ty::UintTy::U8));
+
+        // Asking again gives back the same instances.
+        assert_eq!(cx.mono_instances(), instances);
+
+        self.checked = true;
+        Compilation::Stop
+    }
+}
+
+fn main() {
+    let path = std::path::PathBuf::from(std::env::var_os("RUST_TEST_TMPDIR").unwrap())
+        .join("input.rs");
+    let mut file = std::fs::File::create(&path).unwrap();
+    write!(
+        file,
+        r#"
+        #[inline(never)]
+        pub fn identity<T>(value: T) -> T {{
+            value
+        }}
+
+        pub fn foo(i: u8) -> bool {{
+            identity(i) > 10
+        }}
+        "#
+    )
+    .unwrap();
+
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_str().unwrap().to_string(),
+    ];
+    let mut calls = StableMirCalls { checked: false };
+    rustc_driver::catch_fatal_errors(|| rustc_driver::RunCompiler::new(&args, &mut calls).run())
+        .unwrap()
+        .unwrap();
+    assert!(calls.checked);
+}
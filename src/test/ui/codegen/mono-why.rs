@@ -0,0 +1,15 @@
+// build-pass
+// compile-flags: -Zmono-why=generic::<*Foo>
+
+struct Foo;
+
+fn generic<T>(_: T) {}
+
+fn helper() {
+    generic(Foo);
+}
+
+fn main() {
+    helper();
+    generic(1u8);
+}
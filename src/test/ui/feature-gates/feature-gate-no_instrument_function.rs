@@ -0,0 +1,4 @@
+#![crate_type = "lib"]
+
+#[no_instrument_function] //~ ERROR the `#[no_instrument_function]` attribute is an experimental feature
+fn requires_feature_no_instrument_function() {}
@@ -0,0 +1,4 @@
+// compile-flags: -Z instrument-functions -Z instrument-mcount
+// error-pattern: `-Zinstrument-functions` is incompatible with `-Zinstrument-mcount`
+
+fn main() {}
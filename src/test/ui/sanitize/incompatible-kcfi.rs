@@ -0,0 +1,8 @@
+// compile-flags: -Z sanitizer=cfi -Z sanitizer=kcfi -Clto --target x86_64-unknown-linux-gnu
+// needs-llvm-components: x86
+// min-llvm-version: 16
+// error-pattern: error: `-Zsanitizer=cfi` is incompatible with `-Zsanitizer=kcfi`
+
+#![feature(no_core)]
+#![no_core]
+#![no_main]
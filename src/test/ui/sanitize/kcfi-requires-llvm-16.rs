@@ -0,0 +1,10 @@
+// Older LLVM versions would silently ignore the KCFI operand bundles and module flag.
+//
+// compile-flags: -Z sanitizer=kcfi --target x86_64-unknown-linux-gnu
+// needs-llvm-components: x86
+// ignore-llvm-version: 16 - 99
+// error-pattern: error: `-Zsanitizer=kcfi` requires LLVM 16 or later
+
+#![feature(no_core)]
+#![no_core]
+#![no_main]
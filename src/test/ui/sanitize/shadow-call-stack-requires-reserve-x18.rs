@@ -0,0 +1,15 @@
+// Verifies that `-Zsanitizer=shadow-call-stack` requires x18 to be reserved on targets where it
+// is not reserved by default.
+//
+// compile-flags: -Z sanitizer=shadow-call-stack --target aarch64-unknown-none
+// needs-llvm-components: aarch64
+// check-fail
+
+#![feature(no_core, lang_items)]
+#![no_core]
+#![crate_type = "lib"]
+
+#[lang = "sized"]
+trait Sized {}
+
+pub fn foo() {}
@@ -221,12 +221,18 @@ fn debugger() {
 fn sanitizers() {
     let mut config = config();
 
-    // Target that supports all sanitizers:
+    // Target that supports all sanitizers with a runtime:
     config.target = "x86_64-unknown-linux-gnu".to_owned();
     assert!(!check_ignore(&config, "// needs-sanitizer-address"));
     assert!(!check_ignore(&config, "// needs-sanitizer-leak"));
     assert!(!check_ignore(&config, "// needs-sanitizer-memory"));
     assert!(!check_ignore(&config, "// needs-sanitizer-thread"));
+    assert!(!check_ignore(&config, "// needs-sanitizer-kcfi"));
+    assert!(check_ignore(&config, "// needs-sanitizer-shadow-call-stack"));
+
+    config.target = "aarch64-unknown-none".to_owned();
+    assert!(!check_ignore(&config, "// needs-sanitizer-kcfi"));
+    assert!(!check_ignore(&config, "// needs-sanitizer-shadow-call-stack"));
 
     // Target that doesn't support sanitizers:
     config.target = "wasm32-unknown-emscripten".to_owned();
@@ -234,6 +240,8 @@ fn sanitizers() {
     assert!(check_ignore(&config, "// needs-sanitizer-leak"));
     assert!(check_ignore(&config, "// needs-sanitizer-memory"));
     assert!(check_ignore(&config, "// needs-sanitizer-thread"));
+    assert!(check_ignore(&config, "// needs-sanitizer-kcfi"));
+    assert!(check_ignore(&config, "// needs-sanitizer-shadow-call-stack"));
 }
 
 #[test]
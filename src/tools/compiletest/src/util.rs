@@ -120,6 +120,24 @@
 pub const MEMTAG_SUPPORTED_TARGETS: &[&str] =
     &["aarch64-linux-android", "aarch64-unknown-linux-gnu"];
 
+pub const KCFI_SUPPORTED_TARGETS: &[&str] = &[
+    "aarch64-linux-android",
+    "aarch64-unknown-linux-gnu",
+    "aarch64-unknown-none",
+    "aarch64-unknown-none-softfloat",
+    "x86_64-linux-android",
+    "x86_64-unknown-linux-gnu",
+    "x86_64-unknown-none",
+    "x86_64-unknown-none-linuxkernel",
+];
+
+pub const SHADOWCALLSTACK_SUPPORTED_TARGETS: &[&str] = &[
+    "aarch64-linux-android",
+    "aarch64-unknown-linux-gnu",
+    "aarch64-unknown-none",
+    "aarch64-unknown-none-softfloat",
+];
+
 const BIG_ENDIAN: &[&str] = &[
     "aarch64_be",
     "armebv7r",
@@ -0,0 +1,126 @@
+#!/usr/bin/env python3
+# -*- coding: utf-8 -*-
+
+# This script finds the codegen unit, or the LLVM pass, whose optimization
+# miscompiles a program, by bisecting `-Z opt-bisect-cgus` or
+# `-Z llvm-opt-bisect-limit` against a user-supplied test command.
+#
+# The build command is run with `RUSTFLAGS` extended with the bisection flag,
+# which also is in the `OPT_BISECT_FLAGS` environment variable for build
+# commands that invoke `rustc` directly. The test command must succeed when the
+# program behaves correctly and fail when it is miscompiled. For example:
+#
+#     src/tools/opt-bisect.py --build 'cargo build --release' \
+#         --test 'cargo test --release'
+#
+#     src/tools/opt-bisect.py --mode pass \
+#         --build 'rustc -O -Ccodegen-units=1 $OPT_BISECT_FLAGS main.rs' \
+#         --test './main'
+
+from __future__ import print_function
+
+import argparse
+import os
+import re
+import subprocess
+import sys
+
+CGU_LINE = re.compile(
+    r"^note: opt-bisect-cgus: (?:not )?optimizing codegen unit \((\d+)\) of (\d+)")
+PASS_LINE = re.compile(r"^BISECT: (?:NOT )?running pass \((\d+)\)")
+
+# Large enough for LLVM to run, and print, every pass. LLVM disables the
+# bisection, and prints nothing, when the limit is `INT_MAX`.
+ALL_PASSES = 2 ** 31 - 2
+
+
+def run(command, env, capture):
+    # type: (str, dict, bool) -> subprocess.CompletedProcess
+    return subprocess.run(
+        command,
+        shell=True,
+        env=env,
+        stderr=subprocess.PIPE if capture else None,
+        universal_newlines=True,
+    )
+
+
+def build(args, flag, limit):
+    # type: (argparse.Namespace, str, int) -> list
+    """Builds the program with the given bisection limit and returns the bisection lines
+    printed by rustc."""
+    flags = "-Z{}={}".format(flag, limit)
+    env = dict(os.environ)
+    env["OPT_BISECT_FLAGS"] = flags
+    env["RUSTFLAGS"] = (env.get("RUSTFLAGS", "") + " " + flags).strip()
+    result = run(args.build, env, capture=True)
+    if result.returncode != 0:
+        sys.stderr.write(result.stderr)
+        sys.exit("error: the build command failed with {}={}".format(flag, limit))
+    pattern = CGU_LINE if args.mode == "cgu" else PASS_LINE
+    return [line for line in result.stderr.splitlines() if pattern.match(line)]
+
+
+def is_good(args, flag, limit):
+    # type: (argparse.Namespace, str, int) -> bool
+    build(args, flag, limit)
+    good = run(args.test, dict(os.environ), capture=False).returncode == 0
+    print("{}={}: {}".format(flag, limit, "good" if good else "bad"))
+    return good
+
+
+def main():
+    parser = argparse.ArgumentParser(
+        description="Find the codegen unit or the LLVM pass whose optimization "
+        "miscompiles a program.")
+    parser.add_argument("--mode", choices=["cgu", "pass"], default="cgu",
+                        help="bisect codegen units (default) or LLVM passes")
+    parser.add_argument("--build", required=True,
+                        help="the command that builds the program")
+    parser.add_argument("--test", required=True,
+                        help="the command that succeeds if and only if the program is correct")
+    args = parser.parse_args()
+
+    if args.mode == "cgu":
+        flag = "opt-bisect-cgus"
+        # Every crate has its own codegen units, so the highest count is enough
+        # to optimize all codegen units of every crate.
+        lines = build(args, flag, 0)
+        high = max([int(CGU_LINE.match(line).group(2)) for line in lines] or [0])
+    else:
+        flag = "llvm-opt-bisect-limit"
+        lines = build(args, flag, ALL_PASSES)
+        high = max([int(PASS_LINE.match(line).group(1)) for line in lines] or [0])
+    if high == 0:
+        sys.exit("error: rustc did not print any bisection information; "
+                 "is the build command using RUSTFLAGS or $OPT_BISECT_FLAGS?")
+
+    # Invariant: the program is good at `low` and bad at `high`.
+    low = 0
+    if not is_good(args, flag, low):
+        sys.exit("error: the program is bad even without optimizations")
+    if is_good(args, flag, high):
+        sys.exit("error: the program is good even with all optimizations")
+    while high - low > 1:
+        mid = (low + high) // 2
+        if is_good(args, flag, mid):
+            low = mid
+        else:
+            high = mid
+
+    print()
+    if args.mode == "cgu":
+        print("The miscompilation appears when optimizing codegen unit ({}):".format(high))
+        culprits = [line for line in build(args, flag, high)
+                    if int(CGU_LINE.match(line).group(1)) == high
+                    and " not optimizing " not in line]
+    else:
+        print("The miscompilation appears when running pass ({}):".format(high))
+        culprits = [line for line in build(args, flag, high)
+                    if int(PASS_LINE.match(line).group(1)) == high]
+    for line in culprits:
+        print("    " + line)
+
+
+if __name__ == "__main__":
+    main()
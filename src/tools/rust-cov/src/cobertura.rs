@@ -0,0 +1,144 @@
+//! Writes reports in the Cobertura XML format, which is read by CI systems like Jenkins and
+//! GitLab. Each directory is a package, and each source file a class.
+
+use crate::escape;
+use crate::report::{FileCoverage, Report};
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Writes the report, with paths relative to `base_dir` if they are inside of it.
+pub fn write(report: &Report, base_dir: &Path, out: &mut dyn Write) -> io::Result<()> {
+    let mut packages: BTreeMap<String, Vec<(String, &FileCoverage)>> = BTreeMap::new();
+    for file in &report.files {
+        let path = file.path.strip_prefix(base_dir).unwrap_or(&file.path);
+        let package = path.parent().map_or(String::new(), |dir| dir.display().to_string());
+        packages.entry(package).or_default().push((path.display().to_string(), file));
+    }
+
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs());
+    let all = report.files.iter().collect::<Vec<_>>();
+    writeln!(out, r#"<?xml version="1.0" ?>"#)?;
+    writeln!(
+        out,
+        r#"<!DOCTYPE coverage SYSTEM "http://cobertura.sourceforge.net/xml/coverage-04.dtd">"#
+    )?;
+    writeln!(
+        out,
+        r#"<coverage {} complexity="0" version="1.9" timestamp="{}">"#,
+        rates(&all, true),
+        timestamp
+    )?;
+    writeln!(out, "  <sources>")?;
+    writeln!(out, "    <source>{}</source>", escape(&base_dir.display().to_string()))?;
+    writeln!(out, "  </sources>")?;
+    writeln!(out, "  <packages>")?;
+    for (package, files) in &packages {
+        let package_files = files.iter().map(|&(_, file)| file).collect::<Vec<_>>();
+        writeln!(
+            out,
+            r#"    <package name="{}" {} complexity="0">"#,
+            escape(package),
+            rates(&package_files, false)
+        )?;
+        writeln!(out, "      <classes>")?;
+        for (path, file) in files {
+            write_class(path, file, out)?;
+        }
+        writeln!(out, "      </classes>")?;
+        writeln!(out, "    </package>")?;
+    }
+    writeln!(out, "  </packages>")?;
+    writeln!(out, "</coverage>")?;
+    Ok(())
+}
+
+fn write_class(path: &str, file: &FileCoverage, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(
+        out,
+        r#"        <class name="{}" filename="{}" {} complexity="0">"#,
+        escape(path),
+        escape(path),
+        rates(&[file], false)
+    )?;
+    writeln!(out, "          <methods>")?;
+    for function in &file.functions {
+        writeln!(
+            out,
+            r#"            <method name="{}" signature="" line-rate="{}" {}>"#,
+            escape(&function.name),
+            if function.count > 0 { 1 } else { 0 },
+            r#"branch-rate="0" complexity="0""#
+        )?;
+        writeln!(out, "              <lines>")?;
+        writeln!(
+            out,
+            r#"                <line number="{}" hits="{}"/>"#,
+            function.line, function.count
+        )?;
+        writeln!(out, "              </lines>")?;
+        writeln!(out, "            </method>")?;
+    }
+    writeln!(out, "          </methods>")?;
+
+    // The `true` and `false` outcomes of the branches on each line.
+    let mut branches: BTreeMap<u32, (usize, usize)> = BTreeMap::new();
+    for branch in &file.branches {
+        let (outcomes, taken) = branches.entry(branch.line).or_default();
+        *outcomes += 2;
+        *taken += (branch.true_count > 0) as usize + (branch.false_count > 0) as usize;
+    }
+    writeln!(out, "          <lines>")?;
+    for (&line, count) in &file.lines {
+        match branches.get(&line) {
+            Some(&(outcomes, taken)) => writeln!(
+                out,
+                r#"            <line number="{}" hits="{}" branch="true" {}/>"#,
+                line,
+                count,
+                format!(
+                    r#"condition-coverage="{}% ({}/{})""#,
+                    taken * 100 / outcomes,
+                    taken,
+                    outcomes
+                )
+            )?,
+            None => writeln!(
+                out,
+                r#"            <line number="{}" hits="{}" branch="false"/>"#,
+                line, count
+            )?,
+        }
+    }
+    writeln!(out, "          </lines>")?;
+    writeln!(out, "        </class>")?;
+    Ok(())
+}
+
+/// Returns the `line-rate` and `branch-rate` attributes for the given files, and the attributes
+/// with the numbers of lines and branches if `with_totals` is true.
+fn rates(files: &[&FileCoverage], with_totals: bool) -> String {
+    let lines = files.iter().map(|file| file.lines.len()).sum::<usize>();
+    let covered_lines = files.iter().map(|file| file.covered_lines()).sum::<usize>();
+    let (branches, covered_branches) = files
+        .iter()
+        .map(|file| file.branch_outcomes())
+        .fold((0, 0), |(total, taken), (file_total, file_taken)| {
+            (total + file_total, taken + file_taken)
+        });
+    let rate = |covered, total| if total == 0 { 1.0 } else { covered as f64 / total as f64 };
+    let mut attrs = format!(
+        r#"line-rate="{:.4}" branch-rate="{:.4}""#,
+        rate(covered_lines, lines),
+        rate(covered_branches, branches)
+    );
+    if with_totals {
+        attrs += &format!(
+            r#" lines-covered="{}" lines-valid="{}" branches-covered="{}" branches-valid="{}""#,
+            covered_lines, lines, covered_branches, branches
+        );
+    }
+    attrs
+}
@@ -0,0 +1,361 @@
+//! Reads the coverage map that rustc embeds into programs compiled with
+//! `-C instrument-coverage` (see `rustc_codegen_llvm::coverageinfo::mapgen`).
+//!
+//! The coverage map is stored in two sections, in the [LLVM Code Coverage Mapping Format]:
+//!
+//! * `__llvm_covmap` has one record per codegen unit, with the filenames referenced by the
+//!   functions of the codegen unit;
+//! * `__llvm_covfun` has one record per function, with the hash of its name, its structural
+//!   hash, the hash of the filenames record it refers to, and its mapping regions.
+//!
+//! Coverage mapping format versions 5 (LLVM 12) and 6 (LLVM 13 and later) are supported.
+//!
+//! [LLVM Code Coverage Mapping Format]: https://llvm.org/docs/CoverageMappingFormat.html
+
+use crate::profraw;
+use crate::reader::{invalid_data, md5_hash, read_maybe_compressed, Reader};
+
+use object::{Object, ObjectSection};
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The zero-based encodings of the supported coverage mapping format versions.
+const MIN_VERSION: u32 = 4;
+const MAX_VERSION: u32 = 5;
+/// Since version 6 (encoded as 5), the first filename is the compilation directory, which
+/// relative filenames are relative to.
+const VERSION_WITH_COMPILATION_DIR: u32 = 5;
+
+/// The names of the sections used by the coverage map, for ELF and Mach-O, and for COFF.
+const COVMAP_SECTIONS: &[&str] = &["__llvm_covmap", ".lcovmap$M"];
+const COVFUN_SECTIONS: &[&str] = &["__llvm_covfun", ".lcovfun$M"];
+const NAMES_SECTIONS: &[&str] = &["__llvm_prf_names", ".lprfn$M"];
+
+/// A reference to a counter, or to an expression over counters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Counter {
+    Zero,
+    Counter(u32),
+    Expression(u32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExpressionKind {
+    Subtract,
+    Add,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Expression {
+    pub kind: ExpressionKind,
+    pub lhs: Counter,
+    pub rhs: Counter,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegionKind {
+    /// A region of code that was executed as often as its counter.
+    Code,
+    /// A region that expands to the regions of another file ID of the same function, e.g. for
+    /// a macro invocation.
+    Expansion { expanded_file_id: u32 },
+    /// A region of code that was not instrumented, and has no count.
+    Skipped,
+    /// A region between two regions of code, e.g. between the end of an `if` condition and the
+    /// start of its block, which only contributes to the counts of lines it starts on.
+    Gap,
+    /// A branch region: its counter counts how often the condition it spans was `true`, and
+    /// `false_counter` how often it was `false`.
+    Branch { false_counter: Counter },
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct MappingRegion {
+    pub kind: RegionKind,
+    pub counter: Counter,
+    /// The index of the file of this region, in `FunctionRecord::files`.
+    pub file_id: u32,
+    pub line_start: u32,
+    pub col_start: u32,
+    pub line_end: u32,
+    /// The column after the last character of the region.
+    pub col_end: u32,
+}
+
+/// The coverage mapping of one function.
+#[derive(Debug)]
+pub struct FunctionRecord {
+    pub name_hash: u64,
+    pub func_hash: u64,
+    /// The files the regions of the function are in, indexed by their file ID.
+    pub files: Vec<PathBuf>,
+    pub expressions: Vec<Expression>,
+    pub regions: Vec<MappingRegion>,
+}
+
+/// The coverage maps of one or more programs.
+#[derive(Default)]
+pub struct CoverageMap {
+    pub functions: Vec<FunctionRecord>,
+    /// The names of all instrumented functions, by the hash of their name.
+    pub names: HashMap<u64, String>,
+    /// The index of each function in `functions`, by the hash of its name.
+    function_indices: HashMap<u64, usize>,
+}
+
+impl CoverageMap {
+    pub fn new() -> Self {
+        CoverageMap::default()
+    }
+
+    pub fn read_object_file(&mut self, path: &Path) -> io::Result<()> {
+        let data = std::fs::read(path)?;
+        self.read_object(&data)
+            .map_err(|err| io::Error::new(err.kind(), format!("{}: {}", path.display(), err)))
+    }
+
+    /// Adds the functions of the coverage map of an executable or library.
+    pub fn read_object(&mut self, data: &[u8]) -> io::Result<()> {
+        let file = object::File::parse(data).map_err(|err| invalid_data(err.to_string()))?;
+        let section_data = |names: &[&str]| -> io::Result<Option<&[u8]>> {
+            match names.iter().find_map(|name| file.section_by_name(name)) {
+                Some(section) => {
+                    section.data().map(Some).map_err(|err| invalid_data(err.to_string()))
+                }
+                None => Ok(None),
+            }
+        };
+        let (covmap, covfun) =
+            match (section_data(COVMAP_SECTIONS)?, section_data(COVFUN_SECTIONS)?) {
+                (Some(covmap), Some(covfun)) => (covmap, covfun),
+                _ => {
+                    return Err(invalid_data(
+                        "no coverage map (compile with `-C instrument-coverage`)",
+                    ));
+                }
+            };
+        if let Some(names) = section_data(NAMES_SECTIONS)? {
+            profraw::read_names(names, &mut self.names)?;
+        }
+        self.read_sections(covmap, covfun, !file.is_little_endian())
+    }
+
+    /// Adds the functions of the coverage map in the given `__llvm_covmap` and `__llvm_covfun`
+    /// section data.
+    pub fn read_sections(
+        &mut self,
+        covmap: &[u8],
+        covfun: &[u8],
+        big_endian: bool,
+    ) -> io::Result<()> {
+        let mut filenames_by_hash = HashMap::new();
+        let mut reader = Reader::new(covmap);
+        reader.set_big_endian(big_endian);
+        // Each record is aligned to 8 bytes, and the section can be padded with zeros.
+        while reader.remaining() >= 16 {
+            let _zero_was_n_records = reader.u32()?;
+            let filenames_size = reader.u32()? as usize;
+            let _zero_was_coverage_size = reader.u32()?;
+            let version = reader.u32()?;
+            if !(MIN_VERSION..=MAX_VERSION).contains(&version) {
+                return Err(invalid_data(format!(
+                    "unsupported coverage mapping format version {}",
+                    version + 1
+                )));
+            }
+            let filenames_data = reader.bytes(filenames_size)?;
+            let filenames = read_filenames(filenames_data, version)?;
+            filenames_by_hash.insert(md5_hash(filenames_data), filenames);
+            reader.align_to(8);
+        }
+
+        let mut reader = Reader::new(covfun);
+        reader.set_big_endian(big_endian);
+        // `NameRef`, `DataSize`, `FuncHash` and `FilenamesRef`, packed.
+        while reader.remaining() >= 28 {
+            let name_hash = reader.u64()?;
+            let data_size = reader.u32()? as usize;
+            let func_hash = reader.u64()?;
+            let filenames_ref = reader.u64()?;
+            let data = reader.bytes(data_size)?;
+            reader.align_to(8);
+            if name_hash == 0 && data_size == 0 {
+                // Padding between records.
+                continue;
+            }
+            let filenames = filenames_by_hash
+                .get(&filenames_ref)
+                .ok_or_else(|| invalid_data("function record refers to unknown filenames"))?;
+            let function = read_function(name_hash, func_hash, data, filenames)?;
+
+            // The same function can be in more than one codegen unit, or program. Functions that
+            // were not codegenned have dummy records with a zero structural hash, which are only
+            // used if there is no other record.
+            match self.function_indices.get(&name_hash) {
+                None => {
+                    self.function_indices.insert(name_hash, self.functions.len());
+                    self.functions.push(function);
+                }
+                Some(&index) => {
+                    if self.functions[index].func_hash == 0 && func_hash != 0 {
+                        self.functions[index] = function;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn read_filenames(data: &[u8], version: u32) -> io::Result<Vec<PathBuf>> {
+    let mut reader = Reader::new(data);
+    let num_filenames = reader.uleb128_usize()?;
+    let data = read_maybe_compressed(&mut reader)?;
+    let mut reader = Reader::new(&data);
+    let mut filenames = Vec::with_capacity(num_filenames);
+    for _ in 0..num_filenames {
+        let len = reader.uleb128_usize()?;
+        let filename = PathBuf::from(String::from_utf8_lossy(reader.bytes(len)?).into_owned());
+        match filenames.first() {
+            Some(compilation_dir) if version >= VERSION_WITH_COMPILATION_DIR => {
+                let filename = Path::new(compilation_dir).join(filename);
+                filenames.push(filename);
+            }
+            _ => filenames.push(filename),
+        }
+    }
+    Ok(filenames)
+}
+
+/// Decodes the mapping data of a function record, as written by
+/// `llvm::coverage::CoverageMappingWriter`.
+fn read_function(
+    name_hash: u64,
+    func_hash: u64,
+    data: &[u8],
+    filenames: &[PathBuf],
+) -> io::Result<FunctionRecord> {
+    let mut reader = Reader::new(data);
+
+    let num_files = reader.uleb128_usize()?;
+    let files = (0..num_files)
+        .map(|_| {
+            let index = reader.uleb128_usize()?;
+            let filename =
+                filenames.get(index).ok_or_else(|| invalid_data("invalid file index"))?;
+            Ok(filename.clone())
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+
+    // The kind of an expression is only known from the references to it.
+    let num_expressions = reader.uleb128_usize()?;
+    let mut expressions = Vec::with_capacity(num_expressions);
+    let mut kinds = HashMap::new();
+    let mut read_counter = |value: u64| {
+        decode_counter(value, num_expressions, &mut kinds)
+            .ok_or_else(|| invalid_data(format!("invalid counter {}", value)))
+    };
+    for _ in 0..num_expressions {
+        let lhs = read_counter(reader.uleb128()?)?;
+        let rhs = read_counter(reader.uleb128()?)?;
+        expressions.push(Expression { kind: ExpressionKind::Subtract, lhs, rhs });
+    }
+
+    let mut regions = Vec::new();
+    for file_id in 0..num_files as u32 {
+        let num_regions = reader.uleb128_usize()?;
+        let mut line_start = 0u32;
+        for _ in 0..num_regions {
+            let encoded = reader.uleb128()?;
+            let mut kind = RegionKind::Code;
+            let mut counter = Counter::Zero;
+            if encoded & COUNTER_TAG_MASK != 0 {
+                counter = read_counter(encoded)?;
+            } else if encoded & EXPANSION_REGION_BIT != 0 {
+                let expanded_file_id = (encoded >> COUNTER_AND_EXPANSION_TAG_BITS) as u32;
+                if expanded_file_id as usize >= num_files {
+                    return Err(invalid_data("invalid expansion region"));
+                }
+                kind = RegionKind::Expansion { expanded_file_id };
+            } else {
+                match encoded >> COUNTER_AND_EXPANSION_TAG_BITS {
+                    // A code region with a zero counter.
+                    0 => {}
+                    2 => kind = RegionKind::Skipped,
+                    4 => {
+                        counter = read_counter(reader.uleb128()?)?;
+                        let false_counter = read_counter(reader.uleb128()?)?;
+                        kind = RegionKind::Branch { false_counter };
+                    }
+                    _ => return Err(invalid_data("invalid region kind")),
+                }
+            }
+
+            let line_start_delta = reader.uleb128_u32()?;
+            let mut col_start = reader.uleb128_u32()?;
+            let num_lines = reader.uleb128_u32()?;
+            let mut col_end = reader.uleb128_u32()?;
+            if col_end & GAP_REGION_BIT != 0 {
+                if kind == RegionKind::Code {
+                    kind = RegionKind::Gap;
+                }
+                col_end &= !GAP_REGION_BIT;
+            }
+            // Regions that cover whole lines have no columns.
+            if col_start == 0 && col_end == 0 {
+                col_start = 1;
+                col_end = u32::MAX;
+            }
+            line_start = line_start
+                .checked_add(line_start_delta)
+                .ok_or_else(|| invalid_data("invalid region line"))?;
+            let line_end = line_start
+                .checked_add(num_lines)
+                .ok_or_else(|| invalid_data("invalid region line"))?;
+            regions.push(MappingRegion {
+                kind,
+                counter,
+                file_id,
+                line_start,
+                col_start,
+                line_end,
+                col_end,
+            });
+        }
+    }
+
+    for (id, kind) in kinds {
+        expressions[id as usize].kind = kind;
+    }
+    Ok(FunctionRecord { name_hash, func_hash, files, expressions, regions })
+}
+
+/// The low bits of an encoded counter: zero, a counter, or a subtract or add expression.
+const COUNTER_TAG_BITS: u64 = 2;
+const COUNTER_TAG_MASK: u64 = (1 << COUNTER_TAG_BITS) - 1;
+/// For regions without a counter, marks expansion regions.
+const EXPANSION_REGION_BIT: u64 = 1 << COUNTER_TAG_BITS;
+const COUNTER_AND_EXPANSION_TAG_BITS: u64 = COUNTER_TAG_BITS + 1;
+/// Marks gap regions, in the end column.
+const GAP_REGION_BIT: u32 = 1 << 31;
+
+fn decode_counter(
+    value: u64,
+    num_expressions: usize,
+    expression_kinds: &mut HashMap<u32, ExpressionKind>,
+) -> Option<Counter> {
+    let id = u32::try_from(value >> COUNTER_TAG_BITS).ok()?;
+    let kind = match value & COUNTER_TAG_MASK {
+        0 => return Some(Counter::Zero),
+        1 => return Some(Counter::Counter(id)),
+        2 => ExpressionKind::Subtract,
+        _ => ExpressionKind::Add,
+    };
+    if id as usize >= num_expressions {
+        return None;
+    }
+    expression_kinds.insert(id, kind);
+    Some(Counter::Expression(id))
+}
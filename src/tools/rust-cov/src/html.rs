@@ -0,0 +1,196 @@
+//! Writes reports as HTML: an index with the coverage of each source file, and a page per source
+//! file with the execution counts of its lines, functions and branches.
+
+use crate::escape;
+use crate::report::{FileCoverage, Report};
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 1em 2em; }
+table { border-collapse: collapse; }
+th, td { padding: 0.1em 0.6em; text-align: right; }
+th:first-child, td:first-child { text-align: left; }
+.source td { font-family: monospace; padding: 0 0.6em; vertical-align: top; }
+.source td.code { text-align: left; white-space: pre; }
+.covered .count { background: #cfc; }
+.uncovered .count, .uncovered .code { background: #fcc; }
+.branch { color: #666; font-size: small; }
+.branch.partial { color: #a00; }
+";
+
+/// Writes the report into `out_dir`, with paths relative to `base_dir` if they are inside of it.
+/// The sources are read from the paths in the coverage map.
+pub fn write(report: &Report, base_dir: &Path, out_dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(out_dir)?;
+    let mut index = fs::File::create(out_dir.join("index.html"))?;
+    write_header(&mut index, "Coverage report")?;
+    writeln!(index, "<table>")?;
+    writeln!(
+        index,
+        "<tr><th>File</th><th>Lines</th><th>Functions</th><th>Regions</th><th>Branches</th></tr>"
+    )?;
+    let mut totals = Totals::default();
+    for file in &report.files {
+        let path = file.path.strip_prefix(base_dir).unwrap_or(&file.path).display().to_string();
+        let page = page_name(&path);
+        write_file_page(file, &path, &mut fs::File::create(out_dir.join(&page))?)?;
+
+        let file_totals = Totals::of(file);
+        writeln!(
+            index,
+            r#"<tr><td><a href="{}">{}</a></td>{}</tr>"#,
+            escape(&page),
+            escape(&path),
+            file_totals.cells()
+        )?;
+        totals.add(&file_totals);
+    }
+    writeln!(index, "<tr><th>Total</th>{}</tr>", totals.cells())?;
+    writeln!(index, "</table>")?;
+    writeln!(index, "</body>\n</html>")?;
+    Ok(())
+}
+
+fn write_header(out: &mut dyn Write, title: &str) -> io::Result<()> {
+    writeln!(out, "<!DOCTYPE html>\n<html>\n<head>")?;
+    writeln!(out, r#"<meta charset="utf-8">"#)?;
+    writeln!(out, "<title>{}</title>", escape(title))?;
+    writeln!(out, "<style>{}</style>", STYLE)?;
+    writeln!(out, "</head>\n<body>")?;
+    writeln!(out, "<h1>{}</h1>", escape(title))?;
+    Ok(())
+}
+
+/// Returns a file name for the page of the source file at `path`.
+fn page_name(path: &str) -> String {
+    let name = path
+        .trim_start_matches('/')
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+        .collect::<String>();
+    format!("{}.html", name)
+}
+
+fn write_file_page(file: &FileCoverage, path: &str, out: &mut dyn Write) -> io::Result<()> {
+    write_header(out, path)?;
+    writeln!(out, r#"<p><a href="index.html">Index</a></p>"#)?;
+
+    writeln!(out, "<h2>Functions</h2>\n<table>")?;
+    writeln!(out, "<tr><th>Function</th><th>Line</th><th>Count</th></tr>")?;
+    for function in &file.functions {
+        write!(
+            out,
+            r##"<tr class="{}"><td><a href="#L{}">{}</a></td>"##,
+            if function.count > 0 { "covered" } else { "uncovered" },
+            function.line,
+            escape(&function.name)
+        )?;
+        writeln!(
+            out,
+            r#"<td>{}</td><td class="count">{}</td></tr>"#,
+            function.line, function.count
+        )?;
+    }
+    writeln!(out, "</table>")?;
+
+    let mut branches: BTreeMap<u32, Vec<String>> = BTreeMap::new();
+    for branch in &file.branches {
+        let partial = branch.true_count == 0 || branch.false_count == 0;
+        branches.entry(branch.line).or_default().push(format!(
+            r#"<span class="branch{}">column {}: true {}, false {}</span>"#,
+            if partial { " partial" } else { "" },
+            branch.col,
+            branch.true_count,
+            branch.false_count
+        ));
+    }
+
+    writeln!(out, "<h2>Source</h2>")?;
+    let source = match fs::read_to_string(&file.path) {
+        Ok(source) => source,
+        Err(err) => {
+            writeln!(out, "<p>Source not available: {}</p>", escape(&err.to_string()))?;
+            String::new()
+        }
+    };
+    writeln!(out, r#"<table class="source">"#)?;
+    let last_line = file.lines.keys().next_back().copied().unwrap_or(0) as usize;
+    let mut source_lines = source.lines();
+    for line in 1..=source.lines().count().max(last_line) {
+        let code = source_lines.next().unwrap_or("");
+        let (class, count) = match file.lines.get(&(line as u32)) {
+            Some(&count) if count > 0 => ("covered", count.to_string()),
+            Some(_) => ("uncovered", "0".to_string()),
+            None => ("", String::new()),
+        };
+        let mut code = escape(code);
+        if let Some(branches) = branches.get(&(line as u32)) {
+            code += "\n";
+            code += &branches.join("\n");
+        }
+        write!(
+            out,
+            r##"<tr id="L{0}" class="{1}"><td><a href="#L{0}">{0}</a></td>"##,
+            line, class
+        )?;
+        writeln!(out, r#"<td class="count">{}</td><td class="code">{}</td></tr>"#, count, code)?;
+    }
+    writeln!(out, "</table>")?;
+    writeln!(out, "</body>\n</html>")?;
+    Ok(())
+}
+
+/// The numbers of covered and total lines, functions, regions and branch outcomes.
+#[derive(Default)]
+struct Totals {
+    lines: (usize, usize),
+    functions: (usize, usize),
+    regions: (usize, usize),
+    branches: (usize, usize),
+}
+
+impl Totals {
+    fn of(file: &FileCoverage) -> Self {
+        let (branches, taken) = file.branch_outcomes();
+        Totals {
+            lines: (file.covered_lines(), file.lines.len()),
+            functions: (file.covered_functions(), file.functions.len()),
+            regions: (file.covered_regions, file.regions),
+            branches: (taken, branches),
+        }
+    }
+
+    fn add(&mut self, other: &Totals) {
+        for (total, other) in [
+            (&mut self.lines, other.lines),
+            (&mut self.functions, other.functions),
+            (&mut self.regions, other.regions),
+            (&mut self.branches, other.branches),
+        ] {
+            total.0 += other.0;
+            total.1 += other.1;
+        }
+    }
+
+    fn cells(&self) -> String {
+        [self.lines, self.functions, self.regions, self.branches]
+            .iter()
+            .map(|&(covered, total)| {
+                if total == 0 {
+                    "<td>-</td>".to_string()
+                } else {
+                    format!(
+                        "<td>{:.2}% ({}/{})</td>",
+                        covered as f64 * 100.0 / total as f64,
+                        covered,
+                        total
+                    )
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,43 @@
+//! Writes reports in the LCOV tracefile format, as described by `geninfo(1)`, which is read by
+//! `genhtml` and most coverage services.
+
+use crate::report::Report;
+
+use std::io::{self, Write};
+
+pub fn write(report: &Report, out: &mut dyn Write) -> io::Result<()> {
+    for file in &report.files {
+        writeln!(out, "SF:{}", file.path.display())?;
+
+        for function in &file.functions {
+            writeln!(out, "FN:{},{}", function.line, function.name)?;
+        }
+        for function in &file.functions {
+            writeln!(out, "FNDA:{},{}", function.count, function.name)?;
+        }
+        writeln!(out, "FNF:{}", file.functions.len())?;
+        writeln!(out, "FNH:{}", file.covered_functions())?;
+
+        // Each branch has a `true` and a `false` outcome. Outcomes of branches that were never
+        // evaluated are `-`, rather than 0.
+        for (index, branch) in file.branches.iter().enumerate() {
+            let evaluated = branch.true_count > 0 || branch.false_count > 0;
+            for (outcome, count) in [branch.true_count, branch.false_count].into_iter().enumerate()
+            {
+                let taken = if evaluated { count.to_string() } else { "-".to_string() };
+                writeln!(out, "BRDA:{},0,{},{}", branch.line, index * 2 + outcome, taken)?;
+            }
+        }
+        let (outcomes, taken) = file.branch_outcomes();
+        writeln!(out, "BRF:{}", outcomes)?;
+        writeln!(out, "BRH:{}", taken)?;
+
+        for (line, count) in &file.lines {
+            writeln!(out, "DA:{},{}", line, count)?;
+        }
+        writeln!(out, "LF:{}", file.lines.len())?;
+        writeln!(out, "LH:{}", file.covered_lines())?;
+        writeln!(out, "end_of_record")?;
+    }
+    Ok(())
+}
@@ -0,0 +1,27 @@
+//! Generates coverage reports for programs compiled with `-C instrument-coverage`, from the
+//! coverage map embedded into the programs and the raw profiles they write, without needing
+//! `llvm-profdata` and `llvm-cov`.
+
+pub mod cobertura;
+pub mod covmap;
+pub mod html;
+pub mod lcov;
+pub mod profraw;
+pub mod reader;
+pub mod report;
+
+/// Escapes text for HTML and XML.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,141 @@
+//! Generates coverage reports for programs compiled with `-C instrument-coverage`.
+//!
+//! `rust-cov` merges the raw profiles (`.profraw` files) written by the programs, reads the
+//! coverage maps of the programs, and writes the coverage of each source file as an LCOV
+//! tracefile, as Cobertura XML or as HTML pages. Function names are demangled, and crate
+//! disambiguators are removed from them, like `rust-demangler` does by default.
+
+use rust_cov::covmap::CoverageMap;
+use rust_cov::profraw::Profile;
+use rust_cov::report::Report;
+use rust_cov::{cobertura, html, lcov};
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Lcov,
+    Cobertura,
+    Html,
+}
+
+fn usage(progname: &str) -> ! {
+    eprintln!();
+    eprintln!("Usage: {} [OPTIONS] --object <PROGRAM> <PROFRAW>...", progname);
+    eprintln!();
+    eprintln!(
+        "This tool merges raw profiles written by programs compiled with\n\
+        `-C instrument-coverage`, and writes a coverage report. Each <PROFRAW> is a\n\
+        `.profraw` file, or a directory with `.profraw` files."
+    );
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!(
+        "    --object <PATH>       A program or library with a coverage map; can be repeated\n\
+        \x20   --format <FORMAT>     `lcov` (the default), `cobertura` or `html`\n\
+        \x20   -o, --output <PATH>   The file to write the report to (default: standard\n\
+        \x20                         output), or the directory for `html` (default: `coverage`)\n\
+        \x20   --base-dir <DIR>      Show paths relative to this directory in `cobertura` and\n\
+        \x20                         `html` reports (default: the current directory)\n\
+        \x20   -d, --disambiguators  Keep crate disambiguators in demangled function names"
+    );
+    eprintln!();
+    std::process::exit(1)
+}
+
+fn main() -> io::Result<()> {
+    let mut args = std::env::args();
+    let progname = args.next().unwrap();
+    let mut objects = Vec::new();
+    let mut profiles = Vec::new();
+    let mut format = Format::Lcov;
+    let mut output = None;
+    let mut base_dir = None;
+    let mut strip_crate_disambiguators = Some(rust_demangler::create_disambiguator_re());
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().unwrap_or_else(|| usage(&progname));
+        match &arg[..] {
+            "--object" => objects.push(PathBuf::from(value())),
+            "--format" => {
+                format = match &value()[..] {
+                    "lcov" => Format::Lcov,
+                    "cobertura" => Format::Cobertura,
+                    "html" => Format::Html,
+                    _ => usage(&progname),
+                }
+            }
+            "-o" | "--output" => output = Some(PathBuf::from(value())),
+            "--base-dir" => base_dir = Some(PathBuf::from(value())),
+            "-d" | "--disambiguators" => strip_crate_disambiguators = None,
+            _ if arg.starts_with('-') => usage(&progname),
+            _ => profiles.push(PathBuf::from(arg)),
+        }
+    }
+    if objects.is_empty() || profiles.is_empty() {
+        usage(&progname);
+    }
+
+    let mut profile = Profile::new();
+    for path in &profiles {
+        for profraw in profraw_files(path)? {
+            profile.merge_file(&profraw)?;
+        }
+    }
+    if profile.mismatched_records > 0 {
+        eprintln!(
+            "warning: {} function records did not match the records of the same functions in \
+            other profiles, and were ignored; were the profiles written by different builds?",
+            profile.mismatched_records
+        );
+    }
+
+    let mut map = CoverageMap::new();
+    for object in &objects {
+        map.read_object_file(object)?;
+    }
+
+    let report = Report::new(&map, &profile, |name| {
+        let demangled = rustc_demangle::demangle(name).to_string();
+        match &strip_crate_disambiguators {
+            Some(re) => re.replace_all(&demangled, "::").to_string(),
+            None => demangled,
+        }
+    });
+
+    let base_dir = match base_dir {
+        Some(base_dir) => base_dir,
+        None => std::env::current_dir()?,
+    };
+    if format == Format::Html {
+        let out_dir = output.unwrap_or_else(|| PathBuf::from("coverage"));
+        return html::write(&report, &base_dir, &out_dir);
+    }
+    let mut out: Box<dyn Write> = match output {
+        Some(path) => Box::new(io::BufWriter::new(fs::File::create(path)?)),
+        None => Box::new(io::BufWriter::new(io::stdout())),
+    };
+    match format {
+        Format::Lcov => lcov::write(&report, &mut out)?,
+        Format::Cobertura => cobertura::write(&report, &base_dir, &mut out)?,
+        Format::Html => unreachable!(),
+    }
+    out.flush()
+}
+
+/// Returns `path` if it is a file, or the `.profraw` files in it if it is a directory.
+fn profraw_files(path: &Path) -> io::Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files = Vec::new();
+    for entry in fs::read_dir(path)? {
+        let path = entry?.path();
+        if path.extension().map_or(false, |ext| ext == "profraw") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
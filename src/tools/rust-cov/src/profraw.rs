@@ -0,0 +1,171 @@
+//! Reads and merges the raw profiles (`.profraw` files) written by programs compiled with
+//! `-C instrument-coverage`.
+//!
+//! The format is defined by `InstrProfData.inc` in LLVM's `compiler-rt`, which is linked into
+//! instrumented programs through the `profiler_builtins` crate. Raw profiles are written in the
+//! byte order and pointer size of the target, and contain:
+//!
+//! * a header, with the sizes of the following sections;
+//! * the binary IDs of the program (format version 6 and later);
+//! * one data record per function, with the hash of its name, its structural hash, and a pointer
+//!   to its counters;
+//! * the counters, as 64-bit integers;
+//! * the names of the functions, in the same format as the `__llvm_prf_names` section of the
+//!   program.
+//!
+//! Format versions 5 (LLVM 12) to 8 (LLVM 14) are supported.
+
+use crate::reader::{invalid_data, md5_hash, read_maybe_compressed, Reader};
+
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+const MAGIC_64: u64 = u64::from_be_bytes(*b"\xfflprofr\x81");
+const MAGIC_32: u64 = u64::from_be_bytes(*b"\xfflprofR\x81");
+
+const MIN_VERSION: u64 = 5;
+const MAX_VERSION: u64 = 8;
+
+/// The upper 8 bits of the version are flags for profile variants.
+const VARIANT_MASKS_ALL: u64 = 0xff << 56;
+/// Counters are single bytes, which are zero if the counter was hit.
+const VARIANT_MASK_BYTE_COVERAGE: u64 = 1 << 60;
+
+/// The counters of all functions in one or more raw profiles.
+#[derive(Default)]
+pub struct Profile {
+    /// The counters of each function, by the hash of its name and its structural hash.
+    counters: HashMap<(u64, u64), Vec<u64>>,
+    /// The names of all functions, by the hash of their name.
+    pub names: HashMap<u64, String>,
+    /// The number of function records that could not be merged, because they had a different
+    /// number of counters than the same function in another profile.
+    pub mismatched_records: usize,
+}
+
+impl Profile {
+    pub fn new() -> Self {
+        Profile::default()
+    }
+
+    /// Returns the counters of the function with the given name hash and structural hash.
+    pub fn counters(&self, name_hash: u64, func_hash: u64) -> Option<&[u64]> {
+        self.counters.get(&(name_hash, func_hash)).map(|counters| &counters[..])
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counters.is_empty()
+    }
+
+    pub fn merge_file(&mut self, path: &Path) -> io::Result<()> {
+        let data = std::fs::read(path)?;
+        self.merge_raw(&data)
+            .map_err(|err| io::Error::new(err.kind(), format!("{}: {}", path.display(), err)))
+    }
+
+    /// Adds the counters of a raw profile to the counters of this profile.
+    pub fn merge_raw(&mut self, data: &[u8]) -> io::Result<()> {
+        let mut reader = Reader::new(data);
+        let magic = reader.u64()?;
+        let (ptr_size, big_endian) = match magic {
+            MAGIC_64 => (8, false),
+            MAGIC_32 => (4, false),
+            _ if magic.swap_bytes() == MAGIC_64 => (8, true),
+            _ if magic.swap_bytes() == MAGIC_32 => (4, true),
+            _ => return Err(invalid_data("not a raw profile")),
+        };
+        reader.set_big_endian(big_endian);
+
+        let version = reader.u64()?;
+        if version & VARIANT_MASK_BYTE_COVERAGE != 0 {
+            return Err(invalid_data("profiles with single byte counters are not supported"));
+        }
+        let version = version & !VARIANT_MASKS_ALL;
+        if !(MIN_VERSION..=MAX_VERSION).contains(&version) {
+            return Err(invalid_data(format!("unsupported raw profile version {}", version)));
+        }
+        let binary_ids_size = if version >= 6 { reader.u64()? } else { 0 };
+        let data_size = reader.u64()?;
+        let padding_before_counters = reader.u64()?;
+        let counters_size = reader.u64()?;
+        let padding_after_counters = reader.u64()?;
+        let names_size = reader.u64()?;
+        let mut counters_delta = reader.u64()?;
+        let _names_delta = reader.u64()?;
+        let _value_kind_last = reader.u64()?;
+
+        let to_usize = |size: u64| {
+            usize::try_from(size).map_err(|_| invalid_data("section size is too large"))
+        };
+        reader.skip(to_usize(binary_ids_size)?)?;
+
+        // `NameRef`, `FuncHash`, `CounterPtr`, `FunctionPointer`, `Values`, `NumCounters` and
+        // `NumValueSites[2]`, aligned to 8 bytes.
+        let record_size = (16 + 3 * ptr_size + 4 + 2 * 2 + 7) / 8 * 8;
+        let mut records = Reader::new(reader.bytes(to_usize(data_size)? * record_size)?);
+        records.set_big_endian(big_endian);
+        reader.skip(to_usize(padding_before_counters)?)?;
+        let counters = reader.bytes(to_usize(counters_size)? * 8)?;
+        reader.skip(to_usize(padding_after_counters)?)?;
+        read_names(reader.bytes(to_usize(names_size)?)?, &mut self.names)?;
+
+        while !records.is_empty() {
+            let record_start = records.pos();
+            let name_hash = records.u64()?;
+            let func_hash = records.u64()?;
+            let counter_ptr = records.ptr(ptr_size)?;
+            let _function_ptr = records.ptr(ptr_size)?;
+            let _values_ptr = records.ptr(ptr_size)?;
+            let num_counters = records.u32()? as usize;
+            records.skip(record_start + record_size - records.pos())?;
+
+            // Before version 8, `CounterPtr` is the address of the counters, and
+            // `CountersDelta` the start address of the counters section. Since version 8, it
+            // is relative to the address of the record, and `CountersDelta` is the offset of
+            // the counters section from the current record.
+            let offset = counter_ptr.wrapping_sub(counters_delta);
+            if version >= 8 {
+                counters_delta = counters_delta.wrapping_sub(record_size as u64);
+            }
+            let mut counter_reader = Reader::new(counters);
+            counter_reader.set_big_endian(big_endian);
+            if offset % 8 != 0 || offset > counters.len() as u64 {
+                return Err(invalid_data("counter pointer is out of bounds"));
+            }
+            counter_reader.skip(offset as usize)?;
+            let record_counters =
+                (0..num_counters).map(|_| counter_reader.u64()).collect::<io::Result<Vec<_>>>()?;
+            self.add_counters(name_hash, func_hash, record_counters);
+        }
+        Ok(())
+    }
+
+    fn add_counters(&mut self, name_hash: u64, func_hash: u64, counters: Vec<u64>) {
+        let merged = self.counters.entry((name_hash, func_hash)).or_default();
+        if merged.is_empty() {
+            *merged = counters;
+        } else if merged.len() == counters.len() {
+            for (merged, count) in merged.iter_mut().zip(counters) {
+                *merged = merged.saturating_add(count);
+            }
+        } else {
+            self.mismatched_records += 1;
+        }
+    }
+}
+
+/// Reads the names of profiled functions, as written to the `__llvm_prf_names` section of an
+/// instrumented program and to raw profiles: a sequence of blocks of names separated by `\x01`.
+pub fn read_names(data: &[u8], names: &mut HashMap<u64, String>) -> io::Result<()> {
+    let mut reader = Reader::new(data);
+    // Sections can be padded with zeros, which read as empty blocks.
+    while !reader.is_empty() {
+        let block = read_maybe_compressed(&mut reader)?;
+        for name in block.split(|&b| b == 1).filter(|name| !name.is_empty()) {
+            let name = String::from_utf8_lossy(name).into_owned();
+            names.insert(md5_hash(name.as_bytes()), name);
+        }
+    }
+    Ok(())
+}
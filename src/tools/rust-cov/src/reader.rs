@@ -0,0 +1,135 @@
+//! A cursor over the raw bytes of profiles and coverage maps.
+
+use std::io::{self, Read};
+
+pub fn invalid_data(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
+
+pub struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    big_endian: bool,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Reader { data, pos: 0, big_endian: false }
+    }
+
+    pub fn set_big_endian(&mut self, big_endian: bool) {
+        self.big_endian = big_endian;
+    }
+
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.data.len().saturating_sub(self.pos)
+    }
+
+    pub fn bytes(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        let end = self.pos.checked_add(len).filter(|&end| end <= self.data.len());
+        let end = end.ok_or_else(|| invalid_data("unexpected end of data"))?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    pub fn skip(&mut self, len: usize) -> io::Result<()> {
+        self.bytes(len).map(|_| ())
+    }
+
+    /// Skips the padding up to the next multiple of `align`, counting from the start of the data.
+    pub fn align_to(&mut self, align: usize) {
+        let aligned = (self.pos + align - 1) / align * align;
+        self.pos = aligned.min(self.data.len());
+    }
+
+    fn array<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.bytes(N)?);
+        if self.big_endian {
+            array.reverse();
+        }
+        Ok(array)
+    }
+
+    pub fn u16(&mut self) -> io::Result<u16> {
+        self.array().map(u16::from_le_bytes)
+    }
+
+    pub fn u32(&mut self) -> io::Result<u32> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    pub fn u64(&mut self) -> io::Result<u64> {
+        self.array().map(u64::from_le_bytes)
+    }
+
+    /// Reads a pointer of the given size, zero-extended to 64 bits.
+    pub fn ptr(&mut self, ptr_size: usize) -> io::Result<u64> {
+        match ptr_size {
+            4 => self.u32().map(u64::from),
+            _ => self.u64(),
+        }
+    }
+
+    pub fn uleb128(&mut self) -> io::Result<u64> {
+        let mut result = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.bytes(1)?[0];
+            if shift >= 64 {
+                return Err(invalid_data("LEB128 value is too large"));
+            }
+            result |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+            shift += 7;
+        }
+    }
+
+    /// Reads a LEB128 value that must fit into a `u32`.
+    pub fn uleb128_u32(&mut self) -> io::Result<u32> {
+        let value = self.uleb128()?;
+        u32::try_from(value).map_err(|_| invalid_data(format!("value {} is too large", value)))
+    }
+
+    pub fn uleb128_usize(&mut self) -> io::Result<usize> {
+        let value = self.uleb128()?;
+        usize::try_from(value).map_err(|_| invalid_data(format!("value {} is too large", value)))
+    }
+}
+
+/// Reads a block of data in the format LLVM uses for the names of profiled functions and the
+/// filenames of coverage maps: a `(uncompressed_len, compressed_len)` pair of LEB128 values,
+/// followed by the data, either as is or compressed with zlib if `compressed_len` is non-zero.
+/// Returns the decompressed data.
+pub fn read_maybe_compressed(reader: &mut Reader<'_>) -> io::Result<Vec<u8>> {
+    let uncompressed_len = reader.uleb128_usize()?;
+    let compressed_len = reader.uleb128_usize()?;
+    if compressed_len == 0 {
+        return Ok(reader.bytes(uncompressed_len)?.to_vec());
+    }
+    let mut data = Vec::with_capacity(uncompressed_len);
+    flate2::read::ZlibDecoder::new(reader.bytes(compressed_len)?).read_to_end(&mut data)?;
+    if data.len() != uncompressed_len {
+        return Err(invalid_data("decompressed data has an unexpected size"));
+    }
+    Ok(data)
+}
+
+/// Computes the hash LLVM uses to refer to function names and filenames: the first 8 bytes of the
+/// MD5 digest, as a little-endian integer.
+pub fn md5_hash(bytes: &[u8]) -> u64 {
+    use md5::{Digest, Md5};
+    let digest = Md5::digest(bytes);
+    u64::from_le_bytes(digest[..8].try_into().unwrap())
+}
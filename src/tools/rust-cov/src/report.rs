@@ -0,0 +1,273 @@
+//! Combines a coverage map with the counters of a profile into line, function, region and branch
+//! coverage per source file, like `llvm-cov export` does.
+
+use crate::covmap::{Counter, CoverageMap, ExpressionKind, FunctionRecord, RegionKind};
+use crate::profraw::Profile;
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+/// The coverage of all source files of a coverage map.
+pub struct Report {
+    /// The source files, sorted by path.
+    pub files: Vec<FileCoverage>,
+}
+
+pub struct FileCoverage {
+    pub path: PathBuf,
+    /// The functions that start in this file, sorted by line.
+    pub functions: Vec<FunctionCoverage>,
+    /// The execution count of each line with code.
+    pub lines: BTreeMap<u32, u64>,
+    /// The branches in this file, sorted by position.
+    pub branches: Vec<BranchCoverage>,
+    /// The number of code regions, and of the code regions that were executed.
+    pub regions: usize,
+    pub covered_regions: usize,
+}
+
+pub struct FunctionCoverage {
+    pub name: String,
+    pub line: u32,
+    pub count: u64,
+}
+
+pub struct BranchCoverage {
+    pub line: u32,
+    pub col: u32,
+    pub true_count: u64,
+    pub false_count: u64,
+}
+
+/// The position of a region in a source file.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Span {
+    line_start: u32,
+    col_start: u32,
+    line_end: u32,
+    col_end: u32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SegmentKind {
+    Code,
+    Gap,
+    Skipped,
+}
+
+#[derive(Default)]
+struct FileRegions {
+    functions: Vec<FunctionCoverage>,
+    /// The counts of the regions of the file. Regions at the same position in more than one
+    /// function, like in the instances of a generic function, have their counts added up.
+    code: BTreeMap<(Span, SegmentKind), u64>,
+    branches: BTreeMap<Span, (u64, u64)>,
+}
+
+impl Report {
+    /// Computes the coverage of the functions of `map`, with the counters of `profile`. Functions
+    /// that are not in the profile were never executed. `demangle` is applied to function names.
+    pub fn new(map: &CoverageMap, profile: &Profile, demangle: impl Fn(&str) -> String) -> Self {
+        let mut files: HashMap<PathBuf, FileRegions> = HashMap::new();
+        for function in &map.functions {
+            let counters = profile.counters(function.name_hash, function.func_hash).unwrap_or(&[]);
+            let mut evaluator = Evaluator::new(function, counters);
+
+            let name = match map.names.get(&function.name_hash) {
+                Some(name) => demangle(name),
+                None => format!("{:#018x}", function.name_hash),
+            };
+            // The first region is the body of the function.
+            if let Some(first) = function.regions.first() {
+                let file = files.entry(function.files[first.file_id as usize].clone()).or_default();
+                let count = evaluator.eval(first.counter);
+                file.functions.push(FunctionCoverage { name, line: first.line_start, count });
+            }
+
+            for region in &function.regions {
+                let file =
+                    files.entry(function.files[region.file_id as usize].clone()).or_default();
+                let span = Span {
+                    line_start: region.line_start,
+                    col_start: region.col_start,
+                    line_end: region.line_end,
+                    col_end: region.col_end,
+                };
+                let kind = match region.kind {
+                    RegionKind::Code => SegmentKind::Code,
+                    RegionKind::Gap => SegmentKind::Gap,
+                    RegionKind::Skipped => SegmentKind::Skipped,
+                    RegionKind::Branch { false_counter } => {
+                        let true_count = evaluator.eval(region.counter);
+                        let false_count = evaluator.eval(false_counter);
+                        let counts = file.branches.entry(span).or_default();
+                        counts.0 = counts.0.saturating_add(true_count);
+                        counts.1 = counts.1.saturating_add(false_count);
+                        continue;
+                    }
+                    // The expanded regions are in the regions of their own file ID.
+                    RegionKind::Expansion { .. } => continue,
+                };
+                let count =
+                    if kind == SegmentKind::Skipped { 0 } else { evaluator.eval(region.counter) };
+                let total = file.code.entry((span, kind)).or_default();
+                *total = total.saturating_add(count);
+            }
+        }
+
+        let mut files = files
+            .into_iter()
+            .map(|(path, regions)| FileCoverage::new(path, regions))
+            .collect::<Vec<_>>();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Report { files }
+    }
+}
+
+impl FileCoverage {
+    fn new(path: PathBuf, mut regions: FileRegions) -> Self {
+        regions.functions.sort_by(|a, b| (a.line, &a.name).cmp(&(b.line, &b.name)));
+        let code_regions = regions.code.iter().filter(|((_, kind), _)| *kind == SegmentKind::Code);
+        let branches = regions
+            .branches
+            .iter()
+            .map(|(span, &(true_count, false_count))| BranchCoverage {
+                line: span.line_start,
+                col: span.col_start,
+                true_count,
+                false_count,
+            })
+            .collect();
+        FileCoverage {
+            path,
+            lines: line_counts(&regions.code),
+            regions: code_regions.clone().count(),
+            covered_regions: code_regions.filter(|(_, &count)| count > 0).count(),
+            functions: regions.functions,
+            branches,
+        }
+    }
+
+    pub fn covered_lines(&self) -> usize {
+        self.lines.values().filter(|&&count| count > 0).count()
+    }
+
+    pub fn covered_functions(&self) -> usize {
+        self.functions.iter().filter(|function| function.count > 0).count()
+    }
+
+    /// The number of branch outcomes, two for each branch, and of the outcomes that were taken.
+    pub fn branch_outcomes(&self) -> (usize, usize) {
+        let taken = self
+            .branches
+            .iter()
+            .map(|branch| (branch.true_count > 0) as usize + (branch.false_count > 0) as usize)
+            .sum();
+        (self.branches.len() * 2, taken)
+    }
+}
+
+/// Computes the execution count of each line with code, the way `llvm-cov` does: a line is
+/// executed as often as the most executed region that starts on it, or the region it is in if
+/// no region starts on it. Lines in skipped regions have no count.
+fn line_counts(regions: &BTreeMap<(Span, SegmentKind), u64>) -> BTreeMap<u32, u64> {
+    #[derive(Default)]
+    struct Line {
+        /// The count of the most executed code region starting on the line, if any.
+        start_count: Option<u64>,
+        /// The column and kind of the first region starting on the line.
+        first_start: Option<(u32, SegmentKind)>,
+        /// The innermost region the line starts in.
+        wrapped: Option<(Span, SegmentKind, u64)>,
+    }
+
+    let mut lines: BTreeMap<u32, Line> = BTreeMap::new();
+    for (&(span, kind), &count) in regions {
+        let line = lines.entry(span.line_start).or_default();
+        if line.first_start.map_or(true, |first| (span.col_start, kind) < first) {
+            line.first_start = Some((span.col_start, kind));
+        }
+        if kind == SegmentKind::Code {
+            line.start_count = Some(line.start_count.map_or(count, |max| max.max(count)));
+        }
+
+        // Regions end before their end column, so a region that ends in the first column of a
+        // line does not cover it.
+        let last_line =
+            if span.col_end > 1 { span.line_end } else { span.line_end.saturating_sub(1) };
+        for line_number in span.line_start + 1..=last_line {
+            let line = lines.entry(line_number).or_default();
+            let is_inner = line.wrapped.map_or(true, |(wrapped, ..)| {
+                (span.line_start, span.col_start) > (wrapped.line_start, wrapped.col_start)
+                    || (span.line_start, span.col_start) == (wrapped.line_start, wrapped.col_start)
+                        && (span.line_end, span.col_end) < (wrapped.line_end, wrapped.col_end)
+            });
+            if is_inner {
+                line.wrapped = Some((span, kind, count));
+            }
+        }
+    }
+
+    lines
+        .into_iter()
+        .filter_map(|(line_number, line)| {
+            if let Some((_, SegmentKind::Skipped)) = line.first_start {
+                return None;
+            }
+            let wrapped = match line.wrapped {
+                Some((_, SegmentKind::Skipped, _)) | None => None,
+                Some((_, _, count)) => Some(count),
+            };
+            match (line.start_count, wrapped) {
+                (None, None) => None,
+                (start, wrapped) => Some((line_number, start.max(wrapped).unwrap_or(0))),
+            }
+        })
+        .collect()
+}
+
+/// Evaluates the counters and expressions of a function.
+struct Evaluator<'a> {
+    function: &'a FunctionRecord,
+    counters: &'a [u64],
+    /// The values of expressions that were already evaluated, or `None` while an expression is
+    /// being evaluated, which catches cycles.
+    expressions: HashMap<u32, Option<i128>>,
+}
+
+impl<'a> Evaluator<'a> {
+    fn new(function: &'a FunctionRecord, counters: &'a [u64]) -> Self {
+        Evaluator { function, counters, expressions: HashMap::new() }
+    }
+
+    /// Returns the value of a counter. Counters that are missing from the profile, e.g. of
+    /// functions that never ran, are zero. Expressions that would be negative, which can happen
+    /// with profiles of programs that were interrupted, are clamped to zero.
+    fn eval(&mut self, counter: Counter) -> u64 {
+        self.eval_expr(counter).clamp(0, u64::MAX as i128) as u64
+    }
+
+    fn eval_expr(&mut self, counter: Counter) -> i128 {
+        match counter {
+            Counter::Zero => 0,
+            Counter::Counter(id) => self.counters.get(id as usize).copied().unwrap_or(0) as i128,
+            Counter::Expression(id) => {
+                match self.expressions.get(&id) {
+                    Some(Some(value)) => return *value,
+                    Some(None) => return 0,
+                    None => {}
+                }
+                self.expressions.insert(id, None);
+                let expression = self.function.expressions[id as usize];
+                let lhs = self.eval_expr(expression.lhs);
+                let rhs = self.eval_expr(expression.rhs);
+                let value = match expression.kind {
+                    ExpressionKind::Subtract => lhs - rhs,
+                    ExpressionKind::Add => lhs + rhs,
+                };
+                self.expressions.insert(id, Some(value));
+                value
+            }
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use rust_cov::covmap::CoverageMap;
+use rust_cov::lcov;
+use rust_cov::profraw::Profile;
+use rust_cov::reader::md5_hash;
+use rust_cov::report::Report;
+
+fn uleb128(out: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn pad_to_8(out: &mut Vec<u8>) {
+    while out.len() % 8 != 0 {
+        out.push(0);
+    }
+}
+
+/// A version 8 raw profile of a 64-bit little-endian program, with one function.
+fn raw_profile(name: &str, func_hash: u64, counters: &[u64]) -> Vec<u8> {
+    let mut names = Vec::new();
+    uleb128(&mut names, name.len() as u64);
+    uleb128(&mut names, 0);
+    names.extend_from_slice(name.as_bytes());
+
+    let record_size = 48u64;
+    let mut profile = Vec::new();
+    for field in [
+        u64::from_be_bytes(*b"\xfflprofr\x81"),
+        8 | 1 << 56,
+        0,
+        1,
+        0,
+        counters.len() as u64,
+        0,
+        names.len() as u64,
+        record_size,
+        0,
+        1,
+    ] {
+        profile.extend_from_slice(&field.to_le_bytes());
+    }
+    profile.extend_from_slice(&md5_hash(name.as_bytes()).to_le_bytes());
+    profile.extend_from_slice(&func_hash.to_le_bytes());
+    // The counters directly follow the only record.
+    profile.extend_from_slice(&record_size.to_le_bytes());
+    profile.extend_from_slice(&[0; 16]);
+    profile.extend_from_slice(&(counters.len() as u32).to_le_bytes());
+    profile.extend_from_slice(&[0; 4]);
+    for counter in counters {
+        profile.extend_from_slice(&counter.to_le_bytes());
+    }
+    profile.extend_from_slice(&names);
+    pad_to_8(&mut profile);
+    profile
+}
+
+/// The `__llvm_covmap` section for the compilation directory `/src` and the file `lib.rs`, and
+/// the `__llvm_covfun` section for the functions `foo` and `bar`:
+///
+/// ```text
+/// 1  fn foo(x: bool) -> u32 {     // counter 0 (whole function)
+/// 2      let a = if x {           // branch x: counter 1, counter 0 - counter 1
+/// 3          1                    // counter 1
+/// 4      } else {
+/// 5          let b = 2;           // counter 0 - counter 1
+/// 6      };
+/// 7  }
+/// 8
+/// 9  fn bar() {                   // counter 0 (whole function)
+/// 10 }
+/// ```
+fn coverage_map() -> (Vec<u8>, Vec<u8>) {
+    let mut filenames = Vec::new();
+    for filename in ["/src", "lib.rs"] {
+        uleb128(&mut filenames, filename.len() as u64);
+        filenames.extend_from_slice(filename.as_bytes());
+    }
+    let mut filenames_record = Vec::new();
+    uleb128(&mut filenames_record, 2);
+    uleb128(&mut filenames_record, filenames.len() as u64);
+    uleb128(&mut filenames_record, 0);
+    filenames_record.extend_from_slice(&filenames);
+
+    let mut covmap = Vec::new();
+    for field in [0, filenames_record.len() as u32, 0, 5] {
+        covmap.extend_from_slice(&field.to_le_bytes());
+    }
+    covmap.extend_from_slice(&filenames_record);
+    pad_to_8(&mut covmap);
+
+    let mut foo = Vec::new();
+    // One file, `lib.rs`, and one expression, `counter 0 - counter 1`.
+    for value in [1, 1, 1, 0 << 2 | 1, 1 << 2 | 1] {
+        uleb128(&mut foo, value);
+    }
+    // (counter, line delta, column, lines, end column)
+    uleb128(&mut foo, 4);
+    for region in [[0 << 2 | 1, 1, 1, 6, 2], [1 << 2 | 1, 1, 18, 2, 6]] {
+        for value in region {
+            uleb128(&mut foo, value);
+        }
+    }
+    // A branch region, with the `true` and `false` counters.
+    for value in [4 << 3, 1 << 2 | 1, 0 << 2 | 2, 0, 16, 0, 17] {
+        uleb128(&mut foo, value);
+    }
+    for value in [0 << 2 | 2, 3, 9, 1, 6] {
+        uleb128(&mut foo, value);
+    }
+
+    let mut bar = Vec::new();
+    for value in [1, 1, 0, 1, 0 << 2 | 1, 9, 1, 1, 2] {
+        uleb128(&mut bar, value);
+    }
+
+    let mut covfun = Vec::new();
+    for (name, func_hash, data) in [("foo", 1234u64, foo), ("bar", 5678, bar)] {
+        covfun.extend_from_slice(&md5_hash(name.as_bytes()).to_le_bytes());
+        covfun.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        covfun.extend_from_slice(&func_hash.to_le_bytes());
+        covfun.extend_from_slice(&md5_hash(&filenames_record).to_le_bytes());
+        covfun.extend_from_slice(&data);
+        pad_to_8(&mut covfun);
+    }
+    (covmap, covfun)
+}
+
+fn lcov_report(map: &CoverageMap, profile: &Profile) -> String {
+    let report = Report::new(map, profile, |name| name.to_string());
+    let mut out = Vec::new();
+    lcov::write(&report, &mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn test_merge_raw_profiles() {
+    let mut profile = Profile::new();
+    profile.merge_raw(&raw_profile("foo", 1234, &[5, 2])).unwrap();
+    profile.merge_raw(&raw_profile("foo", 1234, &[1, 1])).unwrap();
+    profile.merge_raw(&raw_profile("foo", 4321, &[3])).unwrap();
+    assert_eq!(profile.counters(md5_hash(b"foo"), 1234), Some(&[6, 3][..]));
+    assert_eq!(profile.counters(md5_hash(b"foo"), 4321), Some(&[3][..]));
+    assert_eq!(profile.names.get(&md5_hash(b"foo")).map(|name| &name[..]), Some("foo"));
+    assert_eq!(profile.mismatched_records, 0);
+
+    profile.merge_raw(&raw_profile("foo", 1234, &[1, 2, 3])).unwrap();
+    assert_eq!(profile.counters(md5_hash(b"foo"), 1234), Some(&[6, 3][..]));
+    assert_eq!(profile.mismatched_records, 1);
+}
+
+#[test]
+fn test_invalid_raw_profile() {
+    let mut profile = Profile::new();
+    assert!(profile.merge_raw(b"not a profile").is_err());
+    let mut truncated = raw_profile("foo", 1234, &[5, 2]);
+    truncated.truncate(100);
+    assert!(profile.merge_raw(&truncated).is_err());
+}
+
+#[test]
+fn test_lcov_report() {
+    let (covmap, covfun) = coverage_map();
+    let mut map = CoverageMap::new();
+    map.read_sections(&covmap, &covfun, false).unwrap();
+    map.names.insert(md5_hash(b"foo"), "foo".to_string());
+    map.names.insert(md5_hash(b"bar"), "bar".to_string());
+
+    let mut profile = Profile::new();
+    profile.merge_raw(&raw_profile("foo", 1234, &[5, 2])).unwrap();
+
+    const EXPECTED: &str = "\
+SF:/src/lib.rs
+FN:1,foo
+FN:9,bar
+FNDA:5,foo
+FNDA:0,bar
+FNF:2
+FNH:1
+BRDA:2,0,0,2
+BRDA:2,0,1,3
+BRF:2
+BRH:2
+DA:1,5
+DA:2,5
+DA:3,2
+DA:4,2
+DA:5,5
+DA:6,3
+DA:7,5
+DA:9,0
+DA:10,0
+LF:9
+LH:7
+end_of_record
+";
+    assert_eq!(lcov_report(&map, &profile), EXPECTED);
+}
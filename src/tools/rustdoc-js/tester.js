@@ -55,7 +55,8 @@ function extractFunction(content, functionName) {
             } else if (content[pos] === '*' && pos > 0 && content[pos - 1] === '/') {
                 do {
                     pos += 1;
-                } while (pos < content.length && content[pos] !== '/' && content[pos - 1] !== '*');
+                } while (pos < content.length &&
+                         (content[pos] !== '/' || content[pos - 1] !== '*'));
 
             // Eat quoted strings
             } else if (content[pos] === '"' || content[pos] === "'" || content[pos] === "`") {
@@ -263,7 +264,7 @@ function loadSearchJsAndIndex(searchJs, searchIndex, storageJs, crate) {
     var arraysToLoad = ["itemTypes"];
     var variablesToLoad = ["MAX_LEV_DISTANCE", "MAX_RESULTS", "NO_TYPE_FILTER",
                            "GENERICS_DATA", "NAME", "INPUTS_DATA", "OUTPUT_DATA",
-                           "TY_PRIMITIVE", "TY_KEYWORD",
+                           "TY_PRIMITIVE", "TY_KEYWORD", "TY_GENERIC",
                            "levenshtein_row2"];
     // execQuery first parameter is built in getQuery (which takes in the search input).
     // execQuery last parameter is built in buildIndex.